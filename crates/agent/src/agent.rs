@@ -401,6 +401,21 @@ pub trait SiblingThreadHost {
     fn list_available_agents(&self, cx: &mut App) -> Result<AvailableAgents>;
 }
 
+/// Implemented by the UI layer to run code for the `execute_code` tool in the
+/// REPL kernels of the workspace, so that the agent shares the user's live
+/// kernel state.
+///
+/// Like [`SiblingThreadHost`], `agent_ui::AgentPanel` installs an
+/// implementation on the `NativeAgent`, and tools reach it through
+/// `NativeThreadEnvironment`.
+pub trait CodeExecutionHost {
+    fn execute_code(
+        &self,
+        request: CodeExecutionRequest,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<CodeExecutionResult>>;
+}
+
 pub struct NativeAgent {
     /// Session ID -> Session mapping
     sessions: HashMap<acp::SessionId, Session>,
@@ -414,6 +429,8 @@ pub struct NativeAgent {
     models: LanguageModels,
     /// Handler installed by the UI for `create_thread` / `list_agents_and_models` tools.
     sibling_thread_host: Option<Rc<dyn SiblingThreadHost>>,
+    /// Handler installed by the UI for the `execute_code` tool.
+    code_execution_host: Option<Rc<dyn CodeExecutionHost>>,
     fs: Arc<dyn Fs>,
     _subscriptions: Vec<Subscription>,
     /// Tracks the lifecycle of global skills directory observation. We
@@ -587,6 +604,7 @@ impl NativeAgent {
                 templates,
                 models: LanguageModels::new(cx),
                 sibling_thread_host: None,
+                code_execution_host: None,
                 fs,
                 _subscriptions: subscriptions,
                 skills_state: SkillsState::default(),
@@ -721,6 +739,14 @@ impl NativeAgent {
         self.sibling_thread_host.clone()
     }
 
+    pub fn set_code_execution_host(&mut self, host: Rc<dyn CodeExecutionHost>) {
+        self.code_execution_host = Some(host);
+    }
+
+    pub fn code_execution_host(&self) -> Option<Rc<dyn CodeExecutionHost>> {
+        self.code_execution_host.clone()
+    }
+
     fn new_session(
         &mut self,
         project: Entity<Project>,
@@ -3285,6 +3311,27 @@ impl ThreadEnvironment for NativeThreadEnvironment {
             })?;
         host.list_available_agents(cx)
     }
+
+    fn execute_code(
+        &self,
+        request: CodeExecutionRequest,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<CodeExecutionResult>> {
+        let host = match self
+            .agent
+            .read_with(cx, |agent, _| agent.code_execution_host())
+        {
+            Ok(Some(host)) => host,
+            Ok(None) => {
+                return Task::ready(Err(anyhow!(
+                    "No code-execution host is registered. This usually means the \
+                     agent panel hasn't been initialized in this workspace."
+                )));
+            }
+            Err(err) => return Task::ready(Err(err)),
+        };
+        host.execute_code(request, cx)
+    }
 }
 
 #[derive(Debug, Clone)]
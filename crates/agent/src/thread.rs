@@ -1,11 +1,11 @@
 use crate::{
     ApplyCodeActionTool, CodeActionStore, ContextServerRegistry, CopyPathTool, CreateDirectoryTool,
-    CreateThreadTool, DbLanguageModel, DbThread, DeletePathTool, DiagnosticsTool, EditFileTool,
-    FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool, GoToDefinitionTool, GrepTool,
-    ListAgentsAndModelsTool, ListDirectoryTool, MovePathTool, ProjectSnapshot, ReadFileTool,
-    RenameTool, SandboxedTerminalTool, SpawnAgentTool, SystemPromptTemplate, Template, Templates,
-    TerminalTool, ToolPermissionDecision, WebSearchTool, WriteFileTool,
-    decide_permission_from_settings,
+    CreateThreadTool, DbLanguageModel, DbThread, DebuggerTool, DeletePathTool, DiagnosticsTool,
+    EditFileTool, ExecuteCodeTool, FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool,
+    GitHistoryTool, GoToDefinitionTool, GrepTool, ListAgentsAndModelsTool, ListDirectoryTool,
+    MovePathTool, ProjectSnapshot, ReadFileTool, RenameTool, RunTaskTool, SandboxedTerminalTool,
+    SpawnAgentTool, SymbolInfoTool, SystemPromptTemplate, Template, Templates, TerminalTool,
+    ToolPermissionDecision, WebSearchTool, WriteFileTool, decide_permission_from_settings,
 };
 use acp_thread::{ClientUserMessageId, MentionUri};
 use action_log::ActionLog;
@@ -47,7 +47,7 @@ use language_model::{
     LanguageModelToolUse, LanguageModelToolUseId, MessageContent, Role, SelectedModel, Speed,
     StopReason, TokenUsage, ZED_CLOUD_PROVIDER_ID,
 };
-use project::{Project, trusted_worktrees::TrustedWorktrees};
+use project::{Project, ProjectPath, trusted_worktrees::TrustedWorktrees};
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
 use serde::de::DeserializeOwned;
@@ -798,6 +798,38 @@ pub trait ThreadEnvironment {
             "Listing available agents is not supported in this environment"
         ))
     }
+
+    /// Runs code in the REPL kernel used for a file, starting one if needed.
+    fn execute_code(
+        &self,
+        request: CodeExecutionRequest,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<CodeExecutionResult>> {
+        let _ = request;
+        let _ = cx;
+        Task::ready(Err(anyhow::anyhow!(
+            "Executing code is not supported in this environment"
+        )))
+    }
+}
+
+/// A request to run code in a REPL kernel.
+#[derive(Debug, Clone)]
+pub struct CodeExecutionRequest {
+    /// The file whose kernel session runs the code. A session for the file's
+    /// language is started when the file has none.
+    pub path: ProjectPath,
+    /// The code to run.
+    pub code: String,
+}
+
+/// The outputs of code run in a REPL kernel.
+#[derive(Debug, Clone)]
+pub struct CodeExecutionResult {
+    /// The outputs, rendered as Markdown.
+    pub output: String,
+    /// Whether the kernel reported an error, such as an uncaught exception.
+    pub errored: bool,
 }
 
 /// A request to create a new sibling thread.
@@ -2134,6 +2166,7 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(GitHistoryTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
@@ -2149,6 +2182,11 @@ impl Thread {
             self.project.clone(),
             environment.clone(),
         ));
+        self.add_tool(RunTaskTool::new(self.project.clone(), environment.clone()));
+        self.add_tool(ExecuteCodeTool::new(
+            self.project.clone(),
+            environment.clone(),
+        ));
         self.add_tool(WebSearchTool);
 
         self.add_tool(DiagnosticsTool::new(self.project.clone()));
@@ -2164,7 +2202,9 @@ impl Thread {
             code_action_store,
         ));
         self.add_tool(GoToDefinitionTool::new(self.project.clone()));
+        self.add_tool(SymbolInfoTool::new(self.project.clone()));
         self.add_tool(RenameTool::new(self.project.clone()));
+        self.add_tool(DebuggerTool::new(self.project.clone()));
 
         if self.depth() < MAX_SUBAGENT_DEPTH {
             self.add_tool(SpawnAgentTool::new(environment.clone()));
@@ -3,16 +3,19 @@ mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
 mod create_thread_tool;
+mod debugger_tool;
 mod delete_path_tool;
 mod diagnostics_tool;
 mod edit_file_tool;
 mod edit_session;
 #[cfg(all(test, feature = "unit-eval"))]
 mod evals;
+mod execute_code_tool;
 mod fetch_tool;
 mod find_path_tool;
 mod find_references_tool;
 mod get_code_actions_tool;
+mod git_history_tool;
 mod go_to_definition_tool;
 mod grep_tool;
 mod list_agents_and_models_tool;
@@ -20,8 +23,10 @@ mod list_directory_tool;
 mod move_path_tool;
 mod read_file_tool;
 mod rename_tool;
+mod run_task_tool;
 mod skill_tool;
 mod spawn_agent_tool;
+mod symbol_info_tool;
 mod symbol_locator;
 mod terminal_tool;
 mod tool_permissions;
@@ -30,7 +35,8 @@ mod write_file_tool;
 
 use crate::AgentTool;
 use feature_flags::{
-    CreateThreadToolFeatureFlag, FeatureFlagAppExt as _, LspToolFeatureFlag, RenameToolFeatureFlag,
+    CreateThreadToolFeatureFlag, DebuggerToolFeatureFlag, ExecuteCodeToolFeatureFlag,
+    FeatureFlagAppExt as _, LspToolFeatureFlag, RenameToolFeatureFlag,
 };
 use gpui::App;
 use language_model::{LanguageModelRequestTool, LanguageModelToolSchemaFormat};
@@ -74,13 +80,16 @@ pub use context_server_registry::*;
 pub use copy_path_tool::*;
 pub use create_directory_tool::*;
 pub use create_thread_tool::*;
+pub use debugger_tool::*;
 pub use delete_path_tool::*;
 pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
+pub use execute_code_tool::*;
 pub use fetch_tool::*;
 pub use find_path_tool::*;
 pub use find_references_tool::*;
 pub use get_code_actions_tool::*;
+pub use git_history_tool::*;
 pub use go_to_definition_tool::*;
 pub use grep_tool::*;
 pub use list_agents_and_models_tool::*;
@@ -88,8 +97,10 @@ pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use read_file_tool::*;
 pub use rename_tool::*;
+pub use run_task_tool::*;
 pub use skill_tool::*;
 pub use spawn_agent_tool::*;
+pub use symbol_info_tool::*;
 pub use symbol_locator::*;
 
 pub use terminal_tool::*;
@@ -197,13 +208,16 @@ tools! {
     CopyPathTool,
     CreateDirectoryTool,
     CreateThreadTool,
+    DebuggerTool,
     DeletePathTool,
     DiagnosticsTool,
     EditFileTool,
+    ExecuteCodeTool,
     FetchTool,
     FindPathTool,
     FindReferencesTool,
     GetCodeActionsTool,
+    GitHistoryTool,
     GoToDefinitionTool,
     GrepTool,
     ListAgentsAndModelsTool,
@@ -211,8 +225,10 @@ tools! {
     MovePathTool,
     ReadFileTool,
     RenameTool,
+    RunTaskTool,
     SkillTool,
     SpawnAgentTool,
+    SymbolInfoTool,
     TerminalTool,
     WebSearchTool,
     WriteFileTool,
@@ -231,10 +247,13 @@ pub fn tool_feature_flag_enabled(tool_name: &str, cx: &App) -> bool {
         FindReferencesTool::NAME
         | GetCodeActionsTool::NAME
         | ApplyCodeActionTool::NAME
-        | GoToDefinitionTool::NAME => cx.has_flag::<LspToolFeatureFlag>(),
+        | GoToDefinitionTool::NAME
+        | SymbolInfoTool::NAME => cx.has_flag::<LspToolFeatureFlag>(),
         CreateThreadTool::NAME | ListAgentsAndModelsTool::NAME => {
             cx.has_flag::<CreateThreadToolFeatureFlag>()
         }
+        DebuggerTool::NAME => cx.has_flag::<DebuggerToolFeatureFlag>(),
+        ExecuteCodeTool::NAME => cx.has_flag::<ExecuteCodeToolFeatureFlag>(),
         _ => true,
     }
 }
@@ -244,13 +263,23 @@ mod tests {
     use super::*;
 
     #[test]
-    fn fetch_and_terminal_are_forbidden_in_restricted_mode() {
+    fn fetch_and_executing_tools_are_forbidden_in_restricted_mode() {
         assert!(!tool_allowed_in_restricted_mode(FetchTool::NAME));
         assert!(!tool_allowed_in_restricted_mode(TerminalTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(DebuggerTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(RunTaskTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(ExecuteCodeTool::NAME));
 
         // Every other built-in tool, and unknown (e.g. MCP) tools, are allowed.
+        let forbidden = [
+            FetchTool::NAME,
+            TerminalTool::NAME,
+            DebuggerTool::NAME,
+            RunTaskTool::NAME,
+            ExecuteCodeTool::NAME,
+        ];
         for name in ALL_TOOL_NAMES {
-            let expected = *name != FetchTool::NAME && *name != TerminalTool::NAME;
+            let expected = !forbidden.contains(name);
             assert_eq!(
                 tool_allowed_in_restricted_mode(name),
                 expected,
@@ -0,0 +1,964 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{AgentTool, ToolCallEventStream, ToolInput, ToolPermissionContext};
+use agent_client_protocol::schema::v1 as acp;
+use collections::HashSet;
+use dap::{
+    DapRegistry, SteppingGranularity, adapters::DebugAdapterName, adapters::DebugTaskDefinition,
+};
+use futures::{FutureExt as _, StreamExt as _, channel::mpsc};
+use gpui::{App, AsyncApp, Entity, SharedString, Subscription, Task};
+use project::Project;
+use project::debugger::breakpoint_store::{
+    Breakpoint, BreakpointEditAction, BreakpointStore, BreakpointWithPosition,
+};
+use project::debugger::session::{
+    Session, SessionEvent, SessionQuirks, SessionStateEvent, ThreadId, ThreadStatus,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use task::{TaskContext, TaskVariables, VariableName, substitute_variables_in_str};
+use text::Point;
+use util::markdown::MarkdownInlineCode;
+
+/// How long to wait for the debuggee to stop after starting, continuing or stepping.
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to wait for the debug adapter to answer a threads/stack/variables request.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_STACK_FRAMES: usize = 50;
+const MAX_VARIABLES_PER_SCOPE: usize = 100;
+const MAX_VALUE_LEN: usize = 200;
+
+/// Drives a debug session using the project's debug adapters.
+///
+/// Use this to investigate a failing program or test by stepping through it, instead of adding print statements.
+///
+/// Typical workflow:
+/// 1. `list_scenarios` to see the debug scenarios configured for the project (from `.zed/debug.json` and global settings).
+/// 2. `set_breakpoint` on the lines you want to stop at.
+/// 3. `start` a scenario. The call returns once the program stops (or after a timeout).
+/// 4. `stack_trace`, `variables` and `evaluate` to inspect the stopped program.
+/// 5. `step_over`, `step_in`, `step_out` or `continue` to move on, and `stop` when done.
+///
+/// Sessions started by the user from the debug panel can be inspected too; use `list_sessions` to find them.
+/// When `session_id` is omitted, the most recently started session is used. When `thread_id` is omitted, the first stopped thread is used.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DebuggerToolInput {
+    /// The debugger operation to perform.
+    pub action: DebuggerAction,
+
+    /// For `start`: the label of the debug scenario to launch, as returned by `list_scenarios`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+
+    /// The debug session to operate on. Defaults to the most recently started session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+
+    /// The thread to operate on. Defaults to the first stopped thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+
+    /// For `set_breakpoint` and `remove_breakpoint`: the relative path of the file (e.g. "src/main.rs").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// For `set_breakpoint` and `remove_breakpoint`: the 1-based line number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+
+    /// For `set_breakpoint`: an optional condition expression; the program only stops when it evaluates to true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// For `variables` and `evaluate`: the stack frame to use, as returned by `stack_trace`. Defaults to the top frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<u64>,
+
+    /// For `evaluate`: the expression to evaluate in the debuggee.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebuggerAction {
+    /// List the debug scenarios that can be started.
+    ListScenarios,
+    /// List running debug sessions and their threads.
+    ListSessions,
+    /// Start the debug scenario named by `scenario`.
+    Start,
+    /// Add a breakpoint at `path`:`line`, optionally with a `condition`.
+    SetBreakpoint,
+    /// Remove the breakpoint at `path`:`line`.
+    RemoveBreakpoint,
+    /// Resume the thread until the next breakpoint.
+    Continue,
+    /// Pause the running thread.
+    Pause,
+    /// Step over the current line.
+    StepOver,
+    /// Step into the call on the current line.
+    StepIn,
+    /// Step out of the current function.
+    StepOut,
+    /// Show the call stack of the stopped thread.
+    StackTrace,
+    /// Show the local variables of a stack frame.
+    Variables,
+    /// Evaluate `expression` in a stack frame.
+    Evaluate,
+    /// Stop the debug session.
+    Stop,
+}
+
+pub struct DebuggerTool {
+    project: Entity<Project>,
+}
+
+impl DebuggerTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for DebuggerTool {
+    type Input = DebuggerToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "debugger";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    /// Starting a session runs arbitrary programs, just like the terminal.
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Debugger".into();
+        };
+        match input.action {
+            DebuggerAction::ListScenarios => "List debug scenarios".into(),
+            DebuggerAction::ListSessions => "List debug sessions".into(),
+            DebuggerAction::Start => match &input.scenario {
+                Some(scenario) => format!("Debug {}", MarkdownInlineCode(scenario)).into(),
+                None => "Start debugging".into(),
+            },
+            DebuggerAction::SetBreakpoint | DebuggerAction::RemoveBreakpoint => {
+                let verb = if input.action == DebuggerAction::SetBreakpoint {
+                    "Set"
+                } else {
+                    "Remove"
+                };
+                match (&input.path, input.line) {
+                    (Some(path), Some(line)) => format!(
+                        "{verb} breakpoint at {}",
+                        MarkdownInlineCode(&format!("{path}:{line}"))
+                    )
+                    .into(),
+                    _ => format!("{verb} breakpoint").into(),
+                }
+            }
+            DebuggerAction::Continue => "Continue".into(),
+            DebuggerAction::Pause => "Pause".into(),
+            DebuggerAction::StepOver => "Step over".into(),
+            DebuggerAction::StepIn => "Step in".into(),
+            DebuggerAction::StepOut => "Step out".into(),
+            DebuggerAction::StackTrace => "Read stack trace".into(),
+            DebuggerAction::Variables => "Read variables".into(),
+            DebuggerAction::Evaluate => match &input.expression {
+                Some(expression) => format!("Evaluate {}", MarkdownInlineCode(expression)).into(),
+                None => "Evaluate expression".into(),
+            },
+            DebuggerAction::Stop => "Stop debugging".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            match input.action {
+                DebuggerAction::ListScenarios => list_scenarios(&project, cx),
+                DebuggerAction::ListSessions => Ok(list_sessions(&project, cx)),
+                DebuggerAction::Start => {
+                    let label = input
+                        .scenario
+                        .ok_or("`scenario` is required to start a debug session")?;
+                    let authorize = cx.update(|cx| {
+                        let context = ToolPermissionContext::new(Self::NAME, vec![label.clone()]);
+                        event_stream.authorize(
+                            format!("Start debugging {}", MarkdownInlineCode(&label)),
+                            context,
+                            cx,
+                        )
+                    });
+                    authorize.await.map_err(|e| e.to_string())?;
+                    start_session(&project, &label, cx).await
+                }
+                DebuggerAction::SetBreakpoint | DebuggerAction::RemoveBreakpoint => {
+                    let path = input.path.ok_or("`path` is required for breakpoints")?;
+                    let line = input.line.ok_or("`line` is required for breakpoints")?;
+                    let set = input.action == DebuggerAction::SetBreakpoint;
+                    edit_breakpoint(&project, &path, line, set, input.condition, cx).await
+                }
+                DebuggerAction::Continue
+                | DebuggerAction::Pause
+                | DebuggerAction::StepOver
+                | DebuggerAction::StepIn
+                | DebuggerAction::StepOut => {
+                    let session = find_session(&project, input.session_id, cx)?;
+                    let thread_id = resolve_thread(&session, input.thread_id, cx).await?;
+                    run_execution_command(&session, thread_id, input.action, cx).await
+                }
+                DebuggerAction::StackTrace => {
+                    let session = find_session(&project, input.session_id, cx)?;
+                    let thread_id = resolve_thread(&session, input.thread_id, cx).await?;
+                    stack_trace(&session, thread_id, cx).await
+                }
+                DebuggerAction::Variables => {
+                    let session = find_session(&project, input.session_id, cx)?;
+                    let thread_id = resolve_thread(&session, input.thread_id, cx).await?;
+                    let frame_id = match input.frame_id {
+                        Some(frame_id) => frame_id,
+                        None => top_frame_id(&session, thread_id, cx).await?,
+                    };
+                    variables(&session, frame_id, cx).await
+                }
+                DebuggerAction::Evaluate => {
+                    let expression = input
+                        .expression
+                        .ok_or("`expression` is required for `evaluate`")?;
+                    let session = find_session(&project, input.session_id, cx)?;
+                    let authorize = cx.update(|cx| {
+                        let context =
+                            ToolPermissionContext::new(Self::NAME, vec![expression.clone()]);
+                        event_stream.authorize(
+                            format!("Evaluate {}", MarkdownInlineCode(&expression)),
+                            context,
+                            cx,
+                        )
+                    });
+                    authorize.await.map_err(|e| e.to_string())?;
+
+                    let frame_id = match input.frame_id {
+                        Some(frame_id) => Some(frame_id),
+                        None => match resolve_thread(&session, input.thread_id, cx).await {
+                            Ok(thread_id) => top_frame_id(&session, thread_id, cx).await.ok(),
+                            Err(_) => None,
+                        },
+                    };
+                    let response = session
+                        .update(cx, |session, cx| {
+                            session.evaluate_with_response(expression.clone(), frame_id, cx)
+                        })
+                        .await
+                        .map_err(|e| format!("Evaluating `{expression}` failed: {e}"))?;
+                    let mut output = format!("`{expression}` = {}", truncate(&response.result));
+                    if let Some(type_) = response.type_.filter(|type_| !type_.is_empty()) {
+                        write!(output, " ({type_})").ok();
+                    }
+                    Ok(output)
+                }
+                DebuggerAction::Stop => {
+                    let session = find_session(&project, input.session_id, cx)?;
+                    let session_id = session.read_with(cx, |session, _| session.session_id());
+                    session.update(cx, |session, cx| session.shutdown(cx)).await;
+                    Ok(format!("Stopped debug session {}.", session_id.0))
+                }
+            }
+        })
+    }
+}
+
+/// Collects session events matching a predicate from the moment it is created,
+/// so that an event emitted in response to a request isn't missed.
+struct SessionEventWaiter {
+    events: mpsc::UnboundedReceiver<()>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl SessionEventWaiter {
+    fn new(
+        session: &Entity<Session>,
+        matches: impl Fn(&SessionEvent) -> bool + 'static,
+        cx: &mut AsyncApp,
+    ) -> Self {
+        let (tx, events) = mpsc::unbounded();
+        let shutdown_tx = tx.clone();
+        let session_subscription = cx.subscribe(session, move |_, event: &SessionEvent, _| {
+            if matches(event) {
+                tx.unbounded_send(()).ok();
+            }
+        });
+        // A session that shuts down won't emit anything else, so stop waiting.
+        let state_subscription = cx.subscribe(session, move |_, event: &SessionStateEvent, _| {
+            if *event == SessionStateEvent::Shutdown {
+                shutdown_tx.unbounded_send(()).ok();
+            }
+        });
+        Self {
+            events,
+            _subscriptions: [session_subscription, state_subscription],
+        }
+    }
+
+    /// Returns `true` if a matching event arrived before the timeout elapsed.
+    async fn wait(mut self, timeout: Duration, cx: &AsyncApp) -> bool {
+        let timer = cx.background_executor().timer(timeout);
+        futures::select_biased! {
+            event = self.events.next().fuse() => event.is_some(),
+            _ = timer.fuse() => false,
+        }
+    }
+}
+
+fn list_scenarios(project: &Entity<Project>, cx: &mut AsyncApp) -> Result<String, String> {
+    let scenarios = project.read_with(cx, |project, cx| {
+        let inventory = project.task_store().read(cx).task_inventory().cloned()?;
+        let inventory = inventory.read(cx);
+        let mut scenarios = Vec::new();
+        for worktree in project.visible_worktrees(cx) {
+            let worktree_id = worktree.read(cx).id();
+            scenarios.extend(
+                inventory
+                    .debug_scenarios_from_settings(Some(worktree_id))
+                    .into_iter()
+                    .map(|(_, scenario)| scenario),
+            );
+        }
+        scenarios.extend(
+            inventory
+                .debug_scenarios_from_settings(None)
+                .into_iter()
+                .map(|(_, scenario)| scenario),
+        );
+        Some(scenarios)
+    });
+    let Some(mut scenarios) = scenarios else {
+        return Err("Debugging is not available in this project".into());
+    };
+    // Every worktree's scenarios include the global ones.
+    let mut labels = HashSet::default();
+    scenarios.retain(|scenario| labels.insert(scenario.label.clone()));
+
+    if scenarios.is_empty() {
+        return Ok(
+            "No debug scenarios are configured. Ask the user to add one to `.zed/debug.json`."
+                .into(),
+        );
+    }
+
+    let mut output = String::from("Debug scenarios:\n");
+    for scenario in scenarios {
+        write!(
+            output,
+            "\n- `{}` (adapter: {})",
+            scenario.label, scenario.adapter
+        )
+        .ok();
+        if scenario.build.is_some() {
+            output.push_str(" — has a build step, start it from the debug panel");
+        }
+    }
+    Ok(output)
+}
+
+fn list_sessions(project: &Entity<Project>, cx: &mut AsyncApp) -> String {
+    let sessions = project.read_with(cx, |project, cx| {
+        project
+            .dap_store()
+            .read(cx)
+            .sessions()
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    let mut output = String::new();
+    for session in sessions {
+        session.update(cx, |session, cx| {
+            if session.is_terminated() {
+                return;
+            }
+            let label = session
+                .label()
+                .unwrap_or_else(|| SharedString::from(session.adapter().0.to_string()));
+            write!(
+                output,
+                "\n## Session {} — {label}\n",
+                session.session_id().0
+            )
+            .ok();
+            if !session.is_started() {
+                output.push_str("Starting…\n");
+                return;
+            }
+            for (thread, status) in session.threads(cx) {
+                writeln!(
+                    output,
+                    "- thread {} `{}`: {}",
+                    thread.id,
+                    thread.name,
+                    status.label()
+                )
+                .ok();
+            }
+        });
+    }
+
+    if output.is_empty() {
+        "No debug sessions are running.".into()
+    } else {
+        format!("Debug sessions:\n{output}")
+    }
+}
+
+async fn start_session(
+    project: &Entity<Project>,
+    label: &str,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let (scenario, worktree) = project.read_with(cx, |project, cx| {
+        let inventory = project
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+            .ok_or("Debugging is not available in this project")?;
+        let inventory = inventory.read(cx);
+        for worktree in project.visible_worktrees(cx) {
+            let worktree_id = worktree.read(cx).id();
+            if let Some((_, scenario)) = inventory
+                .debug_scenarios_from_settings(Some(worktree_id))
+                .into_iter()
+                .find(|(_, scenario)| scenario.label.as_ref() == label)
+            {
+                return Ok((scenario, worktree));
+            }
+        }
+        let worktree = project
+            .visible_worktrees(cx)
+            .next()
+            .ok_or("The project has no worktree to debug in")?;
+        inventory
+            .debug_scenarios_from_settings(None)
+            .into_iter()
+            .find(|(_, scenario)| scenario.label.as_ref() == label)
+            .map(|(_, scenario)| (scenario, worktree))
+            .ok_or_else(|| format!("No debug scenario named `{label}`. Use `list_scenarios` to see the available ones."))
+    })?;
+
+    if scenario.build.is_some() {
+        return Err(format!(
+            "Debug scenario `{label}` has a build step, which this tool can't run. \
+             Ask the user to start it from the debug panel, then use `list_sessions` to inspect it."
+        ));
+    }
+
+    let adapter = cx
+        .update(|cx| DapRegistry::global(cx).adapter(&scenario.adapter))
+        .ok_or_else(|| format!("`{}` is not a known debug adapter", scenario.adapter))?;
+
+    let worktree_root = worktree.read_with(cx, |worktree, _| worktree.abs_path());
+    let task_context = worktree_task_context(&worktree_root);
+    let mut config = scenario.config.clone();
+    substitute_variables_in_config(&mut config, &task_context);
+    adapter
+        .request_kind(&config)
+        .await
+        .map_err(|e| format!("Debug scenario `{label}` is invalid: {e}"))?;
+
+    let definition = DebugTaskDefinition {
+        label: scenario.label.clone(),
+        adapter: DebugAdapterName(scenario.adapter.clone()),
+        config,
+        tcp_connection: scenario.tcp_connection.clone(),
+    };
+    let quirks = SessionQuirks {
+        compact: adapter.compact_child_session(),
+        prefer_thread_name: adapter.prefer_thread_name(),
+    };
+
+    let dap_store = project.read_with(cx, |project, _| project.dap_store());
+    let session = dap_store.update(cx, |dap_store, cx| {
+        dap_store.new_session(
+            Some(scenario.label.clone()),
+            DebugAdapterName(scenario.adapter.clone()),
+            task_context.into(),
+            None,
+            quirks,
+            cx,
+        )
+    });
+    let session_id = session.read_with(cx, |session, _| session.session_id());
+
+    let stopped = SessionEventWaiter::new(
+        &session,
+        |event| matches!(event, SessionEvent::Stopped(_)),
+        cx,
+    );
+    let boot = dap_store.update(cx, |dap_store, cx| {
+        dap_store.boot_session(session.clone(), definition, worktree, cx)
+    });
+    if let Err(error) = boot.await {
+        session.update(cx, |session, cx| session.shutdown(cx)).await;
+        return Err(format!("Failed to start debugging `{label}`: {error:#}"));
+    }
+
+    let mut output = format!("Started debug session {} for `{label}`.\n", session_id.0);
+    if stopped.wait(STOP_TIMEOUT, cx).await {
+        output.push_str(&describe_stop(&session, None, cx).await);
+    } else {
+        output.push_str(&describe_running(&session, cx));
+    }
+    Ok(output)
+}
+
+async fn edit_breakpoint(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    set: bool,
+    condition: Option<String>,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let open_buffer = project.update(cx, |project, cx| {
+        let project_path = project
+            .find_project_path(path, cx)
+            .ok_or_else(|| format!("Could not find path '{path}' in project"))?;
+        Ok::<_, String>(project.open_buffer(project_path, cx))
+    })?;
+    let buffer = open_buffer
+        .await
+        .map_err(|e| format!("Failed to open '{path}': {e}"))?;
+    let breakpoint_store = project.read_with(cx, |project, cx| project.breakpoint_store());
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = line.saturating_sub(1);
+        if row > snapshot.max_point().row {
+            return Err(format!(
+                "Line {line} is beyond the end of '{path}' (file has {} lines)",
+                snapshot.max_point().row + 1
+            ));
+        }
+        let abs_path = BreakpointStore::abs_path_from_buffer(&buffer, cx)
+            .ok_or_else(|| format!("'{path}' is not a file on disk"))?;
+        let existing = breakpoint_store.breakpoint_at_row(&abs_path, row, cx);
+
+        match (set, existing) {
+            (true, Some((buffer, existing))) => {
+                if let Some(condition) = condition {
+                    breakpoint_store.toggle_breakpoint(
+                        buffer,
+                        existing,
+                        BreakpointEditAction::EditCondition(condition.into()),
+                        cx,
+                    );
+                    Ok(format!(
+                        "Updated the condition of the breakpoint at {path}:{line}."
+                    ))
+                } else {
+                    Ok(format!("There already is a breakpoint at {path}:{line}."))
+                }
+            }
+            (true, None) => {
+                let breakpoint = BreakpointWithPosition {
+                    position: snapshot.anchor_after(Point::new(row, 0)),
+                    bp: Breakpoint {
+                        condition: condition.map(Into::into),
+                        ..Breakpoint::new_standard()
+                    },
+                };
+                breakpoint_store.toggle_breakpoint(
+                    buffer.clone(),
+                    breakpoint,
+                    BreakpointEditAction::Toggle,
+                    cx,
+                );
+                Ok(format!("Set a breakpoint at {path}:{line}."))
+            }
+            (false, Some((buffer, existing))) => {
+                breakpoint_store.toggle_breakpoint(
+                    buffer,
+                    existing,
+                    BreakpointEditAction::Toggle,
+                    cx,
+                );
+                Ok(format!("Removed the breakpoint at {path}:{line}."))
+            }
+            (false, None) => Ok(format!("There is no breakpoint at {path}:{line}.")),
+        }
+    })
+}
+
+fn find_session(
+    project: &Entity<Project>,
+    session_id: Option<u32>,
+    cx: &mut AsyncApp,
+) -> Result<Entity<Session>, String> {
+    project.read_with(cx, |project, cx| {
+        let dap_store = project.dap_store();
+        let dap_store = dap_store.read(cx);
+        let session = match session_id {
+            Some(session_id) => dap_store
+                .session_by_id(dap::client::SessionId(session_id))
+                .ok_or_else(|| format!("No debug session with id {session_id}"))?,
+            None => dap_store
+                .sessions()
+                .filter(|session| !session.read(cx).is_terminated())
+                .last()
+                .cloned()
+                .ok_or("No debug session is running. Use `start` to start one.")?,
+        };
+        if session.read(cx).is_terminated() {
+            return Err(format!(
+                "Debug session {} has ended",
+                session.read(cx).session_id().0
+            ));
+        }
+        Ok(session)
+    })
+}
+
+async fn resolve_thread(
+    session: &Entity<Session>,
+    thread_id: Option<i64>,
+    cx: &mut AsyncApp,
+) -> Result<ThreadId, String> {
+    if let Some(thread_id) = thread_id {
+        return Ok(ThreadId(thread_id));
+    }
+
+    let threads_loaded =
+        SessionEventWaiter::new(session, |event| matches!(event, SessionEvent::Threads), cx);
+    let mut threads = session.update(cx, |session, cx| session.threads(cx));
+    if threads.is_empty() && threads_loaded.wait(FETCH_TIMEOUT, cx).await {
+        threads = session.update(cx, |session, cx| session.threads(cx));
+    }
+
+    threads
+        .iter()
+        .find(|(_, status)| *status == ThreadStatus::Stopped)
+        .or_else(|| threads.first())
+        .map(|(thread, _)| ThreadId(thread.id))
+        .ok_or_else(|| "The debuggee has no threads".to_string())
+}
+
+async fn run_execution_command(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    action: DebuggerAction,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let stopped = SessionEventWaiter::new(
+        session,
+        |event| matches!(event, SessionEvent::Stopped(_)),
+        cx,
+    );
+    session.update(cx, |session, cx| {
+        let granularity = SteppingGranularity::Line;
+        match action {
+            DebuggerAction::Continue => session.continue_thread(thread_id, cx),
+            DebuggerAction::Pause => session.pause_thread(thread_id, cx),
+            DebuggerAction::StepOver => session.step_over(thread_id, granularity, cx),
+            DebuggerAction::StepIn => session.step_in(thread_id, granularity, cx),
+            DebuggerAction::StepOut => session.step_out(thread_id, granularity, cx),
+            _ => unreachable!("not an execution command: {action:?}"),
+        }
+    });
+
+    if stopped.wait(STOP_TIMEOUT, cx).await {
+        Ok(describe_stop(session, Some(thread_id), cx).await)
+    } else {
+        Ok(describe_running(session, cx))
+    }
+}
+
+fn describe_running(session: &Entity<Session>, cx: &mut AsyncApp) -> String {
+    session.read_with(cx, |session, _| {
+        if session.is_terminated() {
+            "The debug session has ended.".to_string()
+        } else {
+            format!(
+                "The program is still running after {}s. Use `pause`, or set a breakpoint and `continue`.",
+                STOP_TIMEOUT.as_secs()
+            )
+        }
+    })
+}
+
+async fn describe_stop(
+    session: &Entity<Session>,
+    thread_id: Option<ThreadId>,
+    cx: &mut AsyncApp,
+) -> String {
+    if session.read_with(cx, |session, _| session.is_terminated()) {
+        return "The debug session has ended.".into();
+    }
+    let thread_id = match thread_id {
+        Some(thread_id) => thread_id,
+        None => match resolve_thread(session, None, cx).await {
+            Ok(thread_id) => thread_id,
+            Err(error) => return error,
+        },
+    };
+    match load_stack_frames(session, thread_id, cx).await {
+        Ok(frames) => match frames.first() {
+            Some(frame) => format!(
+                "Thread {} stopped in `{}` at {}.",
+                thread_id.0,
+                frame.name,
+                frame_location(frame)
+            ),
+            None => format!("Thread {} stopped.", thread_id.0),
+        },
+        Err(error) => format!(
+            "Thread {} stopped, but its stack trace is unavailable: {error}",
+            thread_id.0
+        ),
+    }
+}
+
+async fn load_stack_frames(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<dap::StackFrame>, String> {
+    let loaded = SessionEventWaiter::new(
+        session,
+        |event| matches!(event, SessionEvent::StackTrace),
+        cx,
+    );
+    let read = |session: &Entity<Session>, cx: &mut AsyncApp| {
+        session.update(cx, |session, cx| {
+            if session.thread_status(thread_id) != ThreadStatus::Stopped {
+                return Err(format!("Thread {} is not stopped", thread_id.0));
+            }
+            session
+                .stack_frames(thread_id, cx)
+                .map(|frames| {
+                    frames
+                        .into_iter()
+                        .map(|frame| frame.dap)
+                        .collect::<Vec<_>>()
+                })
+                .map_err(|e| e.to_string())
+        })
+    };
+    let frames = read(session, cx)?;
+    if !frames.is_empty() || !loaded.wait(FETCH_TIMEOUT, cx).await {
+        return Ok(frames);
+    }
+    read(session, cx)
+}
+
+async fn top_frame_id(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    cx: &mut AsyncApp,
+) -> Result<u64, String> {
+    load_stack_frames(session, thread_id, cx)
+        .await?
+        .first()
+        .map(|frame| frame.id)
+        .ok_or_else(|| format!("Thread {} has no stack frames", thread_id.0))
+}
+
+async fn stack_trace(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let frames = load_stack_frames(session, thread_id, cx).await?;
+    if frames.is_empty() {
+        return Ok(format!("Thread {} has no stack frames.", thread_id.0));
+    }
+
+    let mut output = format!("Stack trace of thread {}:\n", thread_id.0);
+    for (index, frame) in frames.iter().take(MAX_STACK_FRAMES).enumerate() {
+        write!(
+            output,
+            "\n#{index} `{}` at {} (frame_id: {})",
+            frame.name,
+            frame_location(frame),
+            frame.id
+        )
+        .ok();
+    }
+    if frames.len() > MAX_STACK_FRAMES {
+        write!(
+            output,
+            "\n… {} more frames",
+            frames.len() - MAX_STACK_FRAMES
+        )
+        .ok();
+    }
+    Ok(output)
+}
+
+async fn variables(
+    session: &Entity<Session>,
+    frame_id: u64,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let scopes_loaded = SessionEventWaiter::new(
+        session,
+        |event| matches!(event, SessionEvent::Variables),
+        cx,
+    );
+    let mut scopes = session.update(cx, |session, cx| session.scopes(frame_id, cx).to_vec());
+    if scopes.is_empty() && scopes_loaded.wait(FETCH_TIMEOUT, cx).await {
+        scopes = session.update(cx, |session, cx| session.scopes(frame_id, cx).to_vec());
+    }
+    if scopes.is_empty() {
+        return Ok(format!("Stack frame {frame_id} has no scopes."));
+    }
+
+    let mut output = String::new();
+    for scope in scopes {
+        write!(output, "\n## {}\n", scope.name).ok();
+        if scope.expensive {
+            output.push_str("(not loaded: the debug adapter marks this scope as expensive)\n");
+            continue;
+        }
+
+        let variables_loaded = SessionEventWaiter::new(
+            session,
+            |event| matches!(event, SessionEvent::Variables),
+            cx,
+        );
+        let reference = scope.variables_reference;
+        let mut variables = session.update(cx, |session, cx| session.variables(reference, cx));
+        if variables.is_empty() && variables_loaded.wait(FETCH_TIMEOUT, cx).await {
+            variables = session.update(cx, |session, cx| session.variables(reference, cx));
+        }
+
+        if variables.is_empty() {
+            output.push_str("(empty)\n");
+        }
+        for variable in variables.iter().take(MAX_VARIABLES_PER_SCOPE) {
+            write!(output, "- {}", variable.name).ok();
+            if let Some(type_) = variable.type_.as_ref().filter(|type_| !type_.is_empty()) {
+                write!(output, ": {type_}").ok();
+            }
+            writeln!(output, " = {}", truncate(&variable.value)).ok();
+        }
+        if variables.len() > MAX_VARIABLES_PER_SCOPE {
+            writeln!(
+                output,
+                "… {} more variables",
+                variables.len() - MAX_VARIABLES_PER_SCOPE
+            )
+            .ok();
+        }
+    }
+    Ok(format!("Variables of stack frame {frame_id}:\n{output}"))
+}
+
+fn frame_location(frame: &dap::StackFrame) -> String {
+    let path = frame
+        .source
+        .as_ref()
+        .and_then(|source| source.path.clone().or_else(|| source.name.clone()))
+        .unwrap_or_else(|| "<unknown source>".to_string());
+    format!("{path}:{}", frame.line)
+}
+
+fn truncate(value: &str) -> String {
+    if value.chars().count() <= MAX_VALUE_LEN {
+        value.to_string()
+    } else {
+        let truncated = value.chars().take(MAX_VALUE_LEN).collect::<String>();
+        format!("{truncated}… (truncated)")
+    }
+}
+
+fn worktree_task_context(worktree_root: &Path) -> TaskContext {
+    let mut task_variables = TaskVariables::default();
+    task_variables.insert(
+        VariableName::WorktreeRoot,
+        worktree_root.to_string_lossy().into_owned(),
+    );
+    TaskContext {
+        cwd: Some(worktree_root.to_path_buf()),
+        task_variables,
+        project_env: Default::default(),
+    }
+}
+
+fn substitute_variables_in_config(config: &mut serde_json::Value, context: &TaskContext) {
+    match config {
+        serde_json::Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| substitute_variables_in_config(value, context)),
+        serde_json::Value::Array(array) => array
+            .iter_mut()
+            .for_each(|value| substitute_variables_in_config(value, context)),
+        serde_json::Value::String(string) => {
+            if let Some(substituted) = substitute_variables_in_str(string, context) {
+                *string = substituted;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_variables_in_config() {
+        let context = worktree_task_context(Path::new("/root/project"));
+        let mut config = serde_json::json!({
+            "request": "launch",
+            "program": "$ZED_WORKTREE_ROOT/target/debug/app",
+            "args": ["--config", "${ZED_WORKTREE_ROOT}/app.toml"],
+            "stopOnEntry": true,
+        });
+        substitute_variables_in_config(&mut config, &context);
+        assert_eq!(
+            config,
+            serde_json::json!({
+                "request": "launch",
+                "program": "/root/project/target/debug/app",
+                "args": ["--config", "/root/project/app.toml"],
+                "stopOnEntry": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short"), "short");
+        let long = "x".repeat(MAX_VALUE_LEN + 10);
+        let truncated = truncate(&long);
+        assert!(truncated.ends_with("… (truncated)"));
+        assert_eq!(
+            truncated.chars().filter(|c| *c == 'x').count(),
+            MAX_VALUE_LEN
+        );
+    }
+}
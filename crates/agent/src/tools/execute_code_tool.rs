@@ -0,0 +1,186 @@
+use crate::{AgentTool, CodeExecutionRequest, ThreadEnvironment, ToolCallEventStream, ToolInput};
+use agent_client_protocol::schema::v1 as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use util::markdown::MarkdownInlineCode;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Runs code in the user's REPL (Jupyter) kernel for a file, and returns its outputs.
+///
+/// The code runs in the same kernel session the user runs the file's cells in, so variables, imports and loaded data from earlier executions are available, and the code's effects persist for the user. A session for the file's language is started when the file has none.
+///
+/// Outputs are returned as Markdown: text and streams in code blocks, tables as Markdown tables, errors with their tracebacks. Images are described by their size rather than returned.
+///
+/// Use this to inspect data, try out changes or check results in a data-science workflow. Do not use it to run shell commands; use the terminal instead.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExecuteCodeToolInput {
+    /// The file whose kernel runs the code, e.g. a notebook-style script or a Jupytext file.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The code to run, in the file's language.
+    pub code: String,
+    /// Stop waiting for outputs after this many milliseconds. Defaults to 60 seconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+pub struct ExecuteCodeTool {
+    project: Entity<Project>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl ExecuteCodeTool {
+    pub fn new(project: Entity<Project>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            project,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for ExecuteCodeTool {
+    type Input = ExecuteCodeToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "execute_code";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => {
+                format!("Run code in kernel for {}", MarkdownInlineCode(&input.path)).into()
+            }
+            Err(_) => "Run code in kernel".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        cx.spawn(async move |cx| {
+            let input = input.recv().await.map_err(|e| e.to_string())?;
+            if input.code.trim().is_empty() {
+                return Err("No code to run.".to_string());
+            }
+
+            let (path, authorize) = cx.update(|cx| {
+                let path = self
+                    .project
+                    .read(cx)
+                    .find_project_path(&input.path, cx)
+                    .ok_or_else(|| format!("Path {} not found in project", input.path))?;
+                let context =
+                    crate::ToolPermissionContext::new(Self::NAME, vec![input.code.clone()]);
+                let authorize = event_stream.authorize(
+                    format!("Run code in kernel for {}", MarkdownInlineCode(&input.path)),
+                    context,
+                    cx,
+                );
+                Ok::<_, String>((path, authorize))
+            })?;
+            authorize.await.map_err(|e| e.to_string())?;
+
+            let timeout = input
+                .timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_TIMEOUT);
+            let started_at = Instant::now();
+            let execution = self.environment.execute_code(
+                CodeExecutionRequest {
+                    path,
+                    code: input.code,
+                },
+                cx,
+            );
+
+            let result = futures::select! {
+                result = execution.fuse() => result.map_err(|e| format!("{e:#}"))?,
+                _ = cx.background_executor().timer(timeout).fuse() => {
+                    return Err(format!(
+                        "Timed out after {:.1}s waiting for the kernel. It may still be busy; \
+                         the user can interrupt it from the REPL sessions view.",
+                        timeout.as_secs_f64()
+                    ));
+                }
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Stopped waiting for the kernel at the user's request.".to_string());
+                }
+            };
+
+            let status = if result.errored {
+                "raised an error"
+            } else {
+                "finished"
+            };
+            let mut output = format!(
+                "Execution {status} after {:.1}s.",
+                started_at.elapsed().as_secs_f64()
+            );
+            if result.output.trim().is_empty() {
+                output.push_str(" It produced no output.");
+            } else {
+                output.push_str("\n\n");
+                output.push_str(&truncate_output(&result.output, MAX_OUTPUT_BYTES));
+            }
+            Ok(output)
+        })
+    }
+}
+
+/// Keeps the start of `output`, cut at a line boundary, when it's longer than `max_bytes`.
+fn truncate_output(output: &str, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output.to_string();
+    }
+    let end = output.floor_char_boundary(max_bytes);
+    let end = output[..end].rfind('\n').unwrap_or(end);
+    let mut truncated = output[..end].to_string();
+    if truncated.matches("```").count() % 2 == 1 {
+        truncated.push_str("\n```");
+    }
+    truncated.push_str(&format!(
+        "\n\n(output truncated, {} more bytes)",
+        output.len() - end
+    ));
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_output() {
+        assert_eq!(truncate_output("short", 100), "short");
+        assert_eq!(
+            truncate_output("```\nline one\nline two\n```", 16),
+            "```\nline one\n```\n\n(output truncated, 13 more bytes)"
+        );
+    }
+}
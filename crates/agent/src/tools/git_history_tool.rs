@@ -0,0 +1,486 @@
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use git::Oid;
+use git::repository::{CommitDetails, CommitFileStatus, LogSource, RepoPath, SearchCommitArgs};
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use project::Project;
+use project::git_store::Repository;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: usize = 100;
+const DEFAULT_MAX_DIFF_BYTES: usize = 16 * 1024;
+const MAX_DIFF_BYTES: usize = 64 * 1024;
+
+/// Reads the git history of the project. This tool never modifies the repository.
+///
+/// Use it to find out who changed some code and why, e.g. when tracking down a regression:
+/// - `log` lists recent commits, optionally only those touching `path` and/or whose message contains `query`.
+/// - `blame` shows which commit last changed each line in `start_line..=end_line` of `path`, along with the full message of every commit involved.
+/// - `show` shows the message of `commit` and its diff. The diff can be limited to a single `path`, and is truncated after `max_diff_bytes`.
+///
+/// <example>
+/// To see why a function was changed:
+/// { "action": "blame", "path": "project/src/main.rs", "start_line": 40, "end_line": 55 }
+///
+/// Then inspect one of the commits it reported:
+/// { "action": "show", "commit": "1a2b3c4", "path": "project/src/main.rs" }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitHistoryToolInput {
+    /// The kind of history to read.
+    pub action: GitHistoryAction,
+    /// A file or directory in the project.
+    ///
+    /// Required for `blame`. Filters commits for `log` and diffs for `show`.
+    /// When omitted, the active repository of the project is used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// For `log`: only include commits whose message contains this text (case-insensitive).
+    #[serde(default)]
+    pub query: Option<String>,
+    /// For `log`: the maximum number of commits to return. Defaults to 20.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// For `blame`: the first line to blame (1-based). Defaults to the first line of the file.
+    #[serde(default)]
+    pub start_line: Option<u32>,
+    /// For `blame`: the last line to blame (1-based, inclusive). Defaults to the last line of the file.
+    #[serde(default)]
+    pub end_line: Option<u32>,
+    /// For `show`: the commit SHA (full or abbreviated) or any other revision understood by git, such as `HEAD~2`.
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// For `show`: the maximum number of bytes of diff to return. Defaults to 16384.
+    #[serde(default)]
+    pub max_diff_bytes: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHistoryAction {
+    Log,
+    Blame,
+    Show,
+}
+
+pub struct GitHistoryTool {
+    project: Entity<Project>,
+}
+
+impl GitHistoryTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GitHistoryTool {
+    type Input = GitHistoryToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "git_history";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Read git history".into();
+        };
+        match (input.action, input.path, input.commit) {
+            (GitHistoryAction::Show, _, Some(commit)) => {
+                format!("Show commit {}", MarkdownInlineCode(&commit)).into()
+            }
+            (GitHistoryAction::Blame, Some(path), _) => {
+                format!("Blame {}", MarkdownInlineCode(&path)).into()
+            }
+            (GitHistoryAction::Log, Some(path), _) => {
+                format!("Git log for {}", MarkdownInlineCode(&path)).into()
+            }
+            _ => "Read git history".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let result = match input.action {
+                GitHistoryAction::Log => log(&project, &input, cx).await,
+                GitHistoryAction::Blame => blame(&project, &input, cx).await,
+                GitHistoryAction::Show => show(&project, &input, cx).await,
+            };
+            result.map_err(|e| format!("{e:#}"))
+        })
+    }
+}
+
+/// Finds the repository containing `path`, or the active repository if no path is given.
+fn resolve_repository(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    cx: &mut AsyncApp,
+) -> Result<(Entity<Repository>, Option<RepoPath>)> {
+    project.read_with(cx, |project, cx| {
+        let git_store = project.git_store().read(cx);
+        let Some(path) = path.filter(|path| !path.is_empty()) else {
+            let repository = git_store
+                .active_repository()
+                .context("The project is not in a git repository")?;
+            return Ok((repository, None));
+        };
+        let project_path = project
+            .find_project_path(path, cx)
+            .with_context(|| format!("Could not find path {path} in project"))?;
+        let (repository, repo_path) = git_store
+            .repository_and_path_for_project_path(&project_path, cx)
+            .with_context(|| format!("{path} is not inside a git repository"))?;
+        Ok((repository, Some(repo_path).filter(|path| !path.is_empty())))
+    })
+}
+
+async fn log(
+    project: &Entity<Project>,
+    input: &GitHistoryToolInput,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let (repository, repo_path) = resolve_repository(project, input.path.as_deref(), cx)?;
+    let limit = input
+        .limit
+        .unwrap_or(DEFAULT_LOG_LIMIT)
+        .clamp(1, MAX_LOG_LIMIT);
+    let log_source = match &repo_path {
+        Some(repo_path) => LogSource::Path(repo_path.clone()),
+        None => LogSource::Branch("HEAD".into()),
+    };
+    let search_args = SearchCommitArgs {
+        query: input.query.clone().unwrap_or_default().into(),
+        case_sensitive: false,
+    };
+
+    let (request_tx, request_rx) = async_channel::bounded(limit);
+    repository.update(cx, |repository, cx| {
+        repository.search_commits(log_source, search_args, request_tx, cx)
+    });
+
+    let mut shas = Vec::new();
+    while shas.len() < limit {
+        let Ok(sha) = request_rx.recv().await else {
+            break;
+        };
+        shas.push(sha);
+    }
+    // Dropping the receiver stops the underlying `git log`.
+    drop(request_rx);
+
+    if shas.is_empty() {
+        return Ok("No commits found.".to_string());
+    }
+
+    let mut output = String::new();
+    for sha in shas {
+        let details = repository
+            .update(cx, |repository, _| repository.show(sha.to_string()))
+            .await??;
+        write_commit_summary(&mut output, &details);
+    }
+    Ok(output)
+}
+
+async fn blame(
+    project: &Entity<Project>,
+    input: &GitHistoryToolInput,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let path = input
+        .path
+        .as_deref()
+        .filter(|path| !path.is_empty())
+        .context("`path` is required for blame")?;
+    let open_buffer = project.update(cx, |project, cx| {
+        let project_path = project
+            .find_project_path(path, cx)
+            .with_context(|| format!("Could not find path {path} in project"))?;
+        anyhow::Ok(project.open_buffer(project_path, cx))
+    })?;
+    let buffer = open_buffer.await?;
+
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let line_count = snapshot.max_point().row + 1;
+    let (start_row, end_row) = blame_rows(input.start_line, input.end_line, line_count)?;
+
+    let git_store = project.read_with(cx, |project, _| project.git_store().clone());
+    let blame = git_store
+        .update(cx, |git_store, cx| {
+            git_store.blame_buffer(&buffer, None, cx)
+        })
+        .await?
+        .with_context(|| format!("No blame information is available for {path}"))?;
+
+    let mut output = format!(
+        "Blame for {path} lines {}-{}:\n\n",
+        start_row + 1,
+        end_row + 1
+    );
+    let mut shas_in_range = Vec::new();
+    let mut seen_shas = HashSet::default();
+    for row in start_row..=end_row {
+        let entry = blame
+            .entries
+            .iter()
+            .find(|entry| entry.range.contains(&row));
+        let line_text = snapshot.text_for_range(
+            language::Point::new(row, 0)..language::Point::new(row, snapshot.line_len(row)),
+        );
+        let line_text = line_text.collect::<String>();
+        match entry {
+            Some(entry) => {
+                let author = entry.author.as_deref().unwrap_or("unknown");
+                let date = entry.author_time.map(format_timestamp).unwrap_or_default();
+                writeln!(
+                    output,
+                    "{} {author} {date} {:>5}| {line_text}",
+                    entry.sha.display_short(),
+                    row + 1
+                )
+                .ok();
+                if seen_shas.insert(entry.sha) {
+                    shas_in_range.push(entry.sha);
+                }
+            }
+            None => {
+                writeln!(output, "uncommitted {:>5}| {line_text}", row + 1).ok();
+            }
+        }
+    }
+
+    if !shas_in_range.is_empty() {
+        output.push_str("\nCommits:\n");
+        for sha in shas_in_range {
+            write_commit_message(&mut output, sha, &blame.messages);
+        }
+    }
+    Ok(output)
+}
+
+async fn show(
+    project: &Entity<Project>,
+    input: &GitHistoryToolInput,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let commit = input
+        .commit
+        .clone()
+        .filter(|commit| !commit.trim().is_empty())
+        .context("`commit` is required for show")?;
+    // git would parse a revision starting with a dash as an option.
+    anyhow::ensure!(
+        !commit.starts_with('-'),
+        "`commit` must be a revision, not {commit:?}"
+    );
+    let (repository, repo_path) = resolve_repository(project, input.path.as_deref(), cx)?;
+    let max_diff_bytes = input
+        .max_diff_bytes
+        .unwrap_or(DEFAULT_MAX_DIFF_BYTES)
+        .min(MAX_DIFF_BYTES);
+
+    let details = repository
+        .update(cx, |repository, _| repository.show(commit))
+        .await??;
+    let diff = repository
+        .update(cx, |repository, _| {
+            repository.load_commit_diff(details.sha.to_string())
+        })
+        .await??;
+
+    let mut output = String::new();
+    writeln!(output, "commit {}", details.sha).ok();
+    writeln!(
+        output,
+        "Author: {} <{}>",
+        details.author_name, details.author_email
+    )
+    .ok();
+    writeln!(
+        output,
+        "Date: {}\n",
+        format_timestamp(details.commit_timestamp)
+    )
+    .ok();
+    for line in details.message.trim_end().lines() {
+        writeln!(output, "    {line}").ok();
+    }
+    output.push('\n');
+
+    let files = diff
+        .files
+        .iter()
+        .filter(|file| {
+            repo_path
+                .as_ref()
+                .is_none_or(|repo_path| file.path.starts_with(repo_path))
+        })
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        output.push_str("No changes");
+        if let Some(path) = &input.path {
+            write!(output, " to {path}").ok();
+        }
+        output.push_str(".\n");
+        return Ok(output);
+    }
+
+    let mut diff_text = String::new();
+    for file in &files {
+        let path = file.path.as_unix_str();
+        let status = match file.status() {
+            CommitFileStatus::Added => "added",
+            CommitFileStatus::Modified => "modified",
+            CommitFileStatus::Deleted => "deleted",
+        };
+        writeln!(diff_text, "--- {path} ({status})").ok();
+        if file.is_binary {
+            diff_text.push_str("Binary file changed\n");
+            continue;
+        }
+        diff_text.push_str(&language::unified_diff(
+            file.old_text.as_deref().unwrap_or_default(),
+            file.new_text.as_deref().unwrap_or_default(),
+        ));
+    }
+
+    let truncated = truncate_diff(&mut diff_text, max_diff_bytes);
+    output.push_str(&diff_text);
+    if truncated {
+        write!(
+            output,
+            "\n[Diff truncated at {max_diff_bytes} bytes. Pass `path` to see the changes to a single file.]\n"
+        )
+        .ok();
+    }
+    Ok(output)
+}
+
+/// Converts the optional 1-based inclusive line range of a blame request into 0-based rows.
+fn blame_rows(
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+    line_count: u32,
+) -> Result<(u32, u32)> {
+    let start_line = start_line.unwrap_or(1).max(1);
+    let end_line = end_line.unwrap_or(line_count).min(line_count);
+    if start_line > line_count {
+        return Err(anyhow!(
+            "start_line {start_line} is past the end of the file, which has {line_count} lines"
+        ));
+    }
+    if end_line < start_line {
+        return Err(anyhow!(
+            "end_line {end_line} must not be before start_line {start_line}"
+        ));
+    }
+    Ok((start_line - 1, end_line - 1))
+}
+
+/// Truncates `diff` to at most `max_bytes`, cutting at a line boundary when possible.
+fn truncate_diff(diff: &mut String, max_bytes: usize) -> bool {
+    if diff.len() <= max_bytes {
+        return false;
+    }
+    let mut end = diff.floor_char_boundary(max_bytes);
+    if let Some(newline) = diff[..end].rfind('\n') {
+        end = newline + 1;
+    }
+    diff.truncate(end);
+    true
+}
+
+fn write_commit_summary(output: &mut String, details: &CommitDetails) {
+    let subject = details.message.lines().next().unwrap_or_default();
+    let short_sha = details
+        .sha
+        .get(..git::SHORT_SHA_LENGTH)
+        .unwrap_or(&details.sha);
+    writeln!(
+        output,
+        "{short_sha} {} {} {subject}",
+        format_timestamp(details.commit_timestamp),
+        details.author_name,
+    )
+    .ok();
+}
+
+fn write_commit_message(
+    output: &mut String,
+    sha: Oid,
+    messages: &collections::HashMap<Oid, String>,
+) {
+    writeln!(output, "\n{}", sha.display_short()).ok();
+    let message = messages.get(&sha).map(String::as_str).unwrap_or_default();
+    for line in message.trim_end().lines() {
+        writeln!(output, "    {line}").ok();
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blame_rows() {
+        assert_eq!(blame_rows(None, None, 10).unwrap(), (0, 9));
+        assert_eq!(blame_rows(Some(3), Some(5), 10).unwrap(), (2, 4));
+        assert_eq!(blame_rows(Some(0), Some(50), 10).unwrap(), (0, 9));
+        assert!(blame_rows(Some(11), None, 10).is_err());
+        assert!(blame_rows(Some(5), Some(4), 10).is_err());
+    }
+
+    #[test]
+    fn test_truncate_diff() {
+        let mut diff = "@@ -1 +1 @@\n-old\n+new\n".to_string();
+        assert!(!truncate_diff(&mut diff, 100));
+        assert_eq!(diff, "@@ -1 +1 @@\n-old\n+new\n");
+
+        assert!(truncate_diff(&mut diff, 15));
+        assert_eq!(diff, "@@ -1 +1 @@\n");
+
+        let mut diff = "ééééé".to_string();
+        assert!(truncate_diff(&mut diff, 3));
+        assert_eq!(diff, "é");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14");
+    }
+}
@@ -0,0 +1,739 @@
+use crate::{AgentTool, ThreadEnvironment, ToolCallEventStream, ToolInput};
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use futures::FutureExt as _;
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use language::{Buffer, Point, ToOffset as _};
+use project::{Inventory, Location, Project, TaskSourceKind, task_store::TaskStore};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    rc::Rc,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+use task::{
+    ResolvedTask, RunnableTag, SpawnInTerminal, TaskContext, TaskTemplate, TaskVariables,
+    VariableName,
+};
+use util::markdown::MarkdownInlineCode;
+use util::shell::ShellKind;
+
+const OUTPUT_TAIL_LINES: usize = 80;
+const OUTPUT_TAIL_BYTES: usize = 8 * 1024;
+const MAX_FAILURES: usize = 50;
+
+/// Lists and runs the project's tasks, including tests and other runnables detected in source files.
+///
+/// Prefer this tool over `terminal` for building and testing: tasks are resolved the same way as when the user runs them, with the user's environment and toolchains.
+///
+/// - `list` returns the tasks available in the project. When `path` is given, it also includes the language's tasks and the runnables (e.g. individual tests) found in that file; when `line` is given too, only runnables enclosing that line are listed.
+/// - `run` runs the task whose label is `label`, using the same `path` and `line` that listed it. It returns the exit code, the duration, any failures recognized in the output, and the tail of the output.
+///
+/// <example>
+/// To run the test that encloses line 42 of a file:
+/// { "action": "list", "path": "project/src/parser.rs", "line": 42 }
+///
+/// followed by:
+/// { "action": "run", "path": "project/src/parser.rs", "line": 42, "label": "cargo test -p project parser::tests::test_parse" }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunTaskToolInput {
+    /// Whether to list the available tasks or to run one of them.
+    pub action: RunTaskAction,
+    /// A file whose language tasks and runnables should be included.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// A 1-based line in `path`. Narrows runnables to those enclosing the line, and provides the context (e.g. the current symbol) tasks are resolved with.
+    #[serde(default)]
+    pub line: Option<u32>,
+    /// For `run`: the label of the task to run, exactly as returned by `list`.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// For `run`: stop the task if it runs for longer than this many milliseconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTaskAction {
+    List,
+    Run,
+}
+
+pub struct RunTaskTool {
+    project: Entity<Project>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl RunTaskTool {
+    pub fn new(project: Entity<Project>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            project,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for RunTaskTool {
+    type Input = RunTaskToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "run_task";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(RunTaskToolInput {
+                action: RunTaskAction::Run,
+                label: Some(label),
+                ..
+            }) => format!("Run task {}", MarkdownInlineCode(&label)).into(),
+            Ok(RunTaskToolInput {
+                action: RunTaskAction::List,
+                path: Some(path),
+                ..
+            }) => format!("List tasks for {}", MarkdownInlineCode(&path)).into(),
+            Ok(RunTaskToolInput {
+                action: RunTaskAction::Run,
+                ..
+            }) => "Run task".into(),
+            _ => "List tasks".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        cx.spawn(async move |cx| {
+            let input = input.recv().await.map_err(|e| e.to_string())?;
+            let candidates = collect_candidates(
+                &self.project,
+                input.path.as_deref().filter(|path| !path.is_empty()),
+                input.line,
+                cx,
+            )
+            .await
+            .map_err(|e| format!("{e:#}"))?;
+
+            match input.action {
+                RunTaskAction::List => Ok(list_candidates(&candidates)),
+                RunTaskAction::Run => {
+                    let label = input
+                        .label
+                        .as_deref()
+                        .filter(|label| !label.is_empty())
+                        .ok_or("`label` is required to run a task")?;
+                    let candidate = candidates
+                        .iter()
+                        .find(|candidate| candidate.task.resolved_label == label)
+                        .or_else(|| {
+                            candidates
+                                .iter()
+                                .find(|candidate| candidate.task.original_task().label == label)
+                        })
+                        .ok_or_else(|| {
+                            format!(
+                                "No task labeled {} was found. Available tasks:\n\n{}",
+                                MarkdownInlineCode(label),
+                                list_candidates(&candidates)
+                            )
+                        })?;
+                    run_candidate(
+                        &self.project,
+                        &self.environment,
+                        candidate,
+                        input.timeout_ms.map(Duration::from_millis),
+                        &event_stream,
+                        cx,
+                    )
+                    .await
+                }
+            }
+        })
+    }
+}
+
+struct TaskCandidate {
+    source: String,
+    task: ResolvedTask,
+}
+
+async fn collect_candidates(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    line: Option<u32>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TaskCandidate>> {
+    let (task_store, inventory) = project.read_with(cx, |project, cx| {
+        let task_store = project.task_store().clone();
+        let inventory = task_store.read(cx).task_inventory().cloned();
+        (task_store, inventory)
+    });
+    let inventory = inventory.context("Tasks are not available in this project")?;
+
+    let mut candidates = Vec::new();
+    match path {
+        Some(path) => {
+            let open_buffer = project.update(cx, |project, cx| {
+                let project_path = project
+                    .find_project_path(path, cx)
+                    .with_context(|| format!("Could not find path {path} in project"))?;
+                anyhow::Ok(project.open_buffer(project_path, cx))
+            })?;
+            let buffer = open_buffer.await?;
+            collect_buffer_candidates(&task_store, &inventory, &buffer, line, &mut candidates, cx)
+                .await?;
+        }
+        None => {
+            let worktrees = project.read_with(cx, |project, cx| {
+                project
+                    .visible_worktrees(cx)
+                    .map(|worktree| {
+                        let worktree = worktree.read(cx);
+                        (worktree.id(), worktree.abs_path())
+                    })
+                    .collect::<Vec<_>>()
+            });
+            for (worktree_id, abs_path) in worktrees {
+                let mut task_variables = TaskVariables::default();
+                task_variables.insert(
+                    VariableName::WorktreeRoot,
+                    abs_path.to_string_lossy().into_owned(),
+                );
+                let context = TaskContext {
+                    cwd: Some(abs_path.to_path_buf()),
+                    task_variables,
+                    project_env: Default::default(),
+                };
+                let templates = inventory
+                    .read_with(cx, |inventory, cx| {
+                        inventory.list_tasks(None, None, Some(worktree_id), cx)
+                    })
+                    .await;
+                push_resolved(&mut candidates, templates, &context, None);
+            }
+        }
+    }
+
+    let mut seen = HashSet::default();
+    candidates.retain(|candidate| {
+        seen.insert((
+            candidate.task.resolved_label.clone(),
+            candidate.task.resolved.command_label.clone(),
+        ))
+    });
+    Ok(candidates)
+}
+
+async fn collect_buffer_candidates(
+    task_store: &Entity<TaskStore>,
+    inventory: &Entity<Inventory>,
+    buffer: &Entity<Buffer>,
+    line: Option<u32>,
+    candidates: &mut Vec<TaskCandidate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let (snapshot, language, worktree_id) = buffer.read_with(cx, |buffer, cx| {
+        (
+            buffer.snapshot(),
+            buffer.language().cloned(),
+            buffer.file().map(|file| file.worktree_id(cx)),
+        )
+    });
+    let row = line
+        .map(|line| line.saturating_sub(1))
+        .unwrap_or(0)
+        .min(snapshot.max_point().row);
+    let line_offset = Point::new(row, 0).to_offset(&snapshot);
+
+    let context = task_context_at(
+        task_store,
+        buffer,
+        Point::new(row, 0),
+        TaskVariables::default(),
+        cx,
+    )
+    .await?;
+    let templates = inventory
+        .read_with(cx, |inventory, cx| {
+            inventory.list_tasks(Some(buffer.clone()), language, worktree_id, cx)
+        })
+        .await;
+    push_resolved(candidates, templates, &context, None);
+
+    let runnables = snapshot
+        .runnable_ranges(0..snapshot.len())
+        .filter(|runnable| {
+            line.is_none()
+                || runnable.full_range.contains(&line_offset)
+                || runnable.full_range.end == line_offset
+        })
+        .collect::<Vec<_>>();
+    for runnable in runnables {
+        let tags = runnable.runnable.tags.clone();
+        let templates = inventory
+            .read_with(cx, |inventory, cx| {
+                inventory.list_tasks(
+                    Some(buffer.clone()),
+                    Some(runnable.runnable.language.clone()),
+                    worktree_id,
+                    cx,
+                )
+            })
+            .await;
+        let templates = templates_with_tags(templates, &tags);
+        if templates.is_empty() {
+            continue;
+        }
+
+        let mut captured_variables = TaskVariables::default();
+        for (capture_name, value) in runnable.extra_captures {
+            captured_variables.insert(VariableName::Custom(capture_name.into()), value);
+        }
+        let position = snapshot.offset_to_point(runnable.run_range.start);
+        let context = task_context_at(task_store, buffer, position, captured_variables, cx).await?;
+        push_resolved(candidates, templates, &context, Some(position.row + 1));
+    }
+    Ok(())
+}
+
+async fn task_context_at(
+    task_store: &Entity<TaskStore>,
+    buffer: &Entity<Buffer>,
+    position: Point,
+    captured_variables: TaskVariables,
+    cx: &mut AsyncApp,
+) -> Result<TaskContext> {
+    let anchor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(position));
+    let location = Location {
+        buffer: buffer.clone(),
+        range: anchor..anchor,
+    };
+    let context = task_store
+        .update(cx, |task_store, cx| {
+            task_store.task_context_for_location(captured_variables, location, cx)
+        })
+        .await?;
+    Ok(context.unwrap_or_default())
+}
+
+/// Keeps the templates matching one of the runnable's tags, preferring the strongest source
+/// the same way the editor does for its gutter runnables.
+fn templates_with_tags(
+    templates: Vec<(TaskSourceKind, TaskTemplate)>,
+    tags: &[RunnableTag],
+) -> Vec<(TaskSourceKind, TaskTemplate)> {
+    let mut templates = templates
+        .into_iter()
+        .filter(|(_, template)| {
+            tags.iter()
+                .any(|RunnableTag(tag)| template.tags.iter().any(|source_tag| source_tag == tag))
+        })
+        .collect::<Vec<_>>();
+    templates.sort_by_key(|(kind, _)| kind.to_owned());
+    if let Some((leading_source, _)) = templates.first() {
+        let first_mismatch = templates
+            .iter()
+            .position(|(source, _)| source != leading_source);
+        if let Some(index) = first_mismatch {
+            templates.truncate(index);
+        }
+    }
+    templates
+}
+
+fn push_resolved(
+    candidates: &mut Vec<TaskCandidate>,
+    templates: Vec<(TaskSourceKind, TaskTemplate)>,
+    context: &TaskContext,
+    runnable_line: Option<u32>,
+) {
+    for (kind, template) in templates {
+        let Some(task) = template.resolve_task(&kind.to_id_base(), context) else {
+            continue;
+        };
+        let source = match (runnable_line, &kind) {
+            (Some(line), _) => format!("runnable at line {line}"),
+            (None, TaskSourceKind::Worktree { .. }) => "worktree".to_string(),
+            (None, TaskSourceKind::AbsPath { .. }) => "global".to_string(),
+            (None, TaskSourceKind::Language { name }) => name.to_string(),
+            (None, TaskSourceKind::Lsp { language_name, .. }) => {
+                format!("{language_name} language server")
+            }
+            (None, TaskSourceKind::UserInput) => "user input".to_string(),
+        };
+        candidates.push(TaskCandidate { source, task });
+    }
+}
+
+fn list_candidates(candidates: &[TaskCandidate]) -> String {
+    if candidates.is_empty() {
+        return "No tasks found.".to_string();
+    }
+    let mut output = String::new();
+    for candidate in candidates {
+        writeln!(
+            output,
+            "- {} ({}): {}",
+            MarkdownInlineCode(&candidate.task.resolved_label),
+            candidate.source,
+            MarkdownInlineCode(&candidate.task.resolved.command_label),
+        )
+        .ok();
+    }
+    output
+}
+
+async fn run_candidate(
+    project: &Entity<Project>,
+    environment: &Rc<dyn ThreadEnvironment>,
+    candidate: &TaskCandidate,
+    timeout: Option<Duration>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let spawn = &candidate.task.resolved;
+    let (command, authorize) = cx.update(|cx| {
+        let project = project.read(cx);
+        let shell = project
+            .remote_client()
+            .and_then(|remote_client| remote_client.read(cx).default_system_shell())
+            .unwrap_or_else(util::shell::get_default_system_shell_preferring_bash);
+        let shell_kind = ShellKind::new(&shell, project.path_style(cx).is_windows());
+        let command = shell_command(spawn, shell_kind);
+        let context = crate::ToolPermissionContext::new(RunTaskTool::NAME, vec![command.clone()]);
+        let authorize = event_stream.authorize(
+            format!("Run task {}", MarkdownInlineCode(&spawn.label)),
+            context,
+            cx,
+        );
+        (command, authorize)
+    });
+    authorize.await.map_err(|e| e.to_string())?;
+
+    let extra_env = spawn
+        .env
+        .iter()
+        .map(|(name, value)| acp::EnvVariable::new(name, value))
+        .collect();
+    let started_at = Instant::now();
+    let terminal = environment
+        .create_terminal(command, extra_env, spawn.cwd.clone(), None, None, cx)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+
+    let terminal_id = terminal.id(cx).map_err(|e| e.to_string())?;
+    event_stream.update_fields(acp::ToolCallUpdateFields::new().content(vec![
+        acp::ToolCallContent::Terminal(acp::Terminal::new(terminal_id)),
+    ]));
+
+    let wait_for_exit = terminal.wait_for_exit(cx).map_err(|e| e.to_string())?;
+    let timeout_task = match timeout {
+        Some(timeout) => cx.background_executor().timer(timeout).boxed_local(),
+        None => futures::future::pending().boxed_local(),
+    };
+    let mut timed_out = false;
+    let mut user_stopped = false;
+    futures::select! {
+        _ = wait_for_exit.clone().fuse() => {},
+        _ = timeout_task.fuse() => {
+            timed_out = true;
+            terminal.kill(cx).map_err(|e| e.to_string())?;
+            wait_for_exit.clone().await;
+        }
+        _ = event_stream.cancelled_by_user().fuse() => {
+            user_stopped = true;
+            terminal.kill(cx).map_err(|e| e.to_string())?;
+            wait_for_exit.clone().await;
+        }
+    }
+    let duration = started_at.elapsed();
+    let user_stopped = user_stopped
+        || event_stream.was_cancelled_by_user()
+        || terminal.was_stopped_by_user(cx).unwrap_or(false);
+    let exit_status = wait_for_exit.await;
+    let output = terminal.current_output(cx).map_err(|e| e.to_string())?;
+
+    let mut result = String::new();
+    writeln!(result, "Task: {}", spawn.label).ok();
+    writeln!(result, "Command: {}", spawn.command_label).ok();
+    let status = if user_stopped {
+        "stopped by the user".to_string()
+    } else if timed_out {
+        "timed out".to_string()
+    } else {
+        match (exit_status.exit_code, exit_status.signal.as_deref()) {
+            (Some(code), _) => format!("exited with code {code}"),
+            (None, Some(signal)) => format!("terminated by signal {signal}"),
+            (None, None) => "exited with an unknown status".to_string(),
+        }
+    };
+    writeln!(
+        result,
+        "Status: {status} after {:.1}s",
+        duration.as_secs_f64()
+    )
+    .ok();
+
+    let failures = parse_failures(&output.output);
+    if !failures.is_empty() {
+        result.push_str("\nFailures:\n");
+        for failure in &failures {
+            writeln!(result, "- {failure}").ok();
+        }
+    }
+
+    let (tail, omitted_lines) = output_tail(&output.output);
+    if tail.is_empty() {
+        result.push_str("\nThe task produced no output.\n");
+    } else {
+        if omitted_lines > 0 {
+            writeln!(result, "\nOutput (first {omitted_lines} lines omitted):").ok();
+        } else {
+            result.push_str("\nOutput:\n");
+        }
+        writeln!(result, "```\n{tail}\n```").ok();
+    }
+    if user_stopped {
+        result.push_str(
+            "\nSince the user intentionally stopped this task, ask them what they would like to do next \
+             rather than automatically retrying it.",
+        );
+    }
+    Ok(result)
+}
+
+/// Joins the command and arguments of a resolved task the way the task's terminal would.
+fn shell_command(spawn: &SpawnInTerminal, shell_kind: ShellKind) -> String {
+    let command = spawn.command.clone().unwrap_or_default();
+    if spawn.args.is_empty() {
+        return command;
+    }
+    let command = shell_kind
+        .try_quote_prefix_aware(&command)
+        .map(Cow::into_owned)
+        .unwrap_or(command);
+    spawn.args.iter().fold(command, |mut command, arg| {
+        command.push(' ');
+        let shell_variable = shell_kind.to_shell_variable(arg);
+        command.push_str(&match shell_kind.try_quote(&shell_variable) {
+            Some(quoted) => quoted.into_owned(),
+            None => shell_variable,
+        });
+        command
+    })
+}
+
+/// Returns the last lines of the output, along with how many lines were left out.
+fn output_tail(output: &str) -> (String, usize) {
+    let lines = output.trim().lines().collect::<Vec<_>>();
+    let mut start = lines.len().saturating_sub(OUTPUT_TAIL_LINES);
+    while start < lines.len()
+        && lines[start..]
+            .iter()
+            .map(|line| line.len() + 1)
+            .sum::<usize>()
+            > OUTPUT_TAIL_BYTES
+    {
+        start += 1;
+    }
+    (lines[start..].join("\n"), start)
+}
+
+/// Recognizes failed tests and compiler errors in the output of common test runners and build tools.
+fn parse_failures(output: &str) -> Vec<String> {
+    static PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+        [
+            // cargo test
+            r"^test (\S+) \.\.\. FAILED$",
+            // Rust panics
+            r"^thread '(.+)' panicked at (.+):$",
+            // pytest
+            r"^(?:FAILED|ERROR) (\S+::\S+)(?: - (.*))?$",
+            // go test
+            r"^\s*--- FAIL: (\S+)",
+            // jest and vitest
+            r"^\s*(?:●|FAIL) (.+ › .+)$",
+            // file:line:col: error: message, as printed by gcc, clang, tsc, and others
+            r"^(\S+:\d+(?::\d+)?):? (?:error|fatal error)(?:\[\w+\])?: (.+)$",
+        ]
+        .into_iter()
+        .map(|pattern| Regex::new(pattern).unwrap())
+        .collect()
+    });
+    static RUST_ERROR: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^error(?:\[(\w+)\])?: (.+)$").unwrap());
+    static RUST_LOCATION: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*--> (\S+:\d+:\d+)$").unwrap());
+
+    let mut failures = Vec::new();
+    let mut seen = HashSet::default();
+    let mut pending_rust_error: Option<String> = None;
+    for line in output.lines() {
+        let line = line.trim_end();
+        if let Some(message) = pending_rust_error.take() {
+            match RUST_LOCATION.captures(line) {
+                Some(captures) => {
+                    let failure = format!("{}: {message}", &captures[1]);
+                    if seen.insert(failure.clone()) {
+                        failures.push(failure);
+                    }
+                    continue;
+                }
+                None => {
+                    if seen.insert(message.clone()) {
+                        failures.push(message);
+                    }
+                }
+            }
+        }
+
+        if let Some(captures) = RUST_ERROR.captures(line) {
+            // Skip cargo's summary lines, e.g. "error: could not compile `foo`".
+            if !captures[2].starts_with("could not compile")
+                && !captures[2].starts_with("test failed")
+                && !captures[2].starts_with("aborting due to")
+            {
+                pending_rust_error = Some(match captures.get(1) {
+                    Some(code) => format!("error[{}]: {}", code.as_str(), &captures[2]),
+                    None => format!("error: {}", &captures[2]),
+                });
+            }
+            continue;
+        }
+
+        let Some(captures) = PATTERNS.iter().find_map(|pattern| pattern.captures(line)) else {
+            continue;
+        };
+        let failure = captures
+            .iter()
+            .skip(1)
+            .flatten()
+            .map(|capture| capture.as_str())
+            .collect::<Vec<_>>()
+            .join(": ");
+        if seen.insert(failure.clone()) {
+            failures.push(failure);
+        }
+        if failures.len() >= MAX_FAILURES {
+            break;
+        }
+    }
+    if let Some(message) = pending_rust_error
+        && failures.len() < MAX_FAILURES
+        && seen.insert(message.clone())
+    {
+        failures.push(message);
+    }
+    failures.truncate(MAX_FAILURES);
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_cargo_failures() {
+        let output = indoc! {"
+            error[E0308]: mismatched types
+              --> src/lib.rs:10:5
+               |
+            error: could not compile `foo` (lib) due to 1 previous error
+            running 2 tests
+            test tests::passes ... ok
+            test tests::fails ... FAILED
+
+            thread 'tests::fails' panicked at src/lib.rs:20:9:
+            assertion failed
+        "};
+        assert_eq!(
+            parse_failures(output),
+            vec![
+                "src/lib.rs:10:5: error[E0308]: mismatched types",
+                "tests::fails",
+                "tests::fails: src/lib.rs:20:9",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_other_runner_failures() {
+        let output = indoc! {"
+            FAILED tests/test_api.py::test_login - AssertionError: 401
+            --- FAIL: TestParse (0.00s)
+              ● Parser › handles empty input
+            src/main.c:3:10: fatal error: missing.h: No such file or directory
+        "};
+        assert_eq!(
+            parse_failures(output),
+            vec![
+                "tests/test_api.py::test_login: AssertionError: 401",
+                "TestParse",
+                "Parser › handles empty input",
+                "src/main.c:3:10: missing.h: No such file or directory",
+            ]
+        );
+        assert!(parse_failures("all 12 tests passed").is_empty());
+    }
+
+    #[test]
+    fn test_output_tail() {
+        let output = (1..=100)
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (tail, omitted) = output_tail(&output);
+        assert_eq!(omitted, 20);
+        assert!(tail.starts_with("21\n"));
+        assert!(tail.ends_with("\n100"));
+
+        assert_eq!(output_tail("\n  \n"), (String::new(), 0));
+    }
+
+    #[test]
+    fn test_shell_command() {
+        let spawn = SpawnInTerminal {
+            command: Some("cargo".into()),
+            args: vec!["test".into(), "my test".into()],
+            ..SpawnInTerminal::default()
+        };
+        assert_eq!(
+            shell_command(&spawn, ShellKind::Posix),
+            "cargo test 'my test'"
+        );
+
+        let spawn = SpawnInTerminal {
+            command: Some("npm run build && npm test".into()),
+            ..SpawnInTerminal::default()
+        };
+        assert_eq!(
+            shell_command(&spawn, ShellKind::Posix),
+            "npm run build && npm test"
+        );
+    }
+}
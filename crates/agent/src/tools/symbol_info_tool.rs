@@ -0,0 +1,423 @@
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::symbol_locator::{LocationDisplay, ResolvedSymbol, SymbolLocator};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol::schema::v1 as acp;
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use language::{PointUtf16, ToOffset as _};
+use project::{CallHierarchyCall, CallHierarchyDirection, HoverBlockKind, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const MAX_MARKDOWN_LEN: usize = 4000;
+const MAX_RESULTS: usize = 50;
+
+/// Asks the language server about a symbol without reading whole files.
+///
+/// - `hover`: the symbol's type, signature and documentation, e.g. the inferred type of a variable.
+/// - `signature_help`: the signatures of the function being called at this location, and which parameter is active.
+/// - `type_definition`: where the type of the symbol is defined.
+/// - `implementations`: the implementations of a trait, interface or abstract method.
+/// - `incoming_calls`: the functions calling this function.
+/// - `outgoing_calls`: the functions called by this function.
+///
+/// Before using this tool, use read_file or grep to find the exact symbol name and line number.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolInfoToolInput {
+    /// The symbol to get information about.
+    pub symbol: SymbolLocator,
+    /// The kind of information to get.
+    pub info: SymbolInfoKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolInfoKind {
+    Hover,
+    SignatureHelp,
+    TypeDefinition,
+    Implementations,
+    IncomingCalls,
+    OutgoingCalls,
+}
+
+impl SymbolInfoKind {
+    fn title(&self) -> &'static str {
+        match self {
+            SymbolInfoKind::Hover => "Hover",
+            SymbolInfoKind::SignatureHelp => "Get signature of",
+            SymbolInfoKind::TypeDefinition => "Go to type definition of",
+            SymbolInfoKind::Implementations => "Find implementations of",
+            SymbolInfoKind::IncomingCalls => "Find callers of",
+            SymbolInfoKind::OutgoingCalls => "Find calls made by",
+        }
+    }
+}
+
+pub struct SymbolInfoTool {
+    project: Entity<Project>,
+}
+
+impl SymbolInfoTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SymbolInfoTool {
+    type Input = SymbolInfoToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "symbol_info";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("{} `{}`", input.info.title(), input.symbol.symbol_name).into()
+        } else {
+            "Get symbol information".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let resolved = input.symbol.resolve(&project, cx).await?;
+            let symbol_name = &input.symbol.symbol_name;
+
+            match input.info {
+                SymbolInfoKind::Hover => hover(&project, &resolved, symbol_name, cx).await,
+                SymbolInfoKind::SignatureHelp => {
+                    signature_help(&project, &resolved, symbol_name, cx).await
+                }
+                SymbolInfoKind::TypeDefinition | SymbolInfoKind::Implementations => {
+                    locations(&project, &resolved, symbol_name, input.info, cx).await
+                }
+                SymbolInfoKind::IncomingCalls => {
+                    calls(
+                        &project,
+                        &resolved,
+                        symbol_name,
+                        CallHierarchyDirection::Incoming,
+                        cx,
+                    )
+                    .await
+                }
+                SymbolInfoKind::OutgoingCalls => {
+                    calls(
+                        &project,
+                        &resolved,
+                        symbol_name,
+                        CallHierarchyDirection::Outgoing,
+                        cx,
+                    )
+                    .await
+                }
+            }
+        })
+    }
+}
+
+async fn hover(
+    project: &Entity<Project>,
+    resolved: &ResolvedSymbol,
+    symbol_name: &str,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let hovers = project
+        .update(cx, |project, cx| {
+            project.hover(&resolved.buffer, resolved.position, cx)
+        })
+        .await
+        .unwrap_or_default();
+
+    let mut markdown = String::new();
+    for block in hovers.iter().flat_map(|hover| &hover.contents) {
+        let text = block.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if !markdown.is_empty() {
+            markdown.push_str("\n\n");
+        }
+        match &block.kind {
+            HoverBlockKind::Code { language } => {
+                write!(markdown, "```{language}\n{text}\n```").ok();
+            }
+            HoverBlockKind::Markdown | HoverBlockKind::PlainText => markdown.push_str(text),
+        }
+    }
+
+    if markdown.is_empty() {
+        return Ok(format!("No hover information found for '{symbol_name}'."));
+    }
+    Ok(format!(
+        "Hover for `{symbol_name}`:\n\n{}",
+        trim_markdown(&markdown, MAX_MARKDOWN_LEN)
+    ))
+}
+
+async fn signature_help(
+    project: &Entity<Project>,
+    resolved: &ResolvedSymbol,
+    symbol_name: &str,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    // Signature help is requested inside a call's argument list, so move past the
+    // symbol and its opening parenthesis when there is one.
+    let position = resolved.buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        let mut offset = resolved.position.to_offset(&snapshot) + symbol_name.len();
+        let mut chars = snapshot.chars_at(offset);
+        while let Some(ch) = chars.next() {
+            if ch == '(' {
+                offset += 1;
+                break;
+            } else if ch.is_whitespace() || ch == '!' || ch == '<' || ch == ':' {
+                offset += ch.len_utf8();
+            } else {
+                break;
+            }
+        }
+        snapshot.offset_to_point_utf16(offset.min(snapshot.len()))
+    });
+
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let help = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.signature_help(&resolved.buffer, position, cx)
+        })
+        .await
+        .unwrap_or_default();
+
+    let mut markdown = String::new();
+    cx.update(|cx| {
+        for help in &help {
+            for (ix, signature) in help.signatures.iter().enumerate() {
+                if !markdown.is_empty() {
+                    markdown.push_str("\n\n");
+                }
+                let active = if ix == help.active_signature && help.signatures.len() > 1 {
+                    " (active)"
+                } else {
+                    ""
+                };
+                write!(markdown, "```\n{}\n```{active}", signature.label).ok();
+                if let Some(parameter) = signature
+                    .active_parameter
+                    .and_then(|ix| signature.parameters.get(ix))
+                    && let Some(label) = parameter
+                        .label_range
+                        .as_ref()
+                        .and_then(|range| signature.label.get(range.clone()))
+                {
+                    write!(markdown, "\nActive parameter: `{label}`").ok();
+                    if let Some(documentation) = &parameter.documentation {
+                        write!(markdown, " — {}", documentation.read(cx).source().trim()).ok();
+                    }
+                }
+                if let Some(documentation) = &signature.documentation {
+                    let documentation = documentation.read(cx).source().trim().to_string();
+                    if !documentation.is_empty() {
+                        write!(markdown, "\n\n{documentation}").ok();
+                    }
+                }
+            }
+        }
+    });
+
+    if markdown.is_empty() {
+        return Ok(format!(
+            "No signature help found for '{symbol_name}'. Signature help is only available at call sites."
+        ));
+    }
+    Ok(format!(
+        "Signature help for `{symbol_name}`:\n\n{}",
+        trim_markdown(&markdown, MAX_MARKDOWN_LEN)
+    ))
+}
+
+async fn locations(
+    project: &Entity<Project>,
+    resolved: &ResolvedSymbol,
+    symbol_name: &str,
+    info: SymbolInfoKind,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let (what, task) = project.update(cx, |project, cx| match info {
+        SymbolInfoKind::TypeDefinition => (
+            "type definitions",
+            project.type_definitions(&resolved.buffer, resolved.position, cx),
+        ),
+        _ => (
+            "implementations",
+            project.implementations(&resolved.buffer, resolved.position, cx),
+        ),
+    });
+    let links = task
+        .await
+        .map_err(|e| format!("Finding {what} failed: {e}"))?
+        .unwrap_or_default();
+
+    if links.is_empty() {
+        return Ok(format!("No {what} found for '{symbol_name}'."));
+    }
+
+    let mut output = format!("Found {} {what} for `{symbol_name}`:\n", links.len());
+    for link in links.iter().take(MAX_RESULTS) {
+        let display = link
+            .target
+            .buffer
+            .read_with(cx, |_, cx| LocationDisplay::from_location(&link.target, cx));
+        write!(output, "\n## {display}\n").ok();
+    }
+    if links.len() > MAX_RESULTS {
+        write!(output, "\n{} more not shown.\n", links.len() - MAX_RESULTS).ok();
+    }
+    Ok(output)
+}
+
+async fn calls(
+    project: &Entity<Project>,
+    resolved: &ResolvedSymbol,
+    symbol_name: &str,
+    direction: CallHierarchyDirection,
+    cx: &mut AsyncApp,
+) -> Result<String, String> {
+    let calls = project
+        .update(cx, |project, cx| {
+            project.call_hierarchy(&resolved.buffer, resolved.position, direction, cx)
+        })
+        .await
+        .map_err(|e| format!("Call hierarchy failed: {e:#}"))?;
+
+    let what = match direction {
+        CallHierarchyDirection::Incoming => "callers of",
+        CallHierarchyDirection::Outgoing => "functions called by",
+    };
+    if calls.is_empty() {
+        return Ok(format!("No {what} '{symbol_name}' found."));
+    }
+
+    let mut output = format!("Found {} {what} `{symbol_name}`:\n\n", calls.len());
+    project.read_with(cx, |project, cx| {
+        for call in calls.iter().take(MAX_RESULTS) {
+            write_call(&mut output, call, project, cx);
+        }
+    });
+    if calls.len() > MAX_RESULTS {
+        write!(output, "\n{} more not shown.\n", calls.len() - MAX_RESULTS).ok();
+    }
+    Ok(output)
+}
+
+fn write_call(output: &mut String, call: &CallHierarchyCall, project: &Project, cx: &App) {
+    let path = call
+        .abs_path
+        .as_deref()
+        .map(|abs_path| display_path(abs_path, project, cx))
+        .unwrap_or_else(|| "<unknown>".to_string());
+    write!(
+        output,
+        "- `{}` {path}#L{}",
+        call.name,
+        call.position.row + 1
+    )
+    .ok();
+    if let Some(detail) = &call.detail {
+        write!(output, " — {}", detail.lines().next().unwrap_or_default()).ok();
+    }
+    if !call.call_sites.is_empty() {
+        let lines = call
+            .call_sites
+            .iter()
+            .map(|PointUtf16 { row, .. }| format!("L{}", row + 1))
+            .collect::<Vec<_>>();
+        write!(output, " (call sites: {})", lines.join(", ")).ok();
+    }
+    output.push('\n');
+}
+
+/// Shows paths inside the project relative to it, like the other LSP tools do.
+fn display_path(abs_path: &Path, project: &Project, cx: &App) -> String {
+    project
+        .find_project_path(abs_path, cx)
+        .and_then(|project_path| {
+            let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+            Some(worktree.read(cx).full_path(&project_path.path))
+        })
+        .unwrap_or_else(|| abs_path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Collapses runs of blank lines and truncates `markdown` to roughly `max_len` bytes,
+/// closing any code block left open by the truncation.
+fn trim_markdown(markdown: &str, max_len: usize) -> String {
+    let mut trimmed = String::new();
+    let mut previous_blank = false;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        let blank = line.is_empty();
+        if blank && previous_blank {
+            continue;
+        }
+        previous_blank = blank;
+        if trimmed.len() + line.len() > max_len {
+            if trimmed.matches("```").count() % 2 == 1 {
+                trimmed.push_str("```\n");
+            }
+            trimmed.push_str("…(truncated)");
+            return trimmed;
+        }
+        trimmed.push_str(line);
+        trimmed.push('\n');
+    }
+    trimmed.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_trim_markdown() {
+        let markdown = indoc! {"
+            ```rust
+            fn foo() -> u32
+            ```
+
+
+            Returns
+            the answer.
+        "};
+        assert_eq!(
+            trim_markdown(markdown, 100),
+            "```rust\nfn foo() -> u32\n```\n\nReturns\nthe answer."
+        );
+        assert_eq!(
+            trim_markdown(markdown, 20),
+            "```rust\nfn foo() -> u32\n```\n…(truncated)"
+        );
+    }
+}
@@ -68,7 +68,9 @@ use cloud_api_types::Plan;
 use collections::HashMap;
 use editor::{Editor, MultiBuffer};
 use extension_host::ExtensionStore;
-use feature_flags::{CreateThreadToolFeatureFlag, FeatureFlagAppExt as _};
+use feature_flags::{
+    CreateThreadToolFeatureFlag, ExecuteCodeToolFeatureFlag, FeatureFlagAppExt as _,
+};
 
 use fs::Fs;
 use futures::FutureExt as _;
@@ -4578,6 +4580,7 @@ impl AgentPanel {
                     cx.emit(AgentPanelEvent::EntryChanged);
                 }
                 this.ensure_sibling_host_installed(&server_view, window, cx);
+                this.ensure_code_execution_host_installed(&server_view, window, cx);
                 cx.notify();
             },
         )
@@ -4586,6 +4589,7 @@ impl AgentPanel {
         // Try installing the host eagerly as well, in case the connection is
         // already established by the time the observe fires.
         self.ensure_sibling_host_installed(&conversation_view, window, cx);
+        self.ensure_code_execution_host_installed(&conversation_view, window, cx);
 
         if let Some(model) = model_override {
             // The native thread is constructed asynchronously after the
@@ -4632,6 +4636,27 @@ impl AgentPanel {
         });
     }
 
+    fn ensure_code_execution_host_installed(
+        &self,
+        conversation_view: &Entity<ConversationView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !cx.has_flag::<ExecuteCodeToolFeatureFlag>() {
+            return;
+        }
+        let Some(native_connection) = conversation_view.read(cx).as_native_connection(cx) else {
+            return;
+        };
+        let host = Rc::new(AgentPanelCodeExecutionHost {
+            workspace: self.workspace.clone(),
+            window: window.window_handle(),
+        }) as Rc<dyn agent::CodeExecutionHost>;
+        native_connection.0.update(cx, |native_agent, _cx| {
+            native_agent.set_code_execution_host(host);
+        });
+    }
+
     fn active_thread_has_messages(&self, cx: &App) -> bool {
         self.active_agent_thread(cx)
             .is_some_and(|thread| !thread.read(cx).entries().is_empty())
@@ -4694,6 +4719,45 @@ fn parse_provider_slash_model(input: &str) -> Option<language_model::SelectedMod
     })
 }
 
+/// Runs code for the agent's `execute_code` tool in the REPL session of the
+/// requested file, opening the file in the panel's workspace so the session
+/// and its kernel stay visible to the user.
+struct AgentPanelCodeExecutionHost {
+    workspace: WeakEntity<Workspace>,
+    window: gpui::AnyWindowHandle,
+}
+
+impl agent::CodeExecutionHost for AgentPanelCodeExecutionHost {
+    fn execute_code(
+        &self,
+        request: agent::CodeExecutionRequest,
+        cx: &mut gpui::AsyncApp,
+    ) -> Task<Result<agent::CodeExecutionResult>> {
+        let workspace = self.workspace.clone();
+        let window = self.window;
+        cx.spawn(async move |cx| {
+            let open_task = window.update(cx, |_root, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.open_path(request.path.clone(), None, false, window, cx)
+                })
+            })??;
+            let editor = open_task
+                .await?
+                .downcast::<Editor>()
+                .context("File did not open in an editor")?;
+
+            let execution = window.update(cx, |_root, window, cx| {
+                repl::execute_captured(editor.downgrade(), request.code, window, cx)
+            })?;
+            let summary = execution.await?;
+            Ok(agent::CodeExecutionResult {
+                output: summary.to_markdown(),
+                errored: summary.errored(),
+            })
+        })
+    }
+}
+
 /// Bridges agent-side `SiblingThreadHost` calls to `AgentPanel`. Constructed
 /// and installed on a `NativeAgent` by the agent panel when a native-agent
 /// thread is created.
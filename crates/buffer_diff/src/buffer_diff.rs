@@ -1,3 +1,4 @@
+use anyhow::{Result, anyhow, bail, ensure};
 use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
 use imara_diff::{Algorithm, Diff, InternedInput, sources::lines};
 use language::{
@@ -128,6 +129,77 @@ pub struct DiffHunk {
     pub base_word_diffs: Vec<Range<usize>>,
 }
 
+/// The lines picked out of a diff's hunks for staging or unstaging only part
+/// of them. A line is picked when one of the ranges touches it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffLineSelection {
+    /// Offset ranges in the diff's main buffer, picking added lines.
+    pub buffer_ranges: Vec<Range<usize>>,
+    /// Offset ranges in the diff's base text, picking removed lines.
+    pub base_text_ranges: Vec<Range<usize>>,
+}
+
+impl DiffLineSelection {
+    pub fn is_empty(&self) -> bool {
+        self.buffer_ranges.is_empty() && self.base_text_ranges.is_empty()
+    }
+
+    fn picks(ranges: &[Range<usize>], line: &Range<usize>, text_len: usize) -> bool {
+        // A cursor at the very end of the text still picks the last line.
+        let line_end = if line.end == text_len {
+            line.end + 1
+        } else {
+            line.end
+        };
+        ranges.iter().any(|range| {
+            range.start < line_end && (range.end > line.start || range.start >= line.start)
+        })
+    }
+}
+
+/// A line of a hunk, and whether a [`DiffLineSelection`] picks it.
+struct HunkLine {
+    text: String,
+    picked: bool,
+}
+
+/// Joins hunk lines into a replacement text, keeping a line that ended the
+/// file without a newline from running into the line following it.
+fn join_hunk_lines<'a>(lines: impl IntoIterator<Item = &'a HunkLine>) -> String {
+    let mut text = String::new();
+    for line in lines {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&line.text);
+    }
+    text
+}
+
+fn hunk_lines(
+    text: &text::BufferSnapshot,
+    range: Range<usize>,
+    picked_ranges: &[Range<usize>],
+) -> Vec<HunkLine> {
+    let mut offset = range.start;
+    text.text_for_range(range)
+        .collect::<String>()
+        .split_inclusive('\n')
+        .map(|line| {
+            let line_range = offset..offset + line.len();
+            offset = line_range.end;
+            HunkLine {
+                picked: DiffLineSelection::picks(picked_ranges, &line_range, text.len()),
+                text: line.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn is_line_boundary(text: &text::BufferSnapshot, offset: usize) -> bool {
+    offset == text.len() || text.offset_to_point(offset).column == 0
+}
+
 /// We store [`InternalDiffHunk`]s internally so we don't need to store the additional row range.
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct InternalDiffHunk {
@@ -999,6 +1071,178 @@ impl BufferDiffSnapshot {
 }
 
 impl BufferDiffSnapshot {
+    /// Computes the index edits that stage the selected lines of this diff's
+    /// hunks, like editing a hunk in `git add -p`: removed lines that aren't
+    /// selected are kept and added lines that aren't selected are left out.
+    ///
+    /// `self` is either an unstaged diff, whose base text is the index, or an
+    /// uncommitted diff with an unstaged secondary. In the latter, removed lines
+    /// can only be picked from hunks that are entirely unstaged, since only
+    /// those have the same removed lines in HEAD and in the index.
+    pub fn stage_lines(
+        &self,
+        selection: &DiffLineSelection,
+        buffer: &text::BufferSnapshot,
+    ) -> Result<Vec<(Range<usize>, Arc<str>)>> {
+        self.ensure_current(buffer)?;
+        let Some(unstaged) = self.secondary_diff.as_deref() else {
+            return self.line_edits(selection, buffer, true);
+        };
+
+        let mut unstaged_selection = DiffLineSelection {
+            buffer_ranges: selection.buffer_ranges.clone(),
+            base_text_ranges: Vec::new(),
+        };
+        for range in &selection.base_text_ranges {
+            for hunk in self.hunks_intersecting_base_text_range(range.clone(), buffer) {
+                ensure!(
+                    hunk.secondary_status == DiffHunkSecondaryStatus::HasSecondaryHunk,
+                    "removed lines of a partially staged hunk can only be staged from the unstaged changes"
+                );
+                let buffer_range = hunk.buffer_range.to_offset(buffer);
+                let unstaged_hunk = unstaged
+                    .raw_hunks_intersecting_range(hunk.buffer_range.clone(), buffer)
+                    .find(|unstaged_hunk| {
+                        unstaged_hunk.buffer_range.to_offset(buffer) == buffer_range
+                            && unstaged_hunk.diff_base_byte_range.len()
+                                == hunk.diff_base_byte_range.len()
+                    })
+                    .ok_or_else(|| anyhow!("the hunk's removed lines aren't in the index"))?;
+                let start = range.start.max(hunk.diff_base_byte_range.start);
+                let end = range.end.min(hunk.diff_base_byte_range.end).max(start);
+                let delta = unstaged_hunk.diff_base_byte_range.start;
+                let head_start = hunk.diff_base_byte_range.start;
+                unstaged_selection
+                    .base_text_ranges
+                    .push(start - head_start + delta..end - head_start + delta);
+            }
+        }
+        unstaged.line_edits(&unstaged_selection, buffer, true)
+    }
+
+    /// Computes the index edits that unstage the selected lines of this diff's
+    /// hunks: removed lines that are selected are restored to the index and
+    /// added lines that are selected are taken out of it.
+    ///
+    /// `self` is either a staged diff, whose main buffer holds the index text,
+    /// or an uncommitted diff with an unstaged secondary. In the latter, lines
+    /// can only be picked from hunks that are entirely staged, since only those
+    /// have the same added lines in the working copy and in the index.
+    pub fn unstage_lines(
+        &self,
+        selection: &DiffLineSelection,
+        buffer: &text::BufferSnapshot,
+    ) -> Result<Vec<(Range<usize>, Arc<str>)>> {
+        self.ensure_current(buffer)?;
+        let Some(unstaged) = self.secondary_diff.as_deref() else {
+            return self.line_edits(selection, buffer, false);
+        };
+
+        let mut edits = Vec::new();
+        for hunk in self.hunks(buffer) {
+            let Some((removed, added)) = self.lines_of_hunk(&hunk, selection, buffer)? else {
+                continue;
+            };
+            ensure!(
+                hunk.secondary_status == DiffHunkSecondaryStatus::NoSecondaryHunk,
+                "lines of a partially staged hunk can only be unstaged from the staged changes"
+            );
+            let buffer_range = hunk.buffer_range.to_offset(buffer);
+            let index_range =
+                unstaged.base_text_range_for_buffer_range(hunk.buffer_range.clone(), buffer);
+            ensure!(
+                index_range.len() == buffer_range.len(),
+                "the hunk's added lines aren't in the index"
+            );
+            let text = join_hunk_lines(
+                removed
+                    .iter()
+                    .filter(|line| line.picked)
+                    .chain(added.iter().filter(|line| !line.picked)),
+            );
+            edits.push((index_range, Arc::from(text)));
+        }
+        Ok(edits)
+    }
+
+    fn ensure_current(&self, buffer: &text::BufferSnapshot) -> Result<()> {
+        ensure!(
+            buffer.version() == self.buffer_version(),
+            "the diff is out of date, try again once it has been recomputed"
+        );
+        Ok(())
+    }
+
+    /// Computes, for every hunk with selected lines, the edit applying those
+    /// lines to the base text (`to_base`) or reverting them in the buffer.
+    fn line_edits(
+        &self,
+        selection: &DiffLineSelection,
+        buffer: &text::BufferSnapshot,
+        to_base: bool,
+    ) -> Result<Vec<(Range<usize>, Arc<str>)>> {
+        let mut edits = Vec::new();
+        for hunk in self.raw_hunks_intersecting_range(
+            Anchor::min_max_range_for_buffer(buffer.remote_id()),
+            buffer,
+        ) {
+            let Some((removed, added)) = self.lines_of_hunk(&hunk, selection, buffer)? else {
+                continue;
+            };
+            let edit = if to_base {
+                let text = join_hunk_lines(
+                    removed
+                        .iter()
+                        .filter(|line| !line.picked)
+                        .chain(added.iter().filter(|line| line.picked)),
+                );
+                (hunk.diff_base_byte_range.clone(), Arc::from(text))
+            } else {
+                let text = join_hunk_lines(
+                    removed
+                        .iter()
+                        .filter(|line| line.picked)
+                        .chain(added.iter().filter(|line| !line.picked)),
+                );
+                (hunk.buffer_range.to_offset(buffer), Arc::from(text))
+            };
+            edits.push(edit);
+        }
+        Ok(edits)
+    }
+
+    /// Splits `hunk` into its removed and added lines, or returns `None` when
+    /// the selection picks none of them. Fails when the hunk doesn't start and
+    /// end on line boundaries, as the edit computed from it wouldn't apply
+    /// cleanly.
+    fn lines_of_hunk(
+        &self,
+        hunk: &DiffHunk,
+        selection: &DiffLineSelection,
+        buffer: &text::BufferSnapshot,
+    ) -> Result<Option<(Vec<HunkLine>, Vec<HunkLine>)>> {
+        let buffer_range = hunk.buffer_range.to_offset(buffer);
+        let base_range = hunk.diff_base_byte_range.clone();
+        let removed = hunk_lines(
+            &self.base_text,
+            base_range.clone(),
+            &selection.base_text_ranges,
+        );
+        let added = hunk_lines(buffer, buffer_range.clone(), &selection.buffer_ranges);
+        if !removed.iter().chain(&added).any(|line| line.picked) {
+            return Ok(None);
+        }
+        if base_range.end > self.base_text.len()
+            || !is_line_boundary(&self.base_text, base_range.start)
+            || !is_line_boundary(&self.base_text, base_range.end)
+            || !is_line_boundary(buffer, buffer_range.start)
+            || !is_line_boundary(buffer, buffer_range.end)
+        {
+            bail!("the selected lines don't apply cleanly to the hunk");
+        }
+        Ok(Some((removed, added)))
+    }
+
     fn hunks_intersecting_range_impl<'a>(
         &'a self,
         filter: impl 'a + Fn(&DiffHunkSummary) -> bool,
@@ -3083,6 +3327,160 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        fn apply(text: &str, edits: &[(Range<usize>, Arc<str>)]) -> String {
+            let mut text = text.to_string();
+            for (range, replacement) in edits.iter().rev() {
+                text.replace_range(range.clone(), replacement);
+            }
+            text
+        }
+        fn line(text: &str, line: &str) -> Range<usize> {
+            let start = text.find(line).unwrap();
+            start..start + line.len()
+        }
+
+        let head_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let buffer_text = "
+            one
+            TWO
+            three
+            FOUR
+            five
+        "
+        .unindent();
+        let mut buffer = Buffer::new(
+            ReplicaId::LOCAL,
+            BufferId::new(1).unwrap(),
+            buffer_text.clone(),
+        );
+        let unstaged_diff = cx.new(|cx| BufferDiff::new_with_base_text(&head_text, &buffer, cx));
+        let uncommitted_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new_with_base_text(&head_text, &buffer, cx);
+            diff.set_secondary_diff(unstaged_diff.clone());
+            diff
+        });
+
+        let unstaged = unstaged_diff.update(cx, |diff, cx| diff.snapshot(cx));
+        let edits = unstaged
+            .stage_lines(
+                &DiffLineSelection {
+                    buffer_ranges: vec![line(&buffer_text, "five")],
+                    base_text_ranges: Vec::new(),
+                },
+                &buffer,
+            )
+            .unwrap();
+        assert_eq!(
+            apply(&head_text, &edits),
+            "
+            one
+            two
+            three
+            four
+            five
+            "
+            .unindent()
+        );
+
+        let edits = unstaged
+            .stage_lines(
+                &DiffLineSelection {
+                    buffer_ranges: vec![line(&buffer_text, "TWO")],
+                    base_text_ranges: vec![line(&head_text, "two"), line(&head_text, "four")],
+                },
+                &buffer,
+            )
+            .unwrap();
+        assert_eq!(
+            apply(&head_text, &edits),
+            "
+            one
+            TWO
+            three
+            "
+            .unindent()
+        );
+
+        // Removed lines picked in the uncommitted diff map to the index.
+        let uncommitted = uncommitted_diff.update(cx, |diff, cx| diff.snapshot(cx));
+        let edits = uncommitted
+            .stage_lines(
+                &DiffLineSelection {
+                    buffer_ranges: Vec::new(),
+                    base_text_ranges: vec![line(&head_text, "four")],
+                },
+                &buffer,
+            )
+            .unwrap();
+        assert_eq!(
+            apply(&head_text, &edits),
+            "
+            one
+            two
+            three
+            "
+            .unindent()
+        );
+
+        // A staged diff's main buffer is the index.
+        let staged = cx
+            .new(|cx| BufferDiff::new_with_base_text(&head_text, &buffer, cx))
+            .update(cx, |diff, cx| diff.snapshot(cx));
+        let edits = staged
+            .unstage_lines(
+                &DiffLineSelection {
+                    buffer_ranges: vec![line(&buffer_text, "five")],
+                    base_text_ranges: Vec::new(),
+                },
+                &buffer,
+            )
+            .unwrap();
+        assert_eq!(
+            apply(&buffer_text, &edits),
+            "
+            one
+            TWO
+            three
+            FOUR
+            "
+            .unindent()
+        );
+
+        // Hunks the uncommitted diff shows as unstaged can't be unstaged.
+        assert!(
+            uncommitted
+                .unstage_lines(
+                    &DiffLineSelection {
+                        buffer_ranges: vec![line(&buffer_text, "TWO")],
+                        base_text_ranges: Vec::new(),
+                    },
+                    &buffer,
+                )
+                .is_err()
+        );
+
+        buffer.edit([(0..0, "zero\n")]);
+        assert!(
+            unstaged
+                .stage_lines(
+                    &DiffLineSelection {
+                        buffer_ranges: vec![0..0],
+                        base_text_ranges: Vec::new(),
+                    },
+                    &buffer,
+                )
+                .is_err()
+        );
+    }
+
     #[gpui::test]
     async fn test_set_pending_hunks_change_covers_replaced_hunks(cx: &mut TestAppContext) {
         let base_text = "
@@ -485,6 +485,28 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::StashPop>)
             .add_request_handler(forward_mutating_project_request::<proto::StashDrop>)
             .add_request_handler(forward_mutating_project_request::<proto::Commit>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebase>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebaseStep>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCherryPick>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRevert>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCreateTag>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDeleteTag>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitPushTags>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitBisectMark>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitBisectReset>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLoadConflictStages>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetSubmodules>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitSubmoduleCommand>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetCommitSignature>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitFormatPatch>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitCheckPatch>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitApplyPatch>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetWorktreeStatuses>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitPruneWorktrees>)
+            .add_request_handler(
+                forward_mutating_project_request::<proto::GitMoveChangesToWorktree>,
+            )
+            .add_request_handler(forward_read_only_project_request::<proto::GitBlameRevision>)
             .add_request_handler(forward_mutating_project_request::<proto::RunGitHook>)
             .add_request_handler(forward_mutating_project_request::<proto::GitInit>)
             .add_request_handler(forward_read_only_project_request::<proto::GetRemotes>)
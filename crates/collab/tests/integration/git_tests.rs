@@ -6,15 +6,21 @@ use std::{
 use call::ActiveCall;
 use client::RECEIVE_TIMEOUT;
 use collections::HashMap;
+use fs::Fs as _;
 use git::{
     Oid,
     repository::{
-        CommitData, GitCommitTemplate, InitialGraphCommitData, RepoPath, Worktree as GitWorktree,
+        CommitData, ConflictStages, GitCommitTemplate, InitialGraphCommitData, RepoPath,
+        Worktree as GitWorktree, repo_path,
     },
-    status::{DiffStat, FileStatus, StatusCode, TrackedStatus},
+    status::{DiffStat, FileStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode},
 };
 use git_ui::git_graph::GitGraph;
-use git_ui::{git_panel::GitPanel, project_diff::ProjectDiff};
+use git_ui::{
+    git_panel::GitPanel,
+    merge_editor::{AcceptOurs, MarkResolved, MergeEditor},
+    project_diff::ProjectDiff,
+};
 use gpui::{
     AppContext as _, BackgroundExecutor, Entity, IntoElement as _, SharedString, TestAppContext,
     VisualContext as _, VisualTestContext, point, px, size,
@@ -1462,3 +1468,121 @@ async fn test_load_commit_template_over_collab(
 
     assert_eq!(commit_template.template, "feat: add awesome feature");
 }
+
+#[gpui::test]
+async fn test_merge_editor_mark_resolved_over_collab(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "one\n<<<<<<< HEAD\ntwo\n=======\nTWO\n>>>>>>> other\nthree\n",
+            }),
+        )
+        .await;
+    client_a
+        .fs()
+        .with_git_state(Path::new(path!("/project/.git")), true, |state| {
+            state.unmerged_paths.insert(
+                repo_path("a.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            );
+            state.conflict_stages.insert(
+                repo_path("a.txt"),
+                ConflictStages {
+                    base: Some("one\nthree\n".into()),
+                    ours: Some("one\ntwo\nthree\n".into()),
+                    theirs: Some("one\nTWO\nthree\n".into()),
+                },
+            );
+        })
+        .unwrap();
+
+    let (project_a, _) = client_a.build_local_project(path!("/project"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    cx_b.update(editor::init);
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let window_b = cx_b.add_window(|window, cx| {
+        let workspace = cx.new(|cx| {
+            Workspace::new(
+                None,
+                project_b.clone(),
+                client_b.app_state.clone(),
+                window,
+                cx,
+            )
+        });
+        MultiWorkspace::new(workspace, window, cx)
+    });
+    let cx_b = &mut VisualTestContext::from_window(*window_b, cx_b);
+    let workspace_b = window_b
+        .root(cx_b)
+        .unwrap()
+        .read_with(cx_b, |multi_workspace, _| {
+            multi_workspace.workspace().clone()
+        });
+    executor.run_until_parked();
+
+    let repository_b =
+        project_b.read_with(cx_b, |project, cx| project.active_repository(cx).unwrap());
+    workspace_b
+        .update_in(cx_b, |workspace, window, cx| {
+            MergeEditor::open(
+                repository_b,
+                repo_path("a.txt"),
+                workspace.weak_handle(),
+                window,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The guest resolves the conflict, and the host saves and stages the file.
+    cx_b.dispatch_action(AcceptOurs);
+    executor.run_until_parked();
+    cx_b.dispatch_action(MarkResolved);
+    executor.run_until_parked();
+
+    let resolved = "one\ntwo\nthree\n";
+    assert_eq!(
+        client_a
+            .fs()
+            .load(Path::new(path!("/project/a.txt")))
+            .await
+            .unwrap(),
+        resolved
+    );
+    let (unmerged, index_text) = client_a
+        .fs()
+        .with_git_state(Path::new(path!("/project/.git")), false, |state| {
+            (
+                state.unmerged_paths.contains_key(&repo_path("a.txt")),
+                state.index_contents.get(&repo_path("a.txt")).cloned(),
+            )
+        })
+        .unwrap();
+    assert!(!unmerged);
+    assert_eq!(index_text.as_deref(), Some(resolved));
+}
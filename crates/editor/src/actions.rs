@@ -378,6 +378,121 @@ pub struct InsertSnippet {
     pub snippet: Option<String>,
 }
 
+/// Replaces each line of the rectangle between the selection's corners with the given text.
+/// Without text, places a cursor at the rectangle's left edge on each line instead, so that
+/// typing inserts on every line.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct StringRectangle {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Inserts increasing numbers at the left edge of each line of the rectangle.
+#[derive(PartialEq, Clone, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct NumberRectangleLines {
+    /// The text to insert on each line, where `%d` is replaced by the number, optionally with a
+    /// width and zero padding as in `%03d`. Defaults to the number right-aligned, then a space.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// The number for the first line.
+    #[serde(default = "default_one")]
+    pub start: i64,
+    /// The amount added to the number on each following line.
+    #[serde(default = "default_one")]
+    pub increment: i64,
+}
+
+impl Default for NumberRectangleLines {
+    fn default() -> Self {
+        Self {
+            format: None,
+            start: 1,
+            increment: 1,
+        }
+    }
+}
+
+fn default_one() -> i64 {
+    1
+}
+
+/// Aligns the first occurrence of a delimiter in each selected line, padding the text before it
+/// with spaces.
+#[derive(PartialEq, Clone, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct AlignOnDelimiter {
+    #[serde(default = "default_align_delimiter")]
+    pub delimiter: String,
+}
+
+impl Default for AlignOnDelimiter {
+    fn default() -> Self {
+        Self {
+            delimiter: default_align_delimiter(),
+        }
+    }
+}
+
+fn default_align_delimiter() -> String {
+    "=".to_string()
+}
+
+/// Sorts selected lines by the given keys, which are compared in order. Lines with equal keys
+/// keep their order. Without keys, whole lines are compared.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct SortLines {
+    #[serde(default)]
+    pub keys: Vec<SortKey>,
+    /// Whether to keep only the first of the lines with equal keys.
+    #[serde(default)]
+    pub unique: bool,
+}
+
+/// The part of each line to sort by, and how to compare it.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SortKey {
+    /// The field to compare, counting from 1. Without a field, the whole line is compared.
+    #[serde(default)]
+    pub field: Option<usize>,
+    /// The text separating fields. Fields are separated by whitespace by default.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// A regular expression whose match is compared, or its first capture group if it has
+    /// one. Lines without a match sort first, in their original order.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Whether to compare the text after the pattern's match instead of the match itself.
+    #[serde(default)]
+    pub after_match: bool,
+    #[serde(default)]
+    pub order: SortOrder,
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// How sort keys are compared.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Compares characters one by one.
+    #[default]
+    Lexical,
+    /// Compares runs of digits by their value, so that `item2` comes before `item10`.
+    Natural,
+    /// Compares the first number in each key. Keys without a number sort first.
+    Numeric,
+}
+
 actions!(
     debugger,
     [
@@ -397,6 +512,15 @@ actions!(
     ]
 );
 
+actions!(
+    sort_lines,
+    [
+        /// Prompts for sort options, then sorts the selected lines with them.
+        #[action(name = "Toggle")]
+        ToggleSortLinesPrompt
+    ]
+);
+
 actions!(
     markdown,
     [
@@ -423,6 +547,9 @@ actions!(
         Backspace,
         /// Shows git blame information for the current line.
         BlameHover,
+        /// Blames the lines last changed with the current line as they were
+        /// before that commit.
+        BlamePreviousRevision,
         /// Cancels the current operation.
         Cancel,
         /// Cancels the running flycheck operation.
@@ -489,6 +616,8 @@ actions!(
         CopyFileNameWithoutExtension,
         /// Copies a permalink to the current line.
         CopyPermalinkToLine,
+        /// Copies the rectangle between the selection's corners to the clipboard.
+        CopyRectangle,
         /// Cuts selected text to the clipboard.
         Cut,
         /// Deletes the character after the cursor.
@@ -630,6 +759,8 @@ actions!(
         InsertUuidV7,
         /// Joins the current line with the next line.
         JoinLines,
+        /// Cuts the rectangle between the selection's corners to the clipboard.
+        KillRectangle,
         /// Cuts to kill ring (Emacs-style).
         KillRingCut,
         /// Yanks from kill ring (Emacs-style).
@@ -746,6 +877,9 @@ actions!(
         RedoSelection,
         /// Renames the symbol at cursor.
         Rename,
+        /// Restores git blame to the current revision after blaming previous
+        /// revisions.
+        ResetBlameRevisions,
         /// Restarts the language server for the current file.
         RestartLanguageServer,
         /// Reverses the order of selected lines.
@@ -938,6 +1072,8 @@ actions!(
         UnwrapSyntaxNode,
         /// Wraps selections in tag specified by language.
         WrapSelectionsInTag,
+        /// Inserts the clipboard as a rectangle, one line per row starting at the cursor's column.
+        YankRectangle,
         /// Aligns selections from different rows into the same column
         AlignSelections,
         /// Saves the current location to navigation history.
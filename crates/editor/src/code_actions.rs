@@ -314,6 +314,37 @@ impl Editor {
         Some((buffer_snapshot.remote_id(), range.start.row))
     }
 
+    /// Adds a source of code actions for this editor, unless one with the same
+    /// id was already added.
+    pub fn add_code_action_provider(
+        &mut self,
+        provider: Rc<dyn CodeActionProvider>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .code_action_providers
+            .iter()
+            .any(|existing_provider| existing_provider.id() == provider.id())
+        {
+            return;
+        }
+
+        self.code_action_providers.push(provider);
+        self.refresh_code_actions_for_selection(window, cx);
+    }
+
+    pub fn remove_code_action_provider(
+        &mut self,
+        id: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.code_action_providers
+            .retain(|provider| provider.id() != id);
+        self.refresh_code_actions_for_selection(window, cx);
+    }
+
     pub fn code_actions_enabled_for_toolbar(&self, cx: &App) -> bool {
         !self.code_action_providers.is_empty()
             && EditorSettings::get_global(cx).toolbar.code_actions
@@ -376,7 +407,7 @@ impl Editor {
             .into_any_element()
     }
 
-    pub(super) fn refresh_code_actions_for_selection(
+    pub fn refresh_code_actions_for_selection(
         &mut self,
         window: &mut Window,
         cx: &mut Context<Self>,
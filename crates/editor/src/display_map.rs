@@ -185,6 +185,7 @@ pub enum HighlightKey {
     Rename,
     SearchWithinRange,
     SelectedTextHighlight,
+    SpellCheck,
     SyntaxTreeView(usize),
     VimExchange,
 }
@@ -68,8 +68,10 @@ mod edit_prediction;
 mod input;
 mod markdown_actions;
 mod navigation;
+mod rectangle;
 mod rewrap;
 mod selection;
+mod sort_lines;
 
 pub(crate) use actions::*;
 pub use clipboard::ClipboardSelection;
@@ -108,9 +110,9 @@ pub use element::{
 };
 pub use git::blame::BlameRenderer;
 pub use git::{
-    DiffHunkDelegate, ResolvedDiffHunk, ResolvedDiffHunks, RestoreOnlyDiffHunkDelegate,
-    RestoreOnlyUnstagedDiffHunkDelegate, UncommittedDiffHunkDelegate, render_diff_hunk_controls,
-    set_blame_renderer,
+    DiffHunkDelegate, ResolvedDiffHunk, ResolvedDiffHunks, ResolvedDiffLines,
+    RestoreOnlyDiffHunkDelegate, RestoreOnlyUnstagedDiffHunkDelegate, UncommittedDiffHunkDelegate,
+    render_diff_hunk_controls, set_blame_renderer, stage_or_unstage_diff_lines,
 };
 pub(crate) use git::{DiffHunkKey, StoredReviewComment};
 use git::{DiffReviewDragState, DiffReviewOverlay, InlineBlamePopover};
@@ -2605,6 +2607,19 @@ impl Editor {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
             cx.set_global(ScrollbarAutoHide(should_auto_hide_scrollbars));
 
+            // Files opened in large file mode skip everything that reads through the whole
+            // buffer, leaving editing, search and navigation.
+            if multi_buffer
+                .read(cx)
+                .as_singleton()
+                .is_some_and(|buffer| buffer.read(cx).is_large_file())
+            {
+                editor.disable_lsp_data();
+                editor.disable_runnables();
+                editor.word_completions_enabled = false;
+                editor.git_blame_inline_enabled = false;
+            }
+
             if editor.git_blame_inline_enabled {
                 editor.start_git_blame_inline(false, window, cx);
             }
@@ -505,15 +505,20 @@ impl EditorElement {
         register_action(editor, window, Editor::copy_file_name_without_extension);
         register_action(editor, window, Editor::copy_highlight_json);
         register_action(editor, window, Editor::copy_permalink_to_line);
+        register_action(editor, window, Editor::copy_rectangle);
         register_action(editor, window, Editor::open_permalink_to_line);
         register_action(editor, window, Editor::copy_file_location);
         register_action(editor, window, Editor::toggle_git_blame);
         register_action(editor, window, Editor::toggle_git_blame_inline);
         register_action(editor, window, Editor::open_git_blame_commit);
+        register_action(editor, window, Editor::blame_previous_revision);
+        register_action(editor, window, Editor::reset_blame_revisions);
         register_action(editor, window, Editor::toggle_selected_diff_hunks);
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
+        register_action(editor, window, Editor::stage_selected_lines);
+        register_action(editor, window, Editor::unstage_selected_lines);
         register_action(editor, window, Editor::expand_all_diff_hunks);
         register_action(editor, window, Editor::collapse_all_diff_hunks);
         register_action(editor, window, Editor::toggle_all_diff_hunks);
@@ -583,6 +588,7 @@ impl EditorElement {
             register_action(editor, window, Editor::autoindent);
             register_action(editor, window, Editor::delete_line);
             register_action(editor, window, Editor::join_lines);
+            register_action(editor, window, Editor::sort_lines);
             register_action(editor, window, Editor::sort_lines_by_length);
             register_action(editor, window, Editor::sort_lines_case_sensitive);
             register_action(editor, window, Editor::sort_lines_case_insensitive);
@@ -590,6 +596,11 @@ impl EditorElement {
             register_action(editor, window, Editor::unique_lines_case_sensitive);
             register_action(editor, window, Editor::reverse_lines);
             register_action(editor, window, Editor::shuffle_lines);
+            register_action(editor, window, Editor::kill_rectangle);
+            register_action(editor, window, Editor::yank_rectangle);
+            register_action(editor, window, Editor::string_rectangle);
+            register_action(editor, window, Editor::number_rectangle_lines);
+            register_action(editor, window, Editor::align_on_delimiter);
             register_action(editor, window, Editor::rotate_selections_forward);
             register_action(editor, window, Editor::rotate_selections_backward);
             register_action(editor, window, Editor::convert_indentation_to_spaces);
@@ -2,7 +2,7 @@ pub(super) mod blame;
 
 use super::*;
 use ::git::{Restore, blame::BlameEntry, commit::ParsedCommitMessage, status::FileStatus};
-use buffer_diff::{BufferDiff, DiffHunkStatus, DiffHunkStatusKind};
+use buffer_diff::{BufferDiff, DiffHunkStatus, DiffHunkStatusKind, DiffLineSelection};
 
 #[derive(Clone)]
 pub struct ResolvedDiffHunk {
@@ -19,6 +19,38 @@ pub struct ResolvedDiffHunks {
     pub hunks: Vec<ResolvedDiffHunk>,
 }
 
+/// Lines picked out of the hunks of one diff, see [`DiffLineSelection`].
+#[derive(Clone)]
+pub struct ResolvedDiffLines {
+    pub diff: Entity<BufferDiff>,
+    /// The diff's main buffer.
+    pub buffer: Entity<Buffer>,
+    pub selection: DiffLineSelection,
+}
+
+/// Stages or unstages the picked lines through the project, which works out
+/// the index edits from the kind of diff they were picked from.
+pub fn stage_or_unstage_diff_lines(
+    stage: bool,
+    lines: Vec<ResolvedDiffLines>,
+    editor: &mut Editor,
+    cx: &mut Context<Editor>,
+) -> Result<()> {
+    let Some(project) = editor.project().cloned() else {
+        return Ok(());
+    };
+    for lines in lines {
+        project.update(cx, |project, cx| {
+            if stage {
+                project.stage_lines(lines.buffer, lines.diff, lines.selection, cx)
+            } else {
+                project.unstage_lines(lines.buffer, lines.diff, lines.selection, cx)
+            }
+        })?;
+    }
+    Ok(())
+}
+
 pub trait DiffHunkDelegate {
     fn toggle(
         &self,
@@ -64,6 +96,19 @@ pub trait DiffHunkDelegate {
         });
     }
 
+    /// Stages or unstages only the lines picked out of the hunks, as when
+    /// editing a hunk in `git add -p`.
+    fn stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        lines: Vec<ResolvedDiffLines>,
+        editor: &mut Editor,
+        _window: &mut Window,
+        cx: &mut Context<Editor>,
+    ) -> Result<()> {
+        stage_or_unstage_diff_lines(stage, lines, editor, cx)
+    }
+
     fn render_hunk_controls(
         &self,
         row: u32,
@@ -193,6 +238,17 @@ impl DiffHunkDelegate for RestoreOnlyDiffHunkDelegate {
     ) {
     }
 
+    fn stage_or_unstage_lines(
+        &self,
+        _stage: bool,
+        _lines: Vec<ResolvedDiffLines>,
+        _editor: &mut Editor,
+        _window: &mut Window,
+        _cx: &mut Context<Editor>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn render_hunk_controls(
         &self,
         _row: u32,
@@ -230,6 +286,17 @@ impl DiffHunkDelegate for RestoreOnlyUnstagedDiffHunkDelegate {
     ) {
     }
 
+    fn stage_or_unstage_lines(
+        &self,
+        _stage: bool,
+        _lines: Vec<ResolvedDiffLines>,
+        _editor: &mut Editor,
+        _window: &mut Window,
+        _cx: &mut Context<Editor>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn render_hunk_controls(
         &self,
         _row: u32,
@@ -1767,6 +1834,118 @@ impl Editor {
         self.do_stage_or_unstage_and_next(false, window, cx);
     }
 
+    pub(super) fn stage_selected_lines(
+        &mut self,
+        _: &::git::StageLines,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(true, window, cx);
+    }
+
+    pub(super) fn unstage_selected_lines(
+        &mut self,
+        _: &::git::UnstageLines,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(false, window, cx);
+    }
+
+    fn stage_or_unstage_selected_lines(
+        &mut self,
+        stage: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges: Vec<_> = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|s| s.range())
+            .collect();
+        let save = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        let task = cx.spawn_in(window, async move |this, cx| {
+            save.await?;
+            this.update_in(cx, |this, window, cx| {
+                let mut lines = this.resolve_diff_lines(&ranges, cx);
+                if this.diff_hunk_delegate.is_none() {
+                    lines.retain(|lines| lines.diff.read(cx).is_stageable());
+                }
+                if lines.is_empty() {
+                    return Ok(());
+                }
+                let delegate = this.diff_hunk_delegate();
+                delegate.stage_or_unstage_lines(stage, lines, this, window, cx)
+            })?
+        });
+        self.detach_and_notify_err(task, window, cx);
+    }
+
+    /// Resolves the selected ranges into the lines they pick out of each diff,
+    /// whether they're lines of the diff's buffer or deleted lines of its base
+    /// text.
+    fn resolve_diff_lines(&self, ranges: &[Range<Anchor>], cx: &App) -> Vec<ResolvedDiffLines> {
+        let multibuffer = self.buffer().read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let mut resolved: Vec<ResolvedDiffLines> = Vec::new();
+
+        for range in ranges {
+            for (buffer_snapshot, buffer_range, deleted_hunk_anchor) in
+                snapshot.range_to_buffer_ranges_with_deleted_hunks(range.clone())
+            {
+                let main_buffer_id = match deleted_hunk_anchor {
+                    Some(anchor) => {
+                        let Some((text_anchor, _)) = snapshot.anchor_to_buffer_anchor(anchor)
+                        else {
+                            continue;
+                        };
+                        text_anchor.buffer_id
+                    }
+                    None => buffer_snapshot.remote_id(),
+                };
+                let Some(diff) = multibuffer.diff_for(main_buffer_id) else {
+                    continue;
+                };
+                // A diff shown inverted has its base text as the excerpt's buffer.
+                let diff_buffer_id = diff.read(cx).buffer_id;
+                let picks_base_text =
+                    deleted_hunk_anchor.is_some() || diff_buffer_id != buffer_snapshot.remote_id();
+                let buffer_range = buffer_range.start.0..buffer_range.end.0;
+
+                let index = match resolved
+                    .iter()
+                    .position(|lines| lines.diff.read(cx).buffer_id == diff_buffer_id)
+                {
+                    Some(index) => index,
+                    None => {
+                        let Some(buffer) = multibuffer.buffer(diff_buffer_id).or_else(|| {
+                            self.project.as_ref().and_then(|project| {
+                                project.read(cx).buffer_for_id(diff_buffer_id, cx)
+                            })
+                        }) else {
+                            continue;
+                        };
+                        resolved.push(ResolvedDiffLines {
+                            diff,
+                            buffer,
+                            selection: DiffLineSelection::default(),
+                        });
+                        resolved.len() - 1
+                    }
+                };
+                let selection = &mut resolved[index].selection;
+                if picks_base_text {
+                    selection.base_text_ranges.push(buffer_range);
+                } else {
+                    selection.buffer_ranges.push(buffer_range);
+                }
+            }
+        }
+
+        resolved
+    }
+
     pub fn apply_toggle(
         &mut self,
         hunks: Vec<MultiBufferDiffHunk>,
@@ -1939,6 +2118,33 @@ impl Editor {
         self.open_git_blame_commit_internal(window, cx);
     }
 
+    pub(super) fn blame_previous_revision(
+        &mut self,
+        _: &BlamePreviousRevision,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((_, blame_entry)) = self.git_blame_entry_at_cursor(window, cx) else {
+            return;
+        };
+        if let Some(blame) = self.blame.as_ref() {
+            blame.update(cx, |blame, cx| {
+                blame.blame_previous_revision(&blame_entry, cx)
+            });
+        }
+    }
+
+    pub(super) fn reset_blame_revisions(
+        &mut self,
+        _: &ResetBlameRevisions,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(blame) = self.blame.as_ref() {
+            blame.update(cx, |blame, cx| blame.reset_previous_revisions(cx));
+        }
+    }
+
     pub(super) fn toggle_git_blame_inline_internal(
         &mut self,
         user_triggered: bool,
@@ -2153,7 +2359,7 @@ impl Editor {
     ) {
         if let Some(project) = self.project() {
             if let Some(buffer) = self.buffer().read(cx).as_singleton()
-                && buffer.read(cx).file().is_none()
+                && (buffer.read(cx).file().is_none() || buffer.read(cx).is_large_file())
             {
                 return;
             }
@@ -2248,6 +2454,20 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<()> {
+        let (buffer_id, blame_entry) = self.git_blame_entry_at_cursor(window, cx)?;
+        let blame = self.blame.as_ref()?;
+        let renderer = cx.global::<GlobalBlameRenderer>().0.clone();
+        let repo = blame.read(cx).repository(cx, buffer_id)?;
+        let workspace = self.workspace()?.downgrade();
+        renderer.open_blame_commit(blame_entry, repo, workspace, window, cx);
+        None
+    }
+
+    fn git_blame_entry_at_cursor(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<(BufferId, BlameEntry)> {
         let blame = self.blame.as_ref()?;
         let snapshot = self.snapshot(window, cx);
         let cursor = self
@@ -2255,7 +2475,7 @@ impl Editor {
             .newest::<Point>(&snapshot.display_snapshot)
             .head();
         let (buffer, point) = snapshot.buffer_snapshot().point_to_buffer_point(cursor)?;
-        let (_, blame_entry) = blame
+        blame
             .update(cx, |blame, cx| {
                 blame
                     .blame_for_rows(
@@ -2268,12 +2488,7 @@ impl Editor {
                     )
                     .next()
             })
-            .flatten()?;
-        let renderer = cx.global::<GlobalBlameRenderer>().0.clone();
-        let repo = blame.read(cx).repository(cx, buffer.remote_id())?;
-        let workspace = self.workspace()?.downgrade();
-        renderer.open_blame_commit(blame_entry, repo, workspace, window, cx);
-        None
+            .flatten()
     }
 
     fn has_blame_entries(&self, cx: &App) -> bool {
@@ -3153,7 +3368,10 @@ impl Editor {
             let git_store = project.git_store().clone();
             git_store.update(cx, |git_store, cx| {
                 for buffer in buffers {
-                    if project::File::from_dyn(buffer.read(cx).file()).is_some() {
+                    let buffer_ref = buffer.read(cx);
+                    if project::File::from_dyn(buffer_ref.file()).is_some()
+                        && !buffer_ref.is_large_file()
+                    {
                         tasks.push(git_store.open_display_diff(buffer, cx));
                     }
                 }
@@ -6,6 +6,7 @@ use git::{
     GitHostingProviderRegistry, Oid,
     blame::{Blame, BlameEntry},
     commit::ParsedCommitMessage,
+    repository::RepoPath,
 };
 use gpui::{
     AnyElement, App, AppContext as _, Context, Entity, Hsla, Pixels, ScrollHandle, SharedString,
@@ -20,7 +21,7 @@ use project::{
     git_store::{GitStoreEvent, Repository},
 };
 use smallvec::SmallVec;
-use std::{sync::Arc, time::Duration};
+use std::{ops::Range, sync::Arc, time::Duration};
 use sum_tree::SumTree;
 use text::BufferId;
 use workspace::Workspace;
@@ -70,6 +71,7 @@ struct GitBlameBuffer {
     buffer_edits: text::Subscription<usize>,
     commit_details: HashMap<Oid, ParsedCommitMessage>,
     commit_tag_names: HashMap<Oid, Vec<SharedString>>,
+    showing_previous_revision: bool,
 }
 
 pub struct GitBlame {
@@ -344,6 +346,157 @@ impl GitBlame {
         max_author_length
     }
 
+    /// Whether some rows show their blame as of an older revision, after
+    /// [`Self::blame_previous_revision`].
+    pub fn has_previous_revisions(&self) -> bool {
+        self.buffers
+            .values()
+            .any(|buffer| buffer.showing_previous_revision)
+    }
+
+    /// Restores the blame of the current revision for all rows.
+    pub fn reset_previous_revisions(&mut self, cx: &mut Context<Self>) {
+        if self.has_previous_revisions() {
+            self.generate(cx);
+        }
+    }
+
+    /// Replaces the blame of the rows attributed to `entry` with their blame as
+    /// of the revision before `entry`'s commit, which walks back through the
+    /// history of those lines one commit at a time.
+    pub fn blame_previous_revision(&mut self, entry: &BlameEntry, cx: &mut Context<Self>) {
+        self.sync_all(cx);
+        let Some((buffer_id, rows)) = self.rows_for_entry(entry) else {
+            return;
+        };
+        let Some((revision, path)) = entry
+            .previous
+            .as_deref()
+            .and_then(|previous| previous.split_once(' '))
+        else {
+            self.show_error(
+                format!(
+                    "{} added these lines, there is no previous revision to blame",
+                    entry.sha.display_short()
+                ),
+                cx,
+            );
+            return;
+        };
+        let Some(repository) = self.repository(cx, buffer_id) else {
+            return;
+        };
+        let path = match RepoPath::new(path) {
+            Ok(path) => path,
+            Err(error) => {
+                self.show_error(format!("{error:#}"), cx);
+                return;
+            }
+        };
+
+        let start = entry.original_line_number.saturating_sub(1);
+        let remote_url = repository.read(cx).default_remote_url();
+        let blame = repository.update(cx, |repository, cx| {
+            repository.blame_revision(
+                path,
+                revision.to_string(),
+                start..start + rows.len() as u32,
+                cx,
+            )
+        });
+        let entry = entry.clone();
+        cx.spawn(async move |this, cx| {
+            let blame = async { anyhow::Ok(blame.await??) }.await;
+            this.update(cx, |this, cx| match blame {
+                Ok(blame) => this.apply_previous_revision(&entry, start, blame, remote_url, cx),
+                Err(error) => this.show_error(format!("{error:#}"), cx),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn apply_previous_revision(
+        &mut self,
+        entry: &BlameEntry,
+        start: u32,
+        blame: Blame,
+        remote_url: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        // The rows may have been edited or re-blamed while git was running.
+        self.sync_all(cx);
+        let Some((buffer_id, rows)) = self.rows_for_entry(entry) else {
+            return;
+        };
+        let Some(blame_buffer) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+
+        let row_count = rows.len() as u32;
+        let entries = blame
+            .entries
+            .into_iter()
+            .filter_map(|mut entry| {
+                let range_start = entry.range.start.checked_sub(start)?;
+                let range_end = entry.range.end.saturating_sub(start).min(row_count);
+                entry.range = range_start..range_end;
+                (range_start < range_end).then_some(entry)
+            })
+            .collect();
+        let replacement = build_blame_entry_sum_tree(entries, row_count - 1);
+
+        let mut cursor = blame_buffer.entries.cursor::<u32>(());
+        let mut new_entries = cursor.slice(&rows.start, Bias::Right);
+        new_entries.append(replacement, ());
+        cursor.seek(&rows.end, Bias::Right);
+        new_entries.append(cursor.suffix(), ());
+        drop(cursor);
+        blame_buffer.entries = new_entries;
+
+        let provider_registry = GitHostingProviderRegistry::default_global(cx);
+        for (oid, message) in blame.messages {
+            blame_buffer.commit_details.entry(oid).or_insert_with(|| {
+                ParsedCommitMessage::parse(
+                    oid.to_string(),
+                    message,
+                    remote_url.as_deref(),
+                    Some(provider_registry.clone()),
+                )
+            });
+        }
+        for (oid, tag_names) in blame.tag_names {
+            blame_buffer
+                .commit_tag_names
+                .entry(oid)
+                .or_insert_with(|| tag_names.into_iter().map(SharedString::from).collect());
+        }
+        blame_buffer.showing_previous_revision = true;
+        cx.notify();
+    }
+
+    fn rows_for_entry(&self, entry: &BlameEntry) -> Option<(BufferId, Range<u32>)> {
+        self.buffers.iter().find_map(|(&buffer_id, buffer)| {
+            let mut start = 0;
+            for item in buffer.entries.iter() {
+                if item.blame.as_ref() == Some(entry) {
+                    return Some((buffer_id, start..start + item.rows));
+                }
+                start += item.rows;
+            }
+            None
+        })
+    }
+
+    fn show_error(&self, message: String, cx: &mut Context<Self>) {
+        self.project.update(cx, |_, cx| {
+            cx.emit(project::Event::Toast {
+                notification_id: "git-blame".into(),
+                message,
+                link: None,
+            });
+        });
+    }
+
     pub fn blur(&mut self, _: &mut Context<Self>) {
         self.focused = false;
     }
@@ -652,6 +805,7 @@ impl GitBlame {
                             entries,
                             commit_details,
                             commit_tag_names,
+                            showing_previous_revision: false,
                         },
                     );
                 }
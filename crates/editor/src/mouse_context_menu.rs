@@ -217,6 +217,18 @@ pub fn deploy_context_menu(
                         .is_some()
                 });
 
+        let selection_ranges = editor
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|selection| selection.range())
+            .collect::<Vec<_>>();
+        let has_selected_diff_hunks = has_selections
+            && editor
+                .diff_hunks_in_ranges(&selection_ranges, buffer)
+                .next()
+                .is_some();
+
         let evaluate_selection = window.is_action_available(&EvaluateSelectedText, cx);
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
         let format_selections = window.is_action_available(&FormatSelections, cx);
@@ -288,6 +300,12 @@ pub fn deploy_context_menu(
                 .action("Copy", Box::new(Copy))
                 .action("Copy and Trim", Box::new(CopyAndTrim))
                 .action("Paste", Box::new(Paste))
+                .when(has_selected_diff_hunks, |builder| {
+                    builder
+                        .separator()
+                        .action("Stage Selected Lines", Box::new(git::StageLines))
+                        .action("Unstage Selected Lines", Box::new(git::UnstageLines))
+                })
                 .separator()
                 .action_disabled_when(
                     !has_reveal_target,
@@ -0,0 +1,555 @@
+use super::*;
+
+/// One line of a rectangle.
+struct RectangleLine {
+    /// The text of the line inside the rectangle.
+    range: Range<Point>,
+    /// How many columns the line ends before the rectangle's left edge.
+    padding: u32,
+}
+
+impl Editor {
+    /// The lines of the rectangle described by the selections. A selection spanning several lines
+    /// describes the rectangle between its two ends, as in Emacs. Otherwise each selection is one
+    /// line of the rectangle, as after a column selection or a Vim visual block.
+    fn rectangle_lines(&self, display_map: &DisplaySnapshot) -> Vec<RectangleLine> {
+        let selections = self.selections.all::<Point>(display_map);
+        if let [selection] = selections.as_slice()
+            && selection.start.row != selection.end.row
+        {
+            let start = selection.start.to_display_point(display_map);
+            let end = selection.end.to_display_point(display_map);
+            let left = start.column().min(end.column());
+            let right = start.column().max(end.column());
+            return (selection.start.row..=selection.end.row)
+                .map(|row| {
+                    let display_row = Point::new(row, 0).to_display_point(display_map).row();
+                    let line_len = display_map.line_len(display_row);
+                    let point_at_column = |column: u32| {
+                        display_map
+                            .clip_point(
+                                DisplayPoint::new(display_row, column.min(line_len)),
+                                Bias::Left,
+                            )
+                            .to_point(display_map)
+                    };
+                    RectangleLine {
+                        range: point_at_column(left)..point_at_column(right),
+                        padding: left.saturating_sub(line_len),
+                    }
+                })
+                .collect();
+        }
+
+        selections
+            .into_iter()
+            .map(|selection| RectangleLine {
+                range: selection.range(),
+                padding: 0,
+            })
+            .collect()
+    }
+
+    /// The text of the rectangle as a clipboard item, with one clipboard selection per line so
+    /// that pasting into as many cursors gives each cursor its line.
+    fn rectangle_clipboard_item(&self, lines: &[RectangleLine], cx: &App) -> ClipboardItem {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut text = String::new();
+        let mut clipboard_selections = Vec::with_capacity(lines.len());
+        for line in lines {
+            if !clipboard_selections.is_empty() {
+                text.push('\n');
+            }
+            let len = text.len();
+            text.extend(buffer.text_for_range(line.range.clone()));
+            clipboard_selections.push(ClipboardSelection::for_buffer(
+                text.len() - len,
+                false,
+                line.range.clone(),
+                &buffer,
+                self.project.as_ref(),
+                cx,
+            ));
+        }
+        ClipboardItem::new_string_with_json_metadata(text, clipboard_selections)
+    }
+
+    pub fn copy_rectangle(&mut self, _: &CopyRectangle, _: &mut Window, cx: &mut Context<Self>) {
+        let lines = self.rectangle_lines(&self.display_snapshot(cx));
+        let item = self.rectangle_clipboard_item(&lines, cx);
+        cx.write_to_clipboard(item);
+    }
+
+    pub fn kill_rectangle(
+        &mut self,
+        _: &KillRectangle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let lines = self.rectangle_lines(&self.display_snapshot(cx));
+        let item = self.rectangle_clipboard_item(&lines, cx);
+        cx.write_to_clipboard(item);
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let cursor = lines
+            .first()
+            .map(|line| buffer.anchor_before(line.range.start));
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                let edits = lines.iter().map(|line| (line.range.clone(), ""));
+                buffer.edit(edits, None, cx);
+            });
+            this.selection_mark_mode = false;
+            this.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges(cursor.map(|cursor| cursor..cursor));
+            });
+        });
+    }
+
+    pub fn yank_rectangle(
+        &mut self,
+        _: &YankRectangle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+        let Some(entry) = item.entries().iter().find_map(|entry| match entry {
+            ClipboardEntry::String(entry) => Some(entry),
+            _ => None,
+        }) else {
+            return;
+        };
+        let lines = rectangle_lines_from_clipboard(
+            entry.text(),
+            entry.metadata_json::<Vec<ClipboardSelection>>(),
+        );
+
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+        let head = self.selections.newest::<Point>(&display_map).head();
+        let column = head.to_display_point(&display_map).column();
+        let max_point = buffer.max_point();
+
+        let mut edits = Vec::new();
+        let mut appended = String::new();
+        let mut cursor = None;
+        for (ix, line) in lines.iter().enumerate() {
+            let row = head.row + ix as u32;
+            if row > max_point.row {
+                appended.push('\n');
+                appended.extend(iter::repeat_n(' ', column as usize));
+                appended.push_str(line);
+                cursor = Some(buffer.anchor_after(max_point));
+                continue;
+            }
+            let display_row = Point::new(row, 0).to_display_point(&display_map).row();
+            let line_len = display_map.line_len(display_row);
+            let position = display_map
+                .clip_point(
+                    DisplayPoint::new(display_row, column.min(line_len)),
+                    Bias::Left,
+                )
+                .to_point(&display_map);
+            let padding = " ".repeat(column.saturating_sub(line_len) as usize);
+            edits.push((position..position, format!("{padding}{line}")));
+            cursor = Some(buffer.anchor_after(position));
+        }
+        if !appended.is_empty() {
+            edits.push((max_point..max_point, appended));
+        }
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges(cursor.map(|cursor| cursor..cursor));
+            });
+        });
+    }
+
+    pub fn string_rectangle(
+        &mut self,
+        action: &StringRectangle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let lines = self.rectangle_lines(&self.display_snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let (edits, selections): (Vec<_>, Vec<_>) = lines
+            .iter()
+            .map(|line| {
+                let padding = " ".repeat(line.padding as usize);
+                match &action.text {
+                    Some(text) => {
+                        let end = buffer.anchor_after(line.range.end);
+                        ((line.range.clone(), format!("{padding}{text}")), end..end)
+                    }
+                    None => {
+                        let start = if line.padding > 0 {
+                            buffer.anchor_after(line.range.start)
+                        } else {
+                            buffer.anchor_before(line.range.start)
+                        };
+                        let end = buffer.anchor_after(line.range.end);
+                        ((line.range.start..line.range.start, padding), start..end)
+                    }
+                }
+            })
+            .unzip();
+
+        // Typing replaces the rectangle on every line, but a single cursor is
+        // left after the inserted text, as in Emacs.
+        let selections = if action.text.is_some() {
+            selections.into_iter().last().into_iter().collect()
+        } else {
+            selections
+        };
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(
+                    edits
+                        .into_iter()
+                        .filter(|(range, text)| !range.is_empty() || !text.is_empty()),
+                    None,
+                    cx,
+                )
+            });
+            this.selection_mark_mode = false;
+            this.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges(selections);
+            });
+        });
+    }
+
+    pub fn number_rectangle_lines(
+        &mut self,
+        action: &NumberRectangleLines,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let lines = self.rectangle_lines(&self.display_snapshot(cx));
+        let numbers = (0..lines.len() as i64)
+            .map(|ix| {
+                action
+                    .start
+                    .saturating_add(ix.saturating_mul(action.increment))
+            })
+            .collect::<Vec<_>>();
+        let format = action.format.clone().unwrap_or_else(|| {
+            let width = numbers
+                .iter()
+                .map(|number| number.to_string().len())
+                .max()
+                .unwrap_or(1);
+            format!("%{width}d ")
+        });
+
+        let edits = lines
+            .iter()
+            .zip(numbers)
+            .map(|(line, number)| {
+                let padding = " ".repeat(line.padding as usize);
+                let start = line.range.start;
+                (
+                    start..start,
+                    format!("{padding}{}", format_rectangle_number(&format, number)),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.selection_mark_mode = false;
+        });
+    }
+
+    pub fn align_on_delimiter(
+        &mut self,
+        action: &AlignOnDelimiter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) || action.delimiter.is_empty() {
+            return;
+        }
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+
+        // The part of each row that is searched for the delimiter: the
+        // selected text for selections within a row, and whole lines otherwise.
+        let mut rows = BTreeMap::new();
+        for selection in self.selections.all::<Point>(&display_map) {
+            if selection.start.row == selection.end.row && !selection.is_empty() {
+                rows.entry(selection.start.row).or_insert(selection.range());
+                continue;
+            }
+            let mut end_row = selection.end.row;
+            if selection.end.column == 0 && end_row > selection.start.row {
+                end_row -= 1;
+            }
+            for row in selection.start.row..=end_row {
+                let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                rows.entry(row).or_insert(Point::new(row, 0)..line_end);
+            }
+        }
+
+        struct DelimiterLine {
+            /// The whitespace before the delimiter.
+            whitespace: Range<Point>,
+            /// The display column where that whitespace starts.
+            column: u32,
+        }
+        let delimiter_lines = rows
+            .into_values()
+            .filter_map(|range| {
+                let text = buffer.text_for_range(range.clone()).collect::<String>();
+                let delimiter_offset = text.find(&action.delimiter)?;
+                let whitespace_offset = text[..delimiter_offset].trim_end().len();
+                let whitespace = Point::new(
+                    range.start.row,
+                    range.start.column + whitespace_offset as u32,
+                )
+                    ..Point::new(
+                        range.start.row,
+                        range.start.column + delimiter_offset as u32,
+                    );
+                let column = whitespace.start.to_display_point(&display_map).column();
+                Some(DelimiterLine { whitespace, column })
+            })
+            .collect::<Vec<_>>();
+
+        // Keep a space before the delimiter, unless it came right after the
+        // text on every line.
+        let spacing = delimiter_lines
+            .iter()
+            .any(|line| !line.whitespace.is_empty()) as u32;
+        let Some(target_column) = delimiter_lines.iter().map(|line| line.column).max() else {
+            return;
+        };
+        let edits = delimiter_lines
+            .into_iter()
+            .map(|line| {
+                let spaces = target_column - line.column + spacing;
+                (line.whitespace, " ".repeat(spaces as usize))
+            })
+            .collect::<Vec<_>>();
+        self.transact(window, cx, |this, _, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        });
+    }
+}
+
+/// Splits clipboard text into the lines of a rectangle. Text copied from several cursors has a
+/// line per cursor, and other text is split at line breaks.
+fn rectangle_lines_from_clipboard(
+    text: &str,
+    clipboard_selections: Option<Vec<ClipboardSelection>>,
+) -> Vec<String> {
+    if let Some(clipboard_selections) = clipboard_selections.filter(|selections| {
+        selections.len() > 1
+            && selections
+                .iter()
+                .map(|selection| selection.len)
+                .sum::<usize>()
+                + selections.len()
+                - 1
+                == text.len()
+    }) {
+        let mut start = 0;
+        return clipboard_selections
+            .iter()
+            .map(|selection| {
+                let line = text[start..start + selection.len].to_string();
+                start += selection.len + 1;
+                line
+            })
+            .collect();
+    }
+    text.lines().map(str::to_string).collect()
+}
+
+/// Formats a number for [`NumberRectangleLines`], replacing `%d` in the format, optionally with a
+/// width and zero padding such as `%3d` or `%03d`, and `%%` with `%`.
+fn format_rectangle_number(format: &str, number: i64) -> String {
+    let mut result = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            result.push(char);
+            continue;
+        }
+        let mut spec = String::new();
+        while let Some(&next) = chars.peek()
+            && next.is_ascii_digit()
+        {
+            spec.push(next);
+            chars.next();
+        }
+        match chars.next() {
+            Some('d') => {
+                let zero_padded = spec.starts_with('0');
+                let width = spec.parse::<usize>().unwrap_or(0);
+                if zero_padded {
+                    result.push_str(&format!("{number:0width$}"));
+                } else {
+                    result.push_str(&format!("{number:width$}"));
+                }
+            }
+            Some('%') if spec.is_empty() => result.push('%'),
+            next => {
+                result.push('%');
+                result.push_str(&spec);
+                result.extend(next);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+
+    #[gpui::test]
+    async fn test_kill_and_yank_rectangle(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("a«bcd\nefgh\nijˇ»kl");
+        cx.update_editor(|editor, window, cx| editor.kill_rectangle(&KillRectangle, window, cx));
+        cx.assert_editor_state("aˇcd\negh\nikl");
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("b\nf\nj".to_string())
+        );
+
+        cx.set_state("acdˇ\negh\nikl");
+        cx.update_editor(|editor, window, cx| editor.yank_rectangle(&YankRectangle, window, cx));
+        cx.assert_editor_state("acdb\neghf\nikljˇ");
+
+        // Lines are added past the end of the buffer, and padded to the
+        // cursor's column.
+        cx.set_state("x\nyˇ");
+        cx.update_editor(|editor, window, cx| editor.yank_rectangle(&YankRectangle, window, cx));
+        cx.assert_editor_state("x\nyb\n f\n jˇ");
+    }
+
+    #[gpui::test]
+    async fn test_string_rectangle(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("o«ne\ntwo\nthˇ»ree");
+        cx.update_editor(|editor, window, cx| {
+            editor.string_rectangle(
+                &StringRectangle {
+                    text: Some("--".to_string()),
+                },
+                window,
+                cx,
+            )
+        });
+        cx.assert_editor_state("o--e\nt--o\nt--ˇree");
+
+        cx.set_state("long«er\nx\nlongˇ»est");
+        cx.update_editor(|editor, window, cx| {
+            editor.string_rectangle(
+                &StringRectangle {
+                    text: Some("|".to_string()),
+                },
+                window,
+                cx,
+            )
+        });
+        cx.assert_editor_state("long|er\nx   |\nlong|ˇest");
+
+        // Without text, each line of the rectangle gets a cursor.
+        cx.set_state("a«b\nc\ndˇ»e");
+        cx.update_editor(|editor, window, cx| {
+            editor.string_rectangle(&StringRectangle::default(), window, cx)
+        });
+        cx.assert_editor_state("aˇb\ncˇ\ndˇe");
+        cx.simulate_input("X");
+        cx.assert_editor_state("aXˇb\ncXˇ\ndXˇe");
+    }
+
+    #[gpui::test]
+    async fn test_number_rectangle_lines(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("«a\nb\nˇ»c");
+        cx.update_editor(|editor, window, cx| {
+            editor.number_rectangle_lines(&NumberRectangleLines::default(), window, cx)
+        });
+        assert_eq!(cx.buffer_text(), "1 a\n2 b\n3 c");
+
+        cx.set_state("«a\nb\nˇ»c");
+        cx.update_editor(|editor, window, cx| {
+            editor.number_rectangle_lines(
+                &NumberRectangleLines {
+                    format: Some("%02d. ".to_string()),
+                    start: 8,
+                    increment: 2,
+                },
+                window,
+                cx,
+            )
+        });
+        assert_eq!(cx.buffer_text(), "08. a\n10. b\n12. c");
+    }
+
+    #[gpui::test]
+    async fn test_align_on_delimiter(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("«a = 1\nbbb = 2\ncc=3\nnone\nˇ»d = 4");
+        cx.update_editor(|editor, window, cx| {
+            editor.align_on_delimiter(&AlignOnDelimiter::default(), window, cx)
+        });
+        assert_eq!(cx.buffer_text(), "a   = 1\nbbb = 2\ncc  =3\nnone\nd = 4");
+
+        // Selections within a line pick which delimiter to align.
+        cx.set_state("x: «1: aˇ»\nlonger: «22: bˇ»");
+        cx.update_editor(|editor, window, cx| {
+            editor.align_on_delimiter(
+                &AlignOnDelimiter {
+                    delimiter: ":".to_string(),
+                },
+                window,
+                cx,
+            )
+        });
+        assert_eq!(cx.buffer_text(), "x: 1 : a\nlonger: 22: b");
+    }
+
+    #[test]
+    fn test_format_rectangle_number() {
+        assert_eq!(format_rectangle_number("%d. ", 7), "7. ");
+        assert_eq!(format_rectangle_number("%3d|", 7), "  7|");
+        assert_eq!(format_rectangle_number("%03d", 7), "007");
+        assert_eq!(format_rectangle_number("%03d", -7), "-07");
+        assert_eq!(format_rectangle_number("100%% %x %d", 5), "100% %x 5");
+    }
+}
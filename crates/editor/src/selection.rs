@@ -193,6 +193,17 @@ impl Editor {
         cx.notify();
     }
 
+    /// Whether cursor movements extend the selections, as after
+    /// [`actions::SetMark`].
+    pub fn selection_mark_mode(&self) -> bool {
+        self.selection_mark_mode
+    }
+
+    pub fn set_selection_mark_mode(&mut self, selection_mark_mode: bool, cx: &mut Context<Self>) {
+        self.selection_mark_mode = selection_mark_mode;
+        cx.notify();
+    }
+
     pub fn swap_selection_ends(
         &mut self,
         _: &actions::SwapSelectionEnds,
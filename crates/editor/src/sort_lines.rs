@@ -0,0 +1,331 @@
+use super::*;
+use std::sync::LazyLock;
+use util::paths::natural_sort;
+
+static NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"-?\d+").unwrap());
+
+impl SortLines {
+    /// Parses sort options written as flags, as in Vim's `:sort`: `i` ignores case, `n` compares
+    /// numbers, `!` sorts in descending order, `u` keeps only unique lines, `/pattern/` compares
+    /// the text after the pattern's match and `r` compares the match itself. Beyond Vim's flags,
+    /// `v` compares naturally, `k2` adds a key comparing the second field, and `t,` separates that
+    /// key's fields by `,`. Flags before the first `k` apply to every key.
+    ///
+    /// Patterns are converted to regular expressions with `translate_pattern`.
+    pub fn parse_flags(flags: &str, translate_pattern: impl Fn(&str) -> String) -> Result<Self> {
+        struct ParsedKey {
+            key: SortKey,
+            compare_match: bool,
+        }
+
+        let mut defaults = ParsedKey {
+            key: SortKey::default(),
+            compare_match: false,
+        };
+        let mut keys: Vec<ParsedKey> = Vec::new();
+        let mut unique = false;
+        let mut chars = flags.chars().peekable();
+        while let Some(c) = chars.next() {
+            let parsed = keys.last_mut().unwrap_or(&mut defaults);
+            match c {
+                '!' => parsed.key.descending = true,
+                'i' => parsed.key.ignore_case = true,
+                'n' => parsed.key.order = SortOrder::Numeric,
+                'v' => parsed.key.order = SortOrder::Natural,
+                'r' => parsed.compare_match = true,
+                'u' => unique = true,
+                'k' => {
+                    let mut digits = String::new();
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    let field = digits
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|field| *field > 0)
+                        .context("expected a field number after `k`")?;
+                    let key = SortKey {
+                        field: Some(field),
+                        ..defaults.key.clone()
+                    };
+                    keys.push(ParsedKey {
+                        key,
+                        compare_match: defaults.compare_match,
+                    });
+                }
+                't' => {
+                    let delimiter = chars.next().context("expected a delimiter after `t`")?;
+                    parsed.key.delimiter = Some(delimiter.to_string());
+                }
+                c if c.is_whitespace() => {}
+                c if c.is_ascii_punctuation() => {
+                    let mut pattern = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(end) if end == c => break,
+                            Some('\\') if chars.next_if_eq(&c).is_some() => pattern.push(c),
+                            Some('\\') => {
+                                pattern.push('\\');
+                                pattern.extend(chars.next());
+                            }
+                            Some(next) => pattern.push(next),
+                            None => bail!("expected a `{c}` after the pattern"),
+                        }
+                    }
+                    let pattern = translate_pattern(&pattern);
+                    Regex::new(&pattern).context("invalid pattern")?;
+                    parsed.key.pattern = Some(pattern);
+                }
+                c => bail!("unknown sort option `{c}`"),
+            }
+        }
+
+        if keys.is_empty() {
+            keys.push(defaults);
+        }
+        let keys = keys
+            .into_iter()
+            .map(|parsed| SortKey {
+                after_match: parsed.key.pattern.is_some() && !parsed.compare_match,
+                ..parsed.key
+            })
+            .collect();
+        Ok(Self { keys, unique })
+    }
+}
+
+/// A [`SortKey`] with its pattern compiled.
+struct SortKeyMatcher {
+    key: SortKey,
+    pattern: Option<Regex>,
+}
+
+enum SortValue<'a> {
+    Text(Cow<'a, str>),
+    Number(i128),
+}
+
+impl SortKeyMatcher {
+    fn new(key: SortKey) -> Result<Self> {
+        let pattern = key
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("invalid sort pattern")?;
+        Ok(Self { key, pattern })
+    }
+
+    /// The value of the key in the line, which is missing when the line has no such field, the
+    /// pattern doesn't match or there is no number to compare.
+    fn value<'a>(&self, line: &'a str) -> Option<SortValue<'a>> {
+        let mut text = line;
+        if let Some(field) = self.key.field {
+            let index = field.saturating_sub(1);
+            text = match self.key.delimiter.as_deref() {
+                Some(delimiter) if !delimiter.is_empty() => text.split(delimiter).nth(index),
+                _ => text.split_whitespace().nth(index),
+            }?;
+        }
+        if let Some(pattern) = &self.pattern {
+            let captures = pattern.captures(text)?;
+            let whole = captures.get(0)?;
+            text = if self.key.after_match {
+                &text[whole.end()..]
+            } else {
+                captures.get(1).unwrap_or(whole).as_str()
+            };
+        }
+
+        match self.key.order {
+            SortOrder::Numeric => {
+                let number = NUMBER_REGEX.find(text)?.as_str();
+                let value = number.parse().unwrap_or(if number.starts_with('-') {
+                    i128::MIN
+                } else {
+                    i128::MAX
+                });
+                Some(SortValue::Number(value))
+            }
+            _ if self.key.ignore_case => Some(SortValue::Text(Cow::Owned(text.to_lowercase()))),
+            _ => Some(SortValue::Text(Cow::Borrowed(text))),
+        }
+    }
+
+    fn compare(&self, a: &Option<SortValue>, b: &Option<SortValue>) -> Ordering {
+        let ordering = match (a, b) {
+            (Some(SortValue::Number(a)), Some(SortValue::Number(b))) => a.cmp(b),
+            (Some(SortValue::Text(a)), Some(SortValue::Text(b))) => match self.key.order {
+                SortOrder::Natural => natural_sort(a, b),
+                _ => a.cmp(b),
+            },
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+        if self.key.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl Editor {
+    pub fn sort_lines(&mut self, action: &SortLines, window: &mut Window, cx: &mut Context<Self>) {
+        let keys = if action.keys.is_empty() {
+            vec![SortKey::default()]
+        } else {
+            action.keys.clone()
+        };
+        let Some(matchers) = keys
+            .into_iter()
+            .map(SortKeyMatcher::new)
+            .collect::<Result<Vec<_>>>()
+            .log_err()
+        else {
+            return;
+        };
+
+        self.manipulate_immutable_lines(window, cx, |lines| {
+            let mut keyed_lines = lines
+                .drain(..)
+                .map(|line| {
+                    let values = matchers
+                        .iter()
+                        .map(|matcher| matcher.value(line))
+                        .collect::<Vec<_>>();
+                    (values, line)
+                })
+                .collect::<Vec<_>>();
+            keyed_lines.sort_by(|(a, _), (b, _)| compare_sort_values(&matchers, a, b));
+            if action.unique {
+                keyed_lines.dedup_by(|(a, _), (b, _)| compare_sort_values(&matchers, a, b).is_eq());
+            }
+            lines.extend(keyed_lines.into_iter().map(|(_, line)| line));
+        })
+    }
+}
+
+/// Compares the values of each key in turn, until one differs.
+fn compare_sort_values(
+    matchers: &[SortKeyMatcher],
+    a: &[Option<SortValue<'_>>],
+    b: &[Option<SortValue<'_>>],
+) -> Ordering {
+    matchers
+        .iter()
+        .zip(a.iter().zip(b))
+        .map(|(matcher, (a, b))| matcher.compare(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+
+    fn parse(flags: &str) -> SortLines {
+        SortLines::parse_flags(flags, ToString::to_string).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sort_flags() {
+        assert_eq!(
+            parse(""),
+            SortLines {
+                keys: vec![SortKey::default()],
+                unique: false,
+            }
+        );
+        assert_eq!(
+            parse("! i u"),
+            SortLines {
+                keys: vec![SortKey {
+                    ignore_case: true,
+                    descending: true,
+                    ..Default::default()
+                }],
+                unique: true,
+            }
+        );
+        assert_eq!(
+            parse(r"n /a\/b/"),
+            SortLines {
+                keys: vec![SortKey {
+                    pattern: Some("a/b".to_string()),
+                    after_match: true,
+                    order: SortOrder::Numeric,
+                    ..Default::default()
+                }],
+                unique: false,
+            }
+        );
+        assert!(!parse(r"r /\d+/").keys[0].after_match);
+        assert_eq!(
+            parse("i k2t,n! k1").keys,
+            vec![
+                SortKey {
+                    field: Some(2),
+                    delimiter: Some(",".to_string()),
+                    order: SortOrder::Numeric,
+                    ignore_case: true,
+                    descending: true,
+                    ..Default::default()
+                },
+                SortKey {
+                    field: Some(1),
+                    ignore_case: true,
+                    ..Default::default()
+                },
+            ]
+        );
+
+        for invalid in ["x", "k", "k0", "t", "/unterminated", "/(/"] {
+            assert!(
+                SortLines::parse_flags(invalid, ToString::to_string).is_err(),
+                "{invalid:?} should not parse"
+            );
+        }
+    }
+
+    #[gpui::test]
+    async fn test_sort_lines_by_keys(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("«file10\nfile2\nFile1\nfile2ˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse("v"), window, cx));
+        cx.assert_editor_state("«File1\nfile2\nfile2\nfile10ˇ»");
+
+        cx.set_state("«x 10\nno number\ny -3\nz 2ˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse("n"), window, cx));
+        cx.assert_editor_state("«no number\ny -3\nz 2\nx 10ˇ»");
+
+        // Keys are compared in order, and lines with equal keys keep their order.
+        cx.set_state("«b,2,one\na,1,two\nc,2,three\nd,1,fourˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse("t, k2n! k1"), window, cx));
+        cx.assert_editor_state("«b,2,one\nc,2,three\na,1,two\nd,1,fourˇ»");
+
+        // As in Vim, the text after the match is compared, or the match itself with `r`.
+        cx.set_state("«a3 z\nb1 y\nno match\nc2 xˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse(r"/\d /"), window, cx));
+        cx.assert_editor_state("«no match\nc2 x\nb1 y\na3 zˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse(r"r /\d/"), window, cx));
+        cx.assert_editor_state("«no match\nb1 y\nc2 x\na3 zˇ»");
+
+        cx.set_state("«b\nA\na\nB\nbˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse("iu"), window, cx));
+        cx.assert_editor_state("«A\nbˇ»");
+
+        // Each selection is sorted separately, in a single transaction.
+        cx.set_state("«c\nbˇ»\na\n«z\nyˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse("!"), window, cx));
+        cx.assert_editor_state("«c\nbˇ»\na\n«z\nyˇ»");
+        cx.update_editor(|editor, window, cx| editor.sort_lines(&parse(""), window, cx));
+        cx.assert_editor_state("«b\ncˇ»\na\n«y\nzˇ»");
+        cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+        assert_eq!(cx.buffer_text(), "c\nb\na\nz\ny");
+    }
+}
@@ -0,0 +1,352 @@
+//! Stateful Emacs editing commands for the Emacs base keymap: a kill ring with
+//! yank-pop, a mark ring, numeric prefix arguments and incremental search.
+//!
+//! The commands are registered on every editor, and the Emacs keymap binds
+//! them in place of the plain editor actions.
+
+mod isearch;
+mod kill_ring;
+mod mark;
+mod prefix_argument;
+
+use editor::{Addon, Anchor, Editor};
+use gpui::{Action, App, Context, Global, KeyContext, WeakEntity, Window, actions};
+use std::ops::Range;
+use workspace::Workspace;
+
+use crate::{isearch::Isearch, mark::MarkRing, prefix_argument::PrefixArgument};
+
+pub use prefix_argument::DigitArgument;
+
+actions!(
+    emacs,
+    [
+        /// Kills the rest of the line, or the line break at the end of a line.
+        /// With a prefix argument, kills that many lines.
+        KillLine,
+        /// Kills characters forward until the end of a word.
+        KillWord,
+        /// Kills characters backward until the start of a word.
+        BackwardKillWord,
+        /// Kills the text in the region.
+        KillRegion,
+        /// Saves the region to the kill ring without killing it.
+        KillRingSave,
+        /// Inserts the most recent kill. With a numeric prefix argument, inserts
+        /// an older kill instead.
+        Yank,
+        /// Replaces the text that was just yanked with an older kill.
+        YankPop,
+        /// Sets the mark and activates the region. With a prefix argument, jumps
+        /// to the mark and pops the mark ring instead.
+        SetMark,
+        /// Swaps the cursor with the mark, and activates the region.
+        ExchangePointAndMark,
+        /// Starts a prefix argument of 4, or multiplies the current one by 4.
+        UniversalArgument,
+        /// Negates the prefix argument.
+        NegativeArgument,
+        /// Starts an incremental search forward, or moves to the next match.
+        IsearchForward,
+        /// Starts an incremental search backward, or moves to the previous match.
+        IsearchBackward,
+        /// Ends the incremental search at the current match.
+        IsearchExit,
+        /// Cancels the incremental search, moving back to where it started.
+        IsearchAbort,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.set_global(EmacsState::default());
+    prefix_argument::init(cx);
+
+    cx.observe_new(|editor: &mut Editor, _, cx| register_editor(editor, cx))
+        .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| isearch::register(workspace))
+        .detach();
+}
+
+/// State that is shared by all editors, since Emacs commands like yank-pop
+/// depend on the previous command.
+#[derive(Default)]
+struct EmacsState {
+    /// The name of the last command run from the keyboard, ignoring prefix
+    /// arguments.
+    last_command: Option<&'static str>,
+    prefix_argument: Option<PrefixArgument>,
+    /// The editor in which the pending prefix argument was typed.
+    prefix_argument_editor: Option<WeakEntity<Editor>>,
+    isearch: Option<Isearch>,
+}
+
+impl Global for EmacsState {}
+
+impl EmacsState {
+    /// Whether the last command was one of the given actions, by name.
+    fn last_command_was(cx: &App, names: &[&str]) -> bool {
+        cx.try_global::<Self>()
+            .and_then(|state| state.last_command)
+            .is_some_and(|last_command| names.contains(&last_command))
+    }
+}
+
+/// The per-editor Emacs state.
+#[derive(Default)]
+struct EmacsAddon {
+    mark_ring: MarkRing,
+    /// The ranges inserted by the last yank, which yank-pop replaces.
+    last_yank: Option<Vec<Range<Anchor>>>,
+}
+
+impl Addon for EmacsAddon {
+    fn extend_key_context(&self, key_context: &mut KeyContext, cx: &App) {
+        if cx
+            .try_global::<EmacsState>()
+            .and_then(|state| state.prefix_argument.as_ref())
+            .is_some_and(|argument| argument.accepts_digits())
+        {
+            key_context.add("emacs_prefix_argument");
+        }
+    }
+
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+fn register_editor(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    editor.register_addon(EmacsAddon::default());
+
+    register_action(editor, cx, kill_ring::kill_line);
+    register_action(editor, cx, kill_ring::kill_word);
+    register_action(editor, cx, kill_ring::backward_kill_word);
+    register_action(editor, cx, kill_ring::kill_region);
+    register_action(editor, cx, kill_ring::kill_ring_save);
+    register_action(editor, cx, kill_ring::yank);
+    register_action(editor, cx, kill_ring::yank_pop);
+    register_action(editor, cx, mark::set_mark);
+    register_action(editor, cx, mark::exchange_point_and_mark);
+    register_action(editor, cx, prefix_argument::universal_argument);
+    register_action(editor, cx, prefix_argument::digit_argument);
+    register_action(editor, cx, prefix_argument::negative_argument);
+    register_action(editor, cx, |editor, _: &IsearchForward, window, cx| {
+        isearch::start(editor, false, window, cx)
+    });
+    register_action(editor, cx, |editor, _: &IsearchBackward, window, cx| {
+        isearch::start(editor, true, window, cx)
+    });
+}
+
+fn register_action<A: Action>(
+    editor: &mut Editor,
+    cx: &mut Context<Editor>,
+    listener: fn(&mut Editor, &A, &mut Window, &mut Context<Editor>),
+) {
+    let handle = cx.weak_entity();
+    editor
+        .register_action(move |action: &A, window, cx| {
+            handle
+                .update(cx, |editor, cx| listener(editor, action, window, cx))
+                .ok();
+        })
+        .detach();
+}
+
+fn addon_mut(editor: &mut Editor) -> Option<&mut EmacsAddon> {
+    editor.addon_mut::<EmacsAddon>()
+}
+
+#[cfg(test)]
+mod tests {
+    use editor::test::editor_test_context::EditorTestContext;
+    use gpui::{KeyBinding, TestAppContext};
+
+    use super::*;
+
+    async fn init_test(cx: &mut TestAppContext) -> EditorTestContext {
+        cx.update(|cx| {
+            let store = settings::SettingsStore::test(cx);
+            cx.set_global(store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init(semver::Version::new(0, 0, 0), cx);
+            editor::init(cx);
+            init(cx);
+            cx.bind_keys([
+                KeyBinding::new("ctrl-k", KillLine, Some("Editor")),
+                KeyBinding::new("alt-d", KillWord, Some("Editor")),
+                KeyBinding::new("alt-backspace", BackwardKillWord, Some("Editor")),
+                KeyBinding::new("ctrl-w", KillRegion, Some("Editor")),
+                KeyBinding::new("alt-w", KillRingSave, Some("Editor")),
+                KeyBinding::new("ctrl-y", Yank, Some("Editor")),
+                KeyBinding::new("alt-y", YankPop, Some("Editor")),
+                KeyBinding::new("ctrl-space", SetMark, Some("Editor")),
+                KeyBinding::new("ctrl-x ctrl-x", ExchangePointAndMark, Some("Editor")),
+                KeyBinding::new("ctrl-u", UniversalArgument, Some("Editor")),
+                KeyBinding::new("alt-5", DigitArgument(5), Some("Editor")),
+                KeyBinding::new("ctrl-f", editor::actions::MoveRight, Some("Editor")),
+                KeyBinding::new(
+                    "ctrl-f",
+                    editor::actions::SelectRight,
+                    Some("Editor && selection_mode"),
+                ),
+            ]);
+            cx.bind_keys((0..10).map(|digit| {
+                KeyBinding::new(
+                    &digit.to_string(),
+                    DigitArgument(digit),
+                    Some("Editor && emacs_prefix_argument"),
+                )
+            }));
+            cx.bind_keys([KeyBinding::new(
+                "-",
+                NegativeArgument,
+                Some("Editor && emacs_prefix_argument"),
+            )]);
+        });
+        EditorTestContext::new(cx).await
+    }
+
+    #[gpui::test]
+    async fn test_prefix_argument(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state("ˇabcdefghijklmnopqrstuvwxyz");
+        cx.simulate_keystrokes("ctrl-u ctrl-f");
+        cx.assert_editor_state("abcdˇefghijklmnopqrstuvwxyz");
+        cx.simulate_keystrokes("ctrl-u 3 ctrl-f");
+        cx.assert_editor_state("abcdefgˇhijklmnopqrstuvwxyz");
+        cx.simulate_keystrokes("alt-5 ctrl-f");
+        cx.assert_editor_state("abcdefghijklˇmnopqrstuvwxyz");
+        cx.simulate_keystrokes("ctrl-u ctrl-u ctrl-f");
+        cx.assert_editor_state("abcdefghijklmnopqrstuvwxyzˇ");
+
+        // Digits are only part of the argument right after `ctrl-u`.
+        cx.set_state("ˇ");
+        cx.simulate_keystrokes("ctrl-u 3 x y 3");
+        cx.assert_editor_state("xxxy3ˇ");
+
+        // `ctrl-u` after digits ends the argument, so digits can be repeated.
+        cx.simulate_keystrokes("ctrl-u 2 ctrl-u 0");
+        cx.assert_editor_state("xxxy300ˇ");
+    }
+
+    #[gpui::test]
+    async fn test_consecutive_kills_append(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state("ˇone two three\nfour");
+        cx.simulate_keystrokes("alt-d alt-d ctrl-k");
+        cx.assert_editor_state("ˇ\nfour");
+        cx.simulate_keystrokes("ctrl-k");
+        cx.assert_editor_state("ˇfour");
+        cx.simulate_keystrokes("ctrl-y");
+        cx.assert_editor_state("one two three\nˇfour");
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("one two three\n".to_string())
+        );
+
+        // Backward kills prepend to the kill.
+        cx.set_state("one twoˇ");
+        cx.simulate_keystrokes("alt-backspace alt-backspace");
+        cx.assert_editor_state("ˇ");
+        cx.simulate_keystrokes("ctrl-y");
+        cx.assert_editor_state("one twoˇ");
+    }
+
+    #[gpui::test]
+    async fn test_yank_pop(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state("ˇa b c");
+        cx.simulate_keystrokes("alt-d ctrl-f alt-d");
+        cx.assert_editor_state(" ˇ c");
+        cx.simulate_keystrokes("ctrl-y");
+        cx.assert_editor_state(" bˇ c");
+        cx.simulate_keystrokes("alt-y");
+        cx.assert_editor_state(" aˇ c");
+        cx.simulate_keystrokes("alt-y");
+        cx.assert_editor_state(" bˇ c");
+
+        // Yank-pop only replaces text that was just yanked.
+        cx.simulate_keystrokes("ctrl-f alt-y");
+        cx.assert_editor_state(" b ˇc");
+
+        // A numeric argument yanks an older kill.
+        cx.set_state("ˇ");
+        cx.simulate_keystrokes("ctrl-u 2 ctrl-y");
+        cx.assert_editor_state("aˇ");
+
+        // Text copied elsewhere becomes the next yank.
+        cx.write_to_clipboard(gpui::ClipboardItem::new_string("copied".to_string()));
+        cx.simulate_keystrokes("ctrl-y alt-y");
+        cx.assert_editor_state("abˇ");
+    }
+
+    #[gpui::test]
+    async fn test_kill_line(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state("aˇbc\ndef");
+        cx.simulate_keystrokes("ctrl-k");
+        cx.assert_editor_state("aˇ\ndef");
+
+        // The line break is killed when only whitespace follows the cursor.
+        cx.set_state("abcˇ   \ndef");
+        cx.simulate_keystrokes("ctrl-k");
+        cx.assert_editor_state("abcˇdef");
+
+        cx.set_state("one\ntwˇo\nthree\nfour");
+        cx.simulate_keystrokes("ctrl-u 2 ctrl-k");
+        cx.assert_editor_state("one\ntwˇfour");
+
+        cx.set_state("one\ntwo\nthrˇee");
+        cx.simulate_keystrokes("ctrl-u - ctrl-k");
+        cx.assert_editor_state("one\nˇee");
+    }
+
+    #[gpui::test]
+    async fn test_exchange_point_and_mark(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state("ˇone two");
+        cx.simulate_keystrokes("ctrl-space ctrl-f ctrl-f ctrl-f");
+        cx.assert_editor_state("«oneˇ» two");
+        cx.simulate_keystrokes("ctrl-x ctrl-x");
+        cx.assert_editor_state("«ˇone» two");
+        cx.simulate_keystrokes("ctrl-x ctrl-x alt-w");
+        cx.assert_editor_state("oneˇ two");
+
+        // The region can be reactivated from the mark.
+        cx.simulate_keystrokes("ctrl-f ctrl-x ctrl-x");
+        cx.assert_editor_state("«ˇone »two");
+        cx.simulate_keystrokes("ctrl-w");
+        cx.assert_editor_state("ˇtwo");
+    }
+
+    #[gpui::test]
+    async fn test_mark_ring(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        // Setting the mark twice leaves the region inactive, and `ctrl-u
+        // ctrl-space` jumps back through previous marks.
+        cx.set_state("ˇone two three");
+        cx.simulate_keystrokes("ctrl-space ctrl-space ctrl-u 4 ctrl-f");
+        cx.assert_editor_state("one ˇtwo three");
+        cx.simulate_keystrokes("ctrl-space ctrl-space ctrl-u 4 ctrl-f");
+        cx.assert_editor_state("one two ˇthree");
+        cx.simulate_keystrokes("ctrl-u ctrl-space");
+        cx.assert_editor_state("one ˇtwo three");
+        cx.simulate_keystrokes("ctrl-u ctrl-space");
+        cx.assert_editor_state("ˇone two three");
+    }
+}
@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+use editor::{Anchor, Editor, EditorSettings, SelectionEffects};
+use gpui::{App, Context, Entity, Subscription, WeakEntity, Window};
+use search::{BufferSearchBar, SearchOptions, buffer_search};
+use settings::Settings as _;
+use workspace::{Workspace, searchable::Direction};
+
+use crate::{
+    EmacsState, IsearchAbort, IsearchBackward, IsearchExit, IsearchForward, addon_mut,
+    prefix_argument,
+};
+
+/// An incremental search in progress, which runs in the buffer search bar.
+pub(crate) struct Isearch {
+    editor: WeakEntity<Editor>,
+    /// The selections when the search started, which aborting restores and
+    /// exiting pushes as the mark.
+    origin: Vec<Range<Anchor>>,
+    backwards: bool,
+    _dismissed: Subscription,
+}
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &IsearchForward, window, cx| {
+        search_again(workspace, false, window, cx)
+    });
+    workspace.register_action(|workspace, _: &IsearchBackward, window, cx| {
+        search_again(workspace, true, window, cx)
+    });
+    workspace.register_action(|workspace, _: &IsearchExit, window, cx| exit(workspace, window, cx));
+    workspace
+        .register_action(|workspace, _: &IsearchAbort, window, cx| abort(workspace, window, cx));
+}
+
+/// Opens the search bar for an incremental search from the editor's cursors.
+pub(crate) fn start(
+    editor: &mut Editor,
+    backwards: bool,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    prefix_argument::take(cx);
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    let pane = workspace.read(cx).active_pane().clone();
+    if !pane.read(cx).focus_handle(cx).contains_focused(window, cx) {
+        return;
+    }
+    let Some(search_bar) = pane
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>()
+    else {
+        return;
+    };
+
+    let origin = editor.selections.disjoint_anchor_ranges().collect();
+    let shown = search_bar.update(cx, |search_bar, cx| {
+        if !search_bar.show(window, cx) {
+            return false;
+        }
+        search_bar.select_query(window, cx);
+        cx.focus_self(window);
+
+        let mut options = SearchOptions::NONE;
+        if backwards {
+            options |= SearchOptions::BACKWARDS;
+        }
+        if EditorSettings::get_global(cx).search.case_sensitive {
+            options |= SearchOptions::CASE_SENSITIVE;
+        }
+        search_bar.set_search_options(options, cx);
+        true
+    });
+    if !shown {
+        return;
+    }
+
+    // Dismissing the search bar any other way leaves the cursor at the match.
+    let dismissed = cx.subscribe(&search_bar, |_, _, event: &buffer_search::Event, cx| {
+        if let buffer_search::Event::Dismissed = event {
+            cx.default_global::<EmacsState>().isearch = None;
+        }
+    });
+    cx.default_global::<EmacsState>().isearch = Some(Isearch {
+        editor: cx.weak_entity(),
+        origin,
+        backwards,
+        _dismissed: dismissed,
+    });
+}
+
+/// The search bar of the active pane, when it has focus.
+fn active_search_bar(workspace: &Workspace, cx: &App) -> Option<Entity<BufferSearchBar>> {
+    let search_bar = workspace
+        .active_pane()
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>()?;
+    search_bar
+        .read(cx)
+        .query_editor_focused()
+        .then_some(search_bar)
+}
+
+/// Moves to the next match in the given direction. Repeating `C-s` right
+/// after starting the search searches for the previous query again.
+fn search_again(
+    workspace: &mut Workspace,
+    backwards: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(search_bar) = active_search_bar(workspace, cx) else {
+        return;
+    };
+    if let Some(isearch) = cx.default_global::<EmacsState>().isearch.as_mut() {
+        isearch.backwards = backwards;
+    }
+    if search_bar.read(cx).query(cx).is_empty() {
+        window.dispatch_action(Box::new(search::PreviousHistoryQuery), cx);
+        return;
+    }
+    let direction = if backwards {
+        Direction::Prev
+    } else {
+        Direction::Next
+    };
+    search_bar.update(cx, |search_bar, cx| {
+        search_bar.select_match(direction, 1, window, cx);
+    });
+}
+
+/// Ends the search with the cursor after the match, or before it when
+/// searching backwards, and the mark where the search started.
+fn exit(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(search_bar) = active_search_bar(workspace, cx) else {
+        return;
+    };
+    let isearch = cx.default_global::<EmacsState>().isearch.take();
+    search_bar.update(cx, |search_bar, cx| {
+        search_bar.dismiss(&buffer_search::Dismiss, window, cx);
+    });
+    let Some(isearch) = isearch else {
+        return;
+    };
+    let Some(editor) = isearch.editor.upgrade() else {
+        return;
+    };
+    editor.update(cx, |editor, cx| {
+        editor.set_selection_mark_mode(false, cx);
+        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+            s.move_with(&mut |_, selection| {
+                let cursor = if isearch.backwards {
+                    selection.start
+                } else {
+                    selection.end
+                };
+                selection.collapse_to(cursor, selection.goal);
+            });
+        });
+        if let Some(origin) = isearch.origin.last()
+            && let Some(addon) = addon_mut(editor)
+        {
+            addon.mark_ring.push(origin.start);
+        }
+    });
+}
+
+/// Ends the search and restores the selections from before it.
+fn abort(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(search_bar) = active_search_bar(workspace, cx) else {
+        return;
+    };
+    let isearch = cx.default_global::<EmacsState>().isearch.take();
+    search_bar.update(cx, |search_bar, cx| {
+        search_bar.dismiss(&buffer_search::Dismiss, window, cx);
+    });
+    let Some(isearch) = isearch else {
+        return;
+    };
+    let Some(editor) = isearch.editor.upgrade() else {
+        return;
+    };
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+            s.select_anchor_ranges(isearch.origin);
+        });
+    });
+}
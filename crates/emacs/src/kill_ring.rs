@@ -0,0 +1,419 @@
+use std::{collections::VecDeque, ops::Range};
+
+use editor::{
+    Anchor, ClipboardSelection, Editor, SelectionEffects, ToPoint as _,
+    display_map::ToDisplayPoint as _, movement,
+};
+use gpui::{Action as _, App, ClipboardEntry, ClipboardItem, Context, Global, Window};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+
+use crate::{
+    BackwardKillWord, EmacsState, KillLine, KillRegion, KillRingSave, KillWord, Yank, YankPop,
+    addon_mut, prefix_argument,
+};
+
+/// The number of kills that are remembered, like `kill-ring-max`.
+const KILL_RING_MAX: usize = 120;
+
+#[derive(Clone, Debug)]
+struct Kill {
+    text: String,
+    /// How the text splits between cursors, when it was killed from several.
+    clipboard_selections: Option<Vec<ClipboardSelection>>,
+}
+
+impl From<&Kill> for ClipboardItem {
+    fn from(kill: &Kill) -> Self {
+        match &kill.clipboard_selections {
+            Some(clipboard_selections) => ClipboardItem::new_string_with_json_metadata(
+                kill.text.clone(),
+                clipboard_selections.clone(),
+            ),
+            None => ClipboardItem::new_string(kill.text.clone()),
+        }
+    }
+}
+
+/// The kills shared by all editors, most recent first.
+#[derive(Default)]
+struct KillRing {
+    kills: VecDeque<Kill>,
+    /// The kill that the last yank or yank-pop inserted.
+    yank_index: usize,
+}
+
+impl Global for KillRing {}
+
+impl KillRing {
+    fn push(&mut self, kill: Kill) {
+        self.kills.push_front(kill);
+        self.kills.truncate(KILL_RING_MAX);
+        self.yank_index = 0;
+    }
+
+    /// Adds text copied elsewhere to the ring, so that it is the next yank.
+    fn sync_with_clipboard(&mut self, cx: &App) {
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+        let Some(entry) = item.entries().iter().find_map(|entry| match entry {
+            ClipboardEntry::String(entry) => Some(entry),
+            _ => None,
+        }) else {
+            return;
+        };
+        if self
+            .kills
+            .front()
+            .is_some_and(|kill| kill.text == entry.text())
+        {
+            return;
+        }
+        self.push(Kill {
+            text: entry.text().to_owned(),
+            clipboard_selections: entry.metadata_json::<Vec<ClipboardSelection>>(),
+        });
+    }
+}
+
+/// Whether a kill appends to the previous one, as consecutive kills in Emacs
+/// build up a single entry.
+fn last_command_was_kill(cx: &App) -> bool {
+    EmacsState::last_command_was(
+        cx,
+        &[
+            KillLine::name_for_type(),
+            KillWord::name_for_type(),
+            BackwardKillWord::name_for_type(),
+            KillRegion::name_for_type(),
+            KillRingSave::name_for_type(),
+        ],
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum KillDirection {
+    Forward,
+    Backward,
+}
+
+/// Adds the text in `ranges` to the kill ring and the clipboard, and deletes
+/// it unless `delete` is false.
+fn kill(
+    editor: &mut Editor,
+    ranges: Vec<Range<Point>>,
+    direction: KillDirection,
+    delete: bool,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    if ranges.iter().all(|range| range.is_empty()) || (delete && editor.read_only(cx)) {
+        return;
+    }
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let mut text = String::new();
+    let mut clipboard_selections = Vec::with_capacity(ranges.len());
+    for range in &ranges {
+        if !clipboard_selections.is_empty() {
+            text.push('\n');
+        }
+        let len = text.len();
+        text.extend(buffer.text_for_range(range.clone()));
+        clipboard_selections.push(ClipboardSelection::for_buffer(
+            text.len() - len,
+            false,
+            range.clone(),
+            &buffer,
+            editor.project(),
+            cx,
+        ));
+    }
+
+    let kill = if ranges.len() == 1 {
+        Kill {
+            text,
+            clipboard_selections: None,
+        }
+    } else {
+        Kill {
+            text,
+            clipboard_selections: Some(clipboard_selections),
+        }
+    };
+    let append = last_command_was_kill(cx) && kill.clipboard_selections.is_none();
+    let kill_ring = cx.default_global::<KillRing>();
+    match kill_ring.kills.front_mut() {
+        Some(previous) if append && previous.clipboard_selections.is_none() => {
+            match direction {
+                KillDirection::Forward => previous.text.push_str(&kill.text),
+                KillDirection::Backward => previous.text.insert_str(0, &kill.text),
+            }
+            kill_ring.yank_index = 0;
+        }
+        _ => kill_ring.push(kill),
+    }
+    let item = ClipboardItem::from(&kill_ring.kills[0]);
+    cx.write_to_clipboard(item);
+
+    if delete {
+        editor.transact(window, cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                s.select_ranges(ranges);
+            });
+            editor.insert("", window, cx);
+        });
+    }
+}
+
+fn cursor_points(editor: &Editor, cx: &mut Context<Editor>) -> Vec<Point> {
+    editor
+        .selections
+        .all::<Point>(&editor.display_snapshot(cx))
+        .into_iter()
+        .map(|selection| selection.head())
+        .collect()
+}
+
+pub(crate) fn kill_line(
+    editor: &mut Editor,
+    _: &KillLine,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let count = prefix_argument::take(cx).map(|argument| argument.value());
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let max_point = buffer.max_point();
+    let mut direction = KillDirection::Forward;
+    let ranges = cursor_points(editor, cx)
+        .into_iter()
+        .map(|head| match count {
+            None => {
+                let line_end = Point::new(head.row, buffer.line_len(MultiBufferRow(head.row)));
+                let rest_is_blank = buffer
+                    .text_for_range(head..line_end)
+                    .all(|chunk| chunk.chars().all(char::is_whitespace));
+                if rest_is_blank {
+                    head..Point::new(head.row + 1, 0).min(max_point)
+                } else {
+                    head..line_end
+                }
+            }
+            Some(count) if count > 0 => {
+                let end_row = head.row.saturating_add(count as u32);
+                head..Point::new(end_row, 0).min(max_point)
+            }
+            Some(count) => {
+                direction = KillDirection::Backward;
+                let start_row = head.row.saturating_sub(count.unsigned_abs() as u32);
+                Point::new(start_row, 0)..head
+            }
+        })
+        .collect();
+    kill(editor, ranges, direction, true, window, cx);
+}
+
+pub(crate) fn kill_word(
+    editor: &mut Editor,
+    _: &KillWord,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let count = prefix_argument::take_count(cx);
+    kill_words(editor, count, window, cx);
+}
+
+pub(crate) fn backward_kill_word(
+    editor: &mut Editor,
+    _: &BackwardKillWord,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let count = prefix_argument::take_count(cx);
+    kill_words(editor, -count, window, cx);
+}
+
+/// Kills `count` words forward from each cursor, or backward if `count` is
+/// negative.
+fn kill_words(editor: &mut Editor, count: i64, window: &mut Window, cx: &mut Context<Editor>) {
+    let display_map = editor.display_snapshot(cx);
+    let direction = if count < 0 {
+        KillDirection::Backward
+    } else {
+        KillDirection::Forward
+    };
+    let ranges = cursor_points(editor, cx)
+        .into_iter()
+        .map(|head| {
+            let mut cursor = head.to_display_point(&display_map);
+            for _ in 0..count.unsigned_abs() {
+                cursor = match direction {
+                    KillDirection::Forward => movement::next_word_end(&display_map, cursor),
+                    KillDirection::Backward => movement::previous_word_start(&display_map, cursor),
+                };
+            }
+            let cursor = cursor.to_point(&display_map);
+            head.min(cursor)..head.max(cursor)
+        })
+        .collect();
+    kill(editor, ranges, direction, true, window, cx);
+}
+
+/// The ranges between each cursor and the mark. When no region is active, the
+/// region of the newest cursor ends at the mark set by [`crate::SetMark`].
+fn region_ranges(editor: &mut Editor, cx: &mut Context<Editor>) -> Vec<Range<Point>> {
+    let display_map = editor.display_snapshot(cx);
+    let selections = editor.selections.all::<Point>(&display_map);
+    if selections.iter().any(|selection| !selection.is_empty()) {
+        return selections
+            .into_iter()
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| selection.range())
+            .collect();
+    }
+
+    let head = editor.selections.newest::<Point>(&display_map).head();
+    let Some(mark) = addon_mut(editor).and_then(|addon| addon.mark_ring.mark()) else {
+        return Vec::new();
+    };
+    let mark = mark.to_point(display_map.buffer_snapshot());
+    vec![head.min(mark)..head.max(mark)]
+}
+
+pub(crate) fn kill_region(
+    editor: &mut Editor,
+    _: &KillRegion,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    prefix_argument::take(cx);
+    let ranges = region_ranges(editor, cx);
+    editor.set_selection_mark_mode(false, cx);
+    kill(editor, ranges, KillDirection::Forward, true, window, cx);
+}
+
+pub(crate) fn kill_ring_save(
+    editor: &mut Editor,
+    _: &KillRingSave,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    prefix_argument::take(cx);
+    let ranges = region_ranges(editor, cx);
+    editor.set_selection_mark_mode(false, cx);
+    kill(editor, ranges, KillDirection::Forward, false, window, cx);
+    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+        s.move_with(&mut |_, selection| {
+            selection.collapse_to(selection.head(), selection.goal);
+        });
+    });
+}
+
+pub(crate) fn yank(editor: &mut Editor, _: &Yank, window: &mut Window, cx: &mut Context<Editor>) {
+    let argument = prefix_argument::take(cx);
+    cx.default_global::<KillRing>();
+    cx.update_global::<KillRing, _>(|kill_ring, cx| kill_ring.sync_with_clipboard(cx));
+
+    // `C-u C-y` yanks the most recent kill but leaves the cursor before it,
+    // and `C-u N C-y` yanks the Nth most recent kill.
+    let cursor_at_start = argument.is_some_and(|argument| argument.is_raw());
+    let index = match argument {
+        Some(argument) if !argument.is_raw() => argument.value() - 1,
+        _ => 0,
+    };
+    insert_kill(editor, index, cursor_at_start, window, cx);
+}
+
+pub(crate) fn yank_pop(
+    editor: &mut Editor,
+    _: &YankPop,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let count = prefix_argument::take_count(cx);
+    if !EmacsState::last_command_was(cx, &[Yank::name_for_type(), YankPop::name_for_type()]) {
+        log::info!("yank-pop must follow a yank");
+        return;
+    }
+    let Some(last_yank) = addon_mut(editor).and_then(|addon| addon.last_yank.clone()) else {
+        return;
+    };
+
+    // Yank-pop replaces the text that the last yank inserted, which is only
+    // still there if the cursors haven't moved since.
+    let display_map = editor.display_snapshot(cx);
+    let buffer = display_map.buffer_snapshot();
+    let selections = editor.selections.all::<Point>(&display_map);
+    let cursors_at_yank = selections.len() == last_yank.len()
+        && selections.iter().zip(&last_yank).all(|(selection, range)| {
+            let start = range.start.to_point(buffer);
+            let end = range.end.to_point(buffer);
+            selection.is_empty() && (selection.head() == start || selection.head() == end)
+        });
+    if !cursors_at_yank {
+        return;
+    }
+
+    let index = cx.global::<KillRing>().yank_index as i64 + count;
+    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+        s.select_anchor_ranges(last_yank);
+    });
+    insert_kill(editor, index, false, window, cx);
+}
+
+/// Replaces the selections with the kill at `index`, counting from the most
+/// recent and wrapping around the ring.
+fn insert_kill(
+    editor: &mut Editor,
+    index: i64,
+    cursor_at_start: bool,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let kill_ring = cx.default_global::<KillRing>();
+    if kill_ring.kills.is_empty() {
+        return;
+    }
+    let index = index.rem_euclid(kill_ring.kills.len() as i64) as usize;
+    kill_ring.yank_index = index;
+    let kill = kill_ring.kills[index].clone();
+
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let starts: Vec<Anchor> = editor
+        .selections
+        .all::<Point>(&editor.display_snapshot(cx))
+        .into_iter()
+        .map(|selection| buffer.anchor_before(selection.start))
+        .collect();
+    editor.do_paste(&kill.text, kill.clipboard_selections, false, window, cx);
+
+    let ends: Vec<Anchor> = editor
+        .selections
+        .disjoint_anchors()
+        .iter()
+        .map(|selection| selection.head())
+        .collect();
+    let yanked: Vec<Range<Anchor>> = starts
+        .into_iter()
+        .zip(ends)
+        .map(|(start, end)| start..end)
+        .collect();
+
+    // The mark is left at the other end of the yanked text.
+    if let Some(addon) = addon_mut(editor) {
+        if let Some(yanked) = yanked.last() {
+            let mark = if cursor_at_start {
+                yanked.end
+            } else {
+                yanked.start
+            };
+            addon.mark_ring.push(mark);
+        }
+        addon.last_yank = Some(yanked.clone());
+    }
+    if cursor_at_start {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_anchor_ranges(yanked.into_iter().map(|range| range.start..range.start));
+        });
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+
+use editor::{Anchor, Editor, SelectionEffects};
+use gpui::{Action as _, Context, Window};
+
+use crate::{EmacsState, ExchangePointAndMark, SetMark, addon_mut, prefix_argument};
+
+/// The number of previous marks that are remembered, like `mark-ring-max`.
+const MARK_RING_MAX: usize = 16;
+
+/// The mark and the marks that were set before it, for one editor.
+#[derive(Default)]
+pub(crate) struct MarkRing {
+    mark: Option<Anchor>,
+    ring: VecDeque<Anchor>,
+}
+
+impl MarkRing {
+    pub(crate) fn mark(&self) -> Option<Anchor> {
+        self.mark
+    }
+
+    /// Sets the mark, remembering the previous one in the ring.
+    pub(crate) fn push(&mut self, anchor: Anchor) {
+        if self.mark == Some(anchor) {
+            return;
+        }
+        if let Some(previous) = self.mark.replace(anchor) {
+            self.ring.push_front(previous);
+            self.ring.truncate(MARK_RING_MAX);
+        }
+    }
+
+    /// Returns the mark to jump to, and rotates the ring so that the mark
+    /// becomes the one set before it.
+    fn pop(&mut self) -> Option<Anchor> {
+        let mark = self.mark?;
+        if let Some(previous) = self.ring.pop_front() {
+            self.ring.push_back(mark);
+            self.mark = Some(previous);
+        }
+        Some(mark)
+    }
+}
+
+pub(crate) fn set_mark(
+    editor: &mut Editor,
+    _: &SetMark,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    if prefix_argument::take(cx).is_some() {
+        pop_to_mark(editor, window, cx);
+        return;
+    }
+
+    // Pressing `C-SPC` twice sets the mark without activating the region.
+    if editor.selection_mark_mode() && EmacsState::last_command_was(cx, &[SetMark::name_for_type()])
+    {
+        collapse_selections(editor, window, cx);
+        editor.set_selection_mark_mode(false, cx);
+        return;
+    }
+
+    let head = editor.selections.newest_anchor().head();
+    if let Some(addon) = addon_mut(editor) {
+        addon.mark_ring.push(head);
+    }
+    editor.set_mark(&editor::actions::SetMark, window, cx);
+}
+
+fn pop_to_mark(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let Some(mark) = addon_mut(editor).and_then(|addon| addon.mark_ring.pop()) else {
+        return;
+    };
+    editor.set_selection_mark_mode(false, cx);
+    editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+        s.select_anchor_ranges([mark..mark]);
+    });
+}
+
+pub(crate) fn exchange_point_and_mark(
+    editor: &mut Editor,
+    _: &ExchangePointAndMark,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    prefix_argument::take(cx);
+    let newest = editor.selections.newest_anchor().clone();
+    if editor.selection_mark_mode() || newest.start != newest.end {
+        editor.swap_selection_ends(&editor::actions::SwapSelectionEnds, window, cx);
+        editor.set_selection_mark_mode(true, cx);
+        if let Some(addon) = addon_mut(editor) {
+            addon.mark_ring.mark = Some(newest.head());
+        }
+        return;
+    }
+
+    let Some(mark) = addon_mut(editor).and_then(|addon| addon.mark_ring.mark()) else {
+        return;
+    };
+    let head = newest.head();
+    editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+        // Selecting from the cursor to the mark leaves the cursor at the mark.
+        s.select_anchor_ranges([head..mark]);
+    });
+    if let Some(addon) = addon_mut(editor) {
+        addon.mark_ring.mark = Some(head);
+    }
+    editor.set_selection_mark_mode(true, cx);
+}
+
+fn collapse_selections(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+        s.move_with(&mut |_, selection| {
+            selection.collapse_to(selection.head(), selection.goal);
+        });
+    });
+}
@@ -0,0 +1,187 @@
+use editor::Editor;
+use gpui::{Action, App, Context, KeystrokeEvent, Window};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{EmacsState, NegativeArgument, UniversalArgument};
+
+/// The most times a command is repeated for a prefix argument, so that a
+/// long run of `C-u` can't hang the editor.
+const MAX_REPEAT: i64 = 10_000;
+
+/// Appends a digit to the prefix argument, starting one if none is pending.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = emacs)]
+pub struct DigitArgument(pub u8);
+
+/// A numeric argument typed before a command with `C-u`, `M-<digit>` or `M--`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PrefixArgument {
+    /// The number of times `C-u` was pressed before any digits.
+    universal: u32,
+    digits: Option<u32>,
+    negative: bool,
+    /// Whether plain digits and `-` still extend the argument. Pressing `C-u`
+    /// after digits ends the argument so that the next digit is inserted.
+    accepting_digits: bool,
+}
+
+impl PrefixArgument {
+    /// The numeric value of the argument, as `prefix-numeric-value` computes it.
+    pub(crate) fn value(&self) -> i64 {
+        let magnitude = match self.digits {
+            Some(digits) => digits as i64,
+            None if self.negative && self.universal == 0 => 1,
+            None => 4_i64.saturating_pow(self.universal),
+        };
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Whether the argument was given with `C-u` alone, which some commands
+    /// treat as a flag rather than a count.
+    pub(crate) fn is_raw(&self) -> bool {
+        self.universal > 0 && self.digits.is_none() && !self.negative
+    }
+
+    pub(crate) fn accepts_digits(&self) -> bool {
+        self.accepting_digits
+    }
+}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_keystrokes(observe_keystroke).detach();
+}
+
+/// Takes the pending prefix argument, so that the command running now
+/// consumes it.
+pub(crate) fn take(cx: &mut App) -> Option<PrefixArgument> {
+    if !cx.has_global::<EmacsState>() {
+        return None;
+    }
+    let state = cx.global_mut::<EmacsState>();
+    state.prefix_argument_editor = None;
+    state.prefix_argument.take()
+}
+
+/// The prefix argument as a count, defaulting to 1 when none was given.
+pub(crate) fn take_count(cx: &mut App) -> i64 {
+    take(cx).map_or(1, |argument| argument.value())
+}
+
+pub(crate) fn universal_argument(
+    _: &mut Editor,
+    _: &UniversalArgument,
+    _: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    update_argument(cx, |argument| {
+        if argument.digits.is_some() || argument.negative {
+            argument.accepting_digits = false;
+        } else {
+            argument.universal += 1;
+            argument.accepting_digits = true;
+        }
+    });
+}
+
+pub(crate) fn digit_argument(
+    _: &mut Editor,
+    action: &DigitArgument,
+    _: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let digit = action.0.min(9) as u32;
+    update_argument(cx, |argument| {
+        argument.digits = Some(
+            argument
+                .digits
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit),
+        );
+        argument.accepting_digits = true;
+    });
+}
+
+pub(crate) fn negative_argument(
+    _: &mut Editor,
+    _: &NegativeArgument,
+    _: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    update_argument(cx, |argument| {
+        argument.negative = !argument.negative;
+        argument.accepting_digits = true;
+    });
+}
+
+fn update_argument(cx: &mut Context<Editor>, update: impl FnOnce(&mut PrefixArgument)) {
+    let handle = cx.weak_entity();
+    let state = cx.default_global::<EmacsState>();
+    let is_same_editor = state
+        .prefix_argument_editor
+        .as_ref()
+        .is_some_and(|editor| *editor == handle);
+    if !is_same_editor {
+        state.prefix_argument = None;
+    }
+    update(state.prefix_argument.get_or_insert_default());
+    state.prefix_argument_editor = Some(handle);
+    cx.notify();
+}
+
+fn is_prefix_action(action: &dyn Action) -> bool {
+    action.as_any().is::<UniversalArgument>()
+        || action.as_any().is::<DigitArgument>()
+        || action.as_any().is::<NegativeArgument>()
+}
+
+/// Applies a pending prefix argument to the command that followed it, when
+/// that command didn't consume the argument itself. Editor commands are
+/// repeated, typed characters are inserted repeatedly, and other commands
+/// discard the argument.
+fn observe_keystroke(event: &KeystrokeEvent, window: &mut Window, cx: &mut App) {
+    if !cx.has_global::<EmacsState>() {
+        return;
+    }
+    if let Some(action) = event.action.as_deref()
+        && is_prefix_action(action)
+    {
+        return;
+    }
+
+    cx.global_mut::<EmacsState>().last_command =
+        event.action.as_deref().map(|action| action.name());
+
+    let editor = cx.global::<EmacsState>().prefix_argument_editor.clone();
+    let Some(argument) = take(cx) else {
+        return;
+    };
+    let Some(editor) = editor.and_then(|editor| editor.upgrade()) else {
+        return;
+    };
+    // The key context depends on the pending argument.
+    editor.update(cx, |_, cx| cx.notify());
+
+    let count = argument.value().min(MAX_REPEAT);
+    if count <= 1 {
+        return;
+    }
+    match event.action.as_deref() {
+        Some(action) if action.name().starts_with("editor::") => {
+            for _ in 1..count {
+                window.dispatch_action(action.boxed_clone(), cx);
+            }
+        }
+        Some(_) => {}
+        None => {
+            let Some(text) = event.keystroke.key_char.clone() else {
+                return;
+            };
+            if editor.read(cx).is_focused(window) {
+                let text = text.repeat(count as usize - 1);
+                editor.update(cx, |editor, cx| editor.handle_input(&text, window, cx));
+            }
+        }
+    }
+}
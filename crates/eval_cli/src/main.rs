@@ -681,15 +681,18 @@ async fn run_agent(
                     "copy_path",
                     "create_directory",
                     "create_thread",
+                    "debugger",
                     "delete_path",
                     "diagnostics",
                     "apply_code_action",
                     "edit_file",
+                    "execute_code",
                     "write_file",
                     "fetch",
                     "find_path",
                     "find_references",
                     "get_code_actions",
+                    "git_history",
                     "go_to_definition",
                     "list_agents_and_models",
                     "list_directory",
@@ -697,8 +700,10 @@ async fn run_agent(
                     "rename_symbol",
                     "read_file",
                     "grep",
+                    "run_task",
                     "skill",
                     "spawn_agent",
+                    "symbol_info",
                     "terminal",
                     "search_web",
                 ];
@@ -78,6 +78,34 @@ impl FeatureFlag for RenameToolFeatureFlag {
 }
 register_feature_flag!(RenameToolFeatureFlag);
 
+/// Gates the `debugger` tool, which lets the agent start and drive debug
+/// sessions.
+pub struct DebuggerToolFeatureFlag;
+
+impl FeatureFlag for DebuggerToolFeatureFlag {
+    const NAME: &'static str = "debugger-tool";
+    type Value = PresenceFlag;
+
+    fn enabled_for_staff() -> bool {
+        false
+    }
+}
+register_feature_flag!(DebuggerToolFeatureFlag);
+
+/// Gates the `execute_code` tool, which lets the agent run code in the REPL
+/// kernels of the workspace.
+pub struct ExecuteCodeToolFeatureFlag;
+
+impl FeatureFlag for ExecuteCodeToolFeatureFlag {
+    const NAME: &'static str = "execute-code-tool";
+    type Value = PresenceFlag;
+
+    fn enabled_for_staff() -> bool {
+        false
+    }
+}
+register_feature_flag!(ExecuteCodeToolFeatureFlag);
+
 pub struct ProjectPanelUndoRedoFeatureFlag;
 
 impl FeatureFlag for ProjectPanelUndoRedoFeatureFlag {
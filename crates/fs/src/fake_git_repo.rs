@@ -9,25 +9,30 @@ use futures::future::{self, BoxFuture, join_all};
 use git::repository::GitCommitTemplate;
 use git::{
     Oid, RunHook,
-    blame::Blame,
+    bisect::{BisectMark, BisectState},
+    blame::{Blame, BlameOptions},
+    patch::{ApplyPatchMode, Patch, PatchHunkCheck},
+    rebase::{RebaseOutcome, RebaseState, RebaseStep, RebaseTodoEntry},
     repository::{
-        AskPassDelegate, Branch, CommitData, CommitDataReader, CommitDetails, CommitOptions,
-        CreateWorktreeTarget, FetchOptions, FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE,
-        GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder, LogSource,
-        PushOptions, RefEdit, Remote, RepoPath, ResetMode, SearchCommitArgs, Worktree,
-        commit_hash_search_query,
+        ApplyCommitsOutcome, AskPassDelegate, Branch, CommitData, CommitDataReader, CommitDetails,
+        CommitOptions, ConflictStages, CreateWorktreeTarget, FetchOptions,
+        FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RefEdit, Remote, RepoPath,
+        ResetMode, SearchCommitArgs, Worktree, WorktreeStatus, commit_hash_search_query,
     },
+    signature::{CommitSignature, CommitSigning},
     stash::GitStash,
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
         UnmergedStatus,
     },
+    submodule::{Submodule, SubmoduleOperation},
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
 use ignore::gitignore::GitignoreBuilder;
 use parking_lot::Mutex;
 use rope::Rope;
-use std::{path::PathBuf, sync::Arc, sync::atomic::AtomicBool, time::SystemTime};
+use std::{ops::Range, path::PathBuf, sync::Arc, sync::atomic::AtomicBool, time::SystemTime};
 use text::LineEnding;
 use util::{paths::PathStyle, rel_path::RelPath};
 
@@ -60,6 +65,8 @@ pub struct FakeGitRepositoryState {
     pub commit_history: Vec<FakeCommitSnapshot>,
     pub event_emitter: async_channel::Sender<PathBuf>,
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
+    /// The versions recorded in the index for the paths in `unmerged_paths`.
+    pub conflict_stages: HashMap<RepoPath, ConflictStages>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     // everything in commit contents is in oids
@@ -89,6 +96,7 @@ impl FakeGitRepositoryState {
             head_contents: Default::default(),
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
+            conflict_stages: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
@@ -277,6 +285,16 @@ impl GitRepository for FakeGitRepository {
         self.executor.spawn(fut).boxed()
     }
 
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .conflict_stages
+                .get(&path)
+                .cloned()
+                .unwrap_or_default())
+        })
+    }
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>> {
         self.with_state_async(false, move |state| {
             let sha = match state.refs.get(&commit) {
@@ -299,6 +317,10 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn commit_signature(&self, _commit: String) -> BoxFuture<'_, Result<CommitSignature>> {
+        async { Ok(CommitSignature::unsigned()) }.boxed()
+    }
+
     fn reset(
         &self,
         commit: String,
@@ -853,6 +875,62 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn worktree_statuses(&self) -> BoxFuture<'_, Result<Vec<WorktreeStatus>>> {
+        let fs = self.fs.clone();
+        async move {
+            let mut statuses = Vec::new();
+            for worktree in self.worktrees().await? {
+                statuses.push(WorktreeStatus {
+                    is_prunable: !fs.is_dir(&worktree.path).await,
+                    path: worktree.path,
+                    is_locked: false,
+                    changed_files: 0,
+                    ahead_behind: None,
+                    modified_at: None,
+                });
+            }
+            Ok(statuses)
+        }
+        .boxed()
+    }
+
+    fn prune_worktrees(&self) -> BoxFuture<'_, Result<()>> {
+        let fs = self.fs.clone();
+        let common_dir_path = self.common_dir_path.clone();
+        async move {
+            let worktrees_dir = common_dir_path.join("worktrees");
+            let Ok(mut entries) = fs.read_dir(&worktrees_dir).await else {
+                return Ok(());
+            };
+            use futures::StreamExt;
+            while let Some(Ok(entry_path)) = entries.next().await {
+                let Ok(gitdir) = fs.load(&entry_path.join("gitdir")).await else {
+                    continue;
+                };
+                if !fs.is_file(Path::new(gitdir.trim())).await {
+                    fs.remove_dir(
+                        &entry_path,
+                        RemoveOptions {
+                            recursive: true,
+                            ignore_if_not_exists: true,
+                        },
+                    )
+                    .await?;
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn move_changes_to_worktree(
+        &self,
+        _target: PathBuf,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
     fn rename_worktree(&self, old_path: PathBuf, new_path: PathBuf) -> BoxFuture<'_, Result<()>> {
         let fs = self.fs.clone();
         let executor = self.executor.clone();
@@ -979,6 +1057,7 @@ impl GitRepository for FakeGitRepository {
         path: RepoPath,
         _content: Rope,
         _line_ending: LineEnding,
+        _options: BlameOptions,
     ) -> BoxFuture<'_, Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -989,6 +1068,16 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn blame_revision(
+        &self,
+        _path: RepoPath,
+        _revision: String,
+        _rows: Range<u32>,
+        _options: BlameOptions,
+    ) -> BoxFuture<'_, Result<git::blame::Blame>> {
+        unimplemented!()
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -1009,6 +1098,9 @@ impl GitRepository for FakeGitRepository {
             let contents = join_all(contents).await;
             self.with_state_async(true, move |state| {
                 for (path, content) in contents {
+                    // Staging a conflicted file resolves it.
+                    state.unmerged_paths.remove(&path);
+                    state.conflict_stages.remove(&path);
                     if let Some(content) = content {
                         state.index_contents.insert(path, content);
                     } else {
@@ -1074,6 +1166,7 @@ impl GitRepository for FakeGitRepository {
         _message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
         options: CommitOptions,
+        _signing: CommitSigning,
         _askpass: AskPassDelegate,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
@@ -1132,6 +1225,112 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn cherry_pick(
+        &self,
+        _commit: String,
+        _through: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>> {
+        unimplemented!()
+    }
+
+    fn revert(
+        &self,
+        _commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>> {
+        unimplemented!()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        _message: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.edit_ref(RefEdit::Update {
+            ref_name: format!("refs/tags/{name}"),
+            commit,
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.edit_ref(RefEdit::Delete {
+            ref_name: format!("refs/tags/{name}"),
+        })
+    }
+
+    fn push_tags(
+        &self,
+        _remote_name: String,
+        _tag_name: Option<String>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn rebase_interactive(
+        &self,
+        _onto: String,
+        _todo: Vec<RebaseTodoEntry>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>> {
+        unimplemented!()
+    }
+
+    fn rebase_step(
+        &self,
+        _step: RebaseStep,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>> {
+        unimplemented!()
+    }
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>> {
+        async move { None }.boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        _mark: BisectMark,
+        _commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<BisectState>>> {
+        unimplemented!()
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>> {
+        async move { None }.boxed()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        async move { Ok(Vec::new()) }.boxed()
+    }
+
+    fn submodule_command(
+        &self,
+        _operation: SubmoduleOperation,
+        _paths: Vec<RepoPath>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn fetch(
         &self,
         _fetch_options: FetchOptions,
@@ -1171,6 +1370,28 @@ impl GitRepository for FakeGitRepository {
         future::ready(Ok(String::new())).boxed()
     }
 
+    fn format_patch(
+        &self,
+        _commit: String,
+        _through: Option<String>,
+    ) -> BoxFuture<'_, Result<String>> {
+        unimplemented!()
+    }
+
+    fn check_patch(&self, patch: String) -> BoxFuture<'_, Result<Vec<PatchHunkCheck>>> {
+        let checks = vec![PatchHunkCheck::Applies; Patch::parse(&patch).entry_count()];
+        future::ready(Ok(checks)).boxed()
+    }
+
+    fn apply_patch(
+        &self,
+        _patch: String,
+        _mode: ApplyPatchMode,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
     fn diff_stat(
         &self,
         diff: git::repository::DiffStatType,
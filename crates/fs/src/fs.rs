@@ -41,7 +41,8 @@ use smol::io::AsyncWriteExt;
 #[cfg(feature = "test-support")]
 use std::path::Component;
 use std::{
-    io::{self, Write},
+    io::{self, Read as _, Seek as _, Write},
+    ops::Range,
     path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -133,6 +134,10 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the bytes in the given range of the file, which are fewer than asked for past its end.
+    async fn load_bytes_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>>;
+    /// Overwrites bytes of the file starting at the given offset, leaving the rest of it intact.
+    async fn write_bytes_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -923,6 +928,33 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_bytes_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move {
+                let mut file = std::fs::File::open(&path)?;
+                file.seek(io::SeekFrom::Start(range.start))?;
+                let mut bytes = Vec::new();
+                file.take(range.end.saturating_sub(range.start))
+                    .read_to_end(&mut bytes)?;
+                anyhow::Ok(bytes)
+            })
+            .await
+    }
+
+    async fn write_bytes_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        let path = path.to_path_buf();
+        let content = content.to_owned();
+        self.executor
+            .spawn(async move {
+                let mut file = std::fs::OpenOptions::new().write(true).open(&path)?;
+                file.seek(io::SeekFrom::Start(offset))?;
+                file.write_all(&content)?;
+                anyhow::Ok(())
+            })
+            .await
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -3109,6 +3141,26 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn load_bytes_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let content = self.load_internal(path).await?;
+        let start = (range.start as usize).min(content.len());
+        let end = (range.end as usize).clamp(start, content.len());
+        Ok(content[start..end].to_vec())
+    }
+
+    async fn write_bytes_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        let path = normalize_path(path);
+        let mut new_content = self.load_internal(&path).await?;
+        let offset = offset as usize;
+        let end = offset + content.len();
+        if new_content.len() < end {
+            new_content.resize(end, 0);
+        }
+        new_content[offset..end].copy_from_slice(content);
+        self.write_file_internal(path, new_content, false)?;
+        Ok(())
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -0,0 +1,191 @@
+use gpui::SharedString;
+
+/// The file Git creates in the Git directory when a bisect is started, holding
+/// the branch or commit to return to once it's reset.
+pub(crate) const BISECT_START: &str = "BISECT_START";
+pub(crate) const BISECT_LOG: &str = "BISECT_LOG";
+
+/// The exit code with which a test tells `git bisect run` that the commit
+/// can't be tested.
+pub const SKIP_EXIT_CODE: i32 = 125;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skip",
+        }
+    }
+
+    /// Interprets the exit code of a test the way `git bisect run` does, returning
+    /// `None` when the bisect should be stopped instead.
+    pub fn from_exit_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(Self::Good),
+            SKIP_EXIT_CODE => Some(Self::Skip),
+            1..=127 => Some(Self::Bad),
+            _ => None,
+        }
+    }
+}
+
+/// A bisect that is in progress, as recorded in the Git directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BisectState {
+    pub bad: Option<SharedString>,
+    pub good: Vec<SharedString>,
+    pub skipped: Vec<SharedString>,
+    /// The commit checked out for testing.
+    pub current: Option<SharedString>,
+    /// The number of commits left to test after the current one.
+    pub remaining_revisions: usize,
+    /// The rough number of steps left before the first bad commit is found.
+    pub remaining_steps: usize,
+    /// The commit that introduced the change, once the bisect has found it.
+    pub first_bad: Option<SharedString>,
+    /// The commits that could be the first bad one, when only skipped commits
+    /// are left to test.
+    pub possible_first_bad: Vec<SharedString>,
+}
+
+impl BisectState {
+    /// Whether a commit is being tested, i.e. both a good and a bad commit are
+    /// known and the first bad commit hasn't been found yet.
+    pub fn is_testing(&self) -> bool {
+        self.bad.is_some()
+            && !self.good.is_empty()
+            && self.first_bad.is_none()
+            && self.possible_first_bad.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.first_bad.is_some() || !self.possible_first_bad.is_empty()
+    }
+
+    /// Fills in the marked commits from the output of
+    /// `git for-each-ref --format='%(objectname) %(refname)' refs/bisect/`.
+    pub(crate) fn set_refs(&mut self, output: &str) {
+        for line in output.lines() {
+            let Some((sha, ref_name)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            let sha = SharedString::from(sha.to_string());
+            match ref_name.strip_prefix("refs/bisect/") {
+                Some("bad") => self.bad = Some(sha),
+                Some(name) if name.starts_with("good-") => self.good.push(sha),
+                Some(name) if name.starts_with("skip-") => self.skipped.push(sha),
+                _ => {}
+            }
+        }
+    }
+
+    /// Fills in the progress from the output of `git rev-list --bisect-vars`.
+    pub(crate) fn set_progress(&mut self, output: &str) {
+        for line in output.lines() {
+            let Some((name, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            let value = value.parse().unwrap_or(0);
+            match name {
+                "bisect_nr" => self.remaining_revisions = value,
+                "bisect_steps" => self.remaining_steps = value,
+                _ => {}
+            }
+        }
+    }
+
+    /// Fills in the result of the bisect from the contents of `BISECT_LOG`.
+    ///
+    /// Git appends the result after the command that led to it, so only the
+    /// lines following the last command are taken into account.
+    pub(crate) fn set_result(&mut self, log: &str) {
+        let commit_of = |line: &str| {
+            let sha = line.strip_prefix('[')?.split(']').next()?;
+            Some(SharedString::from(sha.to_string()))
+        };
+
+        self.first_bad = None;
+        self.possible_first_bad.clear();
+        for line in log.lines() {
+            if line.starts_with("git bisect ") {
+                self.first_bad = None;
+                self.possible_first_bad.clear();
+            } else if let Some(commit) = line.strip_prefix("# first bad commit: ") {
+                self.first_bad = commit_of(commit);
+            } else if let Some(commit) = line.strip_prefix("# possible first bad commit: ") {
+                self.possible_first_bad.extend(commit_of(commit));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisect_state() {
+        let mut state = BisectState::default();
+        state.set_refs(
+            "06fe12596cb8a276b962b8b9ece2b8f0503ef303 refs/bisect/bad\n\
+             0770924c247196b13b32e11a04ca00b7b97f4bad refs/bisect/good-0770924c247196b13b32e11a04ca00b7b97f4bad\n\
+             d885ccc11837dfa85a5e8fa3e21efca70cf34c97 refs/bisect/skip-d885ccc11837dfa85a5e8fa3e21efca70cf34c97\n",
+        );
+        state.set_progress(
+            "bisect_rev='d885ccc11837dfa85a5e8fa3e21efca70cf34c97'\n\
+             bisect_nr=4\n\
+             bisect_good=4\n\
+             bisect_bad=3\n\
+             bisect_all=9\n\
+             bisect_steps=2\n",
+        );
+        state.set_result(
+            "git bisect start\n\
+             # bad: [06fe12596cb8a276b962b8b9ece2b8f0503ef303] Second\n\
+             git bisect bad 06fe12596cb8a276b962b8b9ece2b8f0503ef303\n",
+        );
+        assert_eq!(
+            state.bad.as_deref(),
+            Some("06fe12596cb8a276b962b8b9ece2b8f0503ef303")
+        );
+        assert_eq!(state.good.len(), 1);
+        assert_eq!(state.skipped.len(), 1);
+        assert_eq!((state.remaining_revisions, state.remaining_steps), (4, 2));
+        assert!(state.is_testing());
+
+        state.set_result(
+            "git bisect bad 06fe12596cb8a276b962b8b9ece2b8f0503ef303\n\
+             # first bad commit: [06fe12596cb8a276b962b8b9ece2b8f0503ef303] Second\n",
+        );
+        assert_eq!(
+            state.first_bad.as_deref(),
+            Some("06fe12596cb8a276b962b8b9ece2b8f0503ef303")
+        );
+        assert!(state.is_finished());
+
+        // Marking another commit after the result was found invalidates it.
+        state.set_result(
+            "# first bad commit: [06fe12596cb8a276b962b8b9ece2b8f0503ef303] Second\n\
+             git bisect good 06fe12596cb8a276b962b8b9ece2b8f0503ef303\n",
+        );
+        assert!(!state.is_finished());
+    }
+
+    #[test]
+    fn test_mark_from_exit_code() {
+        assert_eq!(BisectMark::from_exit_code(0), Some(BisectMark::Good));
+        assert_eq!(BisectMark::from_exit_code(1), Some(BisectMark::Bad));
+        assert_eq!(BisectMark::from_exit_code(125), Some(BisectMark::Skip));
+        assert_eq!(BisectMark::from_exit_code(127), Some(BisectMark::Bad));
+        assert_eq!(BisectMark::from_exit_code(128), None);
+        assert_eq!(BisectMark::from_exit_code(-1), None);
+    }
+}
@@ -6,13 +6,54 @@ use collections::{HashMap, HashSet};
 use futures::{AsyncWriteExt, TryFutureExt, try_join};
 use serde::{Deserialize, Serialize};
 use smol::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use std::ffi::OsString;
 use std::ops::Range;
+use std::path::PathBuf;
 use text::{LineEnding, Rope};
 use time::OffsetDateTime;
 use time::UtcOffset;
 use time::macros::format_description;
 use util::command::Stdio;
 
+/// The file git reads revisions to skip from by convention, relative to the
+/// repository root.
+pub const BLAME_IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlameMoveDetection {
+    #[default]
+    Off,
+    /// Follow lines moved or copied within the blamed file (`-M`).
+    WithinFile,
+    /// Also follow lines moved or copied from other files modified in the
+    /// same commit (`-C`).
+    AcrossFiles,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlameOptions {
+    /// Files listing revisions to skip, passed as `--ignore-revs-file`.
+    /// They must exist, since git refuses to run otherwise.
+    pub ignore_revs_files: Vec<PathBuf>,
+    pub move_detection: BlameMoveDetection,
+}
+
+impl BlameOptions {
+    fn args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        for file in &self.ignore_revs_files {
+            args.push("--ignore-revs-file".into());
+            args.push(file.as_os_str().to_owned());
+        }
+        match self.move_detection {
+            BlameMoveDetection::Off => {}
+            BlameMoveDetection::WithinFile => args.push("-M".into()),
+            BlameMoveDetection::AcrossFiles => args.push("-C".into()),
+        }
+        args
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Blame {
     pub entries: Vec<BlameEntry>,
@@ -26,9 +67,38 @@ impl Blame {
         path: &RepoPath,
         content: &Rope,
         line_ending: LineEnding,
+        options: &BlameOptions,
     ) -> Result<Self> {
-        let mut entries = run_git_blame(git, path, content, line_ending).await?;
+        let entries = run_git_blame(
+            git,
+            path,
+            BlameSource::Contents(content, line_ending),
+            options,
+        )
+        .await?;
+        Self::with_commit_details(git, entries).await
+    }
+
+    /// Blames `rows` (0-based) of the file at `path` as it was in `revision`,
+    /// which lets callers step back past the commit that last changed a line.
+    pub(crate) async fn for_revision(
+        git: &GitBinary,
+        path: &RepoPath,
+        revision: &str,
+        rows: Range<u32>,
+        options: &BlameOptions,
+    ) -> Result<Self> {
+        // `git blame` doesn't accept `--end-of-options`.
+        anyhow::ensure!(
+            !revision.starts_with('-'),
+            "`revision` must be a revision, not {revision:?}"
+        );
+        let entries =
+            run_git_blame(git, path, BlameSource::Revision { revision, rows }, options).await?;
+        Self::with_commit_details(git, entries).await
+    }
 
+    async fn with_commit_details(git: &GitBinary, mut entries: Vec<BlameEntry>) -> Result<Self> {
         let mut unique_shas = HashSet::default();
 
         for entry in entries.iter_mut() {
@@ -61,20 +131,51 @@ impl Blame {
 
 const GIT_BLAME_NO_COMMIT_ERROR: &str = "fatal: no such ref: HEAD";
 const GIT_BLAME_NO_PATH: &str = "fatal: no such path";
+// Reported when `-L` points past the end of the file in an older revision.
+const GIT_BLAME_RANGE_PAST_END: &str = " has only ";
 const BLAME_PARSE_YIELD_INTERVAL: usize = 512;
 
+enum BlameSource<'a> {
+    /// Blame the given (possibly unsaved) contents of the file, fed via stdin.
+    Contents(&'a Rope, LineEnding),
+    /// Blame a range of rows of the file as it was in a given revision.
+    Revision { revision: &'a str, rows: Range<u32> },
+}
+
+impl BlameSource<'_> {
+    fn args(&self) -> Vec<OsString> {
+        match self {
+            BlameSource::Contents(..) => vec!["--contents".into(), "-".into()],
+            BlameSource::Revision { revision, rows } => {
+                let start = rows.start.saturating_add(1);
+                vec![
+                    "-L".into(),
+                    format!("{},{}", start, rows.end.max(start)).into(),
+                    OsString::from(revision),
+                ]
+            }
+        }
+    }
+}
+
 async fn run_git_blame(
     git: &GitBinary,
     path: &RepoPath,
-    contents: &Rope,
-    line_ending: LineEnding,
+    source: BlameSource<'_>,
+    options: &BlameOptions,
 ) -> Result<Vec<BlameEntry>> {
     let mut child = {
         let span = ztracing::debug_span!("spawning git-blame command", path = path.as_unix_str());
         let _enter = span.enter();
-        git.build_command(&["blame", "--incremental", "--contents", "-", "--"])
+        git.build_command(&["blame", "--incremental"])
+            .args(options.args())
+            .args(source.args())
+            .arg("--")
             .arg(path.as_unix_str())
-            .stdin(Stdio::piped())
+            .stdin(match &source {
+                BlameSource::Contents(..) => Stdio::piped(),
+                BlameSource::Revision { .. } => Stdio::null(),
+            })
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .kill_on_drop(true)
@@ -82,10 +183,18 @@ async fn run_git_blame(
             .context("starting git blame process")?
     };
 
-    let mut stdin = child
-        .stdin
-        .take()
-        .context("failed to get pipe to stdin of git blame command")?;
+    let is_revision = matches!(source, BlameSource::Revision { .. });
+    let stdin = match source {
+        BlameSource::Contents(contents, line_ending) => Some((
+            child
+                .stdin
+                .take()
+                .context("failed to get pipe to stdin of git blame command")?,
+            contents,
+            line_ending,
+        )),
+        BlameSource::Revision { .. } => None,
+    };
     let stdout = child
         .stdout
         .take()
@@ -96,6 +205,9 @@ async fn run_git_blame(
         .context("failed to get stderr from git blame command")?;
 
     let write_stdin = async move {
+        let Some((mut stdin, contents, line_ending)) = stdin else {
+            return Ok(());
+        };
         for chunk in text::chunks_with_line_ending(contents, line_ending) {
             stdin.write_all(chunk.as_bytes()).await?;
         }
@@ -151,7 +263,10 @@ async fn run_git_blame(
 
     if !status.success() {
         let trimmed = stderr.trim();
-        if trimmed == GIT_BLAME_NO_COMMIT_ERROR || trimmed.contains(GIT_BLAME_NO_PATH) {
+        if trimmed == GIT_BLAME_NO_COMMIT_ERROR
+            || trimmed.contains(GIT_BLAME_NO_PATH)
+            || (is_revision && trimmed.contains(GIT_BLAME_RANGE_PAST_END))
+        {
             return Ok(Vec::new());
         }
         anyhow::bail!("git blame process failed: {stderr}");
@@ -389,7 +504,7 @@ mod tests {
 
     use crate::blame::GitBlameParser;
 
-    use super::BlameEntry;
+    use super::{BLAME_IGNORE_REVS_FILE, BlameEntry, BlameMoveDetection, BlameOptions};
 
     fn parse_git_blame(output: &str) -> anyhow::Result<Vec<BlameEntry>> {
         let mut parser = GitBlameParser::new();
@@ -459,4 +574,23 @@ mod tests {
         let entries = parse_git_blame(&output).unwrap();
         assert_eq_golden(&entries, "blame_incremental_complex");
     }
+
+    #[test]
+    fn test_blame_options_args() {
+        let ignore_revs = std::path::PathBuf::from("/repo").join(BLAME_IGNORE_REVS_FILE);
+
+        let options = BlameOptions {
+            ignore_revs_files: vec![ignore_revs.clone()],
+            move_detection: BlameMoveDetection::AcrossFiles,
+        };
+        assert_eq!(
+            options.args(),
+            vec![
+                "--ignore-revs-file".into(),
+                ignore_revs.into_os_string(),
+                std::ffi::OsString::from("-C"),
+            ]
+        );
+        assert!(BlameOptions::default().args().is_empty());
+    }
 }
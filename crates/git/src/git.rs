@@ -1,10 +1,15 @@
+pub mod bisect;
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod patch;
+pub mod rebase;
 mod remote;
 pub mod repository;
+pub mod signature;
 pub mod stash;
 pub mod status;
+pub mod submodule;
 
 pub use crate::hosting_provider::*;
 pub use crate::remote::*;
@@ -49,6 +54,10 @@ actions!(
         StageAndNext,
         /// Unstages the current hunk and moves to the next one.
         UnstageAndNext,
+        /// Stages only the selected lines of the hunks at the cursor.
+        StageLines,
+        /// Unstages only the selected lines of the hunks at the cursor.
+        UnstageLines,
         /// Restores the selected hunks to their original state.
         #[action(deprecated_aliases = ["editor::RevertSelectedHunks"])]
         Restore,
@@ -84,6 +93,53 @@ actions!(
         TrashUntrackedFiles,
         /// Undoes the last commit, keeping changes in the working directory.
         Uncommit,
+        /// Continues the rebase in progress once its conflicts are resolved.
+        RebaseContinue,
+        /// Skips the commit the rebase in progress stopped at.
+        RebaseSkip,
+        /// Aborts the rebase in progress, restoring the branch it started from.
+        RebaseAbort,
+        /// Marks the commit being tested by the bisect as good, starting a bisect
+        /// if none is in progress.
+        BisectGood,
+        /// Marks the commit being tested by the bisect as bad, starting a bisect
+        /// if none is in progress.
+        BisectBad,
+        /// Skips the commit being tested by the bisect in progress.
+        BisectSkip,
+        /// Runs a task on each commit the bisect in progress checks out, marking
+        /// it according to the task's exit code.
+        BisectRun,
+        /// Ends the bisect in progress, checking out the commit it started from.
+        BisectReset,
+        /// Clones and checks out the commits recorded for the repository's
+        /// submodules.
+        UpdateSubmodules,
+        /// Registers the repository's submodules from `.gitmodules`.
+        InitSubmodules,
+        /// Copies submodule URLs changed in `.gitmodules` to the repository's config.
+        SyncSubmodules,
+        /// Shows the changes inside the selected submodule.
+        ViewSubmoduleChanges,
+        /// Shows the commit history of the selected submodule.
+        ViewSubmoduleCommits,
+        /// Copies the changes of the active diff, or all uncommitted changes, to
+        /// the clipboard as a patch.
+        CopyChangesAsPatch,
+        /// Saves the changes of the active diff, or all uncommitted changes, to a
+        /// patch file.
+        ExportChangesAsPatch,
+        /// Copies the staged changes to the clipboard as a patch.
+        CopyStagedChangesAsPatch,
+        /// Saves the staged changes to a patch file.
+        ExportStagedChangesAsPatch,
+        /// Previews and applies a patch from the clipboard.
+        ApplyPatchFromClipboard,
+        /// Previews and applies a patch from a file.
+        ApplyPatchFromFile,
+        /// Lists the worktrees of the repository with the state of their
+        /// checkouts, to open, prune or remove them.
+        ManageWorktrees,
         /// Pushes commits to the remote repository.
         Push,
         /// Pushes commits to a specific remote branch.
@@ -121,6 +177,8 @@ actions!(
         OpenModifiedFiles,
         /// Opens the current file in a solo diff view.
         OpenFileDiff,
+        /// Opens the current conflicted file in the three-way merge editor.
+        OpenMergeEditor,
         /// Clones a repository.
         Clone,
         ViewCommit,
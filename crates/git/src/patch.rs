@@ -0,0 +1,350 @@
+use gpui::SharedString;
+
+/// A patch in the unified diff format, as produced by `git diff` or
+/// `git format-patch`, split into files and hunks so that a subset of it can
+/// be applied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Patch {
+    pub files: Vec<PatchFile>,
+    /// The number of messages of a `git format-patch` series, zero for a plain
+    /// diff.
+    pub message_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchFile {
+    /// The path of the file once the patch is applied, or before it for
+    /// deleted files.
+    pub path: String,
+    /// The lines from `diff --git` (or `---`) to the first hunk, including any
+    /// binary patch data.
+    header: String,
+    pub hunks: Vec<PatchHunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchHunk {
+    /// The `@@ -a,b +c,d @@` line of the hunk.
+    pub header: String,
+    text: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Whether a piece of a patch applies to the working tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchHunkCheck {
+    Applies,
+    /// The changes are already in the working tree.
+    AlreadyApplied,
+    Conflicts(SharedString),
+}
+
+/// Where to apply a patch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApplyPatchMode {
+    /// Applies the patch to the working tree with `git apply`.
+    WorkingTree,
+    /// Applies the patch to both the working tree and the index with
+    /// `git apply --index`.
+    WorkingTreeAndIndex,
+    /// Creates a commit for each message of a `git format-patch` series with
+    /// `git am --3way`.
+    Commits,
+}
+
+impl Patch {
+    pub fn parse(text: &str) -> Self {
+        let mut patch = Patch::default();
+        let mut lines = text.split_inclusive('\n').peekable();
+        while let Some(line) = lines.next() {
+            if is_message_start(line) {
+                patch.message_count += 1;
+                continue;
+            }
+            let starts_file = line.starts_with("diff --git ")
+                || line.starts_with("--- ")
+                    && lines.peek().is_some_and(|next| next.starts_with("+++ "));
+            if !starts_file {
+                continue;
+            }
+
+            let mut file = PatchFile {
+                path: String::new(),
+                header: line.to_string(),
+                hunks: Vec::new(),
+            };
+            let mut old_path = match line.strip_prefix("--- ") {
+                Some(path) => header_path(path, "a/"),
+                None => diff_git_path(line),
+            };
+            let mut new_path = old_path.clone();
+            let mut is_binary = false;
+            while let Some(line) = lines.next_if(|line| {
+                !is_section_start(line)
+                    && (is_binary && line.trim_end() != "--"
+                        || HEADER_PREFIXES
+                            .iter()
+                            .any(|prefix| line.starts_with(prefix)))
+            }) {
+                is_binary |= line.starts_with("GIT binary patch");
+                if let Some(path) = line.strip_prefix("--- ") {
+                    old_path = header_path(path, "a/");
+                } else if let Some(path) = line.strip_prefix("+++ ") {
+                    new_path = header_path(path, "b/");
+                }
+                file.header.push_str(line);
+            }
+            file.path = new_path.or(old_path).unwrap_or_default();
+
+            while let Some(header) = lines.next_if(|line| line.starts_with("@@ ")) {
+                let Some((mut old_lines, mut new_lines)) = hunk_line_counts(header) else {
+                    break;
+                };
+                let mut hunk = PatchHunk {
+                    header: header.trim_end().to_string(),
+                    text: header.to_string(),
+                    additions: 0,
+                    deletions: 0,
+                };
+                while old_lines > 0 || new_lines > 0 {
+                    let Some(line) = lines.next() else {
+                        break;
+                    };
+                    match line.as_bytes().first() {
+                        Some(b'+') => {
+                            new_lines = new_lines.saturating_sub(1);
+                            hunk.additions += 1;
+                        }
+                        Some(b'-') => {
+                            old_lines = old_lines.saturating_sub(1);
+                            hunk.deletions += 1;
+                        }
+                        Some(b'\\') => {}
+                        _ => {
+                            old_lines = old_lines.saturating_sub(1);
+                            new_lines = new_lines.saturating_sub(1);
+                        }
+                    }
+                    hunk.text.push_str(line);
+                }
+                if let Some(line) = lines.next_if(|line| line.starts_with('\\')) {
+                    hunk.text.push_str(line);
+                }
+                file.hunks.push(hunk);
+            }
+            patch.files.push(file);
+        }
+        patch
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The pieces of the patch that can be applied on their own: each hunk,
+    /// and each file without hunks, such as a rename or a binary change.
+    pub fn entries(&self) -> impl Iterator<Item = (&PatchFile, Option<&PatchHunk>)> {
+        self.files.iter().flat_map(|file| {
+            let hunks = file.hunks.iter().map(move |hunk| (file, Some(hunk)));
+            let whole_file = file.hunks.is_empty().then_some((file, None));
+            hunks.chain(whole_file)
+        })
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Returns a patch made of the [entries](Self::entries) for which
+    /// `selected` is true.
+    pub fn to_text(&self, selected: &[bool]) -> String {
+        let mut text = String::new();
+        let mut selected = selected.iter().copied();
+        for file in &self.files {
+            let mut file_text = file.header.clone();
+            let mut any_selected = false;
+            if file.hunks.is_empty() {
+                any_selected = selected.next().unwrap_or(false);
+            }
+            for hunk in &file.hunks {
+                if selected.next().unwrap_or(false) {
+                    any_selected = true;
+                    file_text.push_str(&hunk.text);
+                }
+            }
+            if any_selected {
+                text.push_str(&file_text);
+            }
+        }
+        text
+    }
+
+    /// Returns a patch for each of the [entries](Self::entries).
+    pub fn entry_texts(&self) -> Vec<String> {
+        let count = self.entry_count();
+        (0..count)
+            .map(|ix| {
+                let selected = (0..count).map(|other| other == ix).collect::<Vec<_>>();
+                self.to_text(&selected)
+            })
+            .collect()
+    }
+}
+
+/// The lines git may write between `diff --git` and the first hunk.
+const HEADER_PREFIXES: &[&str] = &[
+    "--- ",
+    "+++ ",
+    "index ",
+    "old mode ",
+    "new mode ",
+    "deleted file mode ",
+    "new file mode ",
+    "similarity index ",
+    "dissimilarity index ",
+    "rename from ",
+    "rename to ",
+    "copy from ",
+    "copy to ",
+    "Binary files ",
+    "GIT binary patch",
+];
+
+fn is_message_start(line: &str) -> bool {
+    // `git format-patch` starts each message with a line such as
+    // `From 0770924c247196b13b32e11a04ca00b7b97f4bad Mon Sep 17 00:00:00 2001`.
+    line.strip_prefix("From ")
+        .and_then(|rest| rest.split_once(' '))
+        .is_some_and(|(sha, date)| {
+            sha.len() == 40
+                && sha.bytes().all(|byte| byte.is_ascii_hexdigit())
+                && date.starts_with("Mon Sep 17 00:00:00 2001")
+        })
+}
+
+fn is_section_start(line: &str) -> bool {
+    line.starts_with("@@ ") || line.starts_with("diff --git ") || is_message_start(line)
+}
+
+fn diff_git_path(line: &str) -> Option<String> {
+    let paths = line.strip_prefix("diff --git ")?.trim_end();
+    let (_, new_path) = paths.rsplit_once(" b/")?;
+    Some(new_path.to_string())
+}
+
+fn header_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.trim_end().split('\t').next()?;
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// Parses the number of old and new lines from a `@@ -a,b +c,d @@` line.
+fn hunk_line_counts(header: &str) -> Option<(usize, usize)> {
+    let mut ranges = header.strip_prefix("@@ ")?.split(' ');
+    let count = |range: &str| -> Option<usize> {
+        match range.split_once(',') {
+            Some((_, count)) => count.parse().ok(),
+            None => Some(1),
+        }
+    };
+    let old = count(ranges.next()?.strip_prefix('-')?)?;
+    let new = count(ranges.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const SERIES: &str = indoc! {"
+        From 0770924c247196b13b32e11a04ca00b7b97f4bad Mon Sep 17 00:00:00 2001
+        From: Jane Doe <jane@example.com>
+        Date: Mon, 1 Jan 2024 00:00:00 +0000
+        Subject: [PATCH] Update files
+
+        ---
+         a.txt | 4 ++--
+         1 file changed, 2 insertions(+), 2 deletions(-)
+
+        diff --git a/a.txt b/a.txt
+        index 1111111..2222222 100644
+        --- a/a.txt
+        +++ b/a.txt
+        @@ -1,2 +1,2 @@
+        -one
+        +ONE
+         two
+        @@ -9,2 +9,2 @@ fn main
+         nine
+        --- ten
+        +-- TEN
+        diff --git a/old.rs b/new.rs
+        similarity index 100%
+        rename from old.rs
+        rename to new.rs
+        --
+        2.43.0
+    "};
+
+    #[test]
+    fn test_parse_patch() {
+        let patch = Patch::parse(SERIES);
+        assert_eq!(patch.message_count, 1);
+        assert_eq!(patch.files.len(), 2);
+        assert_eq!(patch.files[0].path, "a.txt");
+        assert_eq!(patch.files[0].hunks.len(), 2);
+        assert_eq!(patch.files[0].hunks[1].header, "@@ -9,2 +9,2 @@ fn main");
+        assert_eq!(
+            (
+                patch.files[0].hunks[1].additions,
+                patch.files[0].hunks[1].deletions
+            ),
+            (1, 1)
+        );
+        assert_eq!(patch.files[1].path, "new.rs");
+        assert!(patch.files[1].hunks.is_empty());
+        assert_eq!(patch.entry_count(), 3);
+
+        let plain = Patch::parse(indoc! {"
+            --- a/b.txt\t2024-01-01
+            +++ /dev/null
+            @@ -1 +0,0 @@
+            -gone
+        "});
+        assert_eq!(plain.message_count, 0);
+        assert_eq!(plain.files[0].path, "b.txt");
+        assert_eq!(plain.files[0].hunks[0].deletions, 1);
+    }
+
+    #[test]
+    fn test_patch_subset() {
+        let patch = Patch::parse(SERIES);
+        assert_eq!(
+            patch.to_text(&[false, true, false]),
+            indoc! {"
+                diff --git a/a.txt b/a.txt
+                index 1111111..2222222 100644
+                --- a/a.txt
+                +++ b/a.txt
+                @@ -9,2 +9,2 @@ fn main
+                 nine
+                --- ten
+                +-- TEN
+            "}
+        );
+        assert_eq!(
+            patch.to_text(&[false, false, true]),
+            indoc! {"
+                diff --git a/old.rs b/new.rs
+                similarity index 100%
+                rename from old.rs
+                rename to new.rs
+            "}
+        );
+        assert_eq!(patch.entry_texts().len(), 3);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{Oid, REBASE_APPLY_DIR, REBASE_MERGE_DIR};
+use anyhow::{Result, bail};
+use gpui::SharedString;
+use std::path::{Path, PathBuf};
+
+/// The directory inside the Git directory holding the todo list and reword
+/// messages of an interactive rebase started by Zed.
+pub(crate) const REBASE_TODO_DIR: &str = "zed-rebase";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseTodoAction {
+    /// Use the commit as is.
+    Pick,
+    /// Use the commit with a new message.
+    Reword,
+    /// Use the commit, then stop so it can be amended.
+    Edit,
+    /// Meld the commit into the previous one, keeping both messages.
+    Squash,
+    /// Meld the commit into the previous one, discarding its message.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl RebaseTodoAction {
+    pub const ALL: [Self; 6] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Edit,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Edit => "edit",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is melded into the one before it.
+    pub fn melds(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub sha: Oid,
+    /// The new message of a [`RebaseTodoAction::Reword`] entry.
+    pub message: Option<String>,
+}
+
+/// Checks that Git can carry out the todo list, which is ordered oldest first.
+pub fn validate_todo(entries: &[RebaseTodoEntry]) -> Result<()> {
+    let mut has_previous_commit = false;
+    for entry in entries {
+        match entry.action {
+            RebaseTodoAction::Drop => {}
+            RebaseTodoAction::Squash | RebaseTodoAction::Fixup if !has_previous_commit => {
+                bail!(
+                    "Can't {} {} without a previous commit",
+                    entry.action.as_str(),
+                    entry.sha.display_short()
+                );
+            }
+            RebaseTodoAction::Reword
+                if entry
+                    .message
+                    .as_ref()
+                    .is_none_or(|message| message.trim().is_empty()) =>
+            {
+                bail!(
+                    "Reworded commit {} has no message",
+                    entry.sha.display_short()
+                );
+            }
+            _ => has_previous_commit = true,
+        }
+    }
+    Ok(())
+}
+
+/// Renders the todo list in the format read by `git rebase --interactive`.
+///
+/// Rewording is done by amending the picked commit with the message stored at
+/// `message_path`, so that no editor has to be opened during the rebase.
+pub(crate) fn todo_list(
+    entries: &[RebaseTodoEntry],
+    message_path: impl Fn(usize) -> PathBuf,
+) -> Result<String> {
+    let mut todo = String::new();
+    for (ix, entry) in entries.iter().enumerate() {
+        match entry.action {
+            RebaseTodoAction::Reword => {
+                let path = message_path(ix);
+                let path = path.to_string_lossy();
+                let Some(path) = util::shell::ShellKind::Posix.try_quote(&path) else {
+                    bail!("Can't pass the message of {} to Git", entry.sha);
+                };
+                todo.push_str(&format!("pick {}\n", entry.sha));
+                todo.push_str(&format!(
+                    "exec git commit --amend --only --quiet -F {path}\n"
+                ));
+            }
+            action => todo.push_str(&format!("{} {}\n", action.as_str(), entry.sha)),
+        }
+    }
+    Ok(todo)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseStep {
+    Continue,
+    Skip,
+    Abort,
+}
+
+impl RebaseStep {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Continue => "continue",
+            Self::Skip => "skip",
+            Self::Abort => "abort",
+        }
+    }
+
+    pub(crate) fn arg(&self) -> &'static str {
+        match self {
+            Self::Continue => "--continue",
+            Self::Skip => "--skip",
+            Self::Abort => "--abort",
+        }
+    }
+}
+
+/// A rebase that is in progress, as recorded in the Git directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseState {
+    /// The ref being rebased, or `None` when HEAD was detached.
+    pub head_name: Option<SharedString>,
+    /// The commit being rebased onto.
+    pub onto: Option<SharedString>,
+    /// The number of the todo entry being applied, starting at 1.
+    pub step: usize,
+    pub total: usize,
+    /// The commit the rebase stopped at, because it conflicted or was marked for editing.
+    pub stopped_sha: Option<SharedString>,
+}
+
+impl RebaseState {
+    pub fn load(git_dir: &Path) -> Option<Self> {
+        let read = |dir: &Path, name: &str| {
+            std::fs::read_to_string(dir.join(name))
+                .ok()
+                .map(|contents| contents.trim().to_string())
+                .filter(|contents| !contents.is_empty())
+        };
+
+        let merge_dir = git_dir.join(REBASE_MERGE_DIR);
+        let apply_dir = git_dir.join(REBASE_APPLY_DIR);
+        let (dir, step_file, total_file) = if merge_dir.is_dir() {
+            (merge_dir, "msgnum", "end")
+        } else if apply_dir.join("rebasing").exists() {
+            (apply_dir, "next", "last")
+        } else {
+            return None;
+        };
+
+        let number = |name: &str| {
+            read(&dir, name)
+                .and_then(|number| number.parse().ok())
+                .unwrap_or(0)
+        };
+        Some(Self {
+            head_name: read(&dir, "head-name")
+                .filter(|name| name != "detached HEAD")
+                .map(SharedString::from),
+            onto: read(&dir, "onto").map(SharedString::from),
+            step: number(step_file),
+            total: number(total_file),
+            stopped_sha: read(&dir, "stopped-sha").map(SharedString::from),
+        })
+    }
+
+    /// The name of the branch being rebased.
+    pub fn branch_name(&self) -> Option<&str> {
+        let head_name = self.head_name.as_deref()?;
+        Some(head_name.strip_prefix("refs/heads/").unwrap_or(head_name))
+    }
+}
+
+/// The result of running a rebase command.
+#[derive(Clone, Debug)]
+pub struct RebaseOutcome {
+    /// The rebase, when it stopped before finishing, e.g. at conflicts or an
+    /// [`RebaseTodoAction::Edit`] entry.
+    pub stopped: Option<RebaseState>,
+    /// Git's output, or the reason it stopped.
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr as _;
+
+    fn entry(action: RebaseTodoAction, sha: &str, message: Option<&str>) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            sha: Oid::from_str(sha).unwrap(),
+            message: message.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn test_todo_list() {
+        let first = "1111111111111111111111111111111111111111";
+        let second = "2222222222222222222222222222222222222222";
+        let third = "3333333333333333333333333333333333333333";
+        let entries = [
+            entry(RebaseTodoAction::Reword, first, Some("New message")),
+            entry(RebaseTodoAction::Fixup, second, None),
+            entry(RebaseTodoAction::Drop, third, None),
+        ];
+
+        validate_todo(&entries).unwrap();
+        assert_eq!(
+            todo_list(&entries, |ix| PathBuf::from(format!(
+                "/tmp/zed-rebase/message-{ix}"
+            )))
+            .unwrap(),
+            format!(
+                "pick {first}\n\
+                 exec git commit --amend --only --quiet -F /tmp/zed-rebase/message-0\n\
+                 fixup {second}\n\
+                 drop {third}\n"
+            )
+        );
+
+        assert!(
+            validate_todo(&[
+                entry(RebaseTodoAction::Drop, first, None),
+                entry(RebaseTodoAction::Squash, second, None),
+            ])
+            .is_err()
+        );
+        assert!(validate_todo(&[entry(RebaseTodoAction::Reword, first, Some(" "))]).is_err());
+    }
+}
@@ -1,8 +1,18 @@
+use crate::bisect::{BISECT_LOG, BISECT_START, BisectMark, BisectState};
+use crate::blame::BlameOptions;
 use crate::commit::{CommitDiffObject, CommitDiffObjectKind, parse_git_diff_raw};
+use crate::patch::{ApplyPatchMode, Patch, PatchHunkCheck};
+use crate::rebase::{
+    REBASE_TODO_DIR, RebaseOutcome, RebaseState, RebaseStep, RebaseTodoEntry, todo_list,
+};
+use crate::signature::{CommitSignature, CommitSigning};
 use crate::stash::GitStash;
 use crate::status::{
     DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
 };
+use crate::submodule::{
+    GITMODULES, Submodule, SubmoduleOperation, parse_recorded_commits, parse_submodule_paths,
+};
 use crate::{Oid, RunHook, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_channel::Sender;
@@ -30,6 +40,7 @@ use std::str::FromStr;
 use std::time::SystemTime;
 use std::{
     cmp::Ordering,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -427,6 +438,92 @@ pub fn parse_worktrees_from_str<T: AsRef<str>>(
     worktrees
 }
 
+/// The state of a worktree's checkout, as shown by the worktree manager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorktreeStatus {
+    pub path: PathBuf,
+    /// The worktree's directory is gone, so `git worktree prune` will remove it.
+    pub is_prunable: bool,
+    pub is_locked: bool,
+    /// The number of changed and untracked files.
+    pub changed_files: usize,
+    /// The number of commits the checked out branch is ahead of and behind its
+    /// upstream.
+    pub ahead_behind: Option<(u32, u32)>,
+    /// The last time git recorded activity in the worktree, such as a commit,
+    /// a checkout or staging changes.
+    pub modified_at: Option<SystemTime>,
+}
+
+impl WorktreeStatus {
+    pub fn is_dirty(&self) -> bool {
+        self.changed_files > 0
+    }
+}
+
+/// Parses the `prunable` and `locked` flags of each entry of
+/// `git worktree list --porcelain`.
+pub fn parse_worktree_statuses_from_str(raw_worktrees: &str) -> Vec<WorktreeStatus> {
+    let normalized = raw_worktrees.replace("\r\n", "\n");
+    normalized
+        .split("\n\n")
+        .filter_map(|entry| {
+            let mut lines = entry.lines().map(str::trim);
+            let path = lines.find_map(|line| line.strip_prefix("worktree "))?;
+            let mut status = WorktreeStatus {
+                path: PathBuf::from(path),
+                is_prunable: false,
+                is_locked: false,
+                changed_files: 0,
+                ahead_behind: None,
+                modified_at: None,
+            };
+            for line in lines {
+                let flag = line.split(' ').next().unwrap_or_default();
+                status.is_prunable |= flag == "prunable";
+                status.is_locked |= flag == "locked";
+            }
+            Some(status)
+        })
+        .collect()
+}
+
+/// Parses the changed files and upstream divergence from the output of
+/// `git status --porcelain=v2 --branch`.
+fn parse_porcelain_v2_status(output: &str) -> (usize, Option<(u32, u32)>) {
+    let mut changed_files = 0;
+    let mut ahead_behind = None;
+    for line in output.lines() {
+        if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            let mut counts = counts.split(' ');
+            let ahead = counts.next().and_then(|ahead| ahead.strip_prefix('+'));
+            let behind = counts.next().and_then(|behind| behind.strip_prefix('-'));
+            if let (Some(Ok(ahead)), Some(Ok(behind))) =
+                (ahead.map(str::parse), behind.map(str::parse))
+            {
+                ahead_behind = Some((ahead, behind));
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            changed_files += 1;
+        }
+    }
+    (changed_files, ahead_behind)
+}
+
+/// The last time git touched the index or HEAD of the worktree at `path`.
+fn worktree_modified_at(path: &Path) -> Option<SystemTime> {
+    let dot_git = path.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        linked_worktree_git_dir(path).ok()?
+    };
+    ["index", "HEAD", "logs/HEAD"]
+        .iter()
+        .filter_map(|file| std::fs::metadata(git_dir.join(file)).ok()?.modified().ok())
+        .max()
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Upstream {
     pub ref_name: SharedString,
@@ -502,6 +599,27 @@ impl RemoteCommandOutput {
     }
 }
 
+/// The result of cherry-picking or reverting commits, which stops at the first
+/// commit whose changes conflict.
+#[derive(Clone, Debug, Default)]
+pub struct ApplyCommitsOutcome {
+    /// The number of commits applied before stopping.
+    pub applied: usize,
+    pub total: usize,
+    /// The commit whose conflicts are left to be resolved and committed.
+    pub conflicted_sha: Option<String>,
+    pub message: String,
+}
+
+/// The versions of a conflicted file recorded in the index by a merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    /// The version in the merge base, or `None` when the file was added on both sides.
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct UpstreamTrackingStatus {
     pub ahead: u32,
@@ -768,6 +886,25 @@ pub trait GitRepository: Send + Sync {
     }
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns the base, ours and theirs versions of a conflicted file, which
+    /// are stored in stages 1 to 3 of the index.
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        let future = self.load_revisions(
+            (1..=3)
+                .map(|stage| format!(":{stage}:{}", path.as_unix_str()))
+                .collect(),
+        );
+        async move {
+            let mut stages = future.await?.into_iter();
+            Ok(ConflictStages {
+                base: stages.next().flatten(),
+                ours: stages.next().flatten(),
+                theirs: stages.next().flatten(),
+            })
+        }
+        .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -865,6 +1002,21 @@ pub trait GitRepository: Send + Sync {
 
     fn rename_worktree(&self, old_path: PathBuf, new_path: PathBuf) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the checkout state of each of the repository's worktrees, see
+    /// [`WorktreeStatus`].
+    fn worktree_statuses(&self) -> BoxFuture<'_, Result<Vec<WorktreeStatus>>>;
+
+    /// Removes the administrative files of worktrees whose directories are gone.
+    fn prune_worktrees(&self) -> BoxFuture<'_, Result<()>>;
+
+    /// Stashes the uncommitted changes of this worktree, including untracked
+    /// files, and restores them in the worktree at `target`.
+    fn move_changes_to_worktree(
+        &self,
+        target: PathBuf,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn reset(
         &self,
         commit: String,
@@ -887,6 +1039,16 @@ pub trait GitRepository: Send + Sync {
         path: RepoPath,
         content: Rope,
         line_ending: LineEnding,
+        options: BlameOptions,
+    ) -> BoxFuture<'_, Result<crate::blame::Blame>>;
+
+    /// Blames `rows` of `path` as it was in `revision`.
+    fn blame_revision(
+        &self,
+        path: RepoPath,
+        revision: String,
+        rows: Range<u32>,
+        options: BlameOptions,
     ) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -927,10 +1089,14 @@ pub trait GitRepository: Send + Sync {
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
         options: CommitOptions,
+        signing: CommitSigning,
         askpass: AskPassDelegate,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Verifies the signature of `commit`.
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<CommitSignature>>;
+
     fn stash_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -980,6 +1146,96 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
 
+    /// Cherry-picks `commit`, or the commits from `commit` through `through` oldest first.
+    fn cherry_pick(
+        &self,
+        commit: String,
+        through: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>>;
+
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>>;
+
+    /// Creates an annotated tag pointing at `commit`.
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Pushes the tag named `tag_name` to the remote, or all tags when it's `None`.
+    fn push_tags(
+        &self,
+        remote_name: String,
+        tag_name: Option<String>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Rebases the commits after `onto` as described by `todo`, which is ordered oldest first.
+    fn rebase_interactive(
+        &self,
+        onto: String,
+        todo: Vec<RebaseTodoEntry>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>>;
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress.
+    fn rebase_step(
+        &self,
+        step: RebaseStep,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>>;
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>>;
+
+    /// Marks `commit`, or HEAD when it's `None`, starting a bisect if none is in
+    /// progress, and returns the state of the bisect afterwards.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<BisectState>>>;
+
+    /// Ends the bisect in progress, checking out the commit it was started from.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>>;
+
+    /// Lists the submodules registered in `.gitmodules`, with the state of their checkouts.
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>>;
+
+    /// Runs `git submodule <operation>` on the submodules at `paths`, or on all
+    /// of them when `paths` is empty.
+    fn submodule_command(
+        &self,
+        operation: SubmoduleOperation,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     fn fetch(
         &self,
         fetch_options: FetchOptions,
@@ -1006,6 +1262,25 @@ pub trait GitRepository: Send + Sync {
     /// Run git diff
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns `commit`, or the commits from `commit` through `through`, as a
+    /// series of patches in the format of `git format-patch`.
+    fn format_patch(
+        &self,
+        commit: String,
+        through: Option<String>,
+    ) -> BoxFuture<'_, Result<String>>;
+
+    /// Checks whether each of the [entries](Patch::entries) of `patch` applies
+    /// to the working tree.
+    fn check_patch(&self, patch: String) -> BoxFuture<'_, Result<Vec<PatchHunkCheck>>>;
+
+    fn apply_patch(
+        &self,
+        patch: String,
+        mode: ApplyPatchMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn diff_stat(
         &self,
         diff: DiffStatType,
@@ -1349,6 +1624,7 @@ impl GitRepository for RealGitRepository {
                         "show",
                         "--no-patch",
                         "--format=%H%x00%B%x00%at%x00%ae%x00%an%x00",
+                        "--end-of-options",
                         &commit,
                     ])
                     .output()
@@ -1374,6 +1650,32 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<CommitSignature>> {
+        let git = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git
+                    .build_command(&[
+                        "show",
+                        "--no-patch",
+                        CommitSignature::FORMAT,
+                        "--end-of-options",
+                        &commit,
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to verify the signature of {commit:?}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(CommitSignature::parse(&String::from_utf8_lossy(
+                    &output.stdout,
+                )))
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let git = self.git_binary();
         cx.background_spawn(async move {
@@ -2221,6 +2523,99 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn worktree_statuses(&self) -> BoxFuture<'_, Result<Vec<WorktreeStatus>>> {
+        let git = self.git_binary();
+        let any_git_binary_path = self.any_git_binary_path.clone();
+        let git_directory = self.path();
+        let executor = self.executor.clone();
+        let is_trusted = self.is_trusted();
+        self.executor
+            .spawn(async move {
+                let output = git.run(&["worktree", "list", "--porcelain"]).await?;
+                let mut statuses = parse_worktree_statuses_from_str(&output);
+                for status in &mut statuses {
+                    if status.is_prunable || !status.path.is_dir() {
+                        continue;
+                    }
+                    let worktree_git = GitBinary::new(
+                        any_git_binary_path.clone(),
+                        status.path.clone(),
+                        git_directory.clone(),
+                        executor.clone(),
+                        is_trusted,
+                    );
+                    // Bare repositories have no working tree to report on.
+                    let Ok(output) = worktree_git
+                        .run(&["status", "--porcelain=v2", "--branch"])
+                        .await
+                    else {
+                        continue;
+                    };
+                    (status.changed_files, status.ahead_behind) =
+                        parse_porcelain_v2_status(&output);
+                    status.modified_at = worktree_modified_at(&status.path);
+                }
+                Ok(statuses)
+            })
+            .boxed()
+    }
+
+    fn prune_worktrees(&self) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary();
+        self.executor
+            .spawn(async move {
+                git.run(&["worktree", "prune"]).await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn move_changes_to_worktree(
+        &self,
+        target: PathBuf,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let target_git = GitBinary::new(
+            self.any_git_binary_path.clone(),
+            target.clone(),
+            self.path(),
+            self.executor.clone(),
+            self.is_trusted(),
+        );
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let status = git.run(&["status", "--porcelain"]).await?;
+                anyhow::ensure!(!status.trim().is_empty(), "There are no changes to move");
+                let message = format!("Moved to {}", target.display());
+                let output = git
+                    .build_command(&["stash", "push", "--include-untracked", "-m", &message])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to stash changes:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                // The stash is shared by all worktrees of the repository.
+                let output = target_git
+                    .build_command(&["stash", "pop"])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to restore the changes in {}, they are kept in the stash:\n{}",
+                    target.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn checkout_branch_in_worktree(
         &self,
         branch_name: String,
@@ -2361,13 +2756,131 @@ impl GitRepository for RealGitRepository {
         path: RepoPath,
         content: Rope,
         line_ending: LineEnding,
+        options: BlameOptions,
+    ) -> BoxFuture<'_, Result<crate::blame::Blame>> {
+        let git = self.git_binary_in_worktree();
+
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                crate::blame::Blame::for_path(&git, &path, &content, line_ending, &options).await
+            })
+            .boxed()
+    }
+
+    fn blame_revision(
+        &self,
+        path: RepoPath,
+        revision: String,
+        rows: Range<u32>,
+        options: BlameOptions,
     ) -> BoxFuture<'_, Result<crate::blame::Blame>> {
         let git = self.git_binary_in_worktree();
 
         self.executor
             .spawn(async move {
                 let git = git?;
-                crate::blame::Blame::for_path(&git, &path, &content, line_ending).await
+                crate::blame::Blame::for_revision(&git, &path, &revision, rows, &options).await
+            })
+            .boxed()
+    }
+
+    fn format_patch(
+        &self,
+        commit: String,
+        through: Option<String>,
+    ) -> BoxFuture<'_, Result<String>> {
+        let git = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = match through {
+                    Some(through) => {
+                        git.build_command(&["format-patch", "--stdout", "--end-of-options"])
+                            .arg(format!("{commit}^..{through}"))
+                            .output()
+                            .await?
+                    }
+                    None => {
+                        git.build_command(&[
+                            "format-patch",
+                            "--stdout",
+                            "-1",
+                            "--end-of-options",
+                            &commit,
+                        ])
+                        .output()
+                        .await?
+                    }
+                };
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to format patches:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+            })
+            .boxed()
+    }
+
+    fn check_patch(&self, patch: String) -> BoxFuture<'_, Result<Vec<PatchHunkCheck>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let mut checks = Vec::new();
+                for entry in Patch::parse(&patch).entry_texts() {
+                    let output =
+                        run_with_input(&git, &["apply", "--check", "-"], &entry, None).await?;
+                    if output.status.success() {
+                        checks.push(PatchHunkCheck::Applies);
+                        continue;
+                    }
+                    let reverse =
+                        run_with_input(&git, &["apply", "--check", "--reverse", "-"], &entry, None)
+                            .await?;
+                    if reverse.status.success() {
+                        checks.push(PatchHunkCheck::AlreadyApplied);
+                    } else {
+                        let error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                        checks.push(PatchHunkCheck::Conflicts(error.into()));
+                    }
+                }
+                Ok(checks)
+            })
+            .boxed()
+    }
+
+    fn apply_patch(
+        &self,
+        patch: String,
+        mode: ApplyPatchMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let args: &[&str] = match mode {
+                    ApplyPatchMode::WorkingTree => &["apply", "-"],
+                    ApplyPatchMode::WorkingTreeAndIndex => &["apply", "--index", "-"],
+                    ApplyPatchMode::Commits => &["am", "--3way", "-"],
+                };
+                let output = run_with_input(&git, args, &patch, Some(&env)).await?;
+                if output.status.success() {
+                    return Ok(());
+                }
+                if mode == ApplyPatchMode::Commits {
+                    // Leave the repository as it was rather than in the middle of `git am`.
+                    git.build_command(&["am", "--abort"])
+                        .envs(env.iter())
+                        .output()
+                        .await
+                        .log_err();
+                }
+                anyhow::bail!(
+                    "Failed to apply patch:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                )
             })
             .boxed()
     }
@@ -2593,6 +3106,7 @@ impl GitRepository for RealGitRepository {
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
         options: CommitOptions,
+        signing: CommitSigning,
         ask_pass: AskPassDelegate,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
@@ -2602,7 +3116,9 @@ impl GitRepository for RealGitRepository {
         // which we want to block on.
         async move {
             let git = git?;
-            let mut cmd = git.build_command(&["commit", "--quiet", "-m"]);
+            let mut args = signing.config_args();
+            args.extend(["commit", "--quiet", "-m"].map(String::from));
+            let mut cmd = git.build_command(&args);
             cmd.envs(env.iter())
                 .arg(&message.to_string())
                 .arg("--cleanup=strip")
@@ -2625,6 +3141,10 @@ impl GitRepository for RealGitRepository {
                 cmd.arg("--no-verify");
             }
 
+            if let Some(arg) = signing.commit_arg() {
+                cmd.arg(arg);
+            }
+
             if let Some((name, email)) = name_and_email {
                 cmd.arg("--author").arg(&format!("{name} <{email}>"));
             }
@@ -2742,6 +3262,447 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn cherry_pick(
+        &self,
+        commit: String,
+        through: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let commits = match through {
+                    Some(through) => {
+                        let output = git
+                            .run(&[
+                                "rev-list",
+                                "--reverse",
+                                "--topo-order",
+                                "--end-of-options",
+                                &format!("{commit}^..{through}"),
+                            ])
+                            .await?;
+                        let commits = output.lines().map(str::to_string).collect::<Vec<_>>();
+                        anyhow::ensure!(
+                            commits.contains(&commit),
+                            "{commit} is not an ancestor of {through}"
+                        );
+                        commits
+                    }
+                    None => vec![commit],
+                };
+                apply_commits(&git, &git_directory, "cherry-pick", commits, &env).await
+            })
+            .boxed()
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<ApplyCommitsOutcome>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                apply_commits(&git, &git_directory, "revert", vec![commit], &env).await
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let output = git
+                    .build_command(&["tag", "--annotate", "--cleanup=strip", "-m"])
+                    .envs(env.iter())
+                    .arg(&message)
+                    .arg("--")
+                    .arg(&name)
+                    .arg(&commit)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let output = git
+                    .build_command(&["tag", "--delete", "--"])
+                    .envs(env.iter())
+                    .arg(&name)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to delete tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn push_tags(
+        &self,
+        remote_name: String,
+        tag_name: Option<String>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.command_directory();
+        let git_directory = self.path();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let is_trusted = self.is_trusted();
+        // Note: Do not spawn this command on the background thread, it might pop open the credential helper
+        // which we want to block on.
+        async move {
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't push")?;
+            let git = GitBinary::new(
+                git_binary_path,
+                working_directory,
+                git_directory,
+                executor.clone(),
+                is_trusted,
+            );
+            let mut command = git.build_command(&["push"]);
+            command.envs(env.iter()).arg(remote_name);
+            match tag_name {
+                Some(tag_name) => command.arg(format!("refs/tags/{tag_name}")),
+                None => command.arg("--tags"),
+            };
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            run_git_command(env, ask_pass, command, executor).await
+        }
+        .boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        onto: String,
+        todo: Vec<RebaseTodoEntry>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        let executor = self.executor.clone();
+        // Note: Do not spawn this command on the background thread, it might pop open the
+        // pinentry program for signing rewritten commits, which we want to block on.
+        async move {
+            let git = git?;
+            let todo_directory = git_directory.join(REBASE_TODO_DIR);
+            smol::fs::create_dir_all(&todo_directory).await?;
+            let message_path = |ix: usize| todo_directory.join(format!("message-{ix}"));
+            for (ix, entry) in todo.iter().enumerate() {
+                if let Some(message) = &entry.message {
+                    smol::fs::write(message_path(ix), message).await?;
+                }
+            }
+            let todo_path = todo_directory.join("git-rebase-todo");
+            smol::fs::write(&todo_path, todo_list(&todo, message_path)?).await?;
+
+            // Git runs the sequence editor through a shell with the todo file as its argument.
+            let todo_path = todo_path.to_string_lossy();
+            let sequence_editor = util::shell::ShellKind::Posix
+                .try_quote(&todo_path)
+                .map(|todo_path| format!("cp {todo_path}"))
+                .context("invalid rebase todo path")?;
+
+            let mut command = git.build_command(&["rebase", "--interactive", "--no-autosquash"]);
+            command
+                .envs(env.iter())
+                .env("GIT_SEQUENCE_EDITOR", sequence_editor)
+                // Keep the combined message of squashed commits without prompting for it.
+                .env("GIT_EDITOR", "true")
+                .arg("--end-of-options")
+                .arg(onto)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            let result = run_git_command(env, ask_pass, command, executor).await;
+            rebase_outcome(&git_directory, result).await
+        }
+        .boxed()
+    }
+
+    fn rebase_step(
+        &self,
+        step: RebaseStep,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<RebaseOutcome>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        let executor = self.executor.clone();
+        async move {
+            let git = git?;
+            let mut command = git.build_command(&["rebase", step.arg()]);
+            command
+                .envs(env.iter())
+                .env("GIT_EDITOR", "true")
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            let result = run_git_command(env, ask_pass, command, executor).await;
+            rebase_outcome(&git_directory, result).await
+        }
+        .boxed()
+    }
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>> {
+        let git_directory = self.path();
+        self.executor
+            .spawn(async move { RebaseState::load(&git_directory) })
+            .boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<BisectState>>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                if !git_directory.join(BISECT_START).exists() {
+                    let output = git
+                        .build_command(&["bisect", "start"])
+                        .envs(env.iter())
+                        .output()
+                        .await?;
+                    anyhow::ensure!(
+                        output.status.success(),
+                        "Failed to start bisect:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                }
+
+                let mut command = git.build_command(&["bisect", mark.as_str()]);
+                command.envs(env.iter());
+                if let Some(commit) = commit {
+                    // `git bisect` doesn't accept `--end-of-options`.
+                    anyhow::ensure!(
+                        !commit.starts_with('-'),
+                        "`commit` must be a revision, not {commit:?}"
+                    );
+                    command.arg(commit);
+                }
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to mark commit as {}:\n{}",
+                    mark.as_str(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(load_bisect_state(&git, &git_directory).await)
+            })
+            .boxed()
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let output = git
+                    .build_command(&["bisect", "reset"])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to reset bisect:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>> {
+        let git = self.git_binary_in_worktree();
+        let git_directory = self.path();
+        self.executor
+            .spawn(async move { load_bisect_state(&git.ok()?, &git_directory).await })
+            .boxed()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                let output = git
+                    .build_command(&[
+                        "config",
+                        "-z",
+                        "--file",
+                        GITMODULES,
+                        "--get-regexp",
+                        r"^submodule\..*\.path$",
+                    ])
+                    .output()
+                    .await?;
+                // Git exits with 1 when `.gitmodules` doesn't exist or lists no paths.
+                if !output.status.success() {
+                    return Ok(Vec::new());
+                }
+                let paths = parse_submodule_paths(&String::from_utf8_lossy(&output.stdout));
+                if paths.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let output = git
+                    .build_command(&["ls-files", "--stage", "-z", "--"])
+                    .args(&paths)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list submodule commits:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let recorded_commits =
+                    parse_recorded_commits(&String::from_utf8_lossy(&output.stdout));
+
+                let mut submodules = Vec::with_capacity(paths.len());
+                for path in paths {
+                    let mut submodule = Submodule::new(RepoPath::new(&path)?);
+                    submodule.recorded_commit = recorded_commits
+                        .get(&path)
+                        .map(|commit| commit.clone().into());
+
+                    // A submodule that isn't initialized is an empty directory, in
+                    // which git finds the superproject instead.
+                    let is_initialized = git
+                        .run(&["-C", path.as_str(), "rev-parse", "--show-prefix"])
+                        .await
+                        .is_ok_and(|prefix| prefix.is_empty());
+                    if is_initialized {
+                        let output = git
+                            .run_raw(&[
+                                "-C",
+                                path.as_str(),
+                                "status",
+                                "--porcelain=v2",
+                                "--branch",
+                                "-z",
+                            ])
+                            .await?;
+                        submodule.set_checkout_status(&output);
+                    }
+
+                    if let Some(recorded_commit) = &submodule.recorded_commit
+                        && submodule.is_modified()
+                    {
+                        let range = format!("HEAD...{recorded_commit}");
+                        // The recorded commit may not have been fetched into the submodule.
+                        if let Ok(output) = git
+                            .run(&[
+                                "-C",
+                                path.as_str(),
+                                "rev-list",
+                                "--left-right",
+                                "--count",
+                                range.as_str(),
+                            ])
+                            .await
+                        {
+                            submodule.set_divergence(&output);
+                        }
+                    }
+                    submodules.push(submodule);
+                }
+                Ok(submodules)
+            })
+            .boxed()
+    }
+
+    fn submodule_command(
+        &self,
+        operation: SubmoduleOperation,
+        paths: Vec<RepoPath>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.command_directory();
+        let git_directory = self.path();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let is_trusted = self.is_trusted();
+        // Note: Do not spawn this command on the background thread, it might pop open the credential helper
+        // which we want to block on.
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't update submodules")?;
+            let git = GitBinary::new(
+                git_binary_path,
+                working_directory,
+                git_directory,
+                executor.clone(),
+                is_trusted,
+            );
+            let mut command = git.build_command(&["submodule", operation.as_str()]);
+            match operation {
+                SubmoduleOperation::Init => {}
+                SubmoduleOperation::Update => {
+                    command.args(["--init", "--recursive"]);
+                }
+                SubmoduleOperation::Sync => {
+                    command.arg("--recursive");
+                }
+            }
+            command
+                .envs(env.iter())
+                .arg("--")
+                .args(paths.iter().map(|path| path.as_unix_str()))
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            run_git_command(env, ask_pass, command, executor).await
+        }
+        .boxed()
+    }
+
     fn fetch(
         &self,
         fetch_options: FetchOptions,
@@ -3818,6 +4779,10 @@ impl GitBinary {
             command.args(["-c", "credential.helper="]);
             command.args(["-c", "protocol.ext.allow=never"]);
             command.args(["-c", "diff.external="]);
+            // Checking a commit's signature runs the repository's configured signing programs.
+            command.args(["-c", "gpg.program=gpg"]);
+            command.args(["-c", "gpg.ssh.program=ssh-keygen"]);
+            command.args(["-c", "gpg.x509.program=gpgsm"]);
         }
         command.args(args);
 
@@ -3878,11 +4843,141 @@ async fn run_git_command(
                 .env("GIT_CONFIG_VALUE_0", gpg_wrapper);
         }
 
-        #[cfg(target_os = "windows")]
-        command.env("ZED_ASKPASS_SOCKET", ask_pass.socket_path());
-        let git_process = command.spawn()?;
+        #[cfg(target_os = "windows")]
+        command.env("ZED_ASKPASS_SOCKET", ask_pass.socket_path());
+        let git_process = command.spawn()?;
+
+        run_askpass_command(ask_pass, git_process).await
+    }
+}
+
+/// Cherry-picks or reverts `commits` one at a time, so that a conflict leaves a
+/// single commit to resolve and commit, rather than a sequence to continue.
+async fn apply_commits(
+    git: &GitBinary,
+    git_directory: &Path,
+    command: &str,
+    commits: Vec<String>,
+    env: &HashMap<String, String>,
+) -> Result<ApplyCommitsOutcome> {
+    let in_progress_head = match command {
+        "revert" => "REVERT_HEAD",
+        _ => "CHERRY_PICK_HEAD",
+    };
+    let mut outcome = ApplyCommitsOutcome {
+        total: commits.len(),
+        ..Default::default()
+    };
+    for commit in commits {
+        let output = git
+            .build_command(&[command, "--no-edit", "--end-of-options"])
+            .envs(env.iter())
+            .arg(&commit)
+            .output()
+            .await?;
+        outcome
+            .message
+            .push_str(&String::from_utf8_lossy(&output.stdout));
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Git only leaves the operation in progress when the changes conflict.
+            anyhow::ensure!(
+                git_directory.join(in_progress_head).exists(),
+                "Failed to {command} {commit}:\n{stderr}"
+            );
+            outcome.message.push_str(&stderr);
+            outcome.conflicted_sha = Some(commit);
+            break;
+        }
+        outcome.applied += 1;
+    }
+    Ok(outcome)
+}
+
+/// Runs git with `input` written to its stdin.
+async fn run_with_input(
+    git: &GitBinary,
+    args: &[&str],
+    input: &str,
+    env: Option<&HashMap<String, String>>,
+) -> Result<Output> {
+    let mut command = git.build_command(args);
+    if let Some(env) = env {
+        command.envs(env.iter());
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().context("no stdin for git process")?;
+    stdin.write_all(input.as_bytes()).await?;
+    stdin.flush().await?;
+    drop(stdin);
+    Ok(child.output().await?)
+}
+
+async fn load_bisect_state(git: &GitBinary, git_directory: &Path) -> Option<BisectState> {
+    if !git_directory.join(BISECT_START).exists() {
+        return None;
+    }
 
-        run_askpass_command(ask_pass, git_process).await
+    let mut state = BisectState::default();
+    let refs = git
+        .run(&[
+            "for-each-ref",
+            "--format=%(objectname) %(refname)",
+            "refs/bisect/",
+        ])
+        .await
+        .log_err()?;
+    state.set_refs(&refs);
+    state.current = git
+        .run(&["rev-parse", "HEAD"])
+        .await
+        .log_err()
+        .map(SharedString::from);
+    if let Some(bad) = &state.bad
+        && !state.good.is_empty()
+    {
+        let mut args = vec!["rev-list", "--bisect-vars", bad.as_ref(), "--not"];
+        args.extend(state.good.iter().map(|good| good.as_ref()));
+        if let Some(progress) = git.run(&args).await.log_err() {
+            state.set_progress(&progress);
+        }
+    }
+    if let Ok(log) = smol::fs::read_to_string(git_directory.join(BISECT_LOG)).await {
+        state.set_result(&log);
+    }
+    Some(state)
+}
+
+/// Git exits with an error when a rebase stops at conflicts, so a failure is
+/// only reported as such when it leaves no rebase in progress.
+async fn rebase_outcome(
+    git_directory: &Path,
+    result: Result<RemoteCommandOutput>,
+) -> Result<RebaseOutcome> {
+    let stopped = RebaseState::load(git_directory);
+    if stopped.is_none() {
+        smol::fs::remove_dir_all(git_directory.join(REBASE_TODO_DIR))
+            .await
+            .ok();
+    }
+    match result {
+        Ok(output) => Ok(RebaseOutcome {
+            stopped,
+            message: if output.stderr.trim().is_empty() {
+                output.stdout
+            } else {
+                output.stderr
+            },
+        }),
+        Err(error) if stopped.is_some() => Ok(RebaseOutcome {
+            stopped,
+            message: error.to_string(),
+        }),
+        Err(error) => Err(error),
     }
 }
 
@@ -5043,6 +6138,32 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_build_command_untrusted_pins_signature_programs(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let dir = tempfile::tempdir().unwrap();
+        git_init_repo(dir.path());
+        git_command(dir.path(), ["config", "gpg.ssh.program", "/tmp/malicious"]);
+
+        let git = GitBinary::new(
+            PathBuf::from("git"),
+            dir.path().to_path_buf(),
+            dir.path().join(".git"),
+            cx.executor(),
+            false,
+        );
+        let output = git
+            .build_command(&["config", "--get", "gpg.ssh.program"])
+            .output()
+            .await
+            .expect("git config should run");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "ssh-keygen",
+            "signature programs should not come from untrusted repos"
+        );
+    }
+
     #[gpui::test]
     async fn test_build_command_trusted_only_disables_fsmonitor(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -5179,6 +6300,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5206,6 +6328,7 @@ mod tests {
             "Commit after checkpoint".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5280,6 +6403,7 @@ mod tests {
             "Commit in untrusted repo".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5297,6 +6421,7 @@ mod tests {
             "Commit blocked by hook".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5313,6 +6438,7 @@ mod tests {
             "Original message".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5335,6 +6461,7 @@ mod tests {
                 no_verify: true,
                 ..Default::default()
             },
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5390,6 +6517,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5516,6 +6644,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5621,6 +6750,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5757,6 +6887,32 @@ mod tests {
         assert_eq!(upstream.branch_name(), Some("feature/git-pull-request"));
     }
 
+    #[test]
+    fn test_parse_worktree_statuses() {
+        let statuses = parse_worktree_statuses_from_str(
+            "worktree /home/user/project\nHEAD abc123\nbranch refs/heads/main\n\n\
+             worktree /home/user/gone\nHEAD def456\ndetached\nprunable gitdir file points to non-existent location\n\n\
+             worktree /home/user/kept\nHEAD 789aaa\nbranch refs/heads/kept\nlocked on a usb drive\n",
+        );
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses[0].path, PathBuf::from("/home/user/project"));
+        assert!(!statuses[0].is_prunable && !statuses[0].is_locked);
+        assert!(statuses[1].is_prunable && !statuses[1].is_locked);
+        assert!(!statuses[2].is_prunable && statuses[2].is_locked);
+
+        let (changed_files, ahead_behind) = parse_porcelain_v2_status(
+            "# branch.oid abc123\n# branch.head main\n# branch.upstream origin/main\n\
+             # branch.ab +2 -1\n1 .M N... 100644 100644 100644 aaa aaa src/main.rs\n? notes.txt\n",
+        );
+        assert_eq!(changed_files, 2);
+        assert_eq!(ahead_behind, Some((2, 1)));
+
+        assert_eq!(
+            parse_porcelain_v2_status("# branch.oid abc123\n# branch.head (detached)\n"),
+            (0, None)
+        );
+    }
+
     #[test]
     fn test_parse_worktrees_from_str() {
         // Empty input
@@ -5887,6 +7043,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -5975,6 +7132,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -6043,6 +7201,97 @@ mod tests {
         assert!(!worktree_path.exists());
     }
 
+    #[gpui::test]
+    async fn test_move_changes_to_worktree(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        let worktrees_dir = temp_dir.path().join("worktrees");
+        git_init_repo(&repo_dir);
+
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        smol::fs::write(repo_dir.join("file.txt"), "content")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file.txt")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            CommitSigning::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            Arc::new(test_commit_envs()),
+        )
+        .await
+        .unwrap();
+
+        let worktree_path = worktrees_dir.join("target");
+        repo.create_worktree(
+            CreateWorktreeTarget::NewBranch {
+                branch_name: "target".to_string(),
+                base_sha: Some("HEAD".to_string()),
+            },
+            worktree_path.clone(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(repo_dir.join("file.txt"), "changed")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.join("new.txt"), "untracked")
+            .await
+            .unwrap();
+
+        let statuses = repo.worktree_statuses().await.unwrap();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].changed_files, 2);
+        assert!(statuses[0].modified_at.is_some());
+        assert!(!statuses[1].is_dirty());
+
+        repo.move_changes_to_worktree(worktree_path.clone(), Arc::new(test_commit_envs()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.join("file.txt"))
+                .await
+                .unwrap(),
+            "content"
+        );
+        assert!(!repo_dir.join("new.txt").exists());
+        assert_eq!(
+            smol::fs::read_to_string(worktree_path.join("file.txt"))
+                .await
+                .unwrap(),
+            "changed"
+        );
+        assert!(worktree_path.join("new.txt").exists());
+
+        // Nothing is left to move, and a stale worktree can be pruned.
+        assert!(
+            repo.move_changes_to_worktree(worktree_path.clone(), Arc::new(test_commit_envs()))
+                .await
+                .is_err()
+        );
+        std::fs::remove_dir_all(&worktree_path).unwrap();
+        let statuses = repo.worktree_statuses().await.unwrap();
+        assert!(statuses[1].is_prunable);
+        repo.prune_worktrees().await.unwrap();
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+    }
+
     #[gpui::test]
     async fn test_rename_worktree(cx: &mut TestAppContext) {
         disable_git_global_config();
@@ -6073,6 +7322,7 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            CommitSigning::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
             Arc::new(test_commit_envs()),
         )
@@ -6309,6 +7559,327 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_rebase_interactive(cx: &mut TestAppContext) {
+        use crate::rebase::RebaseTodoAction;
+
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git_init_repo(repo_dir.path());
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        git_command(repo_dir.path(), ["commit", "--allow-empty", "-m", "Base"]);
+        let mut shas = Vec::new();
+        for (name, contents) in [("a", "one"), ("b", "two"), ("a", "three")] {
+            fs::write(repo_dir.path().join(name), contents).unwrap();
+            git_command(repo_dir.path(), ["add", name]);
+            git_command(
+                repo_dir.path(),
+                ["commit", "-m", format!("Write {name}").as_str()],
+            );
+            shas.push(
+                Oid::from_str(&git_command_output(repo_dir.path(), ["rev-parse", "HEAD"])).unwrap(),
+            );
+        }
+
+        let outcome = repo
+            .rebase_interactive(
+                "HEAD~3".into(),
+                vec![
+                    RebaseTodoEntry {
+                        action: RebaseTodoAction::Reword,
+                        sha: shas[0],
+                        message: Some("Write a, reworded".into()),
+                    },
+                    RebaseTodoEntry {
+                        action: RebaseTodoAction::Fixup,
+                        sha: shas[2],
+                        message: None,
+                    },
+                    RebaseTodoEntry {
+                        action: RebaseTodoAction::Edit,
+                        sha: shas[1],
+                        message: None,
+                    },
+                ],
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                Arc::new(test_commit_envs()),
+            )
+            .await
+            .unwrap();
+
+        let stopped = outcome
+            .stopped
+            .expect("rebase should stop at the edit entry");
+        assert_eq!(stopped.branch_name(), Some("main"));
+        // The reword's `exec` line counts as a step.
+        assert_eq!(stopped.step, 4);
+        assert_eq!(stopped.total, 4);
+        assert_eq!(repo.rebase_state().await, Some(stopped));
+
+        let outcome = repo
+            .rebase_step(
+                RebaseStep::Continue,
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                Arc::new(test_commit_envs()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.stopped, None);
+        assert_eq!(repo.rebase_state().await, None);
+        assert!(!repo_dir.path().join(".git").join(REBASE_TODO_DIR).exists());
+
+        assert_eq!(
+            git_command_output(repo_dir.path(), ["log", "--pretty=%s", "-3"]),
+            "Write b\nWrite a, reworded\nBase"
+        );
+        assert_eq!(
+            fs::read_to_string(repo_dir.path().join("a")).unwrap(),
+            "three"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_range_stops_at_conflict(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git_init_repo(repo_dir.path());
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        fs::write(repo_dir.path().join("a"), "base").unwrap();
+        git_command(repo_dir.path(), ["add", "a"]);
+        git_command(repo_dir.path(), ["commit", "-m", "Base"]);
+        git_command(repo_dir.path(), ["switch", "-c", "other"]);
+        let mut shas = Vec::new();
+        for (name, contents) in [("b", "one"), ("a", "two"), ("c", "three")] {
+            fs::write(repo_dir.path().join(name), contents).unwrap();
+            git_command(repo_dir.path(), ["add", name]);
+            git_command(
+                repo_dir.path(),
+                ["commit", "-m", format!("Write {name}").as_str()],
+            );
+            shas.push(git_command_output(repo_dir.path(), ["rev-parse", "HEAD"]));
+        }
+        git_command(repo_dir.path(), ["switch", "main"]);
+        fs::write(repo_dir.path().join("a"), "conflict").unwrap();
+        git_command(repo_dir.path(), ["commit", "-am", "Change a"]);
+
+        let outcome = repo
+            .cherry_pick(
+                shas[0].clone(),
+                Some(shas[2].clone()),
+                Arc::new(test_commit_envs()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.applied, 1);
+        assert_eq!(outcome.total, 3);
+        assert_eq!(outcome.conflicted_sha.as_ref(), Some(&shas[1]));
+        assert!(repo_dir.path().join(".git/CHERRY_PICK_HEAD").exists());
+        assert!(!repo_dir.path().join(".git/sequencer").exists());
+
+        git_command(repo_dir.path(), ["cherry-pick", "--abort"]);
+        let outcome = repo
+            .revert("HEAD".into(), Arc::new(test_commit_envs()))
+            .await
+            .unwrap();
+        assert_eq!(outcome.applied, 1);
+        assert_eq!(outcome.conflicted_sha, None);
+        assert_eq!(
+            git_command_output(repo_dir.path(), ["log", "--pretty=%s", "-1"]),
+            "Revert \"Write b\""
+        );
+    }
+
+    #[gpui::test]
+    async fn test_load_conflict_stages(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git_init_repo(repo_dir.path());
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        fs::write(repo_dir.path().join("a"), "base\n").unwrap();
+        git_command(repo_dir.path(), ["add", "a"]);
+        git_command(repo_dir.path(), ["commit", "-m", "Base"]);
+        git_command(repo_dir.path(), ["switch", "-c", "other"]);
+        fs::write(repo_dir.path().join("a"), "theirs\n").unwrap();
+        git_command(repo_dir.path(), ["commit", "-am", "Theirs"]);
+        let theirs = git_command_output(repo_dir.path(), ["rev-parse", "HEAD"]);
+        git_command(repo_dir.path(), ["switch", "main"]);
+        fs::write(repo_dir.path().join("a"), "ours\n").unwrap();
+        git_command(repo_dir.path(), ["commit", "-am", "Ours"]);
+        let outcome = repo
+            .cherry_pick(theirs.clone(), None, Arc::new(test_commit_envs()))
+            .await
+            .unwrap();
+        assert_eq!(outcome.conflicted_sha, Some(theirs));
+
+        let stages = repo
+            .load_conflict_stages(RepoPath::new("a").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            stages,
+            ConflictStages {
+                base: Some("base\n".into()),
+                ours: Some("ours\n".into()),
+                theirs: Some("theirs\n".into()),
+            }
+        );
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git_init_repo(repo_dir.path());
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        let mut shas = Vec::new();
+        for ix in 0..8 {
+            fs::write(repo_dir.path().join("a"), ix.to_string()).unwrap();
+            git_command(repo_dir.path(), ["add", "a"]);
+            git_command(
+                repo_dir.path(),
+                ["commit", "-m", format!("Write {ix}").as_str()],
+            );
+            shas.push(git_command_output(repo_dir.path(), ["rev-parse", "HEAD"]));
+        }
+        assert_eq!(repo.bisect_state().await, None);
+
+        let env = Arc::new(HashMap::default());
+        let state = repo
+            .bisect_mark(BisectMark::Bad, None, env.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.bad.as_deref(), Some(shas[7].as_str()));
+        assert!(!state.is_testing());
+
+        // The change was introduced by the fourth commit.
+        let mut state = repo
+            .bisect_mark(BisectMark::Good, Some(shas[0].clone()), env.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        while state.is_testing() {
+            let current = state.current.clone().unwrap();
+            let ix = shas.iter().position(|sha| *sha == current).unwrap();
+            let mark = if ix >= 3 {
+                BisectMark::Bad
+            } else {
+                BisectMark::Good
+            };
+            state = repo
+                .bisect_mark(mark, None, env.clone())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(state.first_bad.as_deref(), Some(shas[3].as_str()));
+        assert_eq!(repo.bisect_state().await, Some(state));
+
+        repo.bisect_reset(env).await.unwrap();
+        assert_eq!(repo.bisect_state().await, None);
+        assert_eq!(
+            git_command_output(repo_dir.path(), ["rev-parse", "HEAD"]),
+            shas[7]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_submodules(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let library_dir = temp_dir.path().join("library");
+        git_init_repo(&library_dir);
+        fs::write(library_dir.join("lib.rs"), "fn lib() {}").unwrap();
+        git_command(&library_dir, ["add", "lib.rs"]);
+        git_command(&library_dir, ["commit", "-m", "Add library"]);
+
+        let repo_dir = temp_dir.path().join("app");
+        git_init_repo(&repo_dir);
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        assert_eq!(repo.submodules().await.unwrap(), Vec::new());
+
+        git_command(
+            &repo_dir,
+            [
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                "../library",
+                "vendor/library",
+            ],
+        );
+        git_command(&repo_dir, ["commit", "-m", "Add submodule"]);
+        let recorded_commit = git_command_output(&library_dir, ["rev-parse", "HEAD"]);
+
+        let [submodule] = repo.submodules().await.unwrap().try_into().unwrap();
+        assert_eq!(submodule.path, repo_path("vendor/library"));
+        assert_eq!(
+            submodule.recorded_commit.as_deref(),
+            Some(recorded_commit.as_str())
+        );
+        assert_eq!(submodule.head, submodule.recorded_commit);
+        assert_eq!(submodule.branch.as_deref(), Some("main"));
+        assert!(!submodule.is_modified());
+        assert!(!submodule.dirty);
+
+        let submodule_dir = repo_dir.join("vendor/library");
+        fs::write(submodule_dir.join("lib.rs"), "fn lib() -> u32 { 1 }").unwrap();
+        git_command(&submodule_dir, ["commit", "-am", "Change library"]);
+        git_command(&submodule_dir, ["checkout", "--detach"]);
+        fs::write(submodule_dir.join("notes.txt"), "").unwrap();
+
+        let [submodule] = repo.submodules().await.unwrap().try_into().unwrap();
+        assert!(submodule.is_detached());
+        assert!(submodule.is_modified());
+        assert!(submodule.dirty);
+        assert_eq!((submodule.ahead, submodule.behind), (1, 0));
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<'_, Result<()>> {
@@ -0,0 +1,169 @@
+use gpui::SharedString;
+
+/// How to sign a commit, overriding the repository's git config where set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitSigning {
+    /// Whether to sign the commit, `None` to follow `commit.gpgSign`.
+    pub sign: Option<bool>,
+    /// The signature format, `None` to follow `gpg.format`.
+    pub format: Option<SigningFormat>,
+    /// The key to sign with, `None` to follow `user.signingKey`.
+    pub key: Option<String>,
+}
+
+impl CommitSigning {
+    /// The configuration overrides to pass to git before the `commit` subcommand.
+    pub fn config_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(format) = self.format {
+            args.push("-c".into());
+            args.push(format!("gpg.format={}", format.as_str()));
+        }
+        if let Some(key) = &self.key {
+            args.push("-c".into());
+            args.push(format!("user.signingKey={key}"));
+        }
+        args
+    }
+
+    /// The argument to pass to `git commit`, if any.
+    pub fn commit_arg(&self) -> Option<&'static str> {
+        match self.sign? {
+            true => Some("--gpg-sign"),
+            false => Some("--no-gpg-sign"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    Ssh,
+    X509,
+}
+
+impl SigningFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenPgp => "openpgp",
+            Self::Ssh => "ssh",
+            Self::X509 => "x509",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignatureStatus {
+    /// The commit isn't signed.
+    Unsigned,
+    /// The signature is good and made with a trusted key.
+    Verified,
+    /// The signature is bad, or made with an untrusted, expired or revoked key.
+    Unverified,
+    /// The signature can't be checked because its key isn't available.
+    UnknownKey,
+}
+
+/// The signature of a commit, as verified by git.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    /// The name of the signer, when git can tell.
+    pub signer: Option<SharedString>,
+    /// The key used to sign the commit.
+    pub key: Option<SharedString>,
+}
+
+impl CommitSignature {
+    pub const FORMAT: &str = "--format=%G?%x00%GS%x00%GK";
+
+    pub fn unsigned() -> Self {
+        Self {
+            status: SignatureStatus::Unsigned,
+            signer: None,
+            key: None,
+        }
+    }
+
+    /// Parses the output of `git show --no-patch` with [`Self::FORMAT`].
+    pub fn parse(output: &str) -> Self {
+        let mut fields = output.trim_end_matches('\n').split('\0');
+        let status = match fields.next().unwrap_or_default() {
+            "G" => SignatureStatus::Verified,
+            "B" | "U" | "X" | "Y" | "R" => SignatureStatus::Unverified,
+            "E" => SignatureStatus::UnknownKey,
+            _ => return Self::unsigned(),
+        };
+        let mut field = || {
+            fields
+                .next()
+                .filter(|field| !field.is_empty())
+                .map(|field| SharedString::from(field.to_string()))
+        };
+        Self {
+            status,
+            signer: field(),
+            key: field(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_signature() {
+        assert_eq!(
+            CommitSignature::parse("G\0Jane Doe <jane@example.com>\0ABCDEF0123456789\n"),
+            CommitSignature {
+                status: SignatureStatus::Verified,
+                signer: Some("Jane Doe <jane@example.com>".into()),
+                key: Some("ABCDEF0123456789".into()),
+            }
+        );
+        assert_eq!(
+            CommitSignature::parse("E\0\0ABCDEF0123456789\n"),
+            CommitSignature {
+                status: SignatureStatus::UnknownKey,
+                signer: None,
+                key: Some("ABCDEF0123456789".into()),
+            }
+        );
+        assert_eq!(
+            CommitSignature::parse("U\0Jane Doe\0ABCDEF0123456789\n").status,
+            SignatureStatus::Unverified
+        );
+        assert_eq!(
+            CommitSignature::parse("N\0\0\n"),
+            CommitSignature::unsigned()
+        );
+    }
+
+    #[test]
+    fn test_commit_signing_args() {
+        let signing = CommitSigning {
+            sign: Some(true),
+            format: Some(SigningFormat::Ssh),
+            key: Some("~/.ssh/id_ed25519.pub".into()),
+        };
+        assert_eq!(
+            signing.config_args(),
+            vec![
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "user.signingKey=~/.ssh/id_ed25519.pub"
+            ]
+        );
+        assert_eq!(signing.commit_arg(), Some("--gpg-sign"));
+
+        let signing = CommitSigning {
+            sign: Some(false),
+            ..Default::default()
+        };
+        assert!(signing.config_args().is_empty());
+        assert_eq!(signing.commit_arg(), Some("--no-gpg-sign"));
+        assert_eq!(CommitSigning::default().commit_arg(), None);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{commit::GITLINK_MODE, repository::RepoPath};
+use collections::HashMap;
+use gpui::SharedString;
+
+/// The file registering the submodules of a repository, relative to its
+/// working directory.
+pub const GITMODULES: &str = ".gitmodules";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubmoduleOperation {
+    /// Registers the submodules' URLs from `.gitmodules` in the repository's config.
+    Init,
+    /// Checks out the commits recorded for the submodules, cloning them if needed.
+    Update,
+    /// Copies URLs changed in `.gitmodules` to the repository's config.
+    Sync,
+}
+
+impl SubmoduleOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Init => "init",
+            Self::Update => "update",
+            Self::Sync => "sync",
+        }
+    }
+}
+
+/// A submodule of a repository, and how its checkout compares to the commit
+/// the superproject records for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    /// The commit recorded for the submodule in the superproject's index.
+    pub recorded_commit: Option<SharedString>,
+    /// The commit checked out in the submodule, `None` when it isn't initialized.
+    pub head: Option<SharedString>,
+    /// The branch checked out in the submodule, `None` when its HEAD is detached.
+    pub branch: Option<SharedString>,
+    /// The number of commits the checkout has that the recorded commit doesn't.
+    pub ahead: usize,
+    /// The number of commits the recorded commit has that the checkout doesn't.
+    pub behind: usize,
+    /// Whether the submodule has uncommitted changes or untracked files.
+    pub dirty: bool,
+}
+
+impl Submodule {
+    pub fn new(path: RepoPath) -> Self {
+        Self {
+            path,
+            recorded_commit: None,
+            head: None,
+            branch: None,
+            ahead: 0,
+            behind: 0,
+            dirty: false,
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.head.is_some()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.is_initialized() && self.branch.is_none()
+    }
+
+    /// Whether the checkout differs from the commit the superproject records.
+    pub fn is_modified(&self) -> bool {
+        self.is_initialized() && self.head != self.recorded_commit
+    }
+
+    /// Fills in the checkout's state from the output of
+    /// `git status --porcelain=v2 --branch -z` run in the submodule.
+    pub(crate) fn set_checkout_status(&mut self, output: &str) {
+        for entry in output.split('\0') {
+            if let Some(oid) = entry.strip_prefix("# branch.oid ") {
+                self.head = (oid != "(initial)").then(|| oid.to_string().into());
+            } else if let Some(head) = entry.strip_prefix("# branch.head ") {
+                self.branch = (head != "(detached)").then(|| head.to_string().into());
+            } else if !entry.is_empty() && !entry.starts_with("# ") {
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Fills in how far the checkout is from the recorded commit from the output
+    /// of `git rev-list --left-right --count HEAD...<recorded commit>`.
+    pub(crate) fn set_divergence(&mut self, output: &str) {
+        let mut counts = output
+            .split_whitespace()
+            .map(|count| count.parse().unwrap_or(0));
+        self.ahead = counts.next().unwrap_or(0);
+        self.behind = counts.next().unwrap_or(0);
+    }
+}
+
+/// Parses the output of
+/// `git config -z --file .gitmodules --get-regexp '^submodule\..*\.path$'`.
+pub(crate) fn parse_submodule_paths(output: &str) -> Vec<String> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (_, path) = entry.split_once('\n')?;
+            Some(path.trim_end_matches('/').to_string())
+        })
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Parses the commits recorded for gitlinks from the output of
+/// `git ls-files --stage -z`, keyed by path.
+pub(crate) fn parse_recorded_commits(output: &str) -> HashMap<String, String> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (info, path) = entry.split_once('\t')?;
+            let mut info = info.split(' ');
+            if info.next()? != GITLINK_MODE {
+                return None;
+            }
+            Some((path.to_string(), info.next()?.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_submodule_paths() {
+        assert_eq!(
+            parse_submodule_paths(
+                "submodule.vendor/lib.path\nvendor/lib\0submodule.tools.path\ntools/\0"
+            ),
+            vec!["vendor/lib".to_string(), "tools".to_string()]
+        );
+        assert!(parse_submodule_paths("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_recorded_commits() {
+        let commits = parse_recorded_commits(
+            "100644 e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 0\t.gitmodules\0\
+             160000 0770924c247196b13b32e11a04ca00b7b97f4bad 0\tvendor/lib\0",
+        );
+        assert_eq!(commits.len(), 1);
+        assert_eq!(
+            commits.get("vendor/lib").map(String::as_str),
+            Some("0770924c247196b13b32e11a04ca00b7b97f4bad")
+        );
+    }
+
+    #[test]
+    fn test_checkout_status() {
+        let mut submodule = Submodule::new(RepoPath::new("vendor/lib").unwrap());
+        submodule.recorded_commit = Some("0770924c247196b13b32e11a04ca00b7b97f4bad".into());
+        submodule.set_checkout_status(
+            "# branch.oid 06fe12596cb8a276b962b8b9ece2b8f0503ef303\0\
+             # branch.head (detached)\0\
+             ? notes.txt\0",
+        );
+        submodule.set_divergence("2\t1\n");
+        assert!(submodule.is_detached());
+        assert!(submodule.is_modified());
+        assert!(submodule.dirty);
+        assert_eq!((submodule.ahead, submodule.behind), (2, 1));
+
+        let mut submodule = Submodule::new(RepoPath::new("tools").unwrap());
+        submodule.set_checkout_status(
+            "# branch.oid 06fe12596cb8a276b962b8b9ece2b8f0503ef303\0\
+             # branch.head main\0\
+             # branch.upstream origin/main\0",
+        );
+        assert_eq!(submodule.branch.as_deref(), Some("main"));
+        assert!(!submodule.dirty);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::commit_context_menu::git_task_context;
+use crate::commit_view::CommitView;
+use crate::git_panel::show_error_toast;
+use crate::picker_prompt;
+use anyhow::{Context as _, Result, bail};
+use git::{Oid, SHORT_SHA_LENGTH, bisect::BisectMark};
+use gpui::{Empty, Entity, Subscription, Task, WeakEntity};
+use notifications::status_toast::StatusToast;
+use project::{
+    Project,
+    git_store::{GitStoreEvent, Repository, RepositoryEvent},
+};
+use std::str::FromStr as _;
+use ui::{Tooltip, prelude::*};
+use workspace::{HideStatusItem, StatusItemView, Workspace, item::ItemHandle};
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..SHORT_SHA_LENGTH).unwrap_or(sha)
+}
+
+/// Marks `commit`, or HEAD when it's `None`, starting a bisect if none is in progress.
+pub(crate) fn mark(
+    mark: BisectMark,
+    commit: Option<Oid>,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let Some(repository_handle) = repository.upgrade() else {
+        return;
+    };
+    let task = repository_handle.update(cx, |repository, _| {
+        repository.bisect_mark(mark, commit.map(|commit| commit.to_string()))
+    });
+    cx.spawn(async move |cx| {
+        let result = task.await.map_err(anyhow::Error::from).flatten();
+        workspace
+            .update(cx, |workspace, cx| match result {
+                Ok(state) => {
+                    if let Some(first_bad) = state.and_then(|state| state.first_bad) {
+                        show_first_bad_commit(first_bad, repository, workspace, cx);
+                    }
+                }
+                Err(error) => {
+                    show_error_toast(cx.entity(), format!("bisect {}", mark.as_str()), error, cx);
+                }
+            })
+            .ok();
+    })
+    .detach();
+}
+
+pub(crate) fn mark_head(workspace: &mut Workspace, bisect_mark: BisectMark, cx: &mut App) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    mark(
+        bisect_mark,
+        None,
+        repository.downgrade(),
+        workspace.weak_handle(),
+        cx,
+    );
+}
+
+pub(crate) fn reset(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let task = repository.update(cx, |repository, _| repository.bisect_reset());
+    cx.spawn(async move |workspace, cx| {
+        if let Err(error) = task.await.map_err(anyhow::Error::from).flatten() {
+            workspace
+                .update(cx, |_, cx| {
+                    show_error_toast(cx.entity(), "bisect reset", error, cx)
+                })
+                .ok();
+        }
+    })
+    .detach();
+}
+
+/// Tests the commits checked out by the bisect in progress with a task, see
+/// [`BisectIndicator::run`].
+pub(crate) fn run(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    if let Some(indicator) = workspace
+        .status_bar()
+        .read(cx)
+        .item_of_type::<BisectIndicator>()
+    {
+        indicator.update(cx, |indicator, cx| indicator.run(window, cx));
+    }
+}
+
+fn show_first_bad_commit(
+    sha: SharedString,
+    repository: WeakEntity<Repository>,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_weak = cx.weak_entity();
+    let status_toast = StatusToast::new(
+        format!("{} is the first bad commit", short_sha(&sha)),
+        cx,
+        move |this, _cx| {
+            this.icon(
+                Icon::new(IconName::Check)
+                    .size(IconSize::Small)
+                    .color(Color::Success),
+            )
+            .action("View Commit", move |window, cx| {
+                CommitView::open(
+                    sha.to_string(),
+                    repository.clone(),
+                    workspace_weak.clone(),
+                    None,
+                    None,
+                    window,
+                    cx,
+                );
+            })
+            .dismiss_button(true)
+        },
+    );
+    workspace.toggle_status_toast(status_toast, cx);
+}
+
+/// Shows the progress of the bisect in progress, and the first bad commit once
+/// it has been found.
+pub struct BisectIndicator {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    run_task: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl BisectIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().clone();
+        let git_store = project.read(cx).git_store().clone();
+        let subscription = cx.subscribe(&git_store, |_, _, event, cx| match event {
+            GitStoreEvent::ActiveRepositoryChanged(_)
+            | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged, true)
+            | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::HeadChanged, true) => {
+                cx.notify()
+            }
+            _ => {}
+        });
+
+        Self {
+            project,
+            workspace: workspace.weak_handle(),
+            run_task: None,
+            _subscription: subscription,
+        }
+    }
+
+    /// Asks for a task, then runs it on each commit the bisect checks out and
+    /// marks the commit according to its exit code, like `git bisect run`.
+    fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.run_task.is_some() {
+            return;
+        }
+        let project = self.project.read(cx);
+        let Some(repository) = project.active_repository(cx) else {
+            return;
+        };
+        let Some(inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+            return;
+        };
+        let worktree_id = project
+            .find_worktree(&repository.read(cx).work_directory_abs_path, cx)
+            .map(|(worktree, _)| worktree.read(cx).id());
+        let templates = inventory.read(cx).list_tasks(None, None, worktree_id, cx);
+        let repository = repository.downgrade();
+        let workspace = self.workspace.clone();
+
+        self.run_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result: Result<Option<SharedString>> = async {
+                let mut templates = templates.await;
+                if templates.is_empty() {
+                    bail!("No tasks are defined to test the commits with");
+                }
+                let labels = templates
+                    .iter()
+                    .map(|(_, template)| SharedString::from(template.label.clone()))
+                    .collect::<Vec<_>>();
+                let selection = cx
+                    .update(|window, cx| {
+                        picker_prompt::prompt(
+                            "Pick the task that tests each commit",
+                            labels,
+                            workspace.clone(),
+                            window,
+                            cx,
+                        )
+                    })?
+                    .await;
+                let Some(selection) = selection else {
+                    return Ok(None);
+                };
+                let (source_kind, template) = templates.swap_remove(selection);
+
+                let mut state =
+                    repository.read_with(cx, |repository, _| repository.merge.bisect.clone())?;
+                while let Some(current) = state
+                    .as_ref()
+                    .filter(|state| state.is_testing())
+                    .and_then(|state| state.current.clone())
+                {
+                    let task_context = cx.update(|_, cx| {
+                        git_task_context(
+                            &Some(repository.clone()),
+                            Oid::from_str(&current)?,
+                            None,
+                            cx,
+                        )
+                        .context("Repository was closed")
+                    })??;
+                    let resolved_task = template
+                        .resolve_task(&source_kind.to_id_base(), &task_context)
+                        .with_context(|| format!("Failed to resolve task {}", template.label))?;
+                    let status = workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.spawn_in_terminal(resolved_task.resolved, window, cx)
+                        })?
+                        .await;
+                    let status = match status {
+                        Some(status) => status?,
+                        // The task was canceled.
+                        None => return Ok(None),
+                    };
+                    let Some(mark) = status.code().and_then(BisectMark::from_exit_code) else {
+                        bail!(
+                            "Stopped bisecting because the task exited with {status} on {}",
+                            short_sha(&current)
+                        );
+                    };
+                    state = repository
+                        .update(cx, |repository, _| repository.bisect_mark(mark, None))?
+                        .await??;
+                }
+                Ok(state.and_then(|state| state.first_bad))
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                this.run_task = None;
+                cx.notify();
+            })
+            .ok();
+            workspace
+                .update(cx, |workspace, cx| match result {
+                    Ok(Some(first_bad)) => {
+                        show_first_bad_commit(first_bad, repository, workspace, cx)
+                    }
+                    Ok(None) => {}
+                    Err(error) => show_error_toast(cx.entity(), "bisect run", error, cx),
+                })
+                .ok();
+        }));
+        cx.notify();
+    }
+
+    fn stop(&mut self, cx: &mut Context<Self>) {
+        self.run_task = None;
+        cx.notify();
+    }
+
+    fn render_first_bad(&self, sha: SharedString, cx: &mut Context<Self>) -> impl IntoElement {
+        let repository = self
+            .project
+            .read(cx)
+            .active_repository(cx)
+            .map(|repository| repository.downgrade());
+        let workspace = self.workspace.clone();
+        h_flex()
+            .gap_1()
+            .child(
+                Icon::new(IconName::Check)
+                    .size(IconSize::Small)
+                    .color(Color::Success),
+            )
+            .child(Label::new("First bad commit").size(LabelSize::Small))
+            .child(
+                Button::new("bisect-first-bad", short_sha(&sha).to_string())
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text("View Commit"))
+                    .on_click(move |_, window, cx| {
+                        let Some(repository) = repository.clone() else {
+                            return;
+                        };
+                        CommitView::open(
+                            sha.to_string(),
+                            repository,
+                            workspace.clone(),
+                            None,
+                            None,
+                            window,
+                            cx,
+                        );
+                    }),
+            )
+    }
+}
+
+impl Render for BisectIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            return Empty.into_any_element();
+        };
+        let Some(bisect) = repository.read(cx).merge.bisect.clone() else {
+            return Empty.into_any_element();
+        };
+        let reset_button = Button::new("bisect-reset", "Reset")
+            .label_size(LabelSize::Small)
+            .tooltip(|_, cx| Tooltip::for_action("Reset Bisect", &git::BisectReset, cx))
+            .on_click(|_, window, cx| window.dispatch_action(git::BisectReset.boxed_clone(), cx));
+
+        if let Some(first_bad) = bisect.first_bad.clone() {
+            return h_flex()
+                .gap_1()
+                .child(self.render_first_bad(first_bad, cx))
+                .child(reset_button)
+                .into_any_element();
+        }
+        if !bisect.possible_first_bad.is_empty() {
+            return h_flex()
+                .gap_1()
+                .child(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
+                .child(
+                    Label::new(format!(
+                        "Only skipped commits left, {} could be the first bad one",
+                        bisect.possible_first_bad.len()
+                    ))
+                    .size(LabelSize::Small),
+                )
+                .child(reset_button)
+                .into_any_element();
+        }
+
+        let message: SharedString = match (&bisect.bad, bisect.good.is_empty()) {
+            (None, _) => "Bisecting: mark a bad commit".into(),
+            (Some(_), true) => "Bisecting: mark a good commit".into(),
+            (Some(_), false) => {
+                let current = bisect.current.as_deref().map(short_sha).unwrap_or_default();
+                match bisect.remaining_steps {
+                    1 => format!("Bisecting {current}, about 1 step left"),
+                    steps => format!("Bisecting {current}, about {steps} steps left"),
+                }
+                .into()
+            }
+        };
+        let is_running = self.run_task.is_some();
+
+        h_flex()
+            .gap_1()
+            .child(
+                Icon::new(IconName::GitBranch)
+                    .size(IconSize::Small)
+                    .color(Color::Warning),
+            )
+            .child(Label::new(message).size(LabelSize::Small))
+            .child(
+                Button::new("bisect-good", "Good")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_running)
+                    .tooltip(|_, cx| Tooltip::for_action("Mark as Good", &git::BisectGood, cx))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(git::BisectGood.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                Button::new("bisect-bad", "Bad")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_running)
+                    .tooltip(|_, cx| Tooltip::for_action("Mark as Bad", &git::BisectBad, cx))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(git::BisectBad.boxed_clone(), cx)
+                    }),
+            )
+            .when(bisect.is_testing(), |this| {
+                this.child(
+                    Button::new("bisect-skip", "Skip")
+                        .label_size(LabelSize::Small)
+                        .disabled(is_running)
+                        .tooltip(|_, cx| {
+                            Tooltip::for_action("Skip the Current Commit", &git::BisectSkip, cx)
+                        })
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(git::BisectSkip.boxed_clone(), cx)
+                        }),
+                )
+                .child(if is_running {
+                    Button::new("bisect-stop", "Stop")
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text("Stop Marking Commits Automatically"))
+                        .on_click(cx.listener(|this, _, _, cx| this.stop(cx)))
+                } else {
+                    Button::new("bisect-run", "Run Task")
+                        .label_size(LabelSize::Small)
+                        .tooltip(|_, cx| {
+                            Tooltip::for_action("Test Each Commit with a Task", &git::BisectRun, cx)
+                        })
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(git::BisectRun.boxed_clone(), cx)
+                        })
+                })
+            })
+            .child(reset_button)
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for BisectIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+
+    fn hide_setting(&self, _: &App) -> Option<HideStatusItem> {
+        // Only visible while a bisect is in progress.
+        None
+    }
+}
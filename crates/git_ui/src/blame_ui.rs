@@ -1,5 +1,5 @@
 use crate::{
-    commit_tooltip::{CommitAvatar, CommitTooltip, commit_tag_chips},
+    commit_tooltip::{CommitAvatar, CommitTooltip, commit_signature_badge, commit_tag_chips},
     commit_view::CommitView,
 };
 use editor::{BlameRenderer, Editor, hover_markdown_style};
@@ -357,6 +357,7 @@ impl BlameRenderer for GitBlameRenderer {
         let pull_request = details
             .as_ref()
             .and_then(|details| details.pull_request.clone());
+        let signature_badge = commit_signature_badge(&sha, &repository, window, cx);
 
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size(cx);
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
@@ -432,6 +433,12 @@ impl BlameRenderer for GitBlameRenderer {
                                             .gap_1()
                                             .min_w_0()
                                             .children(commit_tag_chips(&tag_names))
+                                            .children(signature_badge.map(|badge| {
+                                                h_flex()
+                                                    .gap_1()
+                                                    .child(badge)
+                                                    .child(Divider::vertical())
+                                            }))
                                             .when_some(pull_request, |this, pr| {
                                                 this.child(
                                                     Button::new(
@@ -516,6 +523,11 @@ fn deploy_blame_entry_context_menu(
     window: &mut Window,
     cx: &mut App,
 ) {
+    let blame = editor.read(cx).blame().cloned();
+    let has_previous_revisions = blame
+        .as_ref()
+        .is_some_and(|blame| blame.read(cx).has_previous_revisions());
+    let blame = blame.filter(|_| blame_entry.previous.is_some() || has_previous_revisions);
     let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
         let sha = format!("{}", blame_entry.sha);
         menu.on_blur_subscription(Subscription::new(|| {}))
@@ -530,6 +542,23 @@ fn deploy_blame_entry_context_menu(
                     })
                 },
             )
+            .when_some(blame, |this, blame| {
+                this.separator()
+                    .when(blame_entry.previous.is_some(), |this| {
+                        let blame = blame.clone();
+                        let blame_entry = blame_entry.clone();
+                        this.entry("Blame Previous Revision", None, move |_, cx| {
+                            blame.update(cx, |blame, cx| {
+                                blame.blame_previous_revision(&blame_entry, cx)
+                            });
+                        })
+                    })
+                    .when(has_previous_revisions, |this| {
+                        this.entry("Reset Blame to Current Revision", None, move |_, cx| {
+                            blame.update(cx, |blame, cx| blame.reset_previous_revisions(cx));
+                        })
+                    })
+            })
     });
 
     editor.update(cx, move |editor, cx| {
@@ -0,0 +1,353 @@
+use crate::git_panel::{askpass_delegate, open_output, show_error_toast};
+use crate::picker_prompt;
+use anyhow::{Context as _, anyhow};
+use editor::Editor;
+use git::{Oid, SHORT_SHA_LENGTH, repository::ApplyCommitsOutcome};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, WeakEntity};
+use menu::{Cancel, Confirm};
+use notifications::status_toast::StatusToast;
+use project::git_store::Repository;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr as _};
+
+/// Cherry-picks `commit` onto HEAD, or the commits from `commit` through `through`.
+pub(crate) fn cherry_pick(
+    commit: Oid,
+    through: Option<Oid>,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let Some(repository) = repository.upgrade() else {
+        return;
+    };
+    let task = repository.update(cx, |repository, _| {
+        repository.cherry_pick(
+            commit.to_string(),
+            through.map(|through| through.to_string()),
+        )
+    });
+    cx.spawn(async move |cx| {
+        let result = task.await.map_err(anyhow::Error::from).flatten();
+        workspace
+            .update(cx, |workspace, cx| {
+                show_apply_commits_result("cherry-pick", result, workspace, cx)
+            })
+            .ok();
+    })
+    .detach();
+}
+
+pub(crate) fn revert(
+    commit: Oid,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let Some(repository) = repository.upgrade() else {
+        return;
+    };
+    let task = repository.update(cx, |repository, _| repository.revert(commit.to_string()));
+    cx.spawn(async move |cx| {
+        let result = task.await.map_err(anyhow::Error::from).flatten();
+        workspace
+            .update(cx, |workspace, cx| {
+                show_apply_commits_result("revert", result, workspace, cx)
+            })
+            .ok();
+    })
+    .detach();
+}
+
+fn show_apply_commits_result(
+    operation: &'static str,
+    result: anyhow::Result<ApplyCommitsOutcome>,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            show_error_toast(cx.entity(), operation, error, cx);
+            return;
+        }
+    };
+
+    let (message, icon) = match &outcome.conflicted_sha {
+        Some(sha) => {
+            let sha = sha.get(..SHORT_SHA_LENGTH).unwrap_or(sha);
+            let message = if outcome.total > 1 {
+                format!(
+                    "Applied {} of {} commits. Resolve the conflicts of {sha} and commit to finish",
+                    outcome.applied, outcome.total
+                )
+            } else {
+                format!("Resolve the conflicts of {sha} and commit to finish the {operation}")
+            };
+            (
+                message,
+                Icon::new(IconName::GitMergeConflict).color(Color::Warning),
+            )
+        }
+        None => {
+            let message = match operation {
+                "revert" => "Reverted commit".to_string(),
+                _ if outcome.total == 1 => "Cherry-picked commit".to_string(),
+                _ => format!("Cherry-picked {} commits", outcome.applied),
+            };
+            (message, Icon::new(IconName::Check).color(Color::Success))
+        }
+    };
+    let output = outcome.message;
+    let workspace_weak = cx.weak_entity();
+    let status_toast = StatusToast::new(message, cx, move |this, _cx| {
+        let this = this.icon(icon.size(IconSize::Small));
+        let this = if output.trim().is_empty() {
+            this
+        } else {
+            this.action("View Log", move |window, cx| {
+                let output = output.clone();
+                workspace_weak
+                    .update(cx, move |workspace, cx| {
+                        open_output(operation, workspace, &output, window, cx)
+                    })
+                    .ok();
+            })
+        };
+        this.dismiss_button(true)
+    });
+    workspace.toggle_status_toast(status_toast, cx);
+}
+
+pub(crate) fn delete_tag(
+    tag_name: SharedString,
+    repository: WeakEntity<Repository>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(repository) = repository.upgrade() else {
+        return;
+    };
+    let answer = window.prompt(
+        PromptLevel::Warning,
+        &format!("Delete tag {tag_name}?"),
+        Some("This only deletes the local tag."),
+        &["Delete", "Cancel"],
+        cx,
+    );
+    window
+        .spawn(cx, async move |cx| {
+            if answer.await != Ok(0) {
+                return anyhow::Ok(());
+            }
+            repository
+                .update(cx, |repository, _| {
+                    repository.delete_tag(tag_name.to_string())
+                })
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |_, _, _| None);
+}
+
+/// Pushes `tag_name` to a remote, asking which one when there are several.
+pub(crate) fn push_tag(
+    tag_name: SharedString,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(repository) = repository.upgrade() else {
+        return;
+    };
+    let remotes = repository.update(cx, |repository, _| repository.get_remotes(None, true));
+    window
+        .spawn(cx, async move |cx| {
+            let remotes = remotes
+                .await??
+                .into_iter()
+                .map(|remote| remote.name)
+                .collect::<Vec<_>>();
+            let remote = match remotes.as_slice() {
+                [] => return Err(anyhow!("No remote configured for repository")),
+                [remote] => remote.clone(),
+                _ => {
+                    let selection = cx
+                        .update(|window, cx| {
+                            picker_prompt::prompt(
+                                "Pick which remote to push to",
+                                remotes.clone(),
+                                workspace.clone(),
+                                window,
+                                cx,
+                            )
+                        })?
+                        .await;
+                    let Some(selection) = selection else {
+                        return Ok(());
+                    };
+                    remotes[selection].clone()
+                }
+            };
+
+            let push = cx.update(|window, cx| {
+                let askpass = askpass_delegate(
+                    workspace.clone(),
+                    format!("git push {remote} {tag_name}"),
+                    window,
+                    cx,
+                );
+                repository.update(cx, |repository, cx| {
+                    repository.push_tags(remote.clone(), Some(tag_name.clone()), askpass, cx)
+                })
+            })?;
+            match push.await.context("push was canceled").flatten() {
+                Ok(_) => {
+                    workspace.update(cx, |workspace, cx| {
+                        let toast = StatusToast::new(
+                            format!("Pushed tag {tag_name} to {remote}"),
+                            cx,
+                            |this, _cx| {
+                                this.icon(
+                                    Icon::new(IconName::GitBranch)
+                                        .size(IconSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .dismiss_button(true)
+                            },
+                        );
+                        workspace.toggle_status_toast(toast, cx);
+                    })?;
+                }
+                Err(error) => {
+                    if let Some(workspace) = workspace.upgrade() {
+                        cx.update(|_, cx| show_error_toast(workspace, "push", error, cx))?;
+                    }
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to push tag", window, cx, |_, _, _| None);
+}
+
+/// Asks for the name and message of an annotated tag to create at a commit.
+pub(crate) struct CreateTagModal {
+    commit: Oid,
+    repository: Entity<Repository>,
+    name_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+}
+
+impl CreateTagModal {
+    pub(crate) fn open(
+        commit: Oid,
+        repository: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(repository) = repository.upgrade() else {
+            return;
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    Self::new(commit, repository, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    fn new(
+        commit: Oid,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag name", window, cx);
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Message (defaults to the tag name)", window, cx);
+            editor
+        });
+        Self {
+            commit,
+            repository,
+            name_editor,
+            message_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let message = self.message_editor.read(cx).text(cx).trim().to_string();
+        let message = if message.is_empty() {
+            name.clone()
+        } else {
+            message
+        };
+
+        let task = self.repository.update(cx, |repository, _| {
+            repository.create_tag(name, self.commit.to_string(), message)
+        });
+        cx.spawn(async move |_, _| match task.await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Operation was canceled")),
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateTagModal {}
+impl ModalView for CreateTagModal {}
+impl Focusable for CreateTagModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateTagModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("CreateTagModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Hash).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("Create Tag at {}", self.commit.display_short()))
+                            .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .px_3()
+                    .pb_3()
+                    .gap_2()
+                    .w_full()
+                    .child(self.name_editor.clone())
+                    .child(self.message_editor.clone()),
+            )
+    }
+}
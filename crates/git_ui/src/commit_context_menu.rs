@@ -1,5 +1,9 @@
+use crate::bisect;
+use crate::commit_actions::{self, CreateTagModal};
 use crate::commit_view::CommitView;
-use git::Oid;
+use crate::patch::{self, PatchDestination};
+use crate::rebase_editor::RebaseEditor;
+use git::{Oid, bisect::BisectMark};
 use gpui::{Action, ClipboardItem, Entity, FocusHandle, SharedString, WeakEntity, Window, actions};
 use project::{GIT_COMMAND_TASK_TAG, git_store::Repository};
 
@@ -25,6 +29,8 @@ const CUSTOM_GIT_COMMANDS_DOCS_SLUG: &str = "tasks#custom-git-commands";
 pub(crate) struct CommitContextMenuData {
     pub(crate) sha: Oid,
     pub(crate) tag_names: Vec<SharedString>,
+    /// The other end of a range of commits to cherry-pick, older than `sha`.
+    pub(crate) range_start: Option<Oid>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -50,6 +56,10 @@ pub(crate) fn commit_context_menu(
         &workspace,
         cx,
     );
+    let is_bisecting = repository
+        .as_ref()
+        .and_then(|repository| repository.upgrade())
+        .is_some_and(|repository| repository.read(cx).merge.bisect.is_some());
     let header = match &ref_name {
         Some(ref_name) => format!("Ref {ref_name}"),
         None => format!("Commit {sha_short}"),
@@ -139,6 +149,195 @@ pub(crate) fn commit_context_menu(
                     );
                 })
             })
+            .when_some(repository.clone(), |menu, repository| {
+                let menu = menu.separator();
+                let menu = if source == CommitContextMenuSource::GitGraph {
+                    let menu = menu.entry("Cherry-Pick", None, {
+                        let repository = repository.clone();
+                        let workspace = workspace.clone();
+                        move |_window, cx| {
+                            commit_actions::cherry_pick(
+                                sha,
+                                None,
+                                repository.clone(),
+                                workspace.clone(),
+                                cx,
+                            )
+                        }
+                    });
+                    match commit.range_start {
+                        Some(range_start) => menu.entry(
+                            format!("Cherry-Pick {}..{}", range_start.display_short(), sha_short),
+                            None,
+                            {
+                                let repository = repository.clone();
+                                let workspace = workspace.clone();
+                                move |_window, cx| {
+                                    commit_actions::cherry_pick(
+                                        range_start,
+                                        Some(sha),
+                                        repository.clone(),
+                                        workspace.clone(),
+                                        cx,
+                                    )
+                                }
+                            },
+                        ),
+                        None => menu,
+                    }
+                } else {
+                    menu
+                };
+                menu.entry("Revert", None, {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    move |_window, cx| {
+                        commit_actions::revert(sha, repository.clone(), workspace.clone(), cx)
+                    }
+                })
+                .entry("Copy as Patch", None, {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    move |window, cx| {
+                        patch::export_commits(
+                            sha,
+                            None,
+                            PatchDestination::Clipboard,
+                            repository.clone(),
+                            workspace.clone(),
+                            window,
+                            cx,
+                        )
+                    }
+                })
+                .entry("Export as Patch…", None, {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    move |window, cx| {
+                        patch::export_commits(
+                            sha,
+                            None,
+                            PatchDestination::File,
+                            repository.clone(),
+                            workspace.clone(),
+                            window,
+                            cx,
+                        )
+                    }
+                })
+                .when_some(commit.range_start, |menu, range_start| {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    menu.entry(
+                        format!(
+                            "Export {}..{} as Patches…",
+                            range_start.display_short(),
+                            sha_short
+                        ),
+                        None,
+                        move |window, cx| {
+                            patch::export_commits(
+                                range_start,
+                                Some(sha),
+                                PatchDestination::File,
+                                repository.clone(),
+                                workspace.clone(),
+                                window,
+                                cx,
+                            )
+                        },
+                    )
+                })
+                .entry("Create Tag…", None, {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    move |window, cx| {
+                        CreateTagModal::open(sha, repository.clone(), workspace.clone(), window, cx)
+                    }
+                })
+                .map(|mut menu| {
+                    for tag_name in commit.tag_names.clone() {
+                        let repository = repository.clone();
+                        let workspace = workspace.clone();
+                        menu =
+                            menu.submenu(format!("Tag {tag_name}"), move |menu, _window, _cx| {
+                                menu.entry("Push Tag", None, {
+                                    let tag_name = tag_name.clone();
+                                    let repository = repository.clone();
+                                    let workspace = workspace.clone();
+                                    move |window, cx| {
+                                        commit_actions::push_tag(
+                                            tag_name.clone(),
+                                            repository.clone(),
+                                            workspace.clone(),
+                                            window,
+                                            cx,
+                                        )
+                                    }
+                                })
+                                .entry("Delete Tag", None, {
+                                    let tag_name = tag_name.clone();
+                                    let repository = repository.clone();
+                                    move |window, cx| {
+                                        commit_actions::delete_tag(
+                                            tag_name.clone(),
+                                            repository.clone(),
+                                            window,
+                                            cx,
+                                        )
+                                    }
+                                })
+                            });
+                    }
+                    menu
+                })
+            })
+            .when(source == CommitContextMenuSource::GitGraph, |menu| {
+                let repository = repository.clone();
+                let workspace = workspace.clone();
+                menu.separator().entry(
+                    "Rebase Interactively onto This Commit",
+                    None,
+                    move |window, cx| {
+                        let Some(repository) = repository.clone() else {
+                            return;
+                        };
+                        RebaseEditor::open(sha, repository, workspace.clone(), window, cx);
+                    },
+                )
+            })
+            .when_some(
+                repository
+                    .clone()
+                    .filter(|_| source == CommitContextMenuSource::GitGraph),
+                |menu, repository| {
+                    let workspace = workspace.clone();
+                    menu.submenu("Bisect", move |mut menu, _window, _cx| {
+                        let marks = [
+                            ("Mark as Good", BisectMark::Good),
+                            ("Mark as Bad", BisectMark::Bad),
+                            ("Skip", BisectMark::Skip),
+                        ];
+                        for (label, mark) in marks {
+                            if mark == BisectMark::Skip && !is_bisecting {
+                                continue;
+                            }
+                            let repository = repository.clone();
+                            let workspace = workspace.clone();
+                            menu = menu.entry(label, None, move |_window, cx| {
+                                bisect::mark(
+                                    mark,
+                                    Some(sha),
+                                    repository.clone(),
+                                    workspace.clone(),
+                                    cx,
+                                )
+                            });
+                        }
+                        menu
+                    })
+                },
+            )
             .map(|mut menu| {
                 menu = menu.separator().header("Custom Commands");
 
@@ -179,7 +378,7 @@ pub(crate) fn commit_context_menu(
     })
 }
 
-fn git_task_context(
+pub(crate) fn git_task_context(
     repository: &Option<WeakEntity<Repository>>,
     commit_sha: git::Oid,
     ref_name: Option<&str>,
@@ -3,6 +3,7 @@ use editor::hover_markdown_style;
 use futures::Future;
 use git::blame::BlameEntry;
 use git::repository::CommitSummary;
+use git::signature::{CommitSignature, SignatureStatus};
 use git::{GitRemote, commit::ParsedCommitMessage};
 use gpui::{
     AbsoluteLength, App, Asset, Element, Entity, MouseButton, ParentElement, Pixels, Render,
@@ -15,6 +16,7 @@ use std::hash::Hash;
 use theme_settings::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
 use ui::{Avatar, Chip, CopyButton, Divider, Tooltip, prelude::*, tooltip_container};
+use util::ResultExt as _;
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -71,6 +73,77 @@ pub(crate) fn commit_tag_chips(tag_names: &[SharedString]) -> Option<impl IntoEl
     )
 }
 
+/// A chip showing whether `sha` is signed, and whether git could verify the
+/// signature. Renders nothing until the signature has been checked, and for
+/// unsigned commits.
+pub(crate) fn commit_signature_badge(
+    sha: &SharedString,
+    repository: &Entity<Repository>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Option<impl IntoElement> {
+    let source = CommitSignatureAsset {
+        sha: sha.clone(),
+        repository: repository.clone(),
+    };
+    let signature = window.use_asset::<CommitSignatureAsset>(&source, cx)??;
+    let (label, icon, color) = match signature.status {
+        SignatureStatus::Unsigned => return None,
+        SignatureStatus::Verified => ("Verified", IconName::Check, Color::Success),
+        SignatureStatus::Unverified => ("Unverified", IconName::Warning, Color::Warning),
+        SignatureStatus::UnknownKey => ("Unknown Key", IconName::Lock, Color::Muted),
+    };
+    let mut details = Vec::new();
+    if let Some(signer) = &signature.signer {
+        details.push(format!("Signed by {signer}"));
+    }
+    if let Some(key) = &signature.key {
+        details.push(format!("Key {key}"));
+    }
+    if signature.status == SignatureStatus::UnknownKey {
+        details.push("The signing key isn't in your keyring or allowed signers".to_string());
+    }
+    let tooltip = details.join("\n");
+
+    Some(
+        Chip::new(label)
+            .icon(icon)
+            .icon_color(color)
+            .label_color(color)
+            .when(!tooltip.is_empty(), |chip| {
+                chip.tooltip(Tooltip::text(tooltip))
+            }),
+    )
+}
+
+#[derive(Clone)]
+struct CommitSignatureAsset {
+    sha: SharedString,
+    repository: Entity<Repository>,
+}
+
+impl Hash for CommitSignatureAsset {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sha.hash(state);
+        self.repository.entity_id().hash(state);
+    }
+}
+
+impl Asset for CommitSignatureAsset {
+    type Source = Self;
+    type Output = Option<CommitSignature>;
+
+    fn load(
+        source: Self::Source,
+        cx: &mut App,
+    ) -> impl Future<Output = Self::Output> + Send + 'static {
+        let signature = source.repository.update(cx, |repository, _| {
+            repository.commit_signature(source.sha.to_string())
+        });
+        async move { signature.await.ok()?.log_err() }
+    }
+}
+
 const COMMIT_AVATAR_BORDER_WIDTH: Pixels = px(1.);
 
 pub struct CommitAvatar<'a> {
@@ -321,6 +394,8 @@ impl Render for CommitTooltip {
             .as_ref()
             .and_then(|details| details.pull_request.clone());
         let tag_names = self.commit.tag_names.clone();
+        let signature_badge =
+            commit_signature_badge(&self.commit.sha, &self.repository, window, cx);
 
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size(cx);
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
@@ -397,6 +472,9 @@ impl Render for CommitTooltip {
                                         .gap_1()
                                         .min_w_0()
                                         .children(commit_tag_chips(&tag_names))
+                                        .children(signature_badge.map(|badge| {
+                                            h_flex().gap_1().child(badge).child(Divider::vertical())
+                                        }))
                                         .when_some(pull_request, |this, pr| {
                                             this.child(
                                                 Button::new(
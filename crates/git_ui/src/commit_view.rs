@@ -9,7 +9,7 @@ use futures_lite::future::yield_now;
 use git::repository::{CommitDetails, CommitDiff, RepoPath, is_binary_content};
 use git::status::{FileStatus, StatusCode, TrackedStatus};
 use git::{
-    BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, ParsedGitRemote,
+    BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
 };
 use gpui::{
@@ -33,7 +33,9 @@ use std::{
     sync::Arc,
 };
 use theme::ActiveTheme;
-use ui::{ContextMenu, DiffStat, Disclosure, Divider, Tooltip, WithScrollbar, prelude::*};
+use ui::{
+    ContextMenu, DiffStat, Disclosure, Divider, PopoverMenu, Tooltip, WithScrollbar, prelude::*,
+};
 use util::{ResultExt, paths::PathStyle, rel_path::RelPath, truncate_and_trailoff};
 use workspace::item::TabTooltipContent;
 use workspace::{
@@ -45,7 +47,8 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
-use crate::commit_tooltip::CommitAvatar;
+use crate::commit_actions::{self, CreateTagModal};
+use crate::commit_tooltip::{CommitAvatar, commit_signature_badge};
 use crate::git_panel::GitPanel;
 
 actions!(
@@ -598,6 +601,12 @@ impl CommitView {
             (IconName::Copy, Color::Muted)
         };
 
+        let signature_badge = self
+            .stash
+            .is_none()
+            .then(|| commit_signature_badge(&commit.sha, &self.repository, window, cx))
+            .flatten();
+
         let has_more = self.commit.message.trim().contains('\n');
         let is_expanded = self.message_expanded;
         let expand_tooltip = if is_expanded {
@@ -634,24 +643,28 @@ impl CommitView {
                             )
                             .child(
                                 v_flex()
-                                    .child(h_flex().gap_1().child(Label::new(author_name)).when(
-                                        has_more,
-                                        |this| {
-                                            this.child(
-                                                Disclosure::new(
-                                                    "commit-message-disclosure",
-                                                    is_expanded,
+                                    .child(
+                                        h_flex()
+                                            .gap_1()
+                                            .child(Label::new(author_name))
+                                            .children(signature_badge)
+                                            .when(has_more, |this| {
+                                                this.child(
+                                                    Disclosure::new(
+                                                        "commit-message-disclosure",
+                                                        is_expanded,
+                                                    )
+                                                    .closed_icon(IconName::ExpandVertical)
+                                                    .opened_icon(IconName::FoldVertical)
+                                                    .tooltip(Tooltip::text(expand_tooltip))
+                                                    .on_click(cx.listener(|this, _, _, cx| {
+                                                        this.message_expanded =
+                                                            !this.message_expanded;
+                                                        cx.notify();
+                                                    })),
                                                 )
-                                                .closed_icon(IconName::ExpandVertical)
-                                                .opened_icon(IconName::FoldVertical)
-                                                .tooltip(Tooltip::text(expand_tooltip))
-                                                .on_click(cx.listener(|this, _, _, cx| {
-                                                    this.message_expanded = !this.message_expanded;
-                                                    cx.notify();
-                                                })),
-                                            )
-                                        },
-                                    ))
+                                            }),
+                                    )
                                     .child(
                                         h_flex()
                                             .gap_1p5()
@@ -1297,6 +1310,9 @@ impl Render for CommitViewToolbar {
         });
 
         let sha_for_graph = commit_sha.to_string();
+        let commit_oid = commit_sha.parse::<Oid>().ok();
+        let repository = commit_view_ref.repository.downgrade();
+        let workspace = commit_view_ref.workspace.clone();
 
         h_flex()
             .gap_1()
@@ -1343,6 +1359,59 @@ impl Render for CommitViewToolbar {
                             );
                         }),
                 )
+                .children(commit_oid.map(|sha| {
+                    PopoverMenu::new("commit-actions")
+                        .trigger_with_tooltip(
+                            IconButton::new("commit-actions-trigger", IconName::Ellipsis)
+                                .icon_size(IconSize::Small),
+                            Tooltip::text("Commit Actions"),
+                        )
+                        .menu(move |window, cx| {
+                            let repository = repository.clone();
+                            let workspace = workspace.clone();
+                            Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                                menu.entry("Cherry-Pick", None, {
+                                    let repository = repository.clone();
+                                    let workspace = workspace.clone();
+                                    move |_window, cx| {
+                                        commit_actions::cherry_pick(
+                                            sha,
+                                            None,
+                                            repository.clone(),
+                                            workspace.clone(),
+                                            cx,
+                                        )
+                                    }
+                                })
+                                .entry("Revert", None, {
+                                    let repository = repository.clone();
+                                    let workspace = workspace.clone();
+                                    move |_window, cx| {
+                                        commit_actions::revert(
+                                            sha,
+                                            repository.clone(),
+                                            workspace.clone(),
+                                            cx,
+                                        )
+                                    }
+                                })
+                                .entry("Create Tag…", None, {
+                                    let repository = repository.clone();
+                                    let workspace = workspace.clone();
+                                    move |window, cx| {
+                                        CreateTagModal::open(
+                                            sha,
+                                            repository.clone(),
+                                            workspace.clone(),
+                                            window,
+                                            cx,
+                                        )
+                                    }
+                                })
+                            }))
+                        })
+                        .anchor(gpui::Anchor::TopRight)
+                }))
                 .children(remote_info.map(|(provider_name, url)| {
                     let icon = crate::get_provider_icon(provider_name.as_str());
 
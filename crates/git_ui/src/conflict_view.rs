@@ -696,3 +696,110 @@ impl StatusItemView for MergeConflictIndicator {
         }))
     }
 }
+
+/// Shows the progress of a rebase in the active repository, with buttons to
+/// continue, skip the current commit, or abort it.
+pub struct RebaseIndicator {
+    project: Entity<Project>,
+    _subscription: Subscription,
+}
+
+impl RebaseIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().clone();
+        let git_store = project.read(cx).git_store().clone();
+        let subscription = cx.subscribe(&git_store, |_, _, event, cx| match event {
+            GitStoreEvent::ActiveRepositoryChanged(_)
+            | GitStoreEvent::ConflictsUpdated
+            | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged, true)
+            | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::HeadChanged, true) => {
+                cx.notify()
+            }
+            _ => {}
+        });
+
+        Self {
+            project,
+            _subscription: subscription,
+        }
+    }
+}
+
+impl Render for RebaseIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            return Empty.into_any_element();
+        };
+        let repository = repository.read(cx);
+        let Some(rebase) = repository.merge.rebase.as_ref() else {
+            return Empty.into_any_element();
+        };
+        let has_conflicts = repository
+            .cached_status()
+            .any(|entry| entry.status.is_conflicted());
+
+        let message: SharedString = match rebase.branch_name() {
+            Some(branch_name) => format!("Rebasing {branch_name} {}/{}", rebase.step, rebase.total),
+            None => format!("Rebasing {}/{}", rebase.step, rebase.total),
+        }
+        .into();
+
+        h_flex()
+            .gap_1()
+            .child(
+                Icon::new(IconName::GitBranch)
+                    .size(IconSize::Small)
+                    .color(Color::Warning),
+            )
+            .child(Label::new(message).size(LabelSize::Small))
+            .child(
+                Button::new("rebase-continue", "Continue")
+                    .label_size(LabelSize::Small)
+                    .disabled(has_conflicts)
+                    .tooltip(move |_, cx| {
+                        if has_conflicts {
+                            Tooltip::simple("Resolve the conflicts before continuing", cx)
+                        } else {
+                            Tooltip::for_action("Continue Rebase", &git::RebaseContinue, cx)
+                        }
+                    })
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(git::RebaseContinue.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                Button::new("rebase-skip", "Skip")
+                    .label_size(LabelSize::Small)
+                    .tooltip(|_, cx| {
+                        Tooltip::for_action("Skip the Current Commit", &git::RebaseSkip, cx)
+                    })
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(git::RebaseSkip.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                Button::new("rebase-abort", "Abort")
+                    .label_size(LabelSize::Small)
+                    .tooltip(|_, cx| Tooltip::for_action("Abort Rebase", &git::RebaseAbort, cx))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(git::RebaseAbort.boxed_clone(), cx)
+                    }),
+            )
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for RebaseIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+
+    fn hide_setting(&self, _: &App) -> Option<HideStatusItem> {
+        // Only visible while a rebase is in progress.
+        None
+    }
+}
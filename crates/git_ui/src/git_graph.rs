@@ -2418,6 +2418,13 @@ impl GitGraph {
         let Some(commit) = self.graph_data.commits.get(index) else {
             return;
         };
+        // Commits are listed newest first, so a selected commit further down starts a range
+        // ending at the clicked one.
+        let range_start = self
+            .selected_entry_idx
+            .filter(|selected_entry_idx| *selected_entry_idx > index)
+            .and_then(|selected_entry_idx| self.graph_data.commits.get(selected_entry_idx))
+            .map(|selected_commit| selected_commit.data.sha);
         let repository = self
             .get_repository(cx)
             .map(|repository| repository.downgrade());
@@ -2430,6 +2437,7 @@ impl GitGraph {
                     .into_iter()
                     .map(|tag_name| SharedString::from(tag_name.to_string()))
                     .collect(),
+                range_start,
             },
             CommitContextMenuSource::GitGraph,
             ref_name,
@@ -6,10 +6,12 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::{CommitAvatar, CommitTooltip};
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::GitPanelScrollbarAccessor;
+use crate::merge_editor::MergeEditor;
 use crate::project_diff::{DeployBranchDiff, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::solo_diff_view::SoloDiffView;
 use crate::staged_diff::StagedDiff;
+use crate::submodules;
 use crate::unstaged_diff::UnstagedDiff;
 use crate::{branch_picker, picker_prompt, render_remote_button};
 use crate::{
@@ -36,6 +38,7 @@ use git::repository::{
 };
 use git::stash::GitStash;
 use git::status::{DiffStat, StageStatus};
+use git::submodule::{Submodule, SubmoduleOperation};
 use git::{
     Amend, Commit, Signoff, SkipHooks, ToggleStaged, repository::RepoPath, status::FileStatus,
 };
@@ -46,9 +49,10 @@ use git::{
 };
 use gpui::{
     AbsoluteLength, Action, Anchor, AnyElement, AsyncApp, AsyncWindowContext, ClickEvent,
-    DismissEvent, Empty, Entity, EventEmitter, FocusHandle, Focusable, KeyContext, MouseButton,
-    MouseDownEvent, Pixels, Point, PromptLevel, ScrollStrategy, Subscription, Task, TaskExt,
-    TextStyle, UniformListScrollHandle, WeakEntity, actions, anchored, deferred, uniform_list,
+    DismissEvent, Empty, Entity, EntityId, EventEmitter, FocusHandle, Focusable, KeyContext,
+    MouseButton, MouseDownEvent, Pixels, Point, PromptLevel, ScrollStrategy, Subscription, Task,
+    TaskExt, TextStyle, UniformListScrollHandle, WeakEntity, actions, anchored, deferred,
+    uniform_list,
 };
 use itertools::Itertools;
 use language::{Buffer, BufferEvent, File};
@@ -224,6 +228,7 @@ fn git_panel_context_menu(
             )
             .action_disabled_when(!has_stash_items, "Stash Pop", StashPop.boxed_clone())
             .action("View Stash", zed_actions::git::ViewStash.boxed_clone())
+            .action("Manage Worktrees", git::ManageWorktrees.boxed_clone())
             .separator()
             .action_disabled_when(
                 !has_tracked_changes,
@@ -430,6 +435,42 @@ struct SerializedCommitMessage {
     amend_pending: bool,
 }
 
+/// What the submodules of a repository are scanned from: the commit checked out, and the status
+/// of `.gitmodules` and of the submodules' gitlinks. Other changes, like saving a file, leave it
+/// unchanged, so they don't trigger a rescan.
+#[derive(PartialEq)]
+struct SubmodulesKey {
+    repository: EntityId,
+    head: Option<SharedString>,
+    statuses: Vec<(RepoPath, Option<FileStatus>)>,
+}
+
+impl SubmodulesKey {
+    fn new(
+        repository: &Entity<Repository>,
+        submodules: &HashMap<RepoPath, Submodule>,
+        cx: &App,
+    ) -> Self {
+        let snapshot = repository.read(cx);
+        let gitmodules = RepoPath::from_rel_path(RelPath::unix(".gitmodules").unwrap());
+        let statuses = std::iter::once(gitmodules)
+            .chain(submodules.keys().sorted().cloned())
+            .map(|path| {
+                let status = snapshot.status_for_path(&path).map(|entry| entry.status);
+                (path, status)
+            })
+            .collect();
+        Self {
+            repository: repository.entity_id(),
+            head: snapshot
+                .head_commit
+                .as_ref()
+                .map(|commit| commit.sha.clone()),
+            statuses,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum GitPanelTab {
     Changes,
@@ -944,6 +985,9 @@ pub struct GitPanel {
     tracked_staged_count: usize,
     update_visible_entries_task: Task<()>,
     reopen_commit_buffer_task: Task<()>,
+    submodules: HashMap<RepoPath, Submodule>,
+    submodules_key: Option<SubmodulesKey>,
+    load_submodules_task: Task<()>,
     pub(crate) workspace: WeakEntity<Workspace>,
     context_menu: Option<GitPanelContextMenu>,
     modal_open: bool,
@@ -1245,6 +1289,9 @@ impl GitPanel {
                 tracked_staged_count: 0,
                 update_visible_entries_task: Task::ready(()),
                 reopen_commit_buffer_task: Task::ready(()),
+                submodules: HashMap::default(),
+                submodules_key: None,
+                load_submodules_task: Task::ready(()),
                 show_placeholders: false,
                 local_committer: None,
                 local_committer_task: None,
@@ -1973,6 +2020,131 @@ impl GitPanel {
         });
     }
 
+    fn open_merge_editor(
+        &mut self,
+        _: &git::OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            if !entry.status.is_conflicted() {
+                return None;
+            }
+            let repository = self.active_repository.clone()?;
+
+            MergeEditor::open(
+                repository,
+                entry.repo_path.clone(),
+                self.workspace.clone(),
+                window,
+                cx,
+            )
+            .detach_and_notify_err(self.workspace.clone(), window, cx);
+
+            Some(())
+        });
+    }
+
+    fn selected_submodule(&self) -> Option<&Submodule> {
+        let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+        self.submodules.get(&entry.repo_path)
+    }
+
+    /// Runs `operation` on the selected submodule, or on all submodules when the
+    /// selected entry isn't one.
+    fn run_submodule_command(
+        &mut self,
+        operation: SubmoduleOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repository) = self.active_repository.clone() else {
+            return;
+        };
+        let paths = self
+            .selected_submodule()
+            .map(|submodule| vec![submodule.path.clone()])
+            .unwrap_or_default();
+        submodules::run(
+            operation,
+            paths,
+            repository,
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn update_submodules(
+        &mut self,
+        _: &git::UpdateSubmodules,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_submodule_command(SubmoduleOperation::Update, window, cx);
+    }
+
+    fn init_submodules(
+        &mut self,
+        _: &git::InitSubmodules,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_submodule_command(SubmoduleOperation::Init, window, cx);
+    }
+
+    fn sync_submodules(
+        &mut self,
+        _: &git::SyncSubmodules,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_submodule_command(SubmoduleOperation::Sync, window, cx);
+    }
+
+    fn view_submodule_changes(
+        &mut self,
+        _: &git::ViewSubmoduleChanges,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let path = self.selected_submodule()?.path.clone();
+            let repository = self
+                .active_repository
+                .as_ref()?
+                .read(cx)
+                .submodule_repository(&path, cx)?;
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    submodules::view_changes(repository, workspace, window, cx)
+                })
+                .ok()
+        });
+    }
+
+    fn view_submodule_commits(
+        &mut self,
+        _: &git::ViewSubmoduleCommits,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let path = self.selected_submodule()?.path.clone();
+            let repository = self
+                .active_repository
+                .as_ref()?
+                .read(cx)
+                .submodule_repository(&path, cx)?;
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    submodules::view_commits(repository, workspace, window, cx)
+                })
+                .ok()
+        });
+    }
+
     fn open_selected_entry_on_click(
         &mut self,
         secondary: bool,
@@ -3960,20 +4132,7 @@ impl GitPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AskPassDelegate {
-        let workspace = self.workspace.clone();
-        let operation = operation.into();
-        let window = window.window_handle();
-        AskPassDelegate::new(&mut cx.to_async(), move |prompt, tx, cx| {
-            window
-                .update(cx, |_, window, cx| {
-                    workspace.update(cx, |workspace, cx| {
-                        workspace.toggle_modal(window, cx, |window, cx| {
-                            AskPassModal::new(operation.clone(), prompt.into(), tx, window, cx)
-                        });
-                    })
-                })
-                .ok();
-        })
+        askpass_delegate(self.workspace.clone(), operation, window, cx)
     }
 
     fn can_push_and_pull(&self, cx: &App) -> bool {
@@ -4389,12 +4548,54 @@ impl GitPanel {
                 git_panel
                     .update_in(cx, |git_panel, window, cx| {
                         git_panel.update_visible_entries(window, cx);
+                        git_panel.load_submodules(cx);
                     })
                     .ok();
             }
         });
     }
 
+    /// Scans the submodules again, even if nothing they're scanned from changed, for commands
+    /// like `git submodule init` that only change the repository's configuration.
+    pub(crate) fn reload_submodules(&mut self, cx: &mut Context<Self>) {
+        self.submodules_key = None;
+        self.load_submodules(cx);
+    }
+
+    /// Scans the active repository's submodules, unless what they're scanned from is unchanged
+    /// since the last scan.
+    fn load_submodules(&mut self, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.as_ref() else {
+            self.submodules.clear();
+            self.submodules_key = None;
+            self.load_submodules_task = Task::ready(());
+            return;
+        };
+        let key = SubmodulesKey::new(active_repository, &self.submodules, cx);
+        if self.submodules_key.as_ref() == Some(&key) {
+            return;
+        }
+        self.submodules_key = Some(key);
+        let submodules = active_repository.update(cx, |repository, _| repository.submodules());
+        self.load_submodules_task = cx.spawn(async move |this, cx| {
+            let submodules = match submodules.await.map_err(anyhow::Error::from).flatten() {
+                Ok(submodules) => submodules,
+                Err(error) => {
+                    log::error!("failed to load submodules: {error:?}");
+                    Vec::new()
+                }
+            };
+            this.update(cx, |this, cx| {
+                this.submodules = submodules
+                    .into_iter()
+                    .map(|submodule| (submodule.path.clone(), submodule))
+                    .collect();
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
     fn reopen_commit_buffer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let Some(active_repo) = self.active_repository.as_ref() else {
             self.reopen_commit_buffer_task = Task::ready(());
@@ -6267,6 +6468,7 @@ impl GitPanel {
             CommitContextMenuData {
                 sha: commit.sha,
                 tag_names: commit.tag_names,
+                range_start: None,
             },
             CommitContextMenuSource::GitPanel,
             None,
@@ -7236,8 +7438,12 @@ impl GitPanel {
         } else {
             "Discard Changes"
         };
+        let submodule = self.submodules.get(&entry.repo_path);
+        let submodule_initialized = submodule.is_some_and(Submodule::is_initialized);
+        let is_submodule = submodule.is_some();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             let is_created = entry.status.is_created();
+            let is_conflicted = entry.status.is_conflicted();
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
@@ -7260,6 +7466,26 @@ impl GitPanel {
                 .action("Open Diff", menu::Confirm.boxed_clone())
                 .action("Open File Diff", menu::SecondaryConfirm.boxed_clone())
                 .action("View File", ViewFile.boxed_clone())
+                .when(is_conflicted, |context_menu| {
+                    context_menu.action("Open Merge Editor", git::OpenMergeEditor.boxed_clone())
+                })
+                .when(is_submodule, |context_menu| {
+                    context_menu
+                        .separator()
+                        .action("Update Submodule", git::UpdateSubmodules.boxed_clone())
+                        .action("Init Submodule", git::InitSubmodules.boxed_clone())
+                        .action("Sync Submodule", git::SyncSubmodules.boxed_clone())
+                        .action_disabled_when(
+                            !submodule_initialized,
+                            "View Submodule Changes",
+                            git::ViewSubmoduleChanges.boxed_clone(),
+                        )
+                        .action_disabled_when(
+                            !submodule_initialized,
+                            "View Submodule Commits",
+                            git::ViewSubmoduleCommits.boxed_clone(),
+                        )
+                })
                 .when(!is_created, |context_menu| {
                     context_menu
                         .separator()
@@ -7478,6 +7704,13 @@ impl GitPanel {
             });
 
         let id_for_diff_stat = id.clone();
+        let submodule_status = self.submodules.get(&entry.repo_path).map(|submodule| {
+            (
+                format!("submodule-status-{}", id),
+                submodules::status_label(submodule).unwrap_or_else(|| "submodule".into()),
+                submodules::status_tooltip(submodule),
+            )
+        });
 
         h_flex()
             .id(id)
@@ -7495,6 +7728,19 @@ impl GitPanel {
             .hover(|s| s.bg(hover_bg))
             .active(|s| s.bg(active_bg))
             .child(name_row)
+            .when_some(submodule_status, |el, (id, label, tooltip)| {
+                el.child(
+                    div()
+                        .id(id)
+                        .flex_none()
+                        .child(
+                            Label::new(label)
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .tooltip(Tooltip::text(tooltip)),
+                )
+            })
             .when(GitPanelSettings::get_global(cx).diff_stats, |el| {
                 el.when_some(entry.diff_stat, move |this, stat| {
                     let id = format!("diff-stat-{}", id_for_diff_stat);
@@ -8092,6 +8338,12 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_solo_diff))
             .on_action(cx.listener(Self::view_file))
+            .on_action(cx.listener(Self::open_merge_editor))
+            .on_action(cx.listener(Self::update_submodules))
+            .on_action(cx.listener(Self::init_submodules))
+            .on_action(cx.listener(Self::sync_submodules))
+            .on_action(cx.listener(Self::view_submodule_changes))
+            .on_action(cx.listener(Self::view_submodule_commits))
             .on_action(cx.listener(Self::view_unstaged_changes))
             .on_action(cx.listener(Self::view_staged_changes))
             .on_action(cx.listener(Self::focus_changes_list))
@@ -8785,6 +9037,28 @@ impl Component for PanelRepoFooter {
     }
 }
 
+/// Creates a delegate that prompts for credentials in a modal over `workspace`.
+pub(crate) fn askpass_delegate(
+    workspace: WeakEntity<Workspace>,
+    operation: impl Into<SharedString>,
+    window: &mut Window,
+    cx: &mut App,
+) -> AskPassDelegate {
+    let operation = operation.into();
+    let window = window.window_handle();
+    AskPassDelegate::new(&mut cx.to_async(), move |prompt, tx, cx| {
+        window
+            .update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        AskPassModal::new(operation.clone(), prompt.into(), tx, window, cx)
+                    });
+                })
+            })
+            .ok();
+    })
+}
+
 pub(crate) fn open_output(
     operation: impl Into<SharedString>,
     workspace: &mut Workspace,
@@ -8,8 +8,11 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
+    bisect::BisectMark,
+    rebase::RebaseStep,
     repository::{Branch, CommitDetails, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
+    submodule::SubmoduleOperation,
 };
 use gpui::{
     App, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
@@ -29,13 +32,17 @@ use zed_actions;
 use crate::{
     commit_view::CommitView,
     git_panel::{GitPanel, GitStatusEntry, RemoteOperationKind},
+    merge_editor::MergeEditor,
+    patch::PatchDestination,
     solo_diff_view::SoloDiffView,
     text_diff_view::TextDiffView,
 };
 
 mod askpass_modal;
+mod bisect;
 pub mod branch_diff;
 pub mod branch_picker;
+mod commit_actions;
 mod commit_context_menu;
 mod commit_modal;
 pub mod commit_tooltip;
@@ -49,22 +56,28 @@ pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
 mod git_runtime_diagnostics;
+pub mod merge_editor;
 pub mod multi_diff_view;
+mod patch;
 pub mod picker_prompt;
 pub mod project_diff;
+mod rebase_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod solo_diff_view;
 pub mod staged_diff;
 pub mod stash_picker;
+mod submodules;
 pub mod text_diff_view;
 pub mod unstaged_diff;
+mod worktree_manager;
 pub mod worktree_names;
 pub mod worktree_picker;
 pub mod worktree_service;
 
+pub use bisect::BisectIndicator;
 pub use blame_ui::GitBlameStatus;
-pub use conflict_view::MergeConflictIndicator;
+pub use conflict_view::{MergeConflictIndicator, RebaseIndicator};
 
 pub fn get_provider_icon(name: &str) -> IconName {
     match name {
@@ -126,6 +139,10 @@ pub fn init(cx: &mut App) {
             });
         });
 
+        workspace.register_action(|workspace, _: &git::ManageWorktrees, window, cx| {
+            worktree_manager::WorktreeManager::open(workspace, window, cx);
+        });
+
         workspace.register_action(
             |workspace, action: &zed_actions::OpenWorktreeInNewWindow, window, cx| {
                 let path = action.path.clone();
@@ -277,6 +294,59 @@ pub fn init(cx: &mut App) {
                 panel.uncommit(window, cx);
             })
         });
+        workspace.register_action(|workspace, _: &git::RebaseContinue, window, cx| {
+            rebase_editor::rebase_step(workspace, RebaseStep::Continue, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::RebaseSkip, window, cx| {
+            rebase_editor::rebase_step(workspace, RebaseStep::Skip, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::RebaseAbort, window, cx| {
+            rebase_editor::rebase_step(workspace, RebaseStep::Abort, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectGood, _, cx| {
+            bisect::mark_head(workspace, BisectMark::Good, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectBad, _, cx| {
+            bisect::mark_head(workspace, BisectMark::Bad, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectSkip, _, cx| {
+            bisect::mark_head(workspace, BisectMark::Skip, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectRun, window, cx| {
+            bisect::run(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectReset, _, cx| {
+            bisect::reset(workspace, cx);
+        });
+        workspace.register_action(|workspace, _: &git::UpdateSubmodules, window, cx| {
+            submodules::run_in_active_repository(workspace, SubmoduleOperation::Update, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::InitSubmodules, window, cx| {
+            submodules::run_in_active_repository(workspace, SubmoduleOperation::Init, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::SyncSubmodules, window, cx| {
+            submodules::run_in_active_repository(workspace, SubmoduleOperation::Sync, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::CopyChangesAsPatch, window, cx| {
+            patch::export_diff(workspace, false, PatchDestination::Clipboard, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::ExportChangesAsPatch, window, cx| {
+            patch::export_diff(workspace, false, PatchDestination::File, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::CopyStagedChangesAsPatch, window, cx| {
+            patch::export_diff(workspace, true, PatchDestination::Clipboard, window, cx);
+        });
+        workspace.register_action(
+            |workspace, _: &git::ExportStagedChangesAsPatch, window, cx| {
+                patch::export_diff(workspace, true, PatchDestination::File, window, cx);
+            },
+        );
+        workspace.register_action(|workspace, _: &git::ApplyPatchFromClipboard, window, cx| {
+            patch::apply_from_clipboard(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::ApplyPatchFromFile, window, cx| {
+            patch::apply_from_file(workspace, window, cx);
+        });
         workspace.register_action(|workspace, _action: &git::Init, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
@@ -314,6 +384,28 @@ pub fn init(cx: &mut App) {
                 },
             )
         });
+        workspace.register_action_renderer(|div, workspace, _window, cx| {
+            div.when_some(
+                file_diff_entry(workspace, cx).filter(|(entry, _)| entry.status.is_conflicted()),
+                |div, (entry, repository)| {
+                    let workspace = workspace.weak_handle();
+                    div.on_action(move |_: &git::OpenMergeEditor, window, cx| {
+                        MergeEditor::open(
+                            repository.clone(),
+                            entry.repo_path.clone(),
+                            workspace.clone(),
+                            window,
+                            cx,
+                        )
+                        .detach_and_notify_err(
+                            workspace.clone(),
+                            window,
+                            cx,
+                        );
+                    })
+                },
+            )
+        });
         workspace.register_action(|workspace, _: &git::RenameBranch, window, cx| {
             rename_current_branch(workspace, window, cx);
         });
@@ -0,0 +1,974 @@
+//! MergeEditor shows the base, ours and theirs versions of a conflicted file
+//! next to the file itself, where its conflicts are resolved one at a time.
+
+use crate::{conflict_view::resolve_conflict, file_diff_view::build_buffer_diff};
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use git::repository::RepoPath;
+use gpui::{
+    AnyElement, App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable,
+    Subscription, Task, WeakEntity, actions, relative,
+};
+use language::{Buffer, Capability, Language, LanguageRegistry, Point, ToPoint as _};
+use project::{ConflictRegion, ConflictSet, Project, ProjectPath, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+use ui::{KeyBinding, Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr as _,
+};
+
+actions!(
+    merge_editor,
+    [
+        /// Resolves the current conflict with our version.
+        AcceptOurs,
+        /// Resolves the current conflict with their version.
+        AcceptTheirs,
+        /// Resolves the current conflict with our version followed by theirs.
+        AcceptBoth,
+        /// Resolves the current conflict with the version both sides started from.
+        AcceptBase,
+        /// Moves to the next conflict in the file.
+        NextConflict,
+        /// Moves to the previous conflict in the file.
+        PreviousConflict,
+        /// Saves the file and stages it, marking its conflicts as resolved.
+        MarkResolved,
+    ]
+);
+
+/// One of the read-only versions of the file shown above the result.
+struct StagePane {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    /// Whether the file exists in this version; it doesn't e.g. when one side
+    /// deleted it.
+    exists: bool,
+}
+
+pub struct MergeEditor {
+    project: Entity<Project>,
+    repository: WeakEntity<Repository>,
+    repo_path: RepoPath,
+    buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    editor: Entity<Editor>,
+    base: StagePane,
+    ours: StagePane,
+    theirs: StagePane,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    /// Opens the merge editor for a conflicted file, or focuses the one that's
+    /// already open for it.
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project_path = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx);
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+        window.spawn(cx, async move |cx| {
+            let project_path = project_path.context("conflicted file is not in the project")?;
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await?;
+
+            let existing = workspace.update_in(cx, |workspace, window, cx| {
+                let existing = workspace
+                    .items_of_type::<Self>(cx)
+                    .find(|merge_editor| merge_editor.read(cx).buffer == buffer)?;
+                workspace.activate_item(&existing, true, true, window, cx);
+                Some(existing)
+            })?;
+            if let Some(existing) = existing {
+                return Ok(existing);
+            }
+
+            let stages = stages.await??;
+            let conflict_set = project
+                .update(cx, |project, cx| {
+                    project.git_store().update(cx, |git_store, cx| {
+                        git_store.open_conflict_set(buffer.clone(), cx)
+                    })
+                })
+                .await;
+            let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+            let language = buffer.read_with(cx, |buffer, _| buffer.language().cloned());
+
+            let base_buffer = stage_buffer(&stages.base, &language_registry, &language, cx);
+            let ours_buffer = stage_buffer(&stages.ours, &language_registry, &language, cx);
+            let theirs_buffer = stage_buffer(&stages.theirs, &language_registry, &language, cx);
+            let ours_diff = build_buffer_diff(&base_buffer, &ours_buffer, cx).await?;
+            let theirs_diff = build_buffer_diff(&base_buffer, &theirs_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    let base = StagePane::new(
+                        base_buffer,
+                        None,
+                        stages.base.is_some(),
+                        &project,
+                        window,
+                        cx,
+                    );
+                    let ours = StagePane::new(
+                        ours_buffer,
+                        Some(ours_diff),
+                        stages.ours.is_some(),
+                        &project,
+                        window,
+                        cx,
+                    );
+                    let theirs = StagePane::new(
+                        theirs_buffer,
+                        Some(theirs_diff),
+                        stages.theirs.is_some(),
+                        &project,
+                        window,
+                        cx,
+                    );
+                    Self::new(
+                        project.clone(),
+                        repository.downgrade(),
+                        repo_path,
+                        buffer,
+                        conflict_set,
+                        [base, ours, theirs],
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor.update(cx, |merge_editor, cx| {
+                    merge_editor.go_to_conflict(0, window, cx);
+                });
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        project: Entity<Project>,
+        repository: WeakEntity<Repository>,
+        repo_path: RepoPath,
+        buffer: Entity<Buffer>,
+        conflict_set: Entity<ConflictSet>,
+        [base, ours, theirs]: [StagePane; 3],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor =
+            cx.new(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx));
+
+        // The file's language may load after the versions were created, so
+        // keep their highlighting in sync with it.
+        let stage_buffers = [&base, &ours, &theirs]
+            .map(|stage| stage.buffer.downgrade())
+            .to_vec();
+        let subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+            }),
+            cx.subscribe(&buffer, move |_, buffer, event, cx| {
+                if let language::BufferEvent::LanguageChanged(_) = event {
+                    let language = buffer.read(cx).language().cloned();
+                    for stage_buffer in &stage_buffers {
+                        stage_buffer
+                            .update(cx, |stage_buffer, cx| {
+                                stage_buffer.set_language_async(language.clone(), cx);
+                            })
+                            .ok();
+                    }
+                }
+            }),
+            cx.observe(&conflict_set, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            project,
+            repository,
+            repo_path,
+            buffer,
+            conflict_set,
+            editor,
+            base,
+            ours,
+            theirs,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    /// The index of the conflict at or after the cursor in the result, or of
+    /// the last one when the cursor is past all of them.
+    fn current_conflict_ix(&self, cx: &App) -> Option<usize> {
+        let conflicts = self.conflicts(cx);
+        let cursor = self.cursor(cx)?;
+        let buffer = self.buffer.read(cx).snapshot();
+        let ix = conflicts
+            .iter()
+            .position(|conflict| conflict.range.end.cmp(&cursor, &buffer).is_ge())
+            .unwrap_or(conflicts.len().checked_sub(1)?);
+        Some(ix)
+    }
+
+    fn current_conflict(&self, cx: &App) -> Option<ConflictRegion> {
+        let ix = self.current_conflict_ix(cx)?;
+        self.conflicts(cx).get(ix).cloned()
+    }
+
+    fn go_to_conflict(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflicts(cx).get(ix).cloned() else {
+            return;
+        };
+        let buffer = self.buffer.read(cx).snapshot();
+        let point = conflict.range.start.to_point(&buffer);
+        self.editor.update(cx, |editor, cx| {
+            editor.go_to_singleton_buffer_point(point, window, cx);
+        });
+
+        // Reveal the lines each side of the conflict came from in its version.
+        for (stage, range) in [
+            (&self.ours, Some(&conflict.ours)),
+            (&self.theirs, Some(&conflict.theirs)),
+            (&self.base, conflict.base.as_ref()),
+        ] {
+            let Some(range) = range else {
+                continue;
+            };
+            let text = buffer.text_for_range(range.clone()).collect::<String>();
+            if let Some(point) = stage.find(&text, cx) {
+                stage.editor.update(cx, |editor, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx);
+                });
+            }
+        }
+    }
+
+    fn cursor(&self, cx: &App) -> Option<language::Anchor> {
+        let editor = self.editor.read(cx);
+        let multibuffer = editor.buffer().read(cx).snapshot(cx);
+        let head = editor.selections.newest_anchor().head();
+        let (cursor, _) = multibuffer.anchor_to_buffer_anchor(head)?;
+        Some(cursor)
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cursor) = self.cursor(cx) else {
+            return;
+        };
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let ix = conflicts
+            .iter()
+            .position(|conflict| conflict.range.start.cmp(&cursor, &buffer).is_gt())
+            .unwrap_or(conflicts.len().saturating_sub(1));
+        self.go_to_conflict(ix, window, cx);
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cursor) = self.cursor(cx) else {
+            return;
+        };
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let ix = conflicts
+            .iter()
+            .rposition(|conflict| conflict.range.end.cmp(&cursor, &buffer).is_lt())
+            .unwrap_or(0);
+        self.go_to_conflict(ix, window, cx);
+    }
+
+    fn accept(
+        &mut self,
+        ranges: impl FnOnce(&ConflictRegion) -> Option<Vec<Range<language::Anchor>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(conflict) = self.current_conflict(cx) else {
+            return;
+        };
+        let Some(ranges) = ranges(&conflict) else {
+            return;
+        };
+        let editor = self.editor.downgrade();
+        let resolve = resolve_conflict(editor, conflict, ranges, window, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            resolve.await;
+            this.update_in(cx, |this, window, cx| {
+                if let Some(ix) = this.current_conflict_ix(cx) {
+                    this.go_to_conflict(ix, window, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(|conflict| Some(vec![conflict.ours.clone()]), window, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(|conflict| Some(vec![conflict.theirs.clone()]), window, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(
+            |conflict| Some(vec![conflict.ours.clone(), conflict.theirs.clone()]),
+            window,
+            cx,
+        );
+    }
+
+    fn accept_base(&mut self, _: &AcceptBase, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(|conflict| Some(vec![conflict.base.clone()?]), window, cx);
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.conflicts(cx).is_empty() {
+            return;
+        }
+        let Some(repository) = self.repository.upgrade() else {
+            return;
+        };
+        let save = self.buffer.read(cx).is_dirty().then(|| {
+            self.project.update(cx, |project, cx| {
+                project.save_buffer(self.buffer.clone(), cx)
+            })
+        });
+        let repo_path = self.repo_path.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            if let Some(save) = save {
+                save.await?;
+            }
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })
+                .await
+        })
+        .detach_and_prompt_err(
+            "Failed to mark file as resolved",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
+    fn render_stage(
+        &self,
+        title: SharedString,
+        stage: &StagePane,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .border_r_1()
+            .border_color(colors.border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(Label::new(title).size(LabelSize::Small))
+                    .when(!stage.exists, |this| {
+                        this.child(
+                            Label::new("(not in this version)")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(div().flex_1().min_h_0().child(stage.editor.clone()))
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let conflict_count = self.conflicts(cx).len();
+        let current = self.current_conflict(cx);
+        let has_conflict = current.is_some();
+        let has_base = current.is_some_and(|conflict| conflict.base.is_some());
+        let focus_handle = self.editor.focus_handle(cx);
+
+        let button = |id: &'static str, label: &'static str, action: Box<dyn gpui::Action>| {
+            Button::new(id, label)
+                .label_size(LabelSize::Small)
+                .key_binding(
+                    KeyBinding::for_action_in(action.as_ref(), &focus_handle, cx)
+                        .size(rems_from_px(12.)),
+                )
+                .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(match conflict_count {
+                    0 => "No conflicts left".to_string(),
+                    1 => "1 conflict".to_string(),
+                    count => format!("{count} conflicts"),
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("previous-conflict", IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(!has_conflict)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Previous Conflict",
+                        &PreviousConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(PreviousConflict.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                IconButton::new("next-conflict", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(!has_conflict)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Next Conflict",
+                        &NextConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(NextConflict.boxed_clone(), cx)
+                    }),
+            )
+            .child(div().flex_1())
+            .child(button("accept-ours", "Use Ours", Box::new(AcceptOurs)).disabled(!has_conflict))
+            .child(
+                button("accept-theirs", "Use Theirs", Box::new(AcceptTheirs))
+                    .disabled(!has_conflict),
+            )
+            .child(button("accept-both", "Use Both", Box::new(AcceptBoth)).disabled(!has_conflict))
+            .child(button("accept-base", "Use Base", Box::new(AcceptBase)).disabled(!has_base))
+            .child(
+                button("mark-resolved", "Mark as Resolved", Box::new(MarkResolved))
+                    .style(ButtonStyle::Filled)
+                    .disabled(conflict_count > 0),
+            )
+    }
+}
+
+impl StagePane {
+    fn new(
+        buffer: Entity<Buffer>,
+        diff: Option<Entity<BufferDiff>>,
+        exists: bool,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<MergeEditor>,
+    ) -> Self {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            if let Some(diff) = diff {
+                multibuffer.add_diff(diff, cx);
+            }
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+            editor.set_read_only(true);
+            editor
+        });
+        Self {
+            editor,
+            buffer,
+            exists,
+        }
+    }
+
+    /// Finds the line where `text` starts in this version of the file. Each
+    /// side of a conflict spans whole lines, so only line starts are compared.
+    fn find(&self, text: &str, cx: &App) -> Option<Point> {
+        let snapshot = self.buffer.read(cx).snapshot();
+        (0..=snapshot.max_point().row)
+            .map(|row| Point::new(row, 0))
+            .find(|point| snapshot.contains_str_at(*point, text))
+    }
+}
+
+fn stage_buffer(
+    text: &Option<String>,
+    language_registry: &Arc<LanguageRegistry>,
+    language: &Option<Arc<Language>>,
+    cx: &mut AsyncWindowContext,
+) -> Entity<Buffer> {
+    cx.new(|cx| {
+        let mut buffer = Buffer::local(text.clone().unwrap_or_default(), cx);
+        buffer.set_language_registry(language_registry.clone());
+        buffer.set_language_async(language.clone(), cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitMergeConflict).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self
+            .repo_path
+            .file_name()
+            .unwrap_or(self.repo_path.as_unix_str());
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("Resolve conflicts in {}", self.repo_path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.editor.read(cx).active_project_path(cx)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(options, project, window, cx)
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (ours_title, theirs_title) = self
+            .conflicts(cx)
+            .first()
+            .map(|conflict| {
+                (
+                    conflict.ours_branch_name.clone(),
+                    conflict.theirs_branch_name.clone(),
+                )
+            })
+            .unwrap_or_else(|| ("Ours".into(), "Theirs".into()));
+        let colors = cx.theme().colors();
+
+        v_flex()
+            .key_context("MergeEditor")
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::accept_base))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::mark_resolved))
+            .size_full()
+            .bg(colors.editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .h(relative(0.4))
+                    .flex_none()
+                    .w_full()
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .child(self.render_stage(ours_title, &self.ours, cx))
+                    .child(self.render_stage("Base".into(), &self.base, cx))
+                    .child(self.render_stage(theirs_title, &self.theirs, cx)),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(colors.border_variant)
+                            .child(Label::new("Result").size(LabelSize::Small)),
+                    )
+                    .child(div().flex_1().min_h_0().child(self.editor.clone())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::{
+        repository::{ConflictStages, repo_path},
+        status::{UnmergedStatus, UnmergedStatusCode},
+    };
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use unindent::Unindent as _;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    /// The rest of the file, after its first conflict.
+    fn rest() -> String {
+        "
+        three
+        <<<<<<< HEAD
+        four
+        ||||||| base
+        for
+        =======
+        FOUR
+        >>>>>>> other
+        five
+        "
+        .unindent()
+    }
+
+    async fn open_merge_editor(
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeFs>, Entity<MergeEditor>, &mut VisualTestContext) {
+        init_test(cx);
+        let conflicted = "
+            one
+            <<<<<<< HEAD
+            two
+            ||||||| base
+            too
+            =======
+            TWO
+            >>>>>>> other
+            "
+        .unindent()
+            + &rest();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": conflicted,
+            }),
+        )
+        .await;
+        fs.with_git_state(path!("/project/.git").as_ref(), true, |state| {
+            state.unmerged_paths.insert(
+                repo_path("a.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            );
+            state.conflict_stages.insert(
+                repo_path("a.txt"),
+                ConflictStages {
+                    base: Some("one\ntoo\nthree\nfor\nfive\n".into()),
+                    ours: Some("one\ntwo\nthree\nfour\nfive\n".into()),
+                    theirs: Some("one\nTWO\nthree\nFOUR\nfive\n".into()),
+                },
+            );
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        cx.run_until_parked();
+
+        let repository = project
+            .read_with(cx, |project, cx| project.active_repository(cx))
+            .unwrap();
+        let merge_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeEditor::open(
+                    repository,
+                    repo_path("a.txt"),
+                    workspace.weak_handle(),
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        (fs, merge_editor, cx)
+    }
+
+    fn result_text(merge_editor: &Entity<MergeEditor>, cx: &mut VisualTestContext) -> String {
+        merge_editor.read_with(cx, |merge_editor, cx| merge_editor.buffer.read(cx).text())
+    }
+
+    fn cursor_rows(merge_editor: &Entity<MergeEditor>, cx: &mut VisualTestContext) -> [u32; 4] {
+        merge_editor.update(cx, |merge_editor, cx| {
+            [
+                &merge_editor.editor,
+                &merge_editor.ours.editor,
+                &merge_editor.base.editor,
+                &merge_editor.theirs.editor,
+            ]
+            .map(|editor| {
+                editor.update(cx, |editor, cx| {
+                    let snapshot = editor.display_snapshot(cx);
+                    editor.selections.newest::<Point>(&snapshot).head().row
+                })
+            })
+        })
+    }
+
+    #[gpui::test]
+    async fn test_accept_ours(cx: &mut TestAppContext) {
+        let (_, merge_editor, cx) = open_merge_editor(cx).await;
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_ours(&AcceptOurs, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            result_text(&merge_editor, cx),
+            "one\ntwo\n".to_string() + &rest()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_accept_theirs(cx: &mut TestAppContext) {
+        let (_, merge_editor, cx) = open_merge_editor(cx).await;
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_theirs(&AcceptTheirs, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            result_text(&merge_editor, cx),
+            "one\nTWO\n".to_string() + &rest()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_accept_both(cx: &mut TestAppContext) {
+        let (_, merge_editor, cx) = open_merge_editor(cx).await;
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_both(&AcceptBoth, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            result_text(&merge_editor, cx),
+            "one\ntwo\nTWO\n".to_string() + &rest()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_accept_base(cx: &mut TestAppContext) {
+        let (_, merge_editor, cx) = open_merge_editor(cx).await;
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_base(&AcceptBase, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            result_text(&merge_editor, cx),
+            "one\ntoo\n".to_string() + &rest()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_conflict_navigation(cx: &mut TestAppContext) {
+        let (_, merge_editor, cx) = open_merge_editor(cx).await;
+        // The result and each version show the lines of the first conflict.
+        assert_eq!(cursor_rows(&merge_editor, cx), [1, 1, 1, 1]);
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.next_conflict(&NextConflict, window, cx)
+        });
+        assert_eq!(cursor_rows(&merge_editor, cx), [9, 3, 3, 3]);
+
+        // The last conflict is the next one after itself.
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.next_conflict(&NextConflict, window, cx)
+        });
+        assert_eq!(cursor_rows(&merge_editor, cx), [9, 3, 3, 3]);
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.previous_conflict(&PreviousConflict, window, cx)
+        });
+        assert_eq!(cursor_rows(&merge_editor, cx), [1, 1, 1, 1]);
+    }
+
+    #[gpui::test]
+    async fn test_mark_resolved(cx: &mut TestAppContext) {
+        let (fs, merge_editor, cx) = open_merge_editor(cx).await;
+
+        // Files with conflicts left aren't staged.
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.mark_resolved(&MarkResolved, window, cx)
+        });
+        cx.run_until_parked();
+        let unmerged = fs
+            .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+                state.unmerged_paths.contains_key(&repo_path("a.txt"))
+            })
+            .unwrap();
+        assert!(unmerged);
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_ours(&AcceptOurs, window, cx)
+        });
+        cx.run_until_parked();
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_theirs(&AcceptTheirs, window, cx)
+        });
+        cx.run_until_parked();
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.mark_resolved(&MarkResolved, window, cx)
+        });
+        cx.run_until_parked();
+
+        let resolved = "one\ntwo\nthree\nFOUR\nfive\n";
+        assert_eq!(
+            fs.load(path!("/project/a.txt").as_ref()).await.unwrap(),
+            resolved
+        );
+        let (unmerged, index_text) = fs
+            .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+                (
+                    state.unmerged_paths.contains_key(&repo_path("a.txt")),
+                    state.index_contents.get(&repo_path("a.txt")).cloned(),
+                )
+            })
+            .unwrap();
+        assert!(!unmerged);
+        assert_eq!(index_text.as_deref(), Some(resolved));
+    }
+}
@@ -0,0 +1,532 @@
+use crate::branch_diff::BranchDiff;
+use crate::git_panel::show_error_toast;
+use crate::project_diff::ProjectDiff;
+use crate::staged_diff::StagedDiff;
+use anyhow::{Context as _, anyhow};
+use git::{
+    Oid,
+    patch::{ApplyPatchMode, Patch, PatchHunkCheck},
+    repository::DiffType,
+};
+use gpui::{
+    ClipboardItem, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PathPromptOptions,
+    Task, WeakEntity,
+};
+use menu::{Cancel, Confirm};
+use notifications::status_toast::StatusToast;
+use project::{
+    DirectoryLister,
+    git_store::{Repository, diff_buffer_list::DiffBase},
+};
+use ui::{Checkbox, ElevationIndex, Tooltip, prelude::*};
+use workspace::{ModalView, Workspace};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PatchDestination {
+    Clipboard,
+    File,
+}
+
+/// Exports `commit`, or the commits from `commit` through `through`, in the
+/// format of `git format-patch`.
+pub(crate) fn export_commits(
+    commit: Oid,
+    through: Option<Oid>,
+    destination: PatchDestination,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(repository) = repository.upgrade() else {
+        return;
+    };
+    let file_name = match through {
+        Some(through) => format!(
+            "{}..{}.patch",
+            commit.display_short(),
+            through.display_short()
+        ),
+        None => format!("{}.patch", commit.display_short()),
+    };
+    let task = repository.update(cx, |repository, _| {
+        repository.format_patch(
+            commit.to_string(),
+            through.map(|through| through.to_string()),
+        )
+    });
+    export(task, file_name, destination, workspace, window, cx);
+}
+
+/// Exports the changes shown by the active diff, or all uncommitted changes of
+/// the active repository, as a patch.
+pub(crate) fn export_diff(
+    workspace: &mut Workspace,
+    staged: bool,
+    destination: PatchDestination,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some((repository, diff_type)) = diff_to_export(workspace, staged, cx) else {
+        return;
+    };
+    let file_name = match &diff_type {
+        DiffType::HeadToIndex => "staged.patch".to_string(),
+        DiffType::HeadToWorktree => "unstaged.patch".to_string(),
+        DiffType::MergeBase { base_ref } if base_ref.as_ref() != "HEAD" => {
+            format!("{}.patch", base_ref.replace('/', "-"))
+        }
+        DiffType::MergeBase { .. } => "changes.patch".to_string(),
+    };
+    let task = repository.update(cx, |repository, cx| repository.diff(diff_type, cx));
+    export(
+        task,
+        file_name,
+        destination,
+        workspace.weak_handle(),
+        window,
+        cx,
+    );
+}
+
+fn diff_to_export(
+    workspace: &Workspace,
+    staged: bool,
+    cx: &App,
+) -> Option<(Entity<Repository>, DiffType)> {
+    let active_repository = || workspace.project().read(cx).active_repository(cx);
+    if staged || workspace.active_item_as::<StagedDiff>(cx).is_some() {
+        return Some((active_repository()?, DiffType::HeadToIndex));
+    }
+    let (repository, diff_base) = if let Some(diff) = workspace.active_item_as::<ProjectDiff>(cx) {
+        let diff = diff.read(cx);
+        (diff.repo(cx)?, diff.diff_base(cx).clone())
+    } else if let Some(diff) = workspace.active_item_as::<BranchDiff>(cx) {
+        let diff = diff.read(cx);
+        (diff.repo(cx)?, diff.diff_base(cx).clone())
+    } else {
+        (active_repository()?, DiffBase::Head)
+    };
+    let diff_type = match diff_base {
+        DiffBase::Head => DiffType::MergeBase {
+            base_ref: "HEAD".into(),
+        },
+        DiffBase::Index => DiffType::HeadToWorktree,
+        DiffBase::Staged => DiffType::HeadToIndex,
+        DiffBase::Merge { base_ref } => DiffType::MergeBase { base_ref },
+    };
+    Some((repository, diff_type))
+}
+
+fn export(
+    patch: futures::channel::oneshot::Receiver<anyhow::Result<String>>,
+    file_name: String,
+    destination: PatchDestination,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window
+        .spawn(cx, async move |cx| {
+            let result = async {
+                let patch = patch.await.map_err(anyhow::Error::from).flatten()?;
+                anyhow::ensure!(!patch.is_empty(), "There are no changes to export");
+                match destination {
+                    PatchDestination::Clipboard => {
+                        cx.update(|_, cx| cx.write_to_clipboard(ClipboardItem::new_string(patch)))?;
+                        Ok(Some("Patch copied to clipboard".to_string()))
+                    }
+                    PatchDestination::File => {
+                        let (path, fs) = workspace.update_in(cx, |workspace, window, cx| {
+                            let fs = workspace.app_state().fs.clone();
+                            let lister =
+                                DirectoryLister::Local(workspace.project().clone(), fs.clone());
+                            (
+                                workspace.prompt_for_new_path(lister, Some(file_name), window, cx),
+                                fs,
+                            )
+                        })?;
+                        let Some(path) = path.await?.into_iter().flatten().next() else {
+                            return Ok(None);
+                        };
+                        fs.atomic_write(path.clone(), patch)
+                            .await
+                            .with_context(|| format!("writing {}", path.display()))?;
+                        Ok(Some(format!("Patch saved to {}", path.display())))
+                    }
+                }
+            }
+            .await;
+            workspace
+                .update(cx, |workspace, cx| match result {
+                    Ok(Some(message)) => show_success_toast(message, workspace, cx),
+                    Ok(None) => {}
+                    Err(error) => show_error_toast(cx.entity(), "export patch", error, cx),
+                })
+                .ok();
+        })
+        .detach();
+}
+
+/// Previews a patch read from the clipboard, see [`ApplyPatchModal`].
+pub(crate) fn apply_from_clipboard(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let text = cx
+        .read_from_clipboard()
+        .and_then(|item| item.text())
+        .unwrap_or_default();
+    ApplyPatchModal::open(text, workspace, window, cx);
+}
+
+/// Previews a patch read from a file, see [`ApplyPatchModal`].
+pub(crate) fn apply_from_file(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let paths = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Apply Patch".into()),
+        },
+        DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |workspace, cx| {
+        let result = async {
+            let Some(path) = paths.await?.into_iter().flatten().next() else {
+                return Ok(None);
+            };
+            fs.load(&path)
+                .await
+                .with_context(|| format!("reading {}", path.display()))
+                .map(Some)
+        }
+        .await;
+        workspace
+            .update_in(cx, |workspace, window, cx| match result {
+                Ok(Some(text)) => ApplyPatchModal::open(text, workspace, window, cx),
+                Ok(None) => {}
+                Err(error) => show_error_toast(cx.entity(), "apply patch", error, cx),
+            })
+            .ok();
+    })
+    .detach();
+}
+
+fn show_success_toast(message: String, workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let toast = StatusToast::new(message, cx, |this, _| {
+        this.icon(
+            Icon::new(IconName::Check)
+                .size(IconSize::Small)
+                .color(Color::Success),
+        )
+    });
+    workspace.toggle_status_toast(toast, cx);
+}
+
+/// Lists the hunks of a patch with whether each applies to the working tree,
+/// letting the user pick the ones to apply.
+pub(crate) struct ApplyPatchModal {
+    text: String,
+    patch: Patch,
+    selected: Vec<bool>,
+    /// The result of checking each entry of the patch, `None` while checking.
+    checks: Option<Vec<PatchHunkCheck>>,
+    check_error: Option<SharedString>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    _check: Task<()>,
+}
+
+impl ApplyPatchModal {
+    fn open(
+        text: String,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            show_error_toast(
+                cx.entity(),
+                "apply patch",
+                anyhow!("There is no repository to apply the patch to"),
+                cx,
+            );
+            return;
+        };
+        let patch = Patch::parse(&text);
+        if patch.is_empty() {
+            show_error_toast(
+                cx.entity(),
+                "apply patch",
+                anyhow!("The text isn't a patch in the unified diff format"),
+                cx,
+            );
+            return;
+        }
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |_, cx| {
+            Self::new(text, patch, repository, workspace_handle, cx)
+        });
+    }
+
+    fn new(
+        text: String,
+        patch: Patch,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let task = repository.update(cx, |repository, _| repository.check_patch(text.clone()));
+        let check = cx.spawn(async move |this, cx| {
+            let result = task.await.map_err(anyhow::Error::from).flatten();
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(checks) => {
+                        this.selected = checks
+                            .iter()
+                            .map(|check| *check == PatchHunkCheck::Applies)
+                            .collect();
+                        this.checks = Some(checks);
+                    }
+                    Err(error) => {
+                        this.check_error = Some(error.to_string().into());
+                        this.checks = Some(Vec::new());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+        Self {
+            selected: vec![true; patch.entry_count()],
+            text,
+            patch,
+            checks: None,
+            check_error: None,
+            repository,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            _check: check,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        self.apply(ApplyPatchMode::WorkingTree, cx);
+    }
+
+    fn apply(&mut self, mode: ApplyPatchMode, cx: &mut Context<Self>) {
+        let patch = match mode {
+            // `git am` needs the whole series, with its messages.
+            ApplyPatchMode::Commits => self.text.clone(),
+            ApplyPatchMode::WorkingTree | ApplyPatchMode::WorkingTreeAndIndex => {
+                if !self.selected.contains(&true) {
+                    return;
+                }
+                self.patch.to_text(&self.selected)
+            }
+        };
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.apply_patch(patch, mode));
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            let result = task.await.map_err(anyhow::Error::from).flatten();
+            workspace
+                .update(cx, |workspace, cx| match result {
+                    Ok(()) => show_success_toast("Patch applied".to_string(), workspace, cx),
+                    Err(error) => show_error_toast(cx.entity(), "apply patch", error, cx),
+                })
+                .ok();
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        path: &str,
+        hunk: Option<&git::patch::PatchHunk>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let check = self.checks.as_ref().and_then(|checks| checks.get(ix));
+        let (additions, deletions) = hunk.map_or((0, 0), |hunk| (hunk.additions, hunk.deletions));
+        h_flex()
+            .id(ix)
+            .w_full()
+            .gap_2()
+            .child(
+                Checkbox::new(("patch-entry", ix), self.selected[ix].into())
+                    .fill()
+                    .elevation(ElevationIndex::Surface)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.selected[ix] = !this.selected[ix];
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Label::new(path.to_string())
+                    .size(LabelSize::Small)
+                    .truncate(),
+            )
+            .child(
+                Label::new(match hunk {
+                    Some(hunk) => SharedString::from(hunk.header.clone()),
+                    None => "whole file".into(),
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .truncate(),
+            )
+            .child(div().flex_1())
+            .when(additions > 0, |this| {
+                this.child(
+                    Label::new(format!("+{additions}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Created),
+                )
+            })
+            .when(deletions > 0, |this| {
+                this.child(
+                    Label::new(format!("-{deletions}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Deleted),
+                )
+            })
+            .map(|this| match check {
+                None => this.child(
+                    Label::new("Checking…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+                Some(PatchHunkCheck::Applies) => this.child(
+                    Icon::new(IconName::Check)
+                        .size(IconSize::Small)
+                        .color(Color::Success),
+                ),
+                Some(PatchHunkCheck::AlreadyApplied) => this.child(
+                    Label::new("already applied")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+                Some(PatchHunkCheck::Conflicts(error)) => {
+                    let error = error.clone();
+                    this.child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Warning),
+                    )
+                    .tooltip(Tooltip::text(error))
+                }
+            })
+    }
+}
+
+impl EventEmitter<DismissEvent> for ApplyPatchModal {}
+impl ModalView for ApplyPatchModal {}
+impl Focusable for ApplyPatchModal {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ApplyPatchModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self
+            .patch
+            .entries()
+            .map(|(file, hunk)| (file.path.clone(), hunk.cloned()))
+            .collect::<Vec<_>>();
+        let any_selected = self.selected.contains(&true);
+        let message_count = self.patch.message_count;
+
+        v_flex()
+            .key_context("ApplyPatchModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(40.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Diff).size(IconSize::XSmall))
+                    .child(Headline::new("Apply Patch").size(HeadlineSize::XSmall)),
+            )
+            .when_some(self.check_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px_3()
+                        .pb_1()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("patch-entries")
+                    .px_3()
+                    .pb_2()
+                    .gap_1()
+                    .max_h(rems(24.))
+                    .overflow_y_scroll()
+                    .children(
+                        entries.iter().enumerate().map(|(ix, (path, hunk))| {
+                            self.render_entry(ix, path, hunk.as_ref(), cx)
+                        }),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_2()
+                    .gap_1()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .when(message_count > 0, |this| {
+                        this.child(
+                            Button::new("apply-as-commits", "Apply as Commits")
+                                .tooltip(Tooltip::text(format!(
+                                    "Create the {message_count} commit(s) of the series with git am"
+                                )))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.apply(ApplyPatchMode::Commits, cx)
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new("apply-and-stage", "Apply and Stage")
+                            .disabled(!any_selected)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.apply(ApplyPatchMode::WorkingTreeAndIndex, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("apply", "Apply")
+                            .style(ButtonStyle::Filled)
+                            .disabled(!any_selected)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.apply(ApplyPatchMode::WorkingTree, cx)
+                            })),
+                    ),
+            )
+    }
+}
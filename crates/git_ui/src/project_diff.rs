@@ -3,6 +3,7 @@ use crate::{
     diff_multibuffer::DiffMultibuffer,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
     staged_diff::StagedDiff,
+    submodules,
     unstaged_diff::UnstagedDiff,
 };
 use anyhow::{Context as _, Result};
@@ -11,7 +12,9 @@ use editor::{
     Editor, EditorEvent, SplittableEditor, UncommittedDiffHunkDelegate,
     actions::{GoToHunk, GoToPreviousHunk, SendReviewToAgent},
 };
-use git::{Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext};
+use git::{
+    Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext, repository::RepoPath,
+};
 use gpui::{
     Action, AnyElement, App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, Render,
     Subscription, Task, WeakEntity, actions,
@@ -23,6 +26,7 @@ use project::{
     git_store::{
         Repository,
         diff_buffer_list::{self, DiffBase},
+        is_submodule_git_dir,
     },
 };
 use schemars::JsonSchema;
@@ -30,7 +34,7 @@ use serde::Deserialize;
 use settings::GitDiffBaseSetting;
 use std::any::{Any, TypeId};
 use std::sync::Arc;
-use ui::{DiffStat, Divider, Tooltip, prelude::*};
+use ui::{ContextMenu, DiffStat, Divider, PopoverMenu, Tooltip, prelude::*};
 use workspace::{
     ItemNavHistory, SerializableItem, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
     Workspace,
@@ -273,6 +277,36 @@ impl ProjectDiff {
             .update(cx, |diff, cx| diff.set_repo(repo.clone(), cx));
     }
 
+    /// The submodules with changes in the diffed repository, along with their
+    /// own repositories.
+    fn changed_submodules(&self, cx: &App) -> Vec<(RepoPath, Entity<Repository>)> {
+        let Some(repo) = self.repo(cx) else {
+            return Vec::new();
+        };
+        let repo = repo.read(cx);
+        let mut changed = self
+            .project
+            .read(cx)
+            .repositories(cx)
+            .values()
+            .filter_map(|submodule| {
+                let snapshot = submodule.read(cx);
+                if !is_submodule_git_dir(&snapshot.repository_dir_abs_path) {
+                    return None;
+                }
+                let repo_path = repo.abs_path_to_repo_path(&snapshot.work_directory_abs_path)?;
+                repo.status_for_path(&repo_path)?;
+                Some((repo_path, submodule.clone()))
+            })
+            .collect::<Vec<_>>();
+        changed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        changed
+    }
+
+    fn superproject(&self, cx: &App) -> Option<Entity<Repository>> {
+        self.repo(cx)?.read(cx).superproject_repository(cx)
+    }
+
     pub fn move_to_entry(
         &mut self,
         entry: GitStatusEntry,
@@ -755,6 +789,108 @@ impl ProjectDiffToolbar {
             .ok();
     }
 
+    fn view_submodule(
+        &self,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                submodules::view_changes(repository, workspace, window, cx);
+            })
+            .ok();
+    }
+
+    fn render_submodule_navigation(
+        &self,
+        project_diff: &Entity<ProjectDiff>,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let superproject = project_diff.read(cx).superproject(cx);
+        let path_style = project_diff.read(cx).project.read(cx).path_style(cx);
+        let changed_submodules = project_diff
+            .read(cx)
+            .changed_submodules(cx)
+            .into_iter()
+            .map(|(path, repository)| (path.display(path_style).to_string(), repository))
+            .collect::<Vec<_>>();
+        if superproject.is_none() && changed_submodules.is_empty() {
+            return None;
+        }
+        let workspace = self.workspace.clone();
+
+        Some(
+            h_group_sm()
+                .when_some(superproject, |this, superproject| {
+                    this.child(
+                        IconButton::new("superproject", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Show Superproject Changes"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.view_submodule(superproject.clone(), window, cx)
+                            })),
+                    )
+                })
+                .when(!changed_submodules.is_empty(), |this| {
+                    this.child(
+                        PopoverMenu::new("project-diff-submodules")
+                            .menu(move |window, cx| {
+                                let workspace = workspace.clone();
+                                let changed_submodules = changed_submodules.clone();
+                                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                    for (path, repository) in changed_submodules {
+                                        menu = menu.header(path);
+                                        menu = menu.entry("Show Changes", None, {
+                                            let workspace = workspace.clone();
+                                            let repository = repository.clone();
+                                            move |window, cx| {
+                                                workspace
+                                                    .update(cx, |workspace, cx| {
+                                                        submodules::view_changes(
+                                                            repository.clone(),
+                                                            workspace,
+                                                            window,
+                                                            cx,
+                                                        );
+                                                    })
+                                                    .ok();
+                                            }
+                                        });
+                                        menu = menu.entry("Show Commits", None, {
+                                            let workspace = workspace.clone();
+                                            move |window, cx| {
+                                                workspace
+                                                    .update(cx, |workspace, cx| {
+                                                        submodules::view_commits(
+                                                            repository.clone(),
+                                                            workspace,
+                                                            window,
+                                                            cx,
+                                                        );
+                                                    })
+                                                    .ok();
+                                            }
+                                        });
+                                    }
+                                    menu
+                                }))
+                            })
+                            .trigger_with_tooltip(
+                                Button::new("project-diff-submodules-trigger", "Submodules")
+                                    .end_icon(
+                                        Icon::new(IconName::ChevronDown)
+                                            .size(IconSize::XSmall)
+                                            .color(Color::Muted),
+                                    ),
+                                Tooltip::text("Show Changes Inside a Submodule"),
+                            ),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+
     fn unstage_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.workspace
             .update(cx, |workspace, cx| {
@@ -810,6 +946,7 @@ impl Render for ProjectDiffToolbar {
         let is_multibuffer_empty = project_diff.read(cx).multibuffer(cx).read(cx).is_empty();
 
         let stage_all_button_width = rems(5.);
+        let submodule_navigation = self.render_submodule_navigation(&project_diff, cx);
 
         h_flex()
             .my_neg_1()
@@ -817,6 +954,9 @@ impl Render for ProjectDiffToolbar {
             .gap_1p5()
             .flex_wrap()
             .justify_between()
+            .when_some(submodule_navigation, |this, submodule_navigation| {
+                this.child(submodule_navigation).child(Divider::vertical())
+            })
             .when(!is_multibuffer_empty, |this| {
                 this.child(DiffStat::new(
                     "project-diff-stat",
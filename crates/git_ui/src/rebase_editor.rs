@@ -0,0 +1,748 @@
+use crate::git_panel::{askpass_delegate, open_output, show_error_toast};
+use anyhow::{Context as _, Result, anyhow, bail};
+use editor::Editor;
+use futures::channel::oneshot;
+use git::{
+    Oid, SHORT_SHA_LENGTH,
+    rebase::{RebaseOutcome, RebaseStep, RebaseTodoAction, RebaseTodoEntry, validate_todo},
+};
+use gpui::{
+    AsyncApp, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext, ScrollHandle,
+    Task, WeakEntity, actions,
+};
+use notifications::status_toast::StatusToast;
+use project::git_store::{CommitDataState, Repository};
+use ui::{ContextMenu, KeyBinding, PopoverMenu, prelude::*};
+use workspace::{ModalView, Workspace};
+
+/// The most commits the rebase editor loads, to stay responsive when the base
+/// commit is far from HEAD.
+const MAX_COMMITS: usize = 1000;
+
+actions!(
+    rebase_editor,
+    [
+        /// Moves the selected commit up, so it's applied earlier.
+        MoveUp,
+        /// Moves the selected commit down, so it's applied later.
+        MoveDown,
+        /// Uses the selected commit as is.
+        PickCommit,
+        /// Uses the selected commit with a new message.
+        RewordCommit,
+        /// Stops the rebase after the selected commit so it can be amended.
+        EditCommit,
+        /// Melds the selected commit into the previous one, keeping both messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit.
+        DropCommit,
+    ]
+);
+
+struct TodoItem {
+    sha: Oid,
+    subject: SharedString,
+    message: SharedString,
+    action: RebaseTodoAction,
+    new_message: Option<String>,
+}
+
+#[derive(Clone)]
+struct DraggedTodoItem {
+    ix: usize,
+    sha: Oid,
+    subject: SharedString,
+}
+
+impl Render for DraggedTodoItem {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().colors().background)
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_sm()
+            .child(
+                Label::new(self.sha.display_short())
+                    .buffer_font(cx)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(self.subject.clone()).truncate())
+    }
+}
+
+/// Edits the todo list of an interactive rebase of the commits after a base
+/// commit picked in the git graph.
+pub(crate) struct RebaseEditor {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    onto: Oid,
+    branch_name: Option<SharedString>,
+    items: Vec<TodoItem>,
+    selected_ix: usize,
+    loading: bool,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    message_editor: Entity<Editor>,
+    scroll_handle: ScrollHandle,
+    _load_commits: Task<()>,
+}
+
+impl EventEmitter<DismissEvent> for RebaseEditor {}
+impl ModalView for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl RebaseEditor {
+    pub(crate) fn open(
+        onto: Oid,
+        repository: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(repository) = repository.upgrade() else {
+            return;
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                let workspace_handle = workspace.weak_handle();
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    Self::new(onto, repository, workspace_handle, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    fn new(
+        onto: Oid,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let head = repository
+            .read(cx)
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.parse::<Oid>());
+        let branch_name = repository
+            .read(cx)
+            .branch
+            .as_ref()
+            .map(|branch| SharedString::from(branch.name().to_string()));
+
+        let load_commits = cx.spawn({
+            let repository = repository.clone();
+            async move |this, cx| {
+                let result = match head {
+                    Some(Ok(head)) => load_commits(repository, head, onto, cx).await,
+                    Some(Err(error)) => Err(error),
+                    None => Err(anyhow!("The repository has no commits")),
+                };
+                this.update(cx, |this, cx| {
+                    match result {
+                        Ok(items) if items.is_empty() => {
+                            this.error = Some("There are no commits after this one".into());
+                        }
+                        Ok(items) => this.items = items,
+                        Err(error) => this.error = Some(format!("{error:#}").into()),
+                    }
+                    this.loading = false;
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(3, 8, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+
+        Self {
+            repository,
+            workspace,
+            onto,
+            branch_name,
+            items: Vec::new(),
+            selected_ix: 0,
+            loading: true,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            message_editor,
+            scroll_handle: ScrollHandle::new(),
+            _load_commits: load_commits,
+        }
+    }
+
+    fn dispatch_context(&self, window: &Window, cx: &App) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("RebaseEditor");
+        if self.focus_handle.is_focused(window) {
+            dispatch_context.add("menu");
+            dispatch_context.add("RebaseTodoList");
+        } else if self
+            .message_editor
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            dispatch_context.add("RewordEditor");
+        }
+        dispatch_context
+    }
+
+    fn select(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if ix >= self.items.len() {
+            return;
+        }
+        self.store_message(cx);
+        self.selected_ix = ix;
+        self.load_message(window, cx);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.selected_ix + 1, window, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix.checked_sub(1) {
+            self.select(ix, window, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &menu::SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(0, window, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.items.len().saturating_sub(1), window, cx);
+    }
+
+    fn move_item(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if from == to || from >= self.items.len() || to >= self.items.len() {
+            return;
+        }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        self.selected_ix = to;
+        self.error = None;
+        self.scroll_handle.scroll_to_item(to);
+        cx.notify();
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(to) = self.selected_ix.checked_sub(1) {
+            self.move_item(self.selected_ix, to, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_item(self.selected_ix, self.selected_ix + 1, cx);
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseTodoAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select(ix, window, cx);
+        let Some(item) = self.items.get_mut(ix) else {
+            return;
+        };
+        item.action = action;
+        self.error = None;
+        if action == RebaseTodoAction::Reword {
+            self.load_message(window, cx);
+            window.focus(&self.message_editor.focus_handle(cx), cx);
+        }
+        cx.notify();
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseTodoAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_ix, action, window, cx);
+    }
+
+    /// Saves the message editor's contents to the selected item, when it's reworded.
+    fn store_message(&mut self, cx: &App) {
+        if let Some(item) = self.items.get_mut(self.selected_ix)
+            && item.action == RebaseTodoAction::Reword
+        {
+            item.new_message = Some(self.message_editor.read(cx).text(cx));
+        }
+    }
+
+    fn load_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(item) = self.items.get(self.selected_ix) else {
+            return;
+        };
+        if item.action != RebaseTodoAction::Reword {
+            return;
+        }
+        let message = item
+            .new_message
+            .clone()
+            .unwrap_or_else(|| item.message.to_string());
+        self.message_editor.update(cx, |editor, cx| {
+            editor.set_text(message, window, cx);
+        });
+    }
+
+    fn todo(&self) -> Vec<RebaseTodoEntry> {
+        self.items
+            .iter()
+            .map(|item| {
+                let message = item
+                    .new_message
+                    .clone()
+                    .filter(|message| message.trim_end() != item.message.trim_end());
+                let action = match item.action {
+                    // Rewording without changing the message is the same as picking.
+                    RebaseTodoAction::Reword if message.is_none() => RebaseTodoAction::Pick,
+                    action => action,
+                };
+                RebaseTodoEntry {
+                    action,
+                    sha: item.sha,
+                    message: message.filter(|_| action == RebaseTodoAction::Reword),
+                }
+            })
+            .collect()
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.loading || self.items.is_empty() {
+            return;
+        }
+        self.store_message(cx);
+        let todo = self.todo();
+        if let Err(error) = validate_todo(&todo) {
+            self.error = Some(error.to_string().into());
+            cx.notify();
+            return;
+        }
+
+        let onto = self.onto;
+        let askpass = askpass_delegate(
+            self.workspace.clone(),
+            format!("git rebase --interactive {}", onto.display_short()),
+            window,
+            cx,
+        );
+        let rebase = self.repository.update(cx, |repository, cx| {
+            repository.rebase_interactive(onto.to_string(), todo, askpass, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            let result = rebase.await;
+            workspace
+                .update(cx, |workspace, cx| {
+                    show_rebase_result("rebase", result, workspace, cx)
+                })
+                .ok();
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .message_editor
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            self.store_message(cx);
+            window.focus(&self.focus_handle, cx);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn render_item(&self, ix: usize, item: &TodoItem, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let is_selected = ix == self.selected_ix;
+        let action = item.action;
+        let action_color = match action {
+            RebaseTodoAction::Pick => Color::Default,
+            RebaseTodoAction::Reword => Color::Accent,
+            RebaseTodoAction::Edit => Color::Warning,
+            RebaseTodoAction::Squash | RebaseTodoAction::Fixup => Color::Modified,
+            RebaseTodoAction::Drop => Color::Deleted,
+        };
+        let subject = if action == RebaseTodoAction::Reword {
+            item.new_message
+                .as_deref()
+                .and_then(|message| message.lines().next())
+                .map(|subject| SharedString::from(subject.to_string()))
+                .unwrap_or_else(|| item.subject.clone())
+        } else {
+            item.subject.clone()
+        };
+        let editor = cx.entity();
+        let dragged = DraggedTodoItem {
+            ix,
+            sha: item.sha,
+            subject: item.subject.clone(),
+        };
+
+        h_flex()
+            .id(ix)
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(colors.element_selected))
+            .hover(|style| style.bg(colors.element_hover))
+            .when(action.melds(), |this| this.pl_6())
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select(ix, window, cx);
+                window.focus(&this.focus_handle, cx);
+            }))
+            .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+            .drag_over::<DraggedTodoItem>(move |style, _, _, cx| {
+                style.bg(cx.theme().colors().drop_target_background)
+            })
+            .on_drop(cx.listener(move |this, dragged: &DraggedTodoItem, _, cx| {
+                this.move_item(dragged.ix, ix, cx);
+            }))
+            .child(
+                PopoverMenu::new(("rebase-action", ix))
+                    .trigger(
+                        Button::new(("rebase-action-trigger", ix), action.as_str())
+                            .label_size(LabelSize::Small)
+                            .color(action_color),
+                    )
+                    .menu(move |window, cx| {
+                        let editor = editor.clone();
+                        Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                            for action in RebaseTodoAction::ALL {
+                                let editor = editor.clone();
+                                menu = menu.entry(action.as_str(), None, move |window, cx| {
+                                    editor.update(cx, |editor, cx| {
+                                        editor.set_action(ix, action, window, cx)
+                                    });
+                                });
+                            }
+                            menu
+                        }))
+                    }),
+            )
+            .child(
+                Label::new(item.sha.display_short())
+                    .size(LabelSize::Small)
+                    .buffer_font(cx)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(subject)
+                    .truncate()
+                    .when(action == RebaseTodoAction::Drop, |label| {
+                        label.strikethrough().color(Color::Muted)
+                    }),
+            )
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let is_rewording = self
+            .items
+            .get(self.selected_ix)
+            .is_some_and(|item| item.action == RebaseTodoAction::Reword);
+        let title = match &self.branch_name {
+            Some(branch_name) => format!("Rebase {branch_name} onto {}", self.onto.display_short()),
+            None => format!("Rebase onto {}", self.onto.display_short()),
+        };
+
+        v_flex()
+            .key_context(self.dispatch_context(window, cx))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Pick, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Reword, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &EditCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Edit, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Squash, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Fixup, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                this.set_selected_action(RebaseTodoAction::Drop, window, cx)
+            }))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(|this, _: &menu::SecondaryConfirm, window, cx| {
+                this.confirm(&menu::Confirm, window, cx)
+            }))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(40.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(
+                Label::new("Commits are applied from top to bottom. Drag them or use alt-up and alt-down to reorder.")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .mx_3()
+                    .mb_1(),
+            )
+            .child(
+                v_flex()
+                    .id("rebase-todo-list")
+                    .track_focus(&self.focus_handle)
+                    .max_h(rems(24.))
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .py_1()
+                    .when(self.loading, |this| {
+                        this.child(
+                            Label::new("Loading commits…")
+                                .color(Color::Muted)
+                                .mx_3()
+                                .my_1(),
+                        )
+                    })
+                    .children(
+                        self.items
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, item)| self.render_item(ix, item, cx).into_any_element())
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .when(is_rewording, |this| {
+                this.child(
+                    div()
+                        .px_3()
+                        .py_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .bg(cx.theme().colors().editor_background)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small)),
+                )
+            })
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_1()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Button::new("cancel", "Cancel")
+                            .key_binding(KeyBinding::for_action_in(
+                                &menu::Cancel,
+                                &focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(&menu::Cancel, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("start-rebase", "Start Rebase")
+                            .disabled(self.loading || self.items.is_empty())
+                            .key_binding(KeyBinding::for_action_in(
+                                &menu::Confirm,
+                                &focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(&menu::Confirm, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+/// Walks the first-parent chain from `head` back to `onto`, returning the
+/// commits in between oldest first.
+async fn load_commits(
+    repository: Entity<Repository>,
+    head: Oid,
+    onto: Oid,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TodoItem>> {
+    let mut items = Vec::new();
+    let mut sha = head;
+    while sha != onto {
+        if items.len() == MAX_COMMITS {
+            bail!("There are more than {MAX_COMMITS} commits to rebase");
+        }
+        let state = repository.update(cx, |repository, cx| {
+            repository.fetch_commit_data(sha, true, cx).clone()
+        });
+        let data = match state {
+            CommitDataState::Loaded(data) => data,
+            CommitDataState::Loading(Some(data)) => data
+                .await
+                .with_context(|| format!("Failed to load commit {}", sha.display_short()))?,
+            CommitDataState::Loading(None) => {
+                bail!("Failed to load commit {}", sha.display_short())
+            }
+        };
+        let parent = match data.parents.as_slice() {
+            [parent] => *parent,
+            [] => bail!("Commit {} is not an ancestor of HEAD", onto.display_short()),
+            _ => bail!(
+                "Merge commit {} can't be rebased interactively",
+                sha.display_short()
+            ),
+        };
+        items.push(TodoItem {
+            sha,
+            subject: data.subject.clone(),
+            message: data.message.clone(),
+            action: RebaseTodoAction::Pick,
+            new_message: None,
+        });
+        sha = parent;
+    }
+    items.reverse();
+    Ok(items)
+}
+
+/// Continues, skips the current commit of, or aborts the rebase in progress in
+/// the active repository.
+pub(crate) fn rebase_step(
+    workspace: &mut Workspace,
+    step: RebaseStep,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let askpass = askpass_delegate(
+        workspace.weak_handle(),
+        format!("git rebase --{}", step.name()),
+        window,
+        cx,
+    );
+    let task = repository.update(cx, |repository, cx| {
+        repository.rebase_step(step, askpass, cx)
+    });
+    cx.spawn(async move |workspace, cx| {
+        let result = task.await;
+        workspace
+            .update(cx, |workspace, cx| {
+                show_rebase_result(format!("rebase --{}", step.name()), result, workspace, cx)
+            })
+            .ok();
+    })
+    .detach();
+}
+
+fn show_rebase_result(
+    operation: impl Into<SharedString>,
+    result: Result<Result<RebaseOutcome>, oneshot::Canceled>,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    let outcome = match result
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result)
+    {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            show_error_toast(cx.entity(), operation, error, cx);
+            return;
+        }
+    };
+
+    let message = match &outcome.stopped {
+        None => "Rebase complete".to_string(),
+        Some(state) => {
+            let progress = format!("{}/{}", state.step, state.total);
+            match &state.stopped_sha {
+                Some(sha) => format!(
+                    "Rebase stopped at {} ({progress})",
+                    sha.get(..SHORT_SHA_LENGTH).unwrap_or(sha)
+                ),
+                None => format!("Rebase stopped ({progress})"),
+            }
+        }
+    };
+    let output = outcome.message;
+    let workspace_weak = cx.weak_entity();
+    let status_toast = StatusToast::new(message, cx, move |this, _cx| {
+        let this = this.icon(
+            Icon::new(IconName::GitBranch)
+                .size(IconSize::Small)
+                .color(Color::Muted),
+        );
+        let this = if output.trim().is_empty() {
+            this
+        } else {
+            this.action("View Log", move |window, cx| {
+                let output = output.clone();
+                workspace_weak
+                    .update(cx, move |workspace, cx| {
+                        open_output("rebase", workspace, &output, window, cx)
+                    })
+                    .ok();
+            })
+        };
+        this.dismiss_button(true)
+    });
+    workspace.toggle_status_toast(status_toast, cx);
+}
@@ -5,7 +5,7 @@ use crate::{
 use anyhow::{Context as _, Result};
 use buffer_diff::DiffHunkStatus;
 use editor::{
-    DiffHunkDelegate, Editor, EditorEvent, ResolvedDiffHunks, SplittableEditor,
+    DiffHunkDelegate, Editor, EditorEvent, ResolvedDiffHunks, ResolvedDiffLines, SplittableEditor,
     actions::{GoToHunk, GoToPreviousHunk},
 };
 use git::{Commit, UnstageAll, UnstageAndNext};
@@ -78,6 +78,20 @@ impl DiffHunkDelegate for StagedDiffDelegate {
         }
     }
 
+    fn stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        lines: Vec<ResolvedDiffLines>,
+        editor: &mut Editor,
+        _window: &mut Window,
+        cx: &mut Context<Editor>,
+    ) -> Result<()> {
+        if stage {
+            return Ok(());
+        }
+        editor::stage_or_unstage_diff_lines(stage, lines, editor, cx)
+    }
+
     fn render_hunk_controls(
         &self,
         row: u32,
@@ -0,0 +1,155 @@
+use crate::git_graph::open_or_reuse_graph;
+use crate::git_panel::{GitPanel, askpass_delegate, show_error_toast};
+use crate::project_diff::ProjectDiff;
+use git::repository::{LogSource, RepoPath};
+use git::submodule::{Submodule, SubmoduleOperation};
+use gpui::{Entity, WeakEntity};
+use notifications::status_toast::StatusToast;
+use project::git_store::Repository;
+use ui::prelude::*;
+use workspace::Workspace;
+
+/// A short summary of how a submodule's checkout differs from the commit the
+/// superproject records, `None` when it matches and is clean.
+pub(crate) fn status_label(submodule: &Submodule) -> Option<SharedString> {
+    if !submodule.is_initialized() {
+        return Some("not initialized".into());
+    }
+    let mut parts = Vec::new();
+    if submodule.ahead > 0 {
+        parts.push(format!("↑{}", submodule.ahead));
+    }
+    if submodule.behind > 0 {
+        parts.push(format!("↓{}", submodule.behind));
+    }
+    if submodule.dirty {
+        parts.push("dirty".to_string());
+    }
+    if submodule.is_detached() && (submodule.is_modified() || parts.is_empty()) {
+        parts.insert(0, "detached".to_string());
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" ").into())
+    }
+}
+
+pub(crate) fn status_tooltip(submodule: &Submodule) -> SharedString {
+    if !submodule.is_initialized() {
+        return "Submodule not initialized, run Update Submodules to check it out".into();
+    }
+    let head = match &submodule.branch {
+        Some(branch) => format!("On branch {branch}"),
+        None => "HEAD detached".to_string(),
+    };
+    let mut lines = vec![head];
+    if submodule.is_modified() {
+        lines.push(format!(
+            "{} commit(s) ahead, {} behind the recorded commit",
+            submodule.ahead, submodule.behind
+        ));
+    }
+    if submodule.dirty {
+        lines.push("Has uncommitted changes".to_string());
+    }
+    lines.join("\n").into()
+}
+
+/// Runs `operation` on the submodules at `paths` of `repository`, or on all of
+/// them when `paths` is empty.
+pub(crate) fn run(
+    operation: SubmoduleOperation,
+    paths: Vec<RepoPath>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let action: SharedString = format!("submodule {}", operation.as_str()).into();
+    let askpass = askpass_delegate(workspace.clone(), format!("git {action}"), window, cx);
+    let task = repository.update(cx, |repository, cx| {
+        repository.submodule_command(operation, paths, askpass, cx)
+    });
+    cx.spawn(async move |cx| {
+        let result = task.await.map_err(anyhow::Error::from).flatten();
+        workspace
+            .update(cx, |workspace, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.reload_submodules(cx));
+                }
+                match result {
+                    Ok(_) => {
+                        let toast =
+                            StatusToast::new(format!("git {action} finished"), cx, |this, _| {
+                                this.icon(
+                                    Icon::new(IconName::Check)
+                                        .size(IconSize::Small)
+                                        .color(Color::Success),
+                                )
+                            });
+                        workspace.toggle_status_toast(toast, cx);
+                    }
+                    Err(error) => show_error_toast(cx.entity(), action, error, cx),
+                }
+            })
+            .ok();
+    })
+    .detach();
+}
+
+pub(crate) fn run_in_active_repository(
+    workspace: &mut Workspace,
+    operation: SubmoduleOperation,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    run(
+        operation,
+        Vec::new(),
+        repository,
+        workspace.weak_handle(),
+        window,
+        cx,
+    );
+}
+
+/// Shows the changes inside `repository`, making it the active repository.
+pub(crate) fn view_changes(
+    repository: Entity<Repository>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    repository.update(cx, |repository, cx| repository.set_as_active_repository(cx));
+    ProjectDiff::deploy_at(workspace, None, window, cx);
+}
+
+pub(crate) fn view_commits(
+    repository: Entity<Repository>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let git_store = workspace.project().read(cx).git_store().clone();
+    let (repo_id, head) = {
+        let repository = repository.read(cx);
+        let head = repository
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.to_string());
+        (repository.id, head)
+    };
+    open_or_reuse_graph(
+        workspace,
+        repo_id,
+        git_store,
+        LogSource::All,
+        head,
+        window,
+        cx,
+    );
+}
@@ -0,0 +1,479 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use collections::HashSet;
+use git::repository::{Worktree as GitWorktree, WorktreeStatus};
+use gpui::{
+    Action, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity,
+};
+use menu::Cancel;
+use notifications::status_toast::StatusToast;
+use project::git_store::{Repository, RepositoryEvent};
+use time::OffsetDateTime;
+use ui::{Checkbox, ElevationIndex, Tooltip, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{ModalView, Workspace};
+use zed_actions::OpenWorktreeInNewWindow;
+
+use crate::git_panel::show_error_toast;
+
+struct WorktreeRow {
+    worktree: GitWorktree,
+    status: Option<WorktreeStatus>,
+}
+
+impl WorktreeRow {
+    fn is_prunable(&self) -> bool {
+        self.status
+            .as_ref()
+            .is_some_and(|status| status.is_prunable)
+    }
+}
+
+/// Lists the worktrees of the active repository with the state of their
+/// checkouts, to clean up the ones that are no longer needed.
+pub(crate) struct WorktreeManager {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    rows: Vec<WorktreeRow>,
+    selected: HashSet<PathBuf>,
+    /// Worktrees with an operation in flight.
+    busy: HashSet<PathBuf>,
+    loading: bool,
+    focus_handle: FocusHandle,
+    _refresh: Task<()>,
+    _subscription: Subscription,
+}
+
+impl WorktreeManager {
+    pub(crate) fn open(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            show_error_toast(
+                cx.entity(),
+                "worktree list",
+                anyhow!("There is no repository to manage worktrees for"),
+                cx,
+            );
+            return;
+        };
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |_, cx| {
+            Self::new(repository, workspace_handle, cx)
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&repository, |this, _, event, cx| {
+            if let RepositoryEvent::GitWorktreeListChanged = event {
+                this.refresh(cx);
+            }
+        });
+        let mut this = Self {
+            repository,
+            workspace,
+            rows: Vec::new(),
+            selected: HashSet::default(),
+            busy: HashSet::default(),
+            loading: true,
+            focus_handle: cx.focus_handle(),
+            _refresh: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let (worktrees, statuses) = self.repository.update(cx, |repository, _| {
+            (repository.worktrees(), repository.worktree_statuses())
+        });
+        self._refresh = cx.spawn(async move |this, cx| {
+            let worktrees = worktrees.await.map_err(anyhow::Error::from).flatten();
+            // The statuses are best effort, the list is still useful without them.
+            let statuses = statuses
+                .await
+                .map_err(anyhow::Error::from)
+                .flatten()
+                .unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                match worktrees {
+                    Ok(worktrees) => this.set_worktrees(worktrees, statuses),
+                    Err(error) => {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            show_error_toast(workspace, "worktree list", error, cx);
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn set_worktrees(&mut self, worktrees: Vec<GitWorktree>, statuses: Vec<WorktreeStatus>) {
+        let mut rows = worktrees
+            .into_iter()
+            .filter(|worktree| !worktree.is_bare)
+            .map(|worktree| {
+                let status = statuses
+                    .iter()
+                    .find(|status| status.path == worktree.path)
+                    .cloned();
+                WorktreeRow { worktree, status }
+            })
+            .collect::<Vec<_>>();
+        // The main worktree first, then the most recently used ones.
+        rows.sort_by_key(|row| {
+            (
+                !row.worktree.is_main,
+                std::cmp::Reverse(row.status.as_ref().and_then(|status| status.modified_at)),
+            )
+        });
+        self.selected
+            .retain(|path| rows.iter().any(|row| &row.worktree.path == path));
+        self.rows = rows;
+    }
+
+    fn current_path(&self, cx: &App) -> PathBuf {
+        self.repository
+            .read(cx)
+            .work_directory_abs_path
+            .to_path_buf()
+    }
+
+    fn current_is_dirty(&self, cx: &App) -> bool {
+        let current_path = self.current_path(cx);
+        self.rows
+            .iter()
+            .find(|row| row.worktree.path == current_path)
+            .and_then(|row| row.status.as_ref())
+            .is_some_and(|status| status.is_dirty())
+    }
+
+    fn stale_count(&self) -> usize {
+        self.rows.iter().filter(|row| row.is_prunable()).count()
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn prune(&mut self, cx: &mut Context<Self>) {
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.prune_worktrees());
+        self.run(task, "worktree prune", Vec::new(), None, cx);
+    }
+
+    fn remove_selected(&mut self, cx: &mut Context<Self>) {
+        let paths = self
+            .rows
+            .iter()
+            .map(|row| row.worktree.path.clone())
+            .filter(|path| self.selected.contains(path) && !self.busy.contains(path))
+            .collect::<Vec<_>>();
+        for path in paths {
+            self.remove(path, cx);
+        }
+    }
+
+    fn remove(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let task = self.repository.update(cx, |repository, _| {
+            repository.remove_worktree(path.clone(), false)
+        });
+        self.selected.remove(&path);
+        self.run(task, "worktree remove", vec![path], None, cx);
+    }
+
+    fn move_changes(&mut self, target: PathBuf, cx: &mut Context<Self>) {
+        let task = self.repository.update(cx, |repository, _| {
+            repository.move_changes_to_worktree(target.clone())
+        });
+        let message = format!("Moved changes to {}", target.compact().display());
+        self.run(task, "stash", vec![target], Some(message), cx);
+    }
+
+    fn run(
+        &mut self,
+        task: futures::channel::oneshot::Receiver<anyhow::Result<()>>,
+        action: &'static str,
+        paths: Vec<PathBuf>,
+        success_message: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.busy.extend(paths.iter().cloned());
+        cx.notify();
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |this, cx| {
+            let result = task.await.map_err(anyhow::Error::from).flatten();
+            this.update(cx, |this, cx| {
+                for path in &paths {
+                    this.busy.remove(path);
+                }
+                this.refresh(cx);
+            })
+            .ok();
+            workspace
+                .update(cx, |workspace, cx| match result {
+                    Ok(()) => {
+                        if let Some(message) = success_message {
+                            let toast = StatusToast::new(message, cx, |this, _| {
+                                this.icon(
+                                    Icon::new(IconName::Check)
+                                        .size(IconSize::Small)
+                                        .color(Color::Success),
+                                )
+                            });
+                            workspace.toggle_status_toast(toast, cx);
+                        }
+                    }
+                    Err(error) => show_error_toast(cx.entity(), action, error, cx),
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    fn render_row(
+        &self,
+        ix: usize,
+        row: &WorktreeRow,
+        current_path: &Path,
+        current_is_dirty: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let path = row.worktree.path.clone();
+        let is_current = path == current_path;
+        let is_busy = self.busy.contains(&path);
+        let is_prunable = row.is_prunable();
+        let can_remove = !row.worktree.is_main && !is_current && !is_prunable;
+        let status = row.status.as_ref();
+
+        let state_label = match status {
+            _ if is_prunable => Label::new("stale").color(Color::Warning),
+            Some(status) if status.is_dirty() => {
+                Label::new(format!("{} changed", status.changed_files)).color(Color::Modified)
+            }
+            Some(_) => Label::new("clean").color(Color::Muted),
+            None => Label::new("…").color(Color::Muted),
+        };
+        let ahead_behind = status
+            .and_then(|status| status.ahead_behind)
+            .filter(|(ahead, behind)| *ahead > 0 || *behind > 0)
+            .map(|(ahead, behind)| format!("↑{ahead} ↓{behind}"));
+        let modified_at = status
+            .and_then(|status| status.modified_at)
+            .map(|modified_at| {
+                let modified_at = OffsetDateTime::from(modified_at);
+                let local_offset =
+                    time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+                time_format::format_localized_timestamp(
+                    modified_at,
+                    OffsetDateTime::now_utc(),
+                    local_offset,
+                    time_format::TimestampFormat::Relative,
+                )
+            });
+
+        h_flex()
+            .id(ix)
+            .w_full()
+            .py_1()
+            .gap_2()
+            .child(
+                Checkbox::new(
+                    ("select-worktree", ix),
+                    self.selected.contains(&path).into(),
+                )
+                .fill()
+                .elevation(ElevationIndex::Surface)
+                .disabled(!can_remove)
+                .on_click(cx.listener({
+                    let path = path.clone();
+                    move |this, _, _, cx| {
+                        if !this.selected.remove(&path) {
+                            this.selected.insert(path.clone());
+                        }
+                        cx.notify();
+                    }
+                })),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .child(Label::new(row.worktree.display_name().to_string()).truncate())
+                            .when(row.worktree.is_main, |this| {
+                                this.child(
+                                    Label::new("main")
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .when(is_current, |this| {
+                                this.child(
+                                    Label::new("current")
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Accent),
+                                )
+                            })
+                            .when(status.is_some_and(|status| status.is_locked), |this| {
+                                this.child(
+                                    Icon::new(IconName::Lock)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .child(
+                        Label::new(path.compact().to_string_lossy().into_owned())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .when_some(ahead_behind, |this, ahead_behind| {
+                this.child(
+                    Label::new(ahead_behind)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(state_label.size(LabelSize::Small))
+            .when_some(modified_at, |this, modified_at| {
+                this.child(
+                    Label::new(modified_at)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(
+                IconButton::new(("open-worktree", ix), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .disabled(is_prunable)
+                    .tooltip(Tooltip::text("Open in New Window"))
+                    .on_click({
+                        let path = path.clone();
+                        move |_, window, cx| {
+                            window.dispatch_action(
+                                OpenWorktreeInNewWindow { path: path.clone() }.boxed_clone(),
+                                cx,
+                            );
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new(("move-changes", ix), IconName::ArrowDownRight)
+                    .icon_size(IconSize::Small)
+                    .disabled(is_current || is_prunable || is_busy || !current_is_dirty)
+                    .tooltip(Tooltip::text("Move Uncommitted Changes Here"))
+                    .on_click(cx.listener({
+                        let path = path.clone();
+                        move |this, _, _, cx| this.move_changes(path.clone(), cx)
+                    })),
+            )
+            .child(
+                IconButton::new(("remove-worktree", ix), IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .disabled(!can_remove || is_busy)
+                    .tooltip(Tooltip::text("Remove Worktree"))
+                    .on_click(cx.listener(move |this, _, _, cx| this.remove(path.clone(), cx))),
+            )
+    }
+}
+
+impl EventEmitter<DismissEvent> for WorktreeManager {}
+impl ModalView for WorktreeManager {}
+impl Focusable for WorktreeManager {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for WorktreeManager {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let current_path = self.current_path(cx);
+        let current_is_dirty = self.current_is_dirty(cx);
+        let stale_count = self.stale_count();
+        let selected_count = self.selected.len();
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(ix, row)| self.render_row(ix, row, &current_path, current_is_dirty, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("WorktreeManager")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(48.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitWorktree).size(IconSize::XSmall))
+                    .child(Headline::new("Worktrees").size(HeadlineSize::XSmall))
+                    .child(div().flex_1())
+                    .when(self.loading, |this| {
+                        this.child(
+                            Label::new("Loading…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                v_flex()
+                    .id("worktree-rows")
+                    .px_3()
+                    .pb_2()
+                    .max_h(rems(28.))
+                    .overflow_y_scroll()
+                    .children(rows),
+            )
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_2()
+                    .gap_1()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Button::new("prune-worktrees", format!("Prune Stale ({stale_count})"))
+                            .disabled(stale_count == 0)
+                            .tooltip(Tooltip::text(
+                                "Forget the worktrees whose directories were deleted",
+                            ))
+                            .on_click(cx.listener(|this, _, _, cx| this.prune(cx))),
+                    )
+                    .child(
+                        Button::new(
+                            "remove-selected",
+                            format!("Remove Selected ({selected_count})"),
+                        )
+                        .style(ButtonStyle::Filled)
+                        .disabled(selected_count == 0)
+                        .on_click(cx.listener(|this, _, _, cx| this.remove_selected(cx))),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::DateTime;
+
+/// A value decoded from the bytes at the cursor, read in both byte orders.
+#[derive(Debug, PartialEq)]
+pub struct InspectedValue {
+    pub label: &'static str,
+    pub little_endian: String,
+    pub big_endian: String,
+}
+
+/// Decodes the bytes at the cursor as each kind of value that fits in them.
+pub fn inspect(bytes: &[u8]) -> Vec<InspectedValue> {
+    [
+        value("u8", bytes, |[byte]: [u8; 1], _| byte.to_string()),
+        value("i8", bytes, |[byte]: [u8; 1], _| (byte as i8).to_string()),
+        value("u16", bytes, |bytes, le| {
+            number(bytes, le, u16::from_le_bytes, u16::from_be_bytes)
+        }),
+        value("i16", bytes, |bytes, le| {
+            number(bytes, le, i16::from_le_bytes, i16::from_be_bytes)
+        }),
+        value("u32", bytes, |bytes, le| {
+            number(bytes, le, u32::from_le_bytes, u32::from_be_bytes)
+        }),
+        value("i32", bytes, |bytes, le| {
+            number(bytes, le, i32::from_le_bytes, i32::from_be_bytes)
+        }),
+        value("u64", bytes, |bytes, le| {
+            number(bytes, le, u64::from_le_bytes, u64::from_be_bytes)
+        }),
+        value("i64", bytes, |bytes, le| {
+            number(bytes, le, i64::from_le_bytes, i64::from_be_bytes)
+        }),
+        value("f32", bytes, |bytes, le| {
+            number(bytes, le, f32::from_le_bytes, f32::from_be_bytes)
+        }),
+        value("f64", bytes, |bytes, le| {
+            number(bytes, le, f64::from_le_bytes, f64::from_be_bytes)
+        }),
+        value("time32", bytes, |bytes, le| {
+            let seconds = if le {
+                u32::from_le_bytes(bytes)
+            } else {
+                u32::from_be_bytes(bytes)
+            };
+            unix_time(seconds.into())
+        }),
+        value("time64", bytes, |bytes, le| {
+            let seconds = if le {
+                i64::from_le_bytes(bytes)
+            } else {
+                i64::from_be_bytes(bytes)
+            };
+            unix_time(seconds)
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn value<const N: usize>(
+    label: &'static str,
+    bytes: &[u8],
+    format: impl Fn([u8; N], bool) -> String,
+) -> Option<InspectedValue> {
+    let bytes: [u8; N] = bytes.get(..N)?.try_into().ok()?;
+    Some(InspectedValue {
+        label,
+        little_endian: format(bytes, true),
+        big_endian: format(bytes, false),
+    })
+}
+
+fn number<const N: usize, T: ToString>(
+    bytes: [u8; N],
+    little_endian: bool,
+    from_le_bytes: fn([u8; N]) -> T,
+    from_be_bytes: fn([u8; N]) -> T,
+) -> String {
+    if little_endian {
+        from_le_bytes(bytes).to_string()
+    } else {
+        from_be_bytes(bytes).to_string()
+    }
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+fn unix_time(seconds: i64) -> String {
+    DateTime::from_timestamp(seconds, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "out of range".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let values = inspect(&[0x01, 0x00, 0x00, 0x80]);
+        let value = |label| {
+            let value = values.iter().find(|value| value.label == label).unwrap();
+            (value.little_endian.as_str(), value.big_endian.as_str())
+        };
+        assert_eq!(value("u8"), ("1", "1"));
+        assert_eq!(value("u16"), ("1", "256"));
+        assert_eq!(value("i32"), ("-2147483647", "16777344"));
+        assert_eq!(
+            value("time32"),
+            ("2038-01-19 03:14:09 UTC", "1970-07-14 04:22:24 UTC")
+        );
+        // Values needing more bytes than there are left in the file are left out.
+        assert!(values.iter().all(|value| value.label != "u64"));
+
+        let values = inspect(&1.5f64.to_le_bytes());
+        let f64_value = values.iter().find(|value| value.label == "f64").unwrap();
+        assert_eq!(f64_value.little_endian, "1.5");
+    }
+}
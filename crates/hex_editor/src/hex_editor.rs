@@ -0,0 +1,941 @@
+mod data_inspector;
+mod hex_file;
+
+use anyhow::{Context as _, Result};
+use editor::{Editor, EditorEvent, EditorSettings};
+use file_icons::FileIcons;
+use gpui::{
+    Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent, MouseButton, MouseDownEvent,
+    ScrollWheelEvent, Subscription, Task, actions, canvas,
+};
+use project::Project;
+use settings::Settings as _;
+use theme_settings::ThemeSettings;
+use ui::prelude::*;
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation,
+    item::{HighlightedText, Item, ItemBufferKind, ItemEvent, ProjectItem, SaveOptions},
+};
+
+use crate::data_inspector::inspect;
+pub use crate::hex_file::HexFile;
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor up a row.
+        MoveUp,
+        /// Moves the cursor down a row.
+        MoveDown,
+        /// Moves the cursor up a page.
+        PageUp,
+        /// Moves the cursor down a page.
+        PageDown,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches typing between the hex and the ASCII columns.
+        ToggleColumn,
+        /// Prompts for an offset to move the cursor to.
+        GoToOffset,
+        /// Prompts for bytes or text to search for.
+        Search,
+        /// Moves the cursor to the next match of the search.
+        SelectNextMatch,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+/// How many bytes from the cursor are read for the data inspector.
+const INSPECTED_BYTES: u64 = 8;
+
+pub fn init(cx: &mut App) {
+    workspace::register_binary_project_item::<HexEditor>(cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Prompt {
+    GoToOffset,
+    Search,
+}
+
+pub enum HexEditorEvent {
+    FileChanged,
+}
+
+/// Shows a file's bytes as rows of offsets, hex digits and ASCII characters, reading only the
+/// rows in view, so that files too big or too binary for a text editor can be browsed and
+/// edited in place.
+pub struct HexEditor {
+    hex_file: Entity<HexFile>,
+    focus_handle: FocusHandle,
+    cursor: u64,
+    column: Column,
+    /// The high digit typed for the byte at the cursor, when its low digit is still to be typed.
+    pending_digit: Option<u8>,
+    scroll_row: u64,
+    /// Scrolled pixels that don't yet add up to a row.
+    scroll_remainder: Pixels,
+    rows_height: Pixels,
+    prompt: Option<Prompt>,
+    query_editor: Entity<Editor>,
+    last_search: Option<Vec<u8>>,
+    message: Option<SharedString>,
+    search_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexEditor {
+    pub fn new(hex_file: Entity<HexFile>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let query_editor = cx.new(|cx| Editor::single_line(window, cx));
+        let subscriptions = vec![
+            cx.observe(&hex_file, |_, _, cx| {
+                cx.emit(HexEditorEvent::FileChanged);
+                cx.notify();
+            }),
+            cx.subscribe_in(&query_editor, window, Self::on_query_editor_event),
+        ];
+        Self {
+            hex_file,
+            focus_handle: cx.focus_handle(),
+            cursor: 0,
+            column: Column::Hex,
+            pending_digit: None,
+            scroll_row: 0,
+            scroll_remainder: px(0.),
+            rows_height: px(0.),
+            prompt: None,
+            query_editor,
+            last_search: None,
+            message: None,
+            search_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    fn row_height(cx: &App) -> Pixels {
+        let settings = ThemeSettings::get_global(cx);
+        (settings.buffer_font_size(cx) * settings.line_height()).round()
+    }
+
+    fn row_count(&self, cx: &App) -> u64 {
+        self.hex_file.read(cx).len().div_ceil(BYTES_PER_ROW)
+    }
+
+    fn visible_row_count(&self, cx: &App) -> u64 {
+        ((self.rows_height / Self::row_height(cx)).floor() as u64).max(1)
+    }
+
+    fn set_rows_height(&mut self, rows_height: Pixels, cx: &mut Context<Self>) {
+        if self.rows_height != rows_height {
+            self.rows_height = rows_height;
+            self.autoscroll(false, cx);
+            cx.notify();
+        }
+    }
+
+    fn move_cursor_to(&mut self, offset: u64, center: bool, cx: &mut Context<Self>) {
+        let last_offset = self.hex_file.read(cx).len().saturating_sub(1);
+        self.cursor = offset.min(last_offset);
+        self.pending_digit = None;
+        self.autoscroll(center, cx);
+        cx.notify();
+    }
+
+    fn move_cursor_by(&mut self, delta: i64, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    /// Scrolls the cursor into view, to the middle of it when `center` is set.
+    fn autoscroll(&mut self, center: bool, cx: &App) {
+        let cursor_row = self.cursor / BYTES_PER_ROW;
+        let visible_rows = self.visible_row_count(cx);
+        if center {
+            self.scroll_row = cursor_row.saturating_sub(visible_rows / 2);
+        } else if cursor_row < self.scroll_row {
+            self.scroll_row = cursor_row;
+        } else if cursor_row >= self.scroll_row + visible_rows {
+            self.scroll_row = cursor_row + 1 - visible_rows;
+        }
+        self.clamp_scroll(cx);
+    }
+
+    fn clamp_scroll(&mut self, cx: &App) {
+        let max_scroll_row = self
+            .row_count(cx)
+            .saturating_sub(self.visible_row_count(cx));
+        self.scroll_row = self.scroll_row.min(max_scroll_row);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor >= BYTES_PER_ROW {
+            self.move_cursor_by(-(BYTES_PER_ROW as i64), cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.hex_file.read(cx).len() {
+            self.move_cursor_by(BYTES_PER_ROW as i64, cx);
+        }
+    }
+
+    fn page_up(&mut self, _: &PageUp, _: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_row_count(cx);
+        self.scroll_row = self.scroll_row.saturating_sub(rows);
+        let offset = self.cursor.saturating_sub(rows * BYTES_PER_ROW);
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, _: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_row_count(cx);
+        self.scroll_row += rows;
+        self.clamp_scroll(cx);
+        let offset = self.cursor.saturating_add(rows * BYTES_PER_ROW);
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(u64::MAX, false, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_digit = None;
+        cx.notify();
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(Prompt::GoToOffset, window, cx);
+    }
+
+    fn search(&mut self, _: &Search, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(Prompt::Search, window, cx);
+    }
+
+    fn select_next_match(&mut self, _: &SelectNextMatch, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(pattern) = self.last_search.clone() {
+            self.find(pattern, self.cursor + 1, cx);
+        }
+    }
+
+    fn show_prompt(&mut self, prompt: Prompt, window: &mut Window, cx: &mut Context<Self>) {
+        let placeholder = match prompt {
+            Prompt::GoToOffset => "Offset, in decimal or in hex after \"0x\"",
+            Prompt::Search => "Hex bytes, such as \"de ad be ef\", or text, quoted if it's all hex",
+        };
+        self.prompt = Some(prompt);
+        self.message = None;
+        self.query_editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(placeholder, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        window.focus(&self.query_editor.focus_handle(cx), cx);
+        cx.notify();
+    }
+
+    fn on_query_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let EditorEvent::BufferEdited = event {
+            self.message = None;
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(prompt) = self.prompt else {
+            return;
+        };
+        let query = self.query_editor.read(cx).text(cx);
+        match prompt {
+            Prompt::GoToOffset => match parse_offset(&query) {
+                Ok(offset) => {
+                    self.move_cursor_to(offset, true, cx);
+                    self.dismiss_prompt(window, cx);
+                }
+                Err(error) => self.message = Some(format!("{error:#}").into()),
+            },
+            // The search stays open, so that confirming it again moves to the next match.
+            Prompt::Search => match parse_search_query(&query) {
+                Ok(pattern) => {
+                    let start = match &self.last_search {
+                        Some(last_search) if *last_search == pattern => self.cursor + 1,
+                        _ => self.cursor,
+                    };
+                    self.last_search = Some(pattern.clone());
+                    self.find(pattern, start, cx);
+                }
+                Err(error) => self.message = Some(format!("{error:#}").into()),
+            },
+        }
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.dismiss_prompt(window, cx);
+    }
+
+    fn dismiss_prompt(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.prompt = None;
+        self.message = None;
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn find(&mut self, pattern: Vec<u8>, start: u64, cx: &mut Context<Self>) {
+        let find = self.hex_file.read(cx).find(pattern, start, cx);
+        self.message = Some("Searching…".into());
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let result = find.await;
+            this.update(cx, |this, cx| {
+                this.message = match result {
+                    Ok(Some(offset)) => {
+                        this.move_cursor_to(offset, true, cx);
+                        None
+                    }
+                    Ok(None) => Some("No matches".into()),
+                    Err(error) => Some(format!("{error:#}").into()),
+                };
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let modifiers = event.keystroke.modifiers;
+        if !self.focus_handle.is_focused(window)
+            || modifiers.control
+            || modifiers.alt
+            || modifiers.platform
+            || modifiers.function
+        {
+            return;
+        }
+        let Some(character) = event
+            .keystroke
+            .key_char
+            .as_deref()
+            .and_then(|key_char| key_char.chars().next())
+        else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = character.to_digit(16).map(|digit| digit as u8) else {
+                    return;
+                };
+                match self.pending_digit.take() {
+                    Some(high_digit) => {
+                        self.edit_byte(high_digit << 4 | digit, cx);
+                        self.move_cursor_by(1, cx);
+                    }
+                    None => {
+                        // The byte shows the typed digit right away, keeping its low digit.
+                        let low_digit = self.hex_file.read(cx).byte(self.cursor).unwrap_or(0) & 0xf;
+                        self.edit_byte(digit << 4 | low_digit, cx);
+                        self.pending_digit = Some(digit);
+                    }
+                }
+            }
+            Column::Ascii => {
+                if !character.is_ascii() || character.is_ascii_control() {
+                    return;
+                }
+                self.edit_byte(character as u8, cx);
+                self.move_cursor_by(1, cx);
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn edit_byte(&mut self, value: u8, cx: &mut Context<Self>) {
+        let offset = self.cursor;
+        self.hex_file
+            .update(cx, |hex_file, cx| hex_file.edit(offset, value, cx));
+    }
+
+    fn handle_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_height = Self::row_height(cx);
+        self.scroll_remainder -= event.delta.pixel_delta(row_height).y;
+        let rows = (self.scroll_remainder / row_height).trunc();
+        self.scroll_remainder -= row_height * rows;
+        self.scroll_row = self.scroll_row.saturating_add_signed(rows as i64);
+        self.clamp_scroll(cx);
+        cx.notify();
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: u64,
+        column: Column,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        window.focus(&self.focus_handle, cx);
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    fn offset_digits(&self, cx: &App) -> usize {
+        let len = self.hex_file.read(cx).len();
+        let digits = (u64::BITS - len.leading_zeros()).div_ceil(4) as usize;
+        digits.max(8)
+    }
+
+    fn render_row(&self, row: u64, offset_digits: usize, cx: &Context<Self>) -> AnyElement {
+        let hex_file = self.hex_file.read(cx);
+        let colors = cx.theme().colors();
+        let start = row * BYTES_PER_ROW;
+        let offsets = start..(start + BYTES_PER_ROW).min(hex_file.len());
+
+        let byte_cell = |offset: u64, column: Column, text: String| {
+            let is_cursor = offset == self.cursor;
+            let color = if hex_file.is_edited(offset) {
+                colors.text_accent
+            } else if hex_file.byte(offset).is_none_or(|byte| byte == 0) {
+                colors.text_muted
+            } else {
+                colors.text
+            };
+            div()
+                .child(text)
+                .text_color(color)
+                .when(is_cursor, |cell| {
+                    cell.bg(if column == self.column {
+                        colors.element_selected
+                    } else {
+                        colors.element_hover
+                    })
+                })
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _: &MouseDownEvent, window, cx| {
+                        this.click_byte(offset, column, window, cx)
+                    }),
+                )
+        };
+
+        h_flex()
+            .h(Self::row_height(cx))
+            .gap_4()
+            .child(
+                div()
+                    .text_color(colors.text_muted)
+                    .child(format!("{start:0offset_digits$x}")),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(offsets.clone().map(|offset| {
+                        let text = hex_file
+                            .byte(offset)
+                            .map_or_else(|| "··".to_string(), |byte| format!("{byte:02x}"));
+                        byte_cell(offset, Column::Hex, text)
+                    }))
+                    .children((offsets.end..start + BYTES_PER_ROW).map(|_| div().child("  "))),
+            )
+            .child(h_flex().children(offsets.map(|offset| {
+                let character = match hex_file.byte(offset) {
+                    Some(byte) if byte.is_ascii_graphic() || byte == b' ' => byte as char,
+                    _ => '.',
+                };
+                byte_cell(offset, Column::Ascii, character.to_string())
+            })))
+            .into_any_element()
+    }
+
+    fn render_inspector(&self, cx: &App) -> impl IntoElement {
+        let bytes = self
+            .hex_file
+            .read(cx)
+            .bytes(self.cursor..self.cursor + INSPECTED_BYTES);
+        let colors = cx.theme().colors();
+        v_flex()
+            .w(rems(22.))
+            .h_full()
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(colors.border_variant)
+            .child(Label::new(format!("Offset {0} (0x{0:x})", self.cursor)).color(Color::Muted))
+            .child(
+                h_flex()
+                    .child(div().w(rems(4.)))
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Label::new("Little endian").color(Color::Muted)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Label::new("Big endian").color(Color::Muted)),
+                    ),
+            )
+            .children(inspect(&bytes).into_iter().map(|value| {
+                h_flex()
+                    .child(
+                        div()
+                            .w(rems(4.))
+                            .child(Label::new(value.label).color(Color::Muted)),
+                    )
+                    .child(div().flex_1().overflow_hidden().child(value.little_endian))
+                    .child(div().flex_1().overflow_hidden().child(value.big_endian))
+            }))
+    }
+
+    fn render_prompt(&self, prompt: Prompt, cx: &App) -> impl IntoElement {
+        let label = match prompt {
+            Prompt::GoToOffset => "Go to offset",
+            Prompt::Search => "Search",
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(label))
+            .child(div().flex_1().child(self.query_editor.clone()))
+            .when_some(self.message.clone(), |bar, message| {
+                bar.child(Label::new(message).color(Color::Muted))
+            })
+    }
+}
+
+/// Parses an offset written in decimal, or in hexadecimal after `0x`.
+fn parse_offset(text: &str) -> Result<u64> {
+    let text = text.trim().replace('_', "");
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    }
+    .with_context(|| format!("invalid offset {text:?}"))
+}
+
+/// Parses what to search for: bytes written as pairs of hex digits, which can be separated by
+/// spaces, or otherwise text, which can be quoted to search for text made of hex digits.
+fn parse_search_query(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    anyhow::ensure!(!text.is_empty(), "enter bytes or text to search for");
+    if let Some(quoted) = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .filter(|quoted| !quoted.is_empty())
+    {
+        return Ok(quoted.as_bytes().to_vec());
+    }
+
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.len() % 2 == 0 && digits.iter().all(char::is_ascii_hexdigit) {
+        return Ok(digits
+            .chunks(2)
+            .map(|pair| (pair[0].to_digit(16).unwrap() << 4 | pair[1].to_digit(16).unwrap()) as u8)
+            .collect());
+    }
+    Ok(text.as_bytes().to_vec())
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::FileChanged => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.hex_file.entity_id(), self.hex_file.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.hex_file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        let hex_file = self.hex_file.read(cx);
+        hex_file
+            .project_path()
+            .path
+            .file_name()
+            .map(ToString::to_string)
+            .or_else(|| {
+                let file_name = hex_file.abs_path().file_name()?;
+                Some(file_name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.hex_file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        if EditorSettings::get_global(cx).toolbar.breadcrumbs {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, cx: &App) -> Option<(Vec<HighlightedText>, Option<gpui::Font>)> {
+        let hex_file = self.hex_file.read(cx);
+        let text = format!(
+            "{} ({} bytes)",
+            hex_file.abs_path().compact().display(),
+            hex_file.len()
+        );
+        Some((
+            vec![HighlightedText {
+                text: text.into(),
+                highlights: vec![],
+            }],
+            Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        ))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.hex_file.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.pending_digit = None;
+        self.hex_file.update(cx, |hex_file, cx| hex_file.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.pending_digit = None;
+        self.hex_file.update(cx, |hex_file, cx| hex_file.reload(cx))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexFile;
+
+    fn for_project_item(
+        _: Entity<Project>,
+        _: Option<&Pane>,
+        hex_file: Entity<HexFile>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new(hex_file, window, cx)
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let first_row = self.scroll_row;
+        let rows = first_row..(first_row + self.visible_row_count(cx)).min(self.row_count(cx));
+        let visible_bytes = rows.start * BYTES_PER_ROW..rows.end * BYTES_PER_ROW;
+        let inspected_bytes = self.cursor..self.cursor + INSPECTED_BYTES;
+        self.hex_file.update(cx, |hex_file, cx| {
+            hex_file.load(visible_bytes, cx);
+            hex_file.load(inspected_bytes, cx);
+        });
+
+        let settings = ThemeSettings::get_global(cx);
+        let buffer_font = settings.buffer_font.clone();
+        let font_size = settings.buffer_font_size(cx);
+        let offset_digits = self.offset_digits(cx);
+        let this = cx.entity();
+        let rows = rows
+            .map(|row| self.render_row(row, offset_digits, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .key_context("HexEditor")
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_action(cx.listener(Self::search))
+            .on_action(cx.listener(Self::select_next_match))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .bg(cx.theme().colors().editor_background)
+            .font(buffer_font)
+            .text_size(font_size)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .items_start()
+                    .child(
+                        v_flex()
+                            .relative()
+                            .flex_1()
+                            .h_full()
+                            .px_2()
+                            .overflow_hidden()
+                            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+                            .child(
+                                canvas(
+                                    move |bounds, _, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.set_rows_height(bounds.size.height, cx)
+                                        })
+                                    },
+                                    |_, _, _, _| {},
+                                )
+                                .absolute()
+                                .size_full(),
+                            )
+                            .children(rows),
+                    )
+                    .child(self.render_inspector(cx)),
+            )
+            .when_some(self.prompt, |this, prompt| {
+                this.child(self.render_prompt(prompt, cx))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::Fs as _;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::Path;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace, Workspace};
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024").unwrap(), 1024);
+        assert_eq!(parse_offset(" 0x1_0f ").unwrap(), 0x10f);
+        assert!(parse_offset("0xg").is_err());
+        assert!(parse_offset("").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_query() {
+        assert_eq!(
+            parse_search_query("de AD be ef").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(parse_search_query("cafe").unwrap(), vec![0xca, 0xfe]);
+        assert_eq!(parse_search_query("\"cafe\"").unwrap(), b"cafe");
+        assert_eq!(parse_search_query("ELF").unwrap(), b"ELF");
+        assert_eq!(parse_search_query("abc").unwrap(), b"abc");
+        assert!(parse_search_query("  ").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_hex_editor_opens_binary_files(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut content = vec![0u8; 3 * hex_file::PAGE_SIZE as usize];
+        content[..4].copy_from_slice(b"\x7fELF");
+        let needle_offset = 2 * hex_file::PAGE_SIZE as usize - 2;
+        content[needle_offset..needle_offset + 4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"a.txt": "text"}))
+            .await;
+        fs.insert_file(path!("/dir/data.bin"), content.clone())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+
+        // Text files still open in a text editor.
+        let item = open_path(&workspace, "a.txt", cx).await;
+        assert!(item.downcast::<Editor>().is_some());
+
+        let hex_editor = open_path(&workspace, "data.bin", cx)
+            .await
+            .downcast::<HexEditor>()
+            .unwrap();
+        draw_window(cx);
+        cx.run_until_parked();
+        hex_editor.read_with(cx, |hex_editor, cx| {
+            let hex_file = hex_editor.hex_file.read(cx);
+            assert_eq!(hex_file.len(), content.len() as u64);
+            assert_eq!(hex_file.bytes(0..4), b"\x7fELF");
+        });
+
+        // Searching reads the file in chunks, and finds matches across its pages.
+        cx.dispatch_action(Search);
+        cx.simulate_input("de ad be ef");
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        hex_editor.read_with(cx, |hex_editor, _| {
+            assert_eq!(hex_editor.cursor(), needle_offset as u64)
+        });
+
+        // Going to an offset reads the bytes around it.
+        hex_editor.update_in(cx, |hex_editor, window, cx| {
+            hex_editor.dismiss_prompt(window, cx)
+        });
+        cx.dispatch_action(GoToOffset);
+        cx.simulate_input("0x1");
+        cx.dispatch_action(menu::Confirm);
+        draw_window(cx);
+        cx.run_until_parked();
+        hex_editor.read_with(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.cursor(), 1);
+            assert_eq!(hex_editor.hex_file.read(cx).bytes(0..4), b"\x7fELF");
+        });
+
+        // Typing hex digits replaces the bytes at the cursor, and saving writes them in place.
+        cx.simulate_keystrokes("7 8");
+        cx.dispatch_action(ToggleColumn);
+        cx.simulate_keystrokes("y");
+        hex_editor.read_with(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.cursor(), 3);
+            assert_eq!(hex_editor.hex_file.read(cx).bytes(0..4), b"\x7fxyF");
+            assert!(hex_editor.is_dirty(cx));
+        });
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.save_active_item(workspace::SaveIntent::Save, window, cx)
+            })
+            .await
+            .unwrap();
+        let saved = fs
+            .load_bytes(Path::new(path!("/dir/data.bin")))
+            .await
+            .unwrap();
+        assert_eq!(&saved[..4], b"\x7fxyF");
+        assert_eq!(saved.len(), content.len());
+        hex_editor.read_with(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+    }
+
+    fn draw_window(cx: &mut VisualTestContext) {
+        cx.update(|window, cx| {
+            window.refresh();
+            window.draw(cx).clear(cx);
+        });
+    }
+
+    async fn open_path(
+        workspace: &Entity<Workspace>,
+        path: &str,
+        cx: &mut VisualTestContext,
+    ) -> Box<dyn workspace::ItemHandle> {
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path(path)), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> std::sync::Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+            state
+        })
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use util::ResultExt as _;
+
+/// The size of the pages that a file is read from disk in.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+/// How many pages are kept in memory, past which the pages away from the latest read are dropped.
+const MAX_CACHED_PAGES: usize = 256;
+/// The size of the chunks that a file is read in while searching it.
+const SEARCH_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// A file opened as bytes. It's read a page at a time as its bytes are needed, so that files of
+/// any size open at once, and keeps its edits apart from the pages until they're saved in place.
+pub struct HexFile {
+    fs: Arc<dyn Fs>,
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    len: u64,
+    pages: HashMap<u64, Vec<u8>>,
+    loading_pages: HashMap<u64, Task<()>>,
+    edits: BTreeMap<u64, u8>,
+}
+
+impl project::ProjectItem for HexFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let abs_path = project.absolute_path(path, cx)?;
+        let entry_id = project.entry_for_path(path, cx).map(|entry| entry.id);
+        let fs = project.fs().clone();
+        let project_path = path.clone();
+
+        Some(cx.spawn(async move |cx| {
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} does not exist"))?;
+            anyhow::ensure!(!metadata.is_dir, "{abs_path:?} is a directory");
+            Ok(cx.new(|_| Self {
+                fs,
+                abs_path,
+                project_path,
+                entry_id,
+                len: metadata.len,
+                pages: HashMap::default(),
+                loading_pages: HashMap::default(),
+                edits: BTreeMap::default(),
+            }))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.is_dirty()
+    }
+}
+
+impl HexFile {
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn project_path(&self) -> &ProjectPath {
+        &self.project_path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    /// The byte at the offset, with edits applied, or `None` when its page hasn't been read yet
+    /// or the offset is past the end of the file.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if offset >= self.len {
+            return None;
+        }
+        if let Some(value) = self.edits.get(&offset) {
+            return Some(*value);
+        }
+        let page = self.pages.get(&(offset / PAGE_SIZE))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    /// The bytes in the range that have been read, stopping at the first one that hasn't.
+    pub fn bytes(&self, range: Range<u64>) -> Vec<u8> {
+        range.map_while(|offset| self.byte(offset)).collect()
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    /// Starts reading the pages covering the range that aren't in memory yet, dropping the pages
+    /// away from it when too many are kept.
+    pub fn load(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        if range.is_empty() {
+            return;
+        }
+        let pages = range.start / PAGE_SIZE..=(range.end - 1) / PAGE_SIZE;
+        if self.pages.len() > MAX_CACHED_PAGES {
+            self.pages.retain(|page, _| pages.contains(page));
+        }
+
+        for page in pages {
+            if self.pages.contains_key(&page) || self.loading_pages.contains_key(&page) {
+                continue;
+            }
+            let fs = self.fs.clone();
+            let abs_path = self.abs_path.clone();
+            let task = cx.spawn(async move |this, cx| {
+                let bytes = fs
+                    .load_bytes_range(&abs_path, page * PAGE_SIZE..(page + 1) * PAGE_SIZE)
+                    .await
+                    .with_context(|| format!("reading {abs_path:?}"));
+                this.update(cx, |this, cx| {
+                    this.loading_pages.remove(&page);
+                    if let Some(bytes) = bytes.log_err() {
+                        this.pages.insert(page, bytes);
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+            self.loading_pages.insert(page, task);
+        }
+    }
+
+    /// Replaces the byte at the offset, until the file is saved or reloaded.
+    pub fn edit(&mut self, offset: u64, value: u8, cx: &mut Context<Self>) {
+        if offset >= self.len {
+            return;
+        }
+        let saved_value = self
+            .pages
+            .get(&(offset / PAGE_SIZE))
+            .and_then(|page| page.get((offset % PAGE_SIZE) as usize));
+        if saved_value == Some(&value) {
+            self.edits.remove(&offset);
+        } else {
+            self.edits.insert(offset, value);
+        }
+        cx.notify();
+    }
+
+    /// Writes the edited bytes into the file in place, a run of adjacent bytes at a time.
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let edits = self.edits.clone();
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+            for (&offset, &value) in &edits {
+                match runs.last_mut() {
+                    Some((start, bytes)) if *start + bytes.len() as u64 == offset => {
+                        bytes.push(value)
+                    }
+                    _ => runs.push((offset, vec![value])),
+                }
+            }
+            for (offset, bytes) in runs {
+                fs.write_bytes_at(&abs_path, offset, &bytes)
+                    .await
+                    .with_context(|| format!("writing {abs_path:?}"))?;
+            }
+
+            this.update(cx, |this, cx| {
+                for (offset, value) in edits {
+                    // Bytes edited again while saving stay edited.
+                    if this.edits.get(&offset) == Some(&value) {
+                        this.edits.remove(&offset);
+                    }
+                    if let Some(byte) = this
+                        .pages
+                        .get_mut(&(offset / PAGE_SIZE))
+                        .and_then(|page| page.get_mut((offset % PAGE_SIZE) as usize))
+                    {
+                        *byte = value;
+                    }
+                }
+                cx.notify();
+            })
+        })
+    }
+
+    /// Discards the edits and rereads the file from disk.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} does not exist"))?;
+            this.update(cx, |this, cx| {
+                this.len = metadata.len;
+                this.edits.clear();
+                this.pages.clear();
+                this.loading_pages.clear();
+                cx.notify();
+            })
+        })
+    }
+
+    /// Searches for the bytes, with edits applied, starting at the offset and wrapping around
+    /// the end of the file. The file is read a chunk at a time, so that searching it doesn't
+    /// hold it in memory.
+    pub fn find(&self, pattern: Vec<u8>, start: u64, cx: &App) -> Task<Result<Option<u64>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let edits = self.edits.clone();
+        let len = self.len;
+        let start = start.min(len);
+        cx.background_spawn(async move {
+            if pattern.is_empty() {
+                return Ok(None);
+            }
+            for range in [start..len, 0..start] {
+                if let Some(offset) =
+                    find_in_range(fs.as_ref(), &abs_path, &edits, &pattern, range, len).await?
+                {
+                    return Ok(Some(offset));
+                }
+            }
+            Ok(None)
+        })
+    }
+}
+
+/// Finds the first match of the pattern starting in the range, reading overlapping chunks so that
+/// matches across chunk boundaries are found.
+async fn find_in_range(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &[u8],
+    range: Range<u64>,
+    len: u64,
+) -> Result<Option<u64>> {
+    let overlap = pattern.len() as u64 - 1;
+    let mut chunk_start = range.start;
+    while chunk_start < range.end {
+        let chunk_end = (chunk_start + SEARCH_CHUNK_SIZE + overlap).min(len);
+        let mut chunk = fs
+            .load_bytes_range(abs_path, chunk_start..chunk_end)
+            .await
+            .with_context(|| format!("reading {abs_path:?}"))?;
+        for (offset, value) in edits.range(chunk_start..chunk_start + chunk.len() as u64) {
+            chunk[(offset - chunk_start) as usize] = *value;
+        }
+        if let Some(index) = chunk
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+        {
+            let offset = chunk_start + index as u64;
+            return Ok((offset < range.end).then_some(offset));
+        }
+        chunk_start += SEARCH_CHUNK_SIZE;
+    }
+    Ok(None)
+}
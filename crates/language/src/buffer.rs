@@ -4,8 +4,8 @@ pub mod row_chunk;
 pub use bracket_ranges::BracketMatch;
 
 use crate::{
-    ByteContent, DebuggerTextObject, LanguageScope, ModelineSettings, Outline, OutlineConfig,
-    PLAIN_TEXT, RunnableTag, TextObject, TreeSitterOptions, analyze_byte_content,
+    BinaryFileError, ByteContent, DebuggerTextObject, LanguageScope, ModelineSettings, Outline,
+    OutlineConfig, PLAIN_TEXT, RunnableTag, TextObject, TreeSitterOptions, analyze_byte_content,
     diagnostic_set::{DiagnosticEntry, DiagnosticEntryRef, DiagnosticGroup},
     language_settings::{AutoIndentMode, LanguageSettings},
     outline::OutlineItem,
@@ -141,6 +141,7 @@ pub struct Buffer {
     tree_sitter_data: Arc<TreeSitterData>,
     encoding: &'static Encoding,
     has_bom: bool,
+    large_file: bool,
     reload_with_encoding_txns: HashMap<TransactionId, (&'static Encoding, bool)>,
 }
 
@@ -1012,6 +1013,7 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        this.large_file = message.large_file;
         Ok(this)
     }
 
@@ -1024,6 +1026,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            large_file: self.large_file,
         }
     }
 
@@ -1145,6 +1148,7 @@ impl Buffer {
             _subscriptions: Vec::new(),
             encoding: encoding_rs::UTF_8,
             has_bom: false,
+            large_file: false,
             reload_with_encoding_txns: HashMap::default(),
         }
     }
@@ -1464,6 +1468,16 @@ impl Buffer {
         self.has_bom = has_bom;
     }
 
+    /// Returns whether the buffer was opened in large file mode, in which it is never parsed.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Sets whether the buffer is in large file mode.
+    pub fn set_large_file(&mut self, large_file: bool) {
+        self.large_file = large_file;
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language_async(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.set_language_(language, cfg!(any(test, feature = "test-support")), cx);
@@ -1605,7 +1619,7 @@ impl Buffer {
 
             anyhow::ensure!(
                 analyze_byte_content(&bytes) != ByteContent::Binary,
-                "Binary files are not supported"
+                BinaryFileError
             );
 
             let is_unicode = target_encoding == encoding_rs::UTF_8
@@ -1838,7 +1852,7 @@ impl Buffer {
         if self.text.version() != *self.tree_sitter_data.version() {
             Self::invalidate_tree_sitter_data(&mut self.tree_sitter_data, self.text.snapshot());
         }
-        if self.reparse.is_some() {
+        if self.reparse.is_some() || self.large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
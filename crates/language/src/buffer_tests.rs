@@ -67,6 +67,25 @@ fn test_line_endings(cx: &mut gpui::App) {
     });
 }
 
+#[gpui::test]
+fn test_large_file_replica(cx: &mut TestAppContext) {
+    let base = cx.new(|cx| {
+        let mut buffer = Buffer::local("one\ntwo\nthree\n", cx);
+        buffer.set_large_file(true);
+        buffer
+    });
+    let replica = cx.new(|cx| {
+        Buffer::from_proto(
+            ReplicaId::new(1),
+            Capability::ReadWrite,
+            base.read(cx).to_proto(cx),
+            None,
+        )
+        .unwrap()
+    });
+    replica.read_with(cx, |replica, _| assert!(replica.is_large_file()));
+}
+
 #[gpui::test]
 fn test_set_line_ending(cx: &mut TestAppContext) {
     let base = cx.new(|cx| Buffer::local("one\ntwo\nthree\n", cx));
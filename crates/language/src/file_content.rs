@@ -1,5 +1,15 @@
 pub const FILE_ANALYSIS_BYTES: usize = 1024;
 
+/// The error for a file that can't be opened as text, because its content is binary.
+#[derive(Debug)]
+pub struct BinaryFileError;
+
+impl std::fmt::Display for BinaryFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Binary files are not supported")
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ByteContent {
     Utf16Le,
@@ -298,6 +298,19 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the `vimrc` file.
+pub fn vimrc_file() -> &'static PathBuf {
+    static VIMRC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VIMRC_FILE.get_or_init(|| config_dir().join("vimrc"))
+}
+
+/// Returns the path to the `dictionary.txt` file, the user's list of words
+/// that the spell checker accepts.
+pub fn user_dictionary_file() -> &'static PathBuf {
+    static USER_DICTIONARY_FILE: OnceLock<PathBuf> = OnceLock::new();
+    USER_DICTIONARY_FILE.get_or_init(|| config_dir().join("dictionary.txt"))
+}
+
 /// Returns the path to the `keymap_backup.json` file.
 pub fn keymap_backup_file() -> &'static PathBuf {
     static KEYMAP_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -374,6 +387,14 @@ pub fn themes_dir() -> &'static PathBuf {
     THEMES_DIR.get_or_init(|| config_dir().join("themes"))
 }
 
+/// Returns the path to the dictionaries directory.
+///
+/// This is where Hunspell dictionaries that are not bundled with Zed are stored.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
 /// Returns the path to the snippets directory.
 pub fn snippets_dir() -> &'static PathBuf {
     static SNIPPETS_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -500,6 +521,13 @@ pub fn local_settings_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `dictionary.txt` file within a project.
+pub fn local_dictionary_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::from_unix_str(".zed/dictionary.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `tasks.json` file within a project.
 pub fn local_tasks_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
@@ -12,7 +12,8 @@ use gpui::{
     WeakEntity,
 };
 use language::{
-    Buffer, BufferEvent, Capability, DiskState, File as _, Language, LineEnding, Operation,
+    BinaryFileError, Buffer, BufferEvent, Capability, DiskState, File as _, Language, LineEnding,
+    Operation,
     language_settings::{AllLanguageSettings, LineEndingSetting},
     proto::{
         deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
@@ -28,7 +29,10 @@ use settings::Settings;
 use std::{io, sync::Arc, time::Instant};
 use text::{BufferId, ReplicaId};
 use util::{ResultExt as _, TryFutureExt, debug_panic, maybe, rel_path::RelPath};
-use worktree::{File, PathChange, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings};
+use worktree::{
+    File, LoadedFile, LoadedLargeFile, PathChange, ProjectEntryId, Worktree, WorktreeId,
+    WorktreeSettings,
+};
 
 /// A set of open buffers.
 pub struct BufferStore {
@@ -86,6 +90,13 @@ enum OpenBuffer {
     Operations(Vec<Operation>),
 }
 
+enum LoadedBufferFile {
+    Text(LoadedFile),
+    /// A file past the large file threshold, opened without syntax highlighting, language
+    /// servers or git diffs.
+    Large(LoadedLargeFile),
+}
+
 pub enum BufferStoreEvent {
     BufferAdded(Entity<Buffer>),
     SharedBufferClosed(proto::PeerId, BufferId),
@@ -642,11 +653,21 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
-        let load_file = worktree.update(cx, |worktree, cx| worktree.load_file(path.as_ref(), cx));
+        let load_file = worktree.update(cx, |worktree, cx| {
+            if worktree.is_large_file(&path) {
+                let load_large_file = worktree.load_large_file(path.as_ref(), cx);
+                cx.background_spawn(
+                    async move { load_large_file.await.map(LoadedBufferFile::Large) },
+                )
+            } else {
+                let load_file = worktree.load_file(path.as_ref(), cx);
+                cx.background_spawn(async move { load_file.await.map(LoadedBufferFile::Text) })
+            }
+        });
         cx.spawn(async move |this, cx| {
             let path = path.clone();
             let buffer = match load_file.await {
-                Ok(loaded) => {
+                Ok(LoadedBufferFile::Text(loaded)) => {
                     let is_writable = loaded.is_writable;
                     let capability = if is_writable {
                         Capability::ReadWrite
@@ -667,6 +688,32 @@ impl LocalBufferStore {
                         buffer
                     })
                 }
+                Ok(LoadedBufferFile::Large(loaded)) => {
+                    let capability = if loaded.is_writable {
+                        Capability::ReadWrite
+                    } else {
+                        Capability::Read
+                    };
+                    let reservation = cx.reserve_entity::<Buffer>();
+                    let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
+                    let text_buffer = cx
+                        .background_spawn(async move {
+                            text::Buffer::new_normalized(
+                                ReplicaId::LOCAL,
+                                buffer_id,
+                                loaded.line_ending,
+                                loaded.text,
+                            )
+                        })
+                        .await;
+                    cx.insert_entity(reservation, |_| {
+                        let mut buffer = Buffer::build(text_buffer, Some(loaded.file), capability);
+                        buffer.set_encoding(loaded.encoding);
+                        buffer.set_has_bom(loaded.has_bom);
+                        buffer.set_large_file(true);
+                        buffer
+                    })
+                }
                 Err(error) if is_not_found_error(&error) => cx.new(|cx| {
                     let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
                     let text_buffer = text::Buffer::new(ReplicaId::LOCAL, buffer_id, "");
@@ -899,15 +946,7 @@ impl BufferStore {
             }
         };
 
-        cx.background_spawn(async move {
-            task.await.map_err(|e| {
-                if e.error_code() != ErrorCode::Internal {
-                    anyhow!(e.error_code())
-                } else {
-                    anyhow!("{e}")
-                }
-            })
-        })
+        cx.background_spawn(async move { task.await.map_err(|e| unshare_load_error(&e)) })
     }
 
     pub fn create_buffer(
@@ -1031,13 +1070,7 @@ impl BufferStore {
         self.loading_buffers.iter().map(|(path, task)| {
             let task = task.clone();
             (path, async move {
-                task.await.map_err(|e| {
-                    if e.error_code() != ErrorCode::Internal {
-                        anyhow!(e.error_code())
-                    } else {
-                        anyhow!("{e}")
-                    }
-                })
+                task.await.map_err(|e| unshare_load_error(&e))
             })
         })
     }
@@ -1806,6 +1839,18 @@ impl OpenBuffer {
     }
 }
 
+/// Rebuilds an error shared by everyone waiting for a buffer to load, keeping its error code and
+/// whether the file is binary, so that callers can still match on them.
+fn unshare_load_error(error: &anyhow::Error) -> anyhow::Error {
+    if error.error_code() != ErrorCode::Internal {
+        anyhow!(error.error_code())
+    } else if error.downcast_ref::<BinaryFileError>().is_some() {
+        anyhow!(BinaryFileError)
+    } else {
+        anyhow!("{error}")
+    }
+}
+
 fn is_not_found_error(error: &anyhow::Error) -> bool {
     error
         .root_cause()
@@ -2812,6 +2812,33 @@ impl Session {
         })
     }
 
+    /// Like [`Self::evaluate`], but hands the adapter's response back to the caller
+    /// instead of echoing the expression and its result to the debug console.
+    pub fn evaluate_with_response(
+        &mut self,
+        expression: String,
+        frame_id: Option<u64>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<dap::EvaluateResponse>> {
+        let request = self.state.request_dap(EvaluateCommand {
+            expression,
+            context: Some(EvaluateArgumentsContext::Repl),
+            frame_id,
+            source: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let response = request.await?;
+            this.update(cx, |this, cx| {
+                this.memory.clear(cx.background_executor());
+                this.invalidate_command_type::<ReadMemory>();
+                this.invalidate_command_type::<VariablesCommand>();
+                cx.emit(SessionEvent::Variables);
+                cx.notify();
+            })?;
+            Ok(response)
+        })
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
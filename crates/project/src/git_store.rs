@@ -7,7 +7,7 @@ pub mod pending_op;
 use crate::{
     ProjectEnvironment, ProjectItem, ProjectPath,
     buffer_store::{BufferStore, BufferStoreEvent},
-    project_settings::ProjectSettings,
+    project_settings::{GitSettings, ProjectSettings},
     trusted_worktrees::{
         PathTrust, TrustedWorktrees, TrustedWorktreesEvent, TrustedWorktreesStore,
     },
@@ -15,7 +15,9 @@ use crate::{
 };
 use anyhow::{Context as _, Result, anyhow, bail};
 use askpass::{AskPassDelegate, EncryptedPassword, IKnowWhatIAmDoingAndIHaveReadTheDocs};
-use buffer_diff::{BufferDiff, DiffHunk, DiffHunkSecondaryStatus, PendingHunk, PendingSense};
+use buffer_diff::{
+    BufferDiff, DiffHunk, DiffHunkSecondaryStatus, DiffLineSelection, PendingHunk, PendingSense,
+};
 use client::ProjectId;
 use collections::HashMap;
 pub use conflict_set::{ConflictRegion, ConflictSet, ConflictSetSnapshot, ConflictSetUpdate};
@@ -31,21 +33,28 @@ use futures::{
 };
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
-    blame::Blame,
+    bisect::{BisectMark, BisectState},
+    blame::{Blame, BlameOptions},
     parse_git_remote_url,
+    patch::{ApplyPatchMode, PatchHunkCheck},
+    rebase::{
+        RebaseOutcome, RebaseState, RebaseStep, RebaseTodoAction, RebaseTodoEntry, validate_todo,
+    },
     repository::{
-        Branch, BranchesScanResult, CommitData, CommitDetails, CommitDiff, CommitFile,
-        CommitOptions, CreateWorktreeTarget, DiffStatType, DiffType, FetchOptions,
-        FileHistoryChangedFileSets, GitCommitTemplate, GitRepository, GitRepositoryCheckpoint,
-        InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote, RemoteCommandOutput,
-        RepoPath, ResetMode, SearchCommitArgs, UpstreamTrackingStatus, Worktree as GitWorktree,
-        delete_branch_flag,
+        ApplyCommitsOutcome, Branch, BranchesScanResult, CommitData, CommitDetails, CommitDiff,
+        CommitFile, CommitOptions, ConflictStages, CreateWorktreeTarget, DiffStatType, DiffType,
+        FetchOptions, FileHistoryChangedFileSets, GitCommitTemplate, GitRepository,
+        GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, SearchCommitArgs, UpstreamTrackingStatus,
+        Worktree as GitWorktree, WorktreeStatus, delete_branch_flag,
     },
+    signature::{CommitSignature, SignatureStatus},
     stash::{GitStash, StashEntry},
     status::{
         self, DiffStat, DiffTreeType, FileStatus, GitSummary, StatusCode, TrackedStatus, TreeDiff,
         TreeDiffStatus, UnmergedStatus, UnmergedStatusCode,
     },
+    submodule::{Submodule, SubmoduleOperation},
 };
 use gpui::{
     App, AppContext, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, SharedString,
@@ -64,7 +73,7 @@ use rpc::{
     proto::{self, git_reset, split_repository_update},
 };
 use serde::Deserialize;
-use settings::{GitDiffBaseSetting, Settings, SettingsStore, WorktreeId};
+use settings::{GitDiffBaseSetting, Settings, SettingsLocation, SettingsStore, WorktreeId};
 use smallvec::SmallVec;
 use smol::future::yield_now;
 use std::{
@@ -418,6 +427,8 @@ pub struct RepositoryId(pub u64);
 pub struct MergeDetails {
     pub merge_heads_by_conflicted_path: TreeMap<RepoPath, Vec<Option<SharedString>>>,
     pub message: Option<SharedString>,
+    pub rebase: Option<RebaseState>,
+    pub bisect: Option<BisectState>,
 }
 
 #[derive(Clone)]
@@ -805,6 +816,18 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
+        client.add_entity_request_handler(Self::handle_git_rebase);
+        client.add_entity_request_handler(Self::handle_git_rebase_step);
+        client.add_entity_request_handler(Self::handle_git_cherry_pick);
+        client.add_entity_request_handler(Self::handle_git_revert);
+        client.add_entity_request_handler(Self::handle_git_create_tag);
+        client.add_entity_request_handler(Self::handle_git_delete_tag);
+        client.add_entity_request_handler(Self::handle_git_push_tags);
+        client.add_entity_request_handler(Self::handle_git_bisect_mark);
+        client.add_entity_request_handler(Self::handle_git_bisect_reset);
+        client.add_entity_request_handler(Self::handle_git_load_conflict_stages);
+        client.add_entity_request_handler(Self::handle_git_get_submodules);
+        client.add_entity_request_handler(Self::handle_git_submodule_command);
         client.add_entity_request_handler(Self::handle_fetch);
         client.add_entity_request_handler(Self::handle_stage);
         client.add_entity_request_handler(Self::handle_unstage);
@@ -816,6 +839,14 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_get_commit_signature);
+        client.add_entity_request_handler(Self::handle_format_patch);
+        client.add_entity_request_handler(Self::handle_check_patch);
+        client.add_entity_request_handler(Self::handle_apply_patch);
+        client.add_entity_request_handler(Self::handle_get_worktree_statuses);
+        client.add_entity_request_handler(Self::handle_prune_worktrees);
+        client.add_entity_request_handler(Self::handle_move_changes_to_worktree);
+        client.add_entity_request_handler(Self::handle_blame_revision);
         client.add_entity_request_handler(Self::handle_create_checkpoint);
         client.add_entity_request_handler(Self::handle_create_archive_checkpoint);
         client.add_entity_request_handler(Self::handle_restore_checkpoint);
@@ -1397,6 +1428,89 @@ impl GitStore {
         Ok(())
     }
 
+    /// Stages the lines `selection` picks out of the hunks of `diff`, which is
+    /// either the buffer's unstaged diff or its uncommitted diff. Unlike
+    /// [`Self::stage_hunks`], the hunks stay in the diffs until the new index
+    /// text has been written and the diffs recomputed, since only part of them
+    /// gets staged.
+    pub fn stage_lines(
+        &mut self,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        selection: DiffLineSelection,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if selection.is_empty() {
+            return Ok(());
+        }
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let edits = diff
+            .read(cx)
+            .snapshot(cx)
+            .stage_lines(&selection, &buffer_snapshot)?;
+        self.write_line_edits(buffer_snapshot.remote_id(), edits, cx)
+    }
+
+    /// Unstages the lines `selection` picks out of the hunks of `diff`, which
+    /// is either a staged diff, whose main buffer `buffer` holds the index
+    /// text, or an uncommitted diff.
+    pub fn unstage_lines(
+        &mut self,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        selection: DiffLineSelection,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if selection.is_empty() {
+            return Ok(());
+        }
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let main_buffer_id = buffer_snapshot.remote_id();
+        let buffer_id = self
+            .buffer_ids_by_index_text_buffer_id
+            .get(&main_buffer_id)
+            .copied()
+            .unwrap_or(main_buffer_id);
+        let edits = diff
+            .read(cx)
+            .snapshot(cx)
+            .unstage_lines(&selection, &buffer_snapshot)?;
+        self.write_line_edits(buffer_id, edits, cx)
+    }
+
+    fn write_line_edits(
+        &mut self,
+        buffer_id: BufferId,
+        edits: Vec<(Range<usize>, Arc<str>)>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let diff_state = self
+            .diffs
+            .get(&buffer_id)
+            .cloned()
+            .context("failed to find git state for buffer")?;
+        diff_state.update(cx, |diff_state, _| {
+            // The edits are relative to the index text the diffs were computed
+            // from, which doesn't include optimistic edits still being written.
+            let footprints = edits
+                .iter()
+                .map(|(range, _)| range.clone())
+                .collect::<Vec<_>>();
+            anyhow::ensure!(
+                !diff_state.has_overlapping_pending_index_edits(&footprints),
+                "these lines are still being staged or unstaged"
+            );
+            diff_state.insert_pending_index_edits(Some(edits));
+            Ok(())
+        })?;
+
+        self.write_optimistic_index(buffer_id, cx);
+        Ok(())
+    }
+
     /// Derives the desired index text from the buffer's optimistic patch and
     /// schedules the write.
     fn write_optimistic_index(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
@@ -1984,6 +2098,7 @@ impl GitStore {
         let version = version.unwrap_or(buffer.version());
         let buffer_id = buffer.remote_id();
 
+        let options = repo.read(cx).blame_options(cx);
         let repo = repo.downgrade();
         cx.spawn(async move |_, cx| {
             let repository_state = repo
@@ -1991,11 +2106,14 @@ impl GitStore {
                 .await
                 .map_err(|err| anyhow::anyhow!(err))?;
             match repository_state {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => backend
-                    .blame(repo_path.clone(), content, line_ending)
-                    .await
-                    .with_context(|| format!("Failed to blame {:?}", repo_path.as_ref()))
-                    .map(Some),
+                RepositoryState::Local(LocalRepositoryState { backend, fs, .. }) => {
+                    let options = existing_ignore_revs_files(options, fs.as_ref()).await;
+                    backend
+                        .blame(repo_path.clone(), content, line_ending, options)
+                        .await
+                        .with_context(|| format!("Failed to blame {:?}", repo_path.as_ref()))
+                        .map(Some)
+                }
                 RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
                     let response = client
                         .request(proto::BlameBuffer {
@@ -3008,6 +3126,322 @@ impl GitStore {
         })
     }
 
+    async fn handle_git_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        let todo = envelope
+            .payload
+            .todo
+            .iter()
+            .map(|entry| {
+                Ok(RebaseTodoEntry {
+                    action: match entry.action() {
+                        proto::git_rebase::Action::Pick => RebaseTodoAction::Pick,
+                        proto::git_rebase::Action::Reword => RebaseTodoAction::Reword,
+                        proto::git_rebase::Action::Edit => RebaseTodoAction::Edit,
+                        proto::git_rebase::Action::Squash => RebaseTodoAction::Squash,
+                        proto::git_rebase::Action::Fixup => RebaseTodoAction::Fixup,
+                        proto::git_rebase::Action::Drop => RebaseTodoAction::Drop,
+                    },
+                    sha: Oid::from_str(&entry.sha)?,
+                    message: entry.message.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Collaborators' todo lists are checked again before Git is given them.
+        validate_todo(&todo)?;
+        let onto = Oid::from_str(&envelope.payload.onto)?;
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_interactive(onto.to_string(), todo, askpass, cx)
+            })
+            .await??;
+
+        Ok(rebase_outcome_to_proto(&outcome))
+    }
+
+    async fn handle_git_rebase_step(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseStep>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        let step = match envelope.payload.step() {
+            proto::git_rebase_step::Step::Continue => RebaseStep::Continue,
+            proto::git_rebase_step::Step::Skip => RebaseStep::Skip,
+            proto::git_rebase_step::Step::Abort => RebaseStep::Abort,
+        };
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_step(step, askpass, cx)
+            })
+            .await??;
+
+        Ok(rebase_outcome_to_proto(&outcome))
+    }
+
+    async fn handle_git_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitApplyCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = Oid::from_str(&envelope.payload.commit)?;
+        let through = envelope
+            .payload
+            .through
+            .as_deref()
+            .map(Oid::from_str)
+            .transpose()?;
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(
+                    commit.to_string(),
+                    through.map(|through| through.to_string()),
+                )
+            })
+            .await??;
+
+        Ok(apply_commits_outcome_to_proto(outcome))
+    }
+
+    async fn handle_git_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitApplyCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = Oid::from_str(&envelope.payload.commit)?;
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(commit.to_string())
+            })
+            .await??;
+
+        Ok(apply_commits_outcome_to_proto(outcome))
+    }
+
+    async fn handle_git_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.commit,
+                    envelope.payload.message,
+                )
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_push_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.push_tags(
+                    envelope.payload.remote_name.into(),
+                    envelope.payload.tag_name.map(Into::into),
+                    askpass,
+                    cx,
+                )
+            })
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_git_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let mark = match envelope.payload.mark() {
+            proto::git_bisect_mark::Mark::Good => BisectMark::Good,
+            proto::git_bisect_mark::Mark::Bad => BisectMark::Bad,
+            proto::git_bisect_mark::Mark::Skip => BisectMark::Skip,
+        };
+        let commit = envelope
+            .payload
+            .commit
+            .as_deref()
+            .map(Oid::from_str)
+            .transpose()?;
+
+        let state = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_mark(mark, commit.map(|commit| commit.to_string()))
+            })
+            .await??;
+
+        Ok(proto::GitBisectResponse {
+            state: state.as_ref().map(bisect_state_to_proto),
+        })
+    }
+
+    async fn handle_git_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitConflictStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let repo_path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_stages(repo_path)
+            })
+            .await??;
+
+        Ok(proto::GitConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
+    async fn handle_git_get_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSubmodulesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let submodules = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.submodules()
+            })
+            .await??;
+
+        Ok(proto::GitSubmodulesResponse {
+            submodules: submodules.iter().map(submodule_to_proto).collect(),
+        })
+    }
+
+    async fn handle_git_submodule_command(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSubmoduleCommand>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let operation = match envelope.payload.operation() {
+            proto::git_submodule_command::Operation::Init => SubmoduleOperation::Init,
+            proto::git_submodule_command::Operation::Update => SubmoduleOperation::Update,
+            proto::git_submodule_command::Operation::Sync => SubmoduleOperation::Sync,
+        };
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_proto(path))
+            .collect::<Result<Vec<_>>>()?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.submodule_command(operation, paths, askpass, cx)
+            })
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
     async fn handle_stage(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Stage>,
@@ -3616,15 +4050,82 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_get_branches(
+    async fn handle_get_worktree_statuses(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitGetBranches>,
+        envelope: TypedEnvelope<proto::GitGetWorktreeStatuses>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitBranchesResponse> {
+    ) -> Result<proto::GitWorktreeStatusesResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let branches_scan = repository_handle
+        let statuses = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktree_statuses()
+            })
+            .await??;
+
+        Ok(proto::GitWorktreeStatusesResponse {
+            statuses: statuses.iter().map(worktree_status_to_proto).collect(),
+        })
+    }
+
+    async fn handle_prune_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPruneWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.prune_worktrees()
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_move_changes_to_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitMoveChangesToWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let target = PathBuf::from(envelope.payload.target);
+
+        // The changes are popped from the stash in `target`, so it must be a worktree of this
+        // repository rather than any path the collaborator names.
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })
+            .await??;
+        let target = worktrees
+            .into_iter()
+            .find(|worktree| !worktree.is_bare && worktree.path == target)
+            .with_context(|| format!("{target:?} is not a worktree of this repository"))?
+            .path;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.move_changes_to_worktree(target)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_branches(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetBranches>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBranchesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let branches_scan = repository_handle
             .update(&mut cx, |repository_handle, _| repository_handle.branches())
             .await??;
 
@@ -3789,6 +4290,90 @@ impl GitStore {
         })
     }
 
+    async fn handle_get_commit_signature(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetCommitSignature>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitSignatureResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = Oid::from_str(&envelope.payload.commit)?;
+
+        let signature = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_signature(commit.to_string())
+            })
+            .await??;
+        Ok(commit_signature_to_proto(signature))
+    }
+
+    async fn handle_format_patch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitFormatPatch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitPatchResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = Oid::from_str(&envelope.payload.commit)?;
+        let through = envelope
+            .payload
+            .through
+            .as_deref()
+            .map(Oid::from_str)
+            .transpose()?;
+
+        let patch = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.format_patch(
+                    commit.to_string(),
+                    through.map(|through| through.to_string()),
+                )
+            })
+            .await??;
+        Ok(proto::GitPatchResponse { patch })
+    }
+
+    async fn handle_check_patch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckPatch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCheckPatchResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let checks = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_patch(envelope.payload.patch)
+            })
+            .await??;
+        Ok(proto::GitCheckPatchResponse {
+            checks: checks.into_iter().map(patch_check_to_proto).collect(),
+        })
+    }
+
+    async fn handle_apply_patch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitApplyPatch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let mode = match envelope.payload.mode() {
+            proto::git_apply_patch::Mode::WorkingTree => ApplyPatchMode::WorkingTree,
+            proto::git_apply_patch::Mode::WorkingTreeAndIndex => {
+                ApplyPatchMode::WorkingTreeAndIndex
+            }
+            proto::git_apply_patch::Mode::Commits => ApplyPatchMode::Commits,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.apply_patch(envelope.payload.patch, mode)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_create_checkpoint(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCreateCheckpoint>,
@@ -4336,6 +4921,29 @@ impl GitStore {
         Ok(serialize_blame_buffer_response(blame))
     }
 
+    async fn handle_blame_revision(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBlameRevision>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::BlameBufferResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path).context("invalid repo path")?;
+        let revision = Oid::from_str(&envelope.payload.revision)?;
+        let rows = envelope.payload.start_row..envelope.payload.end_row;
+        anyhow::ensure!(
+            rows.start <= rows.end && rows.end < u32::MAX,
+            "invalid rows to blame: {rows:?}"
+        );
+
+        let blame = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.blame_revision(path, revision.to_string(), rows, cx)
+            })
+            .await??;
+        Ok(serialize_blame_buffer_response(Some(blame)))
+    }
+
     async fn handle_get_permalink_to_line(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetPermalinkToLine>,
@@ -4807,6 +5415,16 @@ impl BufferGitState {
         }
     }
 
+    fn has_overlapping_pending_index_edits(&self, ranges: &[Range<usize>]) -> bool {
+        self.pending_index_edits.as_ref().is_some_and(|edits| {
+            edits.iter().any(|(existing, _)| {
+                ranges.iter().any(|footprint| {
+                    existing.start <= footprint.end && footprint.start <= existing.end
+                })
+            })
+        })
+    }
+
     fn remove_overlapping_pending_index_edits(&mut self, ranges: &[Range<usize>]) {
         if let Some(edits) = &mut self.pending_index_edits {
             edits.retain(|(existing, _)| {
@@ -5437,6 +6055,8 @@ impl RepositorySnapshot {
                 .map(|(repo_path, _)| repo_path.as_unix_str().to_owned())
                 .collect(),
             merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            rebase_state: self.merge.rebase.as_ref().map(rebase_state_to_proto),
+            bisect_state: self.merge.bisect.as_ref().map(bisect_state_to_proto),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
@@ -5524,6 +6144,8 @@ impl RepositorySnapshot {
                 .map(|(path, _)| path.as_unix_str().to_owned())
                 .collect(),
             merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            rebase_state: self.merge.rebase.as_ref().map(rebase_state_to_proto),
+            bisect_state: self.merge.bisect.as_ref().map(bisect_state_to_proto),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
@@ -5689,6 +6311,8 @@ impl MergeDetails {
     ) -> bool {
         log::debug!("load merge details");
         self.message = backend.merge_message().await.map(SharedString::from);
+        let rebase = backend.rebase_state().await;
+        let bisect = backend.bisect_state().await;
         let heads = backend
             .revparse_batch(vec![
                 "MERGE_HEAD".into(),
@@ -5704,7 +6328,12 @@ impl MergeDetails {
             .map(|opt| opt.map(SharedString::from))
             .collect::<Vec<_>>();
 
-        let mut conflicts_changed = false;
+        // A rebase moving to its next stop changes what the conflicts are resolved for.
+        let mut conflicts_changed = rebase != self.rebase;
+        self.rebase = rebase;
+        // So does a bisect checking out its next candidate.
+        conflicts_changed |= bisect != self.bisect;
+        self.bisect = bisect;
 
         // Record the merge state for newly conflicted paths
         for path in &current_conflicted_paths {
@@ -6235,6 +6864,58 @@ impl Repository {
         })
     }
 
+    /// The git settings of the worktree containing this repository's working
+    /// directory, falling back to the global ones.
+    pub fn git_settings(&self, cx: &App) -> GitSettings {
+        let root = self.repo_path_to_project_path(&RepoPath::from_rel_path(RelPath::empty()), cx);
+        let location = root.as_ref().map(|root| SettingsLocation {
+            worktree_id: root.worktree_id,
+            path: &root.path,
+        });
+        ProjectSettings::get(location, cx).git.clone()
+    }
+
+    /// Returns the repository of the submodule at `path`, which only exists once
+    /// the submodule is initialized.
+    pub fn submodule_repository(&self, path: &RepoPath, cx: &App) -> Option<Entity<Repository>> {
+        let abs_path = self.snapshot.repo_path_to_abs_path(path);
+        let git_store = self.git_store.upgrade()?;
+        git_store
+            .read(cx)
+            .repositories
+            .values()
+            .find(|repository| *repository.read(cx).work_directory_abs_path == *abs_path)
+            .cloned()
+    }
+
+    /// The repository this one is checked out in as a submodule, if any.
+    pub fn superproject_repository(&self, cx: &App) -> Option<Entity<Repository>> {
+        if !is_submodule_git_dir(&self.snapshot.repository_dir_abs_path) {
+            return None;
+        }
+        let git_store = self.git_store.upgrade()?;
+        git_store
+            .read(cx)
+            .repositories
+            .values()
+            .filter(|repository| {
+                let work_directory = &repository.read(cx).work_directory_abs_path;
+                *work_directory != self.snapshot.work_directory_abs_path
+                    && self
+                        .snapshot
+                        .work_directory_abs_path
+                        .starts_with(work_directory.as_ref())
+            })
+            .max_by_key(|repository| {
+                repository
+                    .read(cx)
+                    .work_directory_abs_path
+                    .as_os_str()
+                    .len()
+            })
+            .cloned()
+    }
+
     pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
         let git_store = self.git_store.upgrade()?;
         let worktree_store = git_store.read(cx).worktree_store.read(cx);
@@ -6470,6 +7151,129 @@ impl Repository {
         })
     }
 
+    pub fn commit_signature(
+        &mut self,
+        commit: String,
+    ) -> oneshot::Receiver<Result<CommitSignature>> {
+        let id = self.id;
+        self.send_job("commit_signature", None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.commit_signature(commit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetCommitSignature {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+                    Ok(commit_signature_from_proto(response))
+                }
+            }
+        })
+    }
+
+    /// Returns `commit`, or the commits from `commit` through `through`, in the
+    /// format of `git format-patch`.
+    pub fn format_patch(
+        &mut self,
+        commit: String,
+        through: Option<String>,
+    ) -> oneshot::Receiver<Result<String>> {
+        let id = self.id;
+        self.send_job("format_patch", None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.format_patch(commit, through).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitFormatPatch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                            through,
+                        })
+                        .await?;
+                    Ok(response.patch)
+                }
+            }
+        })
+    }
+
+    pub fn check_patch(&mut self, patch: String) -> oneshot::Receiver<Result<Vec<PatchHunkCheck>>> {
+        let id = self.id;
+        self.send_job("check_patch", None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.check_patch(patch).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitCheckPatch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            patch,
+                        })
+                        .await?;
+                    Ok(response
+                        .checks
+                        .into_iter()
+                        .map(patch_check_from_proto)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn apply_patch(
+        &mut self,
+        patch: String,
+        mode: ApplyPatchMode,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let status = match mode {
+            ApplyPatchMode::Commits => "git am",
+            ApplyPatchMode::WorkingTree | ApplyPatchMode::WorkingTreeAndIndex => "git apply",
+        };
+        self.send_job(
+            "apply_patch",
+            Some(status.into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.apply_patch(patch, mode, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let mode = match mode {
+                            ApplyPatchMode::WorkingTree => {
+                                proto::git_apply_patch::Mode::WorkingTree
+                            }
+                            ApplyPatchMode::WorkingTreeAndIndex => {
+                                proto::git_apply_patch::Mode::WorkingTreeAndIndex
+                            }
+                            ApplyPatchMode::Commits => proto::git_apply_patch::Mode::Commits,
+                        };
+                        client
+                            .request(proto::GitApplyPatch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                patch,
+                                mode: mode as i32,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
         let id = self.id;
         self.send_job("load_commit_diff", None, move |git_repo, cx| async move {
@@ -7767,13 +8571,16 @@ impl Repository {
         &mut self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
-        options: CommitOptions,
+        mut options: CommitOptions,
         askpass: AskPassDelegate,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
         let askpass_delegates = self.askpass_delegates.clone();
         let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let signing_settings = self.git_settings(cx).commit_signing;
+        options.signoff |= signing_settings.signoff;
+        let signing = signing_settings.signing();
 
         self.send_job(
             "commit",
@@ -7786,7 +8593,14 @@ impl Repository {
                         ..
                     }) => {
                         backend
-                            .commit(message, name_and_email, options, askpass, environment)
+                            .commit(
+                                message,
+                                name_and_email,
+                                options,
+                                signing,
+                                askpass,
+                                environment,
+                            )
                             .await
                     }
                     RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
@@ -8078,37 +8892,544 @@ impl Repository {
         )
     }
 
-    fn spawn_set_index_text_job(
+    /// Rebases the commits after `onto` as described by `todo`, which is ordered oldest first.
+    pub fn rebase_interactive(
         &mut self,
-        path: RepoPath,
-        content: Option<String>,
-        hunk_staging_operation_count: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        onto: String,
+        todo: Vec<RebaseTodoEntry>,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RebaseOutcome>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
-        let this = cx.weak_entity();
-        let git_store = self.git_store.clone();
-        let abs_path = self.snapshot.repo_path_to_abs_path(&path);
-        self.send_keyed_job(
-            "spawn_set_index_text_job",
-            Some(GitJobKey::WriteIndex(vec![path.clone()])),
-            None,
-            move |git_repo, mut cx| async move {
-                log::debug!(
-                    "start updating index text for buffer {}",
-                    path.as_unix_str()
-                );
 
+        self.send_job(
+            "rebase",
+            Some(format!("git rebase --interactive {onto}").into()),
+            move |git_repo, _cx| async move {
                 match git_repo {
                     RepositoryState::Local(LocalRepositoryState {
-                        fs,
                         backend,
                         environment,
                         ..
                     }) => {
-                        let executable = match fs.metadata(&abs_path).await {
-                            Ok(Some(meta)) => meta.is_executable,
-                            Ok(None) => false,
+                        backend
+                            .rebase_interactive(onto, todo, askpass, environment.clone())
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitRebase {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                                onto,
+                                todo: todo
+                                    .into_iter()
+                                    .map(|entry| proto::git_rebase::TodoEntry {
+                                        action: match entry.action {
+                                            RebaseTodoAction::Pick => {
+                                                proto::git_rebase::Action::Pick
+                                            }
+                                            RebaseTodoAction::Reword => {
+                                                proto::git_rebase::Action::Reword
+                                            }
+                                            RebaseTodoAction::Edit => {
+                                                proto::git_rebase::Action::Edit
+                                            }
+                                            RebaseTodoAction::Squash => {
+                                                proto::git_rebase::Action::Squash
+                                            }
+                                            RebaseTodoAction::Fixup => {
+                                                proto::git_rebase::Action::Fixup
+                                            }
+                                            RebaseTodoAction::Drop => {
+                                                proto::git_rebase::Action::Drop
+                                            }
+                                        } as i32,
+                                        sha: entry.sha.to_string(),
+                                        message: entry.message,
+                                    })
+                                    .collect(),
+                            })
+                            .await?;
+
+                        Ok(proto_to_rebase_outcome(response))
+                    }
+                }
+            },
+        )
+    }
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress.
+    pub fn rebase_step(
+        &mut self,
+        step: RebaseStep,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RebaseOutcome>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            "rebase",
+            Some(format!("git rebase --{}", step.name()).into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .rebase_step(step, askpass, environment.clone())
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitRebaseStep {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                                step: match step {
+                                    RebaseStep::Continue => proto::git_rebase_step::Step::Continue,
+                                    RebaseStep::Skip => proto::git_rebase_step::Step::Skip,
+                                    RebaseStep::Abort => proto::git_rebase_step::Step::Abort,
+                                } as i32,
+                            })
+                            .await?;
+
+                        Ok(proto_to_rebase_outcome(response))
+                    }
+                }
+            },
+        )
+    }
+
+    /// Cherry-picks `commit`, or the commits from `commit` through `through`, stopping at
+    /// the first one that conflicts.
+    pub fn cherry_pick(
+        &mut self,
+        commit: String,
+        through: Option<String>,
+    ) -> oneshot::Receiver<Result<ApplyCommitsOutcome>> {
+        let id = self.id;
+        let command = match &through {
+            Some(through) => format!("git cherry-pick {commit}^..{through}"),
+            None => format!("git cherry-pick {commit}"),
+        };
+        self.send_job(
+            "cherry-pick",
+            Some(command.into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.cherry_pick(commit, through, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let response = client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                                through,
+                            })
+                            .await?;
+
+                        Ok(proto_to_apply_commits_outcome(response))
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commit: String) -> oneshot::Receiver<Result<ApplyCommitsOutcome>> {
+        let id = self.id;
+        self.send_job(
+            "revert",
+            Some(format!("git revert {commit}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.revert(commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let response = client
+                            .request(proto::GitRevert {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(proto_to_apply_commits_outcome(response))
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        commit: String,
+        message: String,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "create_tag",
+            Some(format!("git tag --annotate {name} {commit}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.create_tag(name, commit, message, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                commit,
+                                message,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "delete_tag",
+            Some(format!("git tag --delete {name}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.delete_tag(name, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Pushes the tag named `tag_name` to `remote`, or all tags when it's `None`.
+    pub fn push_tags(
+        &mut self,
+        remote: SharedString,
+        tag_name: Option<SharedString>,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let command = match &tag_name {
+            Some(tag_name) => format!("git push {remote} refs/tags/{tag_name}"),
+            None => format!("git push {remote} --tags"),
+        };
+
+        self.send_job(
+            "push",
+            Some(command.into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .push_tags(
+                                remote.to_string(),
+                                tag_name.map(|tag_name| tag_name.to_string()),
+                                askpass,
+                                environment.clone(),
+                                cx,
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitPushTags {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                                remote_name: remote.to_string(),
+                                tag_name: tag_name.map(|tag_name| tag_name.to_string()),
+                            })
+                            .await?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn load_conflict_stages(
+        &mut self,
+        repo_path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
+        let id = self.id;
+        self.send_job(
+            "load_conflict_stages",
+            None,
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.load_conflict_stages(repo_path).await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let response = client
+                            .request(proto::GitLoadConflictStages {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: repo_path.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(ConflictStages {
+                            base: response.base,
+                            ours: response.ours,
+                            theirs: response.theirs,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn submodules(&mut self) -> oneshot::Receiver<Result<Vec<Submodule>>> {
+        let id = self.id;
+        self.send_job("submodules", None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.submodules().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetSubmodules {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    response.submodules.iter().map(proto_to_submodule).collect()
+                }
+            }
+        })
+    }
+
+    /// Runs `git submodule <operation>` on the submodules at `paths`, or on all
+    /// of them when `paths` is empty.
+    pub fn submodule_command(
+        &mut self,
+        operation: SubmoduleOperation,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let command = format!("git submodule {}", operation.as_str());
+
+        self.send_job(
+            "submodule",
+            Some(command.into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .submodule_command(operation, paths, askpass, environment.clone(), cx)
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let operation = match operation {
+                            SubmoduleOperation::Init => {
+                                proto::git_submodule_command::Operation::Init
+                            }
+                            SubmoduleOperation::Update => {
+                                proto::git_submodule_command::Operation::Update
+                            }
+                            SubmoduleOperation::Sync => {
+                                proto::git_submodule_command::Operation::Sync
+                            }
+                        };
+                        let response = client
+                            .request(proto::GitSubmoduleCommand {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                                operation: operation as i32,
+                                paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            })
+                            .await?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    /// Marks `commit`, or HEAD when it's `None`, starting a bisect if none is in progress.
+    pub fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<Option<BisectState>>> {
+        let id = self.id;
+        let command = match &commit {
+            Some(commit) => format!("git bisect {} {commit}", mark.as_str()),
+            None => format!("git bisect {}", mark.as_str()),
+        };
+        self.send_job(
+            "bisect",
+            Some(command.into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_mark(mark, commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let mark = match mark {
+                            BisectMark::Good => proto::git_bisect_mark::Mark::Good,
+                            BisectMark::Bad => proto::git_bisect_mark::Mark::Bad,
+                            BisectMark::Skip => proto::git_bisect_mark::Mark::Skip,
+                        };
+                        let response = client
+                            .request(proto::GitBisectMark {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                mark: mark as i32,
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(response.state.as_ref().map(proto_to_bisect_state))
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "bisect_reset",
+            Some("git bisect reset".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    fn spawn_set_index_text_job(
+        &mut self,
+        path: RepoPath,
+        content: Option<String>,
+        hunk_staging_operation_count: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        let id = self.id;
+        let this = cx.weak_entity();
+        let git_store = self.git_store.clone();
+        let abs_path = self.snapshot.repo_path_to_abs_path(&path);
+        self.send_keyed_job(
+            "spawn_set_index_text_job",
+            Some(GitJobKey::WriteIndex(vec![path.clone()])),
+            None,
+            move |git_repo, mut cx| async move {
+                log::debug!(
+                    "start updating index text for buffer {}",
+                    path.as_unix_str()
+                );
+
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        fs,
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        let executable = match fs.metadata(&abs_path).await {
+                            Ok(Some(meta)) => meta.is_executable,
+                            Ok(None) => false,
                             Err(_err) => false,
                         };
                         backend
@@ -8586,6 +9907,125 @@ impl Repository {
         })
     }
 
+    pub fn worktree_statuses(&mut self) -> oneshot::Receiver<Result<Vec<WorktreeStatus>>> {
+        let id = self.id;
+        self.send_job("worktree_statuses", None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.worktree_statuses().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetWorktreeStatuses {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response
+                        .statuses
+                        .into_iter()
+                        .map(worktree_status_from_proto)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn prune_worktrees(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "prune_worktrees",
+            Some("git worktree prune".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.prune_worktrees().await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitPruneWorktrees {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Moves the uncommitted changes of this repository's worktree to the
+    /// linked worktree at `target`, through the stash.
+    pub fn move_changes_to_worktree(&mut self, target: PathBuf) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "move_changes_to_worktree",
+            Some("git stash".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.move_changes_to_worktree(target, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitMoveChangesToWorktree {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                target: target.to_string_lossy().into_owned(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// The options files in this repository are blamed with, according to the
+    /// settings of the worktree containing it.
+    pub fn blame_options(&self, cx: &App) -> BlameOptions {
+        self.git_settings(cx)
+            .blame
+            .options(&self.work_directory_abs_path)
+    }
+
+    /// Blames `rows` of `path` as the file was in `revision`, to look past
+    /// the commit that last changed them.
+    pub fn blame_revision(
+        &mut self,
+        path: RepoPath,
+        revision: String,
+        rows: Range<u32>,
+        cx: &App,
+    ) -> oneshot::Receiver<Result<Blame>> {
+        let id = self.id;
+        let options = self.blame_options(cx);
+        self.send_job("blame_revision", None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, fs, .. }) => {
+                    let options = existing_ignore_revs_files(options, fs.as_ref()).await;
+                    backend.blame_revision(path, revision, rows, options).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitBlameRevision {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                            revision,
+                            start_row: rows.start,
+                            end_row: rows.end,
+                        })
+                        .await?;
+                    Ok(deserialize_blame_buffer_response(response).unwrap_or_default())
+                }
+            }
+        })
+    }
+
     pub fn create_archive_checkpoint(&mut self) -> oneshot::Receiver<Result<(String, String)>> {
         let id = self.id;
         self.send_job(
@@ -9140,6 +10580,8 @@ impl Repository {
             self.snapshot.merge.merge_heads_by_conflicted_path != new_merge_heads;
         self.snapshot.merge.merge_heads_by_conflicted_path = new_merge_heads;
         self.snapshot.merge.message = update.merge_message.map(SharedString::from);
+        self.snapshot.merge.rebase = update.rebase_state.as_ref().map(proto_to_rebase_state);
+        self.snapshot.merge.bisect = update.bisect_state.as_ref().map(proto_to_bisect_state);
         let new_stash_entries = GitStash {
             entries: update
                 .stash_entries
@@ -10261,6 +11703,98 @@ fn commit_data_from_proto(commit: proto::CommitData) -> Result<CommitData> {
     })
 }
 
+fn commit_signature_to_proto(signature: CommitSignature) -> proto::GitCommitSignatureResponse {
+    let status = match signature.status {
+        SignatureStatus::Unsigned => proto::git_commit_signature_response::Status::Unsigned,
+        SignatureStatus::Verified => proto::git_commit_signature_response::Status::Verified,
+        SignatureStatus::Unverified => proto::git_commit_signature_response::Status::Unverified,
+        SignatureStatus::UnknownKey => proto::git_commit_signature_response::Status::UnknownKey,
+    };
+    proto::GitCommitSignatureResponse {
+        status: status as i32,
+        signer: signature.signer.map(String::from),
+        key: signature.key.map(String::from),
+    }
+}
+
+fn commit_signature_from_proto(signature: proto::GitCommitSignatureResponse) -> CommitSignature {
+    let status = match signature.status() {
+        proto::git_commit_signature_response::Status::Unsigned => SignatureStatus::Unsigned,
+        proto::git_commit_signature_response::Status::Verified => SignatureStatus::Verified,
+        proto::git_commit_signature_response::Status::Unverified => SignatureStatus::Unverified,
+        proto::git_commit_signature_response::Status::UnknownKey => SignatureStatus::UnknownKey,
+    };
+    CommitSignature {
+        status,
+        signer: signature.signer.map(SharedString::from),
+        key: signature.key.map(SharedString::from),
+    }
+}
+
+fn worktree_status_to_proto(status: &WorktreeStatus) -> proto::WorktreeStatus {
+    proto::WorktreeStatus {
+        path: status.path.to_string_lossy().into_owned(),
+        is_prunable: status.is_prunable,
+        is_locked: status.is_locked,
+        changed_files: status.changed_files as u64,
+        ahead: status.ahead_behind.map(|(ahead, _)| ahead),
+        behind: status.ahead_behind.map(|(_, behind)| behind),
+        modified_at: status.modified_at.map(Into::into),
+    }
+}
+
+fn worktree_status_from_proto(status: proto::WorktreeStatus) -> WorktreeStatus {
+    WorktreeStatus {
+        path: PathBuf::from(status.path),
+        is_prunable: status.is_prunable,
+        is_locked: status.is_locked,
+        changed_files: status.changed_files as usize,
+        ahead_behind: status.ahead.zip(status.behind),
+        modified_at: status.modified_at.map(Into::into),
+    }
+}
+
+fn patch_check_to_proto(check: PatchHunkCheck) -> proto::git_check_patch_response::HunkCheck {
+    let (status, error) = match check {
+        PatchHunkCheck::Applies => (proto::git_check_patch_response::Status::Applies, None),
+        PatchHunkCheck::AlreadyApplied => (
+            proto::git_check_patch_response::Status::AlreadyApplied,
+            None,
+        ),
+        PatchHunkCheck::Conflicts(error) => (
+            proto::git_check_patch_response::Status::Conflicts,
+            Some(error.to_string()),
+        ),
+    };
+    proto::git_check_patch_response::HunkCheck {
+        status: status as i32,
+        error,
+    }
+}
+
+fn patch_check_from_proto(check: proto::git_check_patch_response::HunkCheck) -> PatchHunkCheck {
+    match check.status() {
+        proto::git_check_patch_response::Status::Applies => PatchHunkCheck::Applies,
+        proto::git_check_patch_response::Status::AlreadyApplied => PatchHunkCheck::AlreadyApplied,
+        proto::git_check_patch_response::Status::Conflicts => {
+            PatchHunkCheck::Conflicts(check.error.unwrap_or_default().into())
+        }
+    }
+}
+
+/// Drops the files listing revisions for blame to skip that don't exist, since git refuses to run
+/// with them.
+async fn existing_ignore_revs_files(mut options: BlameOptions, fs: &dyn Fs) -> BlameOptions {
+    let mut existing_files = Vec::with_capacity(options.ignore_revs_files.len());
+    for file in options.ignore_revs_files {
+        if fs.is_file(&file).await {
+            existing_files.push(file);
+        }
+    }
+    options.ignore_revs_files = existing_files;
+    options
+}
+
 fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     proto::Branch {
         is_head: branch.is_head,
@@ -10371,6 +11905,110 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn rebase_state_to_proto(state: &RebaseState) -> proto::RebaseState {
+    proto::RebaseState {
+        head_name: state.head_name.as_ref().map(ToString::to_string),
+        onto: state.onto.as_ref().map(ToString::to_string),
+        step: state.step as u64,
+        total: state.total as u64,
+        stopped_sha: state.stopped_sha.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_rebase_state(proto: &proto::RebaseState) -> RebaseState {
+    RebaseState {
+        head_name: proto.head_name.clone().map(SharedString::from),
+        onto: proto.onto.clone().map(SharedString::from),
+        step: proto.step as usize,
+        total: proto.total as usize,
+        stopped_sha: proto.stopped_sha.clone().map(SharedString::from),
+    }
+}
+
+fn bisect_state_to_proto(state: &BisectState) -> proto::BisectState {
+    let to_strings = |shas: &[SharedString]| shas.iter().map(ToString::to_string).collect();
+    proto::BisectState {
+        bad: state.bad.as_ref().map(ToString::to_string),
+        good: to_strings(&state.good),
+        skipped: to_strings(&state.skipped),
+        current: state.current.as_ref().map(ToString::to_string),
+        remaining_revisions: state.remaining_revisions as u64,
+        remaining_steps: state.remaining_steps as u64,
+        first_bad: state.first_bad.as_ref().map(ToString::to_string),
+        possible_first_bad: to_strings(&state.possible_first_bad),
+    }
+}
+
+fn proto_to_bisect_state(proto: &proto::BisectState) -> BisectState {
+    let to_shared = |shas: &[String]| shas.iter().cloned().map(SharedString::from).collect();
+    BisectState {
+        bad: proto.bad.clone().map(SharedString::from),
+        good: to_shared(&proto.good),
+        skipped: to_shared(&proto.skipped),
+        current: proto.current.clone().map(SharedString::from),
+        remaining_revisions: proto.remaining_revisions as usize,
+        remaining_steps: proto.remaining_steps as usize,
+        first_bad: proto.first_bad.clone().map(SharedString::from),
+        possible_first_bad: to_shared(&proto.possible_first_bad),
+    }
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::Submodule {
+    proto::Submodule {
+        path: submodule.path.to_proto(),
+        recorded_commit: submodule.recorded_commit.as_ref().map(ToString::to_string),
+        head: submodule.head.as_ref().map(ToString::to_string),
+        branch: submodule.branch.as_ref().map(ToString::to_string),
+        ahead: submodule.ahead as u64,
+        behind: submodule.behind as u64,
+        dirty: submodule.dirty,
+    }
+}
+
+fn proto_to_submodule(proto: &proto::Submodule) -> Result<Submodule> {
+    Ok(Submodule {
+        path: RepoPath::from_proto(&proto.path)?,
+        recorded_commit: proto.recorded_commit.clone().map(SharedString::from),
+        head: proto.head.clone().map(SharedString::from),
+        branch: proto.branch.clone().map(SharedString::from),
+        ahead: proto.ahead as usize,
+        behind: proto.behind as usize,
+        dirty: proto.dirty,
+    })
+}
+
+fn rebase_outcome_to_proto(outcome: &RebaseOutcome) -> proto::GitRebaseResponse {
+    proto::GitRebaseResponse {
+        stopped: outcome.stopped.as_ref().map(rebase_state_to_proto),
+        message: outcome.message.clone(),
+    }
+}
+
+fn proto_to_rebase_outcome(response: proto::GitRebaseResponse) -> RebaseOutcome {
+    RebaseOutcome {
+        stopped: response.stopped.as_ref().map(proto_to_rebase_state),
+        message: response.message,
+    }
+}
+
+fn apply_commits_outcome_to_proto(outcome: ApplyCommitsOutcome) -> proto::GitApplyCommitsResponse {
+    proto::GitApplyCommitsResponse {
+        applied: outcome.applied as u64,
+        total: outcome.total as u64,
+        conflicted_sha: outcome.conflicted_sha,
+        message: outcome.message,
+    }
+}
+
+fn proto_to_apply_commits_outcome(response: proto::GitApplyCommitsResponse) -> ApplyCommitsOutcome {
+    ApplyCommitsOutcome {
+        applied: response.applied as usize,
+        total: response.total as usize,
+        conflicted_sha: response.conflicted_sha,
+        message: response.message,
+    }
+}
+
 async fn append_pattern_to_ignore_file(
     fs: Arc<dyn Fs>,
     file_path: PathBuf,
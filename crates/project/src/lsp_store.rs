@@ -9,6 +9,7 @@
 //! - [`LspStore`], which unifies the two under one consistent interface for interacting with language servers.
 //!
 //! Most of the interesting work happens at the local layer, as bulk of the complexity is with managing the lifecycle of language servers. The actual implementation of the LSP protocol is handled by [`lsp`] crate.
+pub mod call_hierarchy;
 pub mod clangd_ext;
 pub mod code_lens;
 mod document_colors;
@@ -5212,6 +5213,11 @@ impl LspStore {
     ) {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        // Buffers in large file mode are left as plain text, so that they're never parsed or sent
+        // to language servers.
+        if buffer.is_large_file() {
+            return;
+        }
         let Some(file) = buffer.file() else {
             return;
         };
@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{Context, Entity, Task};
+use language::{Buffer, PointUtf16, point_from_lsp, point_to_lsp};
+use lsp::{CallHierarchyServerCapability, LanguageServer};
+use settings::Settings as _;
+use worktree::File;
+
+use crate::lsp_command::file_path_to_lsp_url;
+use crate::lsp_store::LspStore;
+use crate::project_settings::ProjectSettings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    /// The functions calling the symbol.
+    Incoming,
+    /// The functions called by the symbol.
+    Outgoing,
+}
+
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The name of the caller or callee.
+    pub name: String,
+    /// Additional details reported by the language server, such as a signature.
+    pub detail: Option<String>,
+    pub abs_path: Option<PathBuf>,
+    /// The start of the caller's or callee's name.
+    pub position: PointUtf16,
+    /// Where the calls happen. These are in the caller: the symbol itself for outgoing calls,
+    /// and the returned item for incoming calls.
+    pub call_sites: Vec<PointUtf16>,
+}
+
+impl LspStore {
+    /// Lists the callers or callees of the symbol at `position`, as reported by the first
+    /// language server of the buffer that supports call hierarchies.
+    ///
+    /// Call hierarchies are only available in local projects.
+    pub fn call_hierarchy(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: PointUtf16,
+        direction: CallHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        if self.as_local().is_none() {
+            return Task::ready(Err(anyhow!(
+                "Call hierarchies are not supported in remote projects"
+            )));
+        }
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Err(anyhow!("Buffer has no file")));
+        };
+        let server = buffer.update(cx, |buffer, cx| {
+            self.running_language_servers_for_local_buffer(buffer, cx)
+                .map(|(_, server)| server.clone())
+                .find(|server| supports_call_hierarchy(server))
+        });
+        let Some(server) = server else {
+            return Task::ready(Err(anyhow!(
+                "No language server supporting call hierarchies is running for this buffer"
+            )));
+        };
+        let request_timeout = ProjectSettings::get_global(cx)
+            .global_lsp_settings
+            .get_request_timeout();
+
+        cx.background_spawn(async move {
+            let uri = file_path_to_lsp_url(&abs_path)?;
+            let items = server
+                .request::<lsp::request::CallHierarchyPrepare>(
+                    lsp::CallHierarchyPrepareParams {
+                        text_document_position_params: lsp::TextDocumentPositionParams::new(
+                            lsp::TextDocumentIdentifier::new(uri),
+                            point_to_lsp(position),
+                        ),
+                        work_done_progress_params: Default::default(),
+                    },
+                    request_timeout,
+                )
+                .await
+                .into_response()
+                .context("preparing call hierarchy")?
+                .unwrap_or_default();
+
+            let mut calls = Vec::new();
+            for item in items {
+                match direction {
+                    CallHierarchyDirection::Incoming => {
+                        let incoming = server
+                            .request::<lsp::request::CallHierarchyIncomingCalls>(
+                                lsp::CallHierarchyIncomingCallsParams {
+                                    item,
+                                    work_done_progress_params: Default::default(),
+                                    partial_result_params: Default::default(),
+                                },
+                                request_timeout,
+                            )
+                            .await
+                            .into_response()
+                            .context("fetching incoming calls")?
+                            .unwrap_or_default();
+                        calls.extend(
+                            incoming
+                                .into_iter()
+                                .map(|call| CallHierarchyCall::new(call.from, &call.from_ranges)),
+                        );
+                    }
+                    CallHierarchyDirection::Outgoing => {
+                        let outgoing = server
+                            .request::<lsp::request::CallHierarchyOutgoingCalls>(
+                                lsp::CallHierarchyOutgoingCallsParams {
+                                    item,
+                                    work_done_progress_params: Default::default(),
+                                    partial_result_params: Default::default(),
+                                },
+                                request_timeout,
+                            )
+                            .await
+                            .into_response()
+                            .context("fetching outgoing calls")?
+                            .unwrap_or_default();
+                        calls.extend(
+                            outgoing
+                                .into_iter()
+                                .map(|call| CallHierarchyCall::new(call.to, &call.from_ranges)),
+                        );
+                    }
+                }
+            }
+            Ok(calls)
+        })
+    }
+}
+
+impl CallHierarchyCall {
+    fn new(item: lsp::CallHierarchyItem, call_ranges: &[lsp::Range]) -> Self {
+        Self {
+            name: item.name,
+            detail: item.detail.filter(|detail| !detail.is_empty()),
+            abs_path: item.uri.to_file_path().ok(),
+            position: point_from_lsp(item.selection_range.start).0,
+            call_sites: call_ranges
+                .iter()
+                .map(|range| point_from_lsp(range.start).0)
+                .collect(),
+        }
+    }
+}
+
+fn supports_call_hierarchy(server: &LanguageServer) -> bool {
+    match server.capabilities().call_hierarchy_provider {
+        Some(CallHierarchyServerCapability::Simple(enabled)) => enabled,
+        Some(CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
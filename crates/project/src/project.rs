@@ -25,7 +25,7 @@ pub mod trusted_worktrees;
 pub mod worktree_store;
 
 mod environment;
-use buffer_diff::BufferDiff;
+use buffer_diff::{BufferDiff, DiffLineSelection};
 use context_server_store::ContextServerStore;
 pub use environment::ProjectEnvironmentEvent;
 use git::repository::get_git_committer;
@@ -168,6 +168,7 @@ pub use lsp_store::{
     DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
     LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
     LspStoreEvent, ProgressToken, SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    call_hierarchy::{CallHierarchyCall, CallHierarchyDirection},
 };
 pub use toolchain_store::{ToolchainStore, Toolchains};
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -3371,6 +3372,40 @@ impl Project {
         })
     }
 
+    /// Stages the lines `selection` picks out of the hunks of `diff`, the
+    /// buffer's unstaged or uncommitted diff.
+    pub fn stage_lines(
+        &mut self,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        selection: DiffLineSelection,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if self.is_disconnected(cx) {
+            return Err(anyhow!(ErrorCode::Disconnected));
+        }
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.stage_lines(buffer, diff, selection, cx)
+        })
+    }
+
+    /// Unstages the lines `selection` picks out of the hunks of `diff`, either
+    /// a staged diff of the index buffer `buffer` or an uncommitted diff.
+    pub fn unstage_lines(
+        &mut self,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        selection: DiffLineSelection,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if self.is_disconnected(cx) {
+            return Err(anyhow!(ErrorCode::Disconnected));
+        }
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.unstage_lines(buffer, diff, selection, cx)
+        })
+    }
+
     pub fn open_buffer_by_id(
         &mut self,
         id: BufferId,
@@ -4412,6 +4447,19 @@ impl Project {
         })
     }
 
+    pub fn call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        direction: CallHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.call_hierarchy(buffer, position, direction, cx)
+        })
+    }
+
     pub fn references<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
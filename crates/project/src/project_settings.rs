@@ -4,8 +4,11 @@ use context_server::ContextServerCommand;
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
+use git::blame::{BLAME_IGNORE_REVS_FILE, BlameMoveDetection, BlameOptions};
 use git::repository::DEFAULT_WORKTREE_DIRECTORY;
+use git::signature::{CommitSigning, SigningFormat};
 use gpui::{AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
+use itertools::Itertools as _;
 use lsp::{DEFAULT_LSP_REQUEST_TIMEOUT_SECS, LanguageServerName};
 use paths::{
     EDITORCONFIG_NAME, local_debug_file_relative_path, local_settings_file_relative_path,
@@ -26,7 +29,13 @@ use settings::{
     LocalSettingsPath, RegisterSetting, SemanticTokenRules, Settings, SettingsLocation,
     SettingsStore, parse_json_with_comments, watch_config_file,
 };
-use std::{cell::OnceCell, collections::BTreeMap, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    cell::OnceCell,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use task::{DebugTaskFile, TaskTemplates, VsCodeDebugTaskFile, VsCodeTaskFile};
 use util::{ResultExt, rel_path::RelPath, serde::default_true};
 use worktree::{PathChange, UpdatedEntriesSet, Worktree, WorktreeId};
@@ -496,6 +505,8 @@ pub struct GitSettings {
     ///
     /// Default: true
     pub show_stage_restore_buttons: bool,
+    /// How commits made from Zed are signed.
+    pub commit_signing: CommitSigningSettings,
     /// Directory where git worktrees are created, relative to the repository
     /// working directory. When the resolved directory is outside the project
     /// root, the project's directory name is automatically appended so that
@@ -580,12 +591,78 @@ pub struct InlineBlameSettings {
     pub show_commit_summary: bool,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug, Default)]
+pub struct CommitSigningSettings {
+    /// Whether to sign commits.
+    ///
+    /// Default: git_config
+    pub sign_commits: settings::SignCommitsSetting,
+    /// The format of the signature.
+    ///
+    /// Default: git_config
+    pub format: settings::CommitSigningFormat,
+    /// The key to sign commits with, `None` to use git's `user.signingKey`.
+    ///
+    /// Default: null
+    pub key: Option<String>,
+    /// Whether to always add a `Signed-off-by` trailer to commits.
+    ///
+    /// Default: false
+    pub signoff: bool,
+}
+
+impl CommitSigningSettings {
+    pub fn signing(&self) -> CommitSigning {
+        CommitSigning {
+            sign: match self.sign_commits {
+                settings::SignCommitsSetting::GitConfig => None,
+                settings::SignCommitsSetting::Always => Some(true),
+                settings::SignCommitsSetting::Never => Some(false),
+            },
+            format: match self.format {
+                settings::CommitSigningFormat::GitConfig => None,
+                settings::CommitSigningFormat::Openpgp => Some(SigningFormat::OpenPgp),
+                settings::CommitSigningFormat::Ssh => Some(SigningFormat::Ssh),
+                settings::CommitSigningFormat::X509 => Some(SigningFormat::X509),
+            },
+            key: self.key.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct BlameSettings {
     /// Whether to show the avatar of the author of the commit.
     ///
     /// Default: true
     pub show_avatar: bool,
+    /// Additional files listing revisions to skip, relative to the repository root.
+    ///
+    /// Default: []
+    pub ignore_revs_files: Vec<String>,
+    /// Whether blame should look past lines that were moved or copied.
+    ///
+    /// Default: off
+    pub detect_moves: settings::BlameMoveDetection,
+}
+
+impl BlameSettings {
+    /// The options to blame files in the repository at `work_directory` with,
+    /// always including its `.git-blame-ignore-revs` file.
+    pub fn options(&self, work_directory: &Path) -> BlameOptions {
+        BlameOptions {
+            ignore_revs_files: std::iter::once(BLAME_IGNORE_REVS_FILE)
+                .chain(self.ignore_revs_files.iter().map(String::as_str))
+                .map(|file| work_directory.join(file))
+                .unique()
+                .collect(),
+            move_detection: match self.detect_moves {
+                settings::BlameMoveDetection::Off => BlameMoveDetection::Off,
+                settings::BlameMoveDetection::WithinFile => BlameMoveDetection::WithinFile,
+                settings::BlameMoveDetection::AcrossFiles => BlameMoveDetection::AcrossFiles,
+            },
+        }
+    }
 }
 
 impl GitSettings {
@@ -700,9 +777,11 @@ impl Settings for ProjectSettings {
                 }
             },
             blame: {
-                let blame = git.blame.unwrap();
+                let blame = git.blame.as_ref().unwrap();
                 BlameSettings {
                     show_avatar: blame.show_avatar.unwrap(),
+                    ignore_revs_files: blame.ignore_revs_files.clone().unwrap_or_default(),
+                    detect_moves: blame.detect_moves.unwrap_or_default(),
                 }
             },
             branch_picker: {
@@ -715,6 +794,16 @@ impl Settings for ProjectSettings {
             diff_base: git.diff_base.unwrap_or_default(),
             path_style: git.path_style.unwrap().into(),
             show_stage_restore_buttons: git.show_stage_restore_buttons.unwrap_or(true),
+            commit_signing: project
+                .git_commit_signing
+                .clone()
+                .map(|signing| CommitSigningSettings {
+                    sign_commits: signing.sign_commits.unwrap_or_default(),
+                    format: signing.format.unwrap_or_default(),
+                    key: signing.key.filter(|key| !key.is_empty()),
+                    signoff: signing.signoff.unwrap_or_default(),
+                })
+                .unwrap_or_default(),
             worktree_directory: git
                 .worktree_directory
                 .clone()
@@ -1,7 +1,7 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::{Ok, Result};
 use client::proto;
-use fancy_regex::{Captures, Regex, RegexBuilder};
+use fancy_regex::{Captures, Expander, Regex, RegexBuilder};
 use gpui::Entity;
 use itertools::Itertools as _;
 use language::{Buffer, BufferSnapshot, CharKind};
@@ -81,6 +81,8 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
         escaped: bool,
+        /// Whether `\u`, `\l`, `\U`, `\L` and `\E` in the replacement convert case, as in Vim.
+        case_conversions: bool,
     },
 }
 
@@ -263,6 +265,7 @@ impl SearchQuery {
             inner,
             one_match_per_line,
             escaped,
+            case_conversions: false,
         })
     }
 
@@ -372,6 +375,19 @@ impl SearchQuery {
         }
     }
 
+    /// Makes `\u`, `\l`, `\U`, `\L` and `\E` in the replacement of a regex query convert the
+    /// case of the text that follows them, as in Vim's `:s`, instead of being kept literally.
+    pub fn with_case_conversions(mut self) -> Self {
+        if let Self::Regex {
+            ref mut case_conversions,
+            ..
+        } = self
+        {
+            *case_conversions = true;
+        }
+        self
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         let mut files_to_include = self.files_to_include().sources();
         let mut files_to_exclude = self.files_to_exclude().sources();
@@ -485,8 +501,14 @@ impl SearchQuery {
                 regex,
                 replacement: Some(replacement),
                 escaped: false,
+                case_conversions,
                 ..
             } => {
+                if *case_conversions && let Some(parts) = split_case_conversions(replacement) {
+                    return Some(regex.replace(text, |captures: &Captures| {
+                        apply_case_conversions(&parts, captures)
+                    }));
+                }
                 static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: LazyLock<Regex> =
                     LazyLock::new(|| Regex::new(r"\\\\|\\n|\\t").unwrap());
                 let replacement = TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX.replace_all(
@@ -756,3 +778,94 @@ impl SearchQuery {
         matches
     }
 }
+
+/// A case conversion requested in a regex replacement with `\u`, `\l`, `\U`, `\L` or `\E`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaseConversion {
+    /// `\u`: uppercase the next character.
+    UpperNext,
+    /// `\l`: lowercase the next character.
+    LowerNext,
+    /// `\U`: uppercase everything up to `\E`.
+    Upper,
+    /// `\L`: lowercase everything up to `\E`.
+    Lower,
+    /// `\E` (or `\e`): end a `\U` or `\L`.
+    End,
+}
+
+/// Splits a replacement into templates, each preceded by the case conversion that applies to it.
+/// Returns `None` if the replacement contains no case conversions.
+fn split_case_conversions(replacement: &str) -> Option<Vec<(Option<CaseConversion>, String)>> {
+    let mut parts = vec![(None, String::new())];
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        let template = &mut parts.last_mut().unwrap().1;
+        if c != '\\' {
+            template.push(c);
+            continue;
+        }
+        let conversion = match chars.next() {
+            Some('u') => CaseConversion::UpperNext,
+            Some('l') => CaseConversion::LowerNext,
+            Some('U') => CaseConversion::Upper,
+            Some('L') => CaseConversion::Lower,
+            Some('e' | 'E') => CaseConversion::End,
+            Some('n') => {
+                template.push('\n');
+                continue;
+            }
+            Some('t') => {
+                template.push('\t');
+                continue;
+            }
+            Some('\\') => {
+                template.push('\\');
+                continue;
+            }
+            Some(c) => {
+                template.push('\\');
+                template.push(c);
+                continue;
+            }
+            None => {
+                template.push('\\');
+                continue;
+            }
+        };
+        parts.push((Some(conversion), String::new()));
+    }
+    (parts.len() > 1).then_some(parts)
+}
+
+fn apply_case_conversions(
+    parts: &[(Option<CaseConversion>, String)],
+    captures: &Captures,
+) -> String {
+    let expander = Expander::default();
+    let mut result = String::new();
+    let mut next = None;
+    let mut ongoing = None;
+    for (conversion, template) in parts {
+        match conversion {
+            Some(CaseConversion::UpperNext | CaseConversion::LowerNext) => next = *conversion,
+            Some(CaseConversion::Upper | CaseConversion::Lower) => ongoing = *conversion,
+            Some(CaseConversion::End) => ongoing = None,
+            None => {}
+        }
+        let mut expanded = String::new();
+        expander.append_expansion(&mut expanded, template, captures);
+        for c in expanded.chars() {
+            match next.take().or(ongoing) {
+                Some(CaseConversion::UpperNext | CaseConversion::Upper) => {
+                    result.extend(c.to_uppercase())
+                }
+                Some(CaseConversion::LowerNext | CaseConversion::Lower) => {
+                    result.extend(c.to_lowercase())
+                }
+                _ => result.push(c),
+            }
+        }
+    }
+    result
+}
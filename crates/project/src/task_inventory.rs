@@ -729,6 +729,18 @@ impl Inventory {
             .collect()
     }
 
+    /// Returns the debug scenarios defined in the worktree's `.zed/debug.json`, followed by the global ones.
+    pub fn debug_scenarios_from_settings(
+        &self,
+        worktree: Option<WorktreeId>,
+    ) -> Vec<(TaskSourceKind, DebugScenario)> {
+        worktree
+            .into_iter()
+            .flat_map(|worktree| self.worktree_scenarios_from_settings(worktree))
+            .chain(self.global_debug_scenarios_from_settings())
+            .collect()
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
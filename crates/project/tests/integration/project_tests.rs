@@ -16250,6 +16250,68 @@ mod disable_ai_settings_tests {
     }
 }
 
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.worktree.large_file_threshold_mb = Some(1);
+            });
+        });
+    });
+
+    // The file is decoded in one megabyte chunks, and the first one ends between a `\r` and
+    // a `\n`.
+    let padding = "x".repeat(1024 * 1024 - "fn main() {}\r\n".len() - 1);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            "small.rs": "fn main() {}\r\n",
+            "large.rs": format!("fn main() {{}}\r\n{padding}\r\nlast line\r\n"),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let (small_buffer, _small_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/root/small.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let (large_buffer, _large_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/root/large.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+    });
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.language().is_none());
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert_eq!(
+            buffer.text(),
+            format!("fn main() {{}}\n{padding}\nlast line\n")
+        );
+        assert!(!buffer.is_dirty());
+    });
+}
+
 /// Regression test for https://github.com/zed-industries/zed/issues/60424.
 ///
 /// The committed text contains repeated `end\n\n` line runs, so the deletion
@@ -329,6 +329,35 @@ messages!(
     (GetRemotes, Background),
     (GetRemotesResponse, Background),
     (Pull, Background),
+    (GitRebase, Background),
+    (GitRebaseStep, Background),
+    (GitRebaseResponse, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitApplyCommitsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTags, Background),
+    (GitBisectMark, Background),
+    (GitBisectResponse, Background),
+    (GitBisectReset, Background),
+    (GitLoadConflictStages, Background),
+    (GitConflictStagesResponse, Background),
+    (GitGetSubmodules, Background),
+    (GitSubmodulesResponse, Background),
+    (GitSubmoduleCommand, Background),
+    (GitGetCommitSignature, Background),
+    (GitCommitSignatureResponse, Background),
+    (GitFormatPatch, Background),
+    (GitPatchResponse, Background),
+    (GitCheckPatch, Background),
+    (GitCheckPatchResponse, Background),
+    (GitApplyPatch, Background),
+    (GitGetWorktreeStatuses, Background),
+    (GitWorktreeStatusesResponse, Background),
+    (GitPruneWorktrees, Background),
+    (GitMoveChangesToWorktree, Background),
+    (GitBlameRevision, Background),
     (RemoteMessageResponse, Background),
     (AskPassRequest, Background),
     (AskPassResponse, Background),
@@ -587,6 +616,26 @@ request_messages!(
     (Fetch, RemoteMessageResponse),
     (GetRemotes, GetRemotesResponse),
     (Pull, RemoteMessageResponse),
+    (GitRebase, GitRebaseResponse),
+    (GitRebaseStep, GitRebaseResponse),
+    (GitCherryPick, GitApplyCommitsResponse),
+    (GitRevert, GitApplyCommitsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTags, RemoteMessageResponse),
+    (GitBisectMark, GitBisectResponse),
+    (GitBisectReset, Ack),
+    (GitLoadConflictStages, GitConflictStagesResponse),
+    (GitGetSubmodules, GitSubmodulesResponse),
+    (GitSubmoduleCommand, RemoteMessageResponse),
+    (GitGetCommitSignature, GitCommitSignatureResponse),
+    (GitFormatPatch, GitPatchResponse),
+    (GitCheckPatch, GitCheckPatchResponse),
+    (GitApplyPatch, Ack),
+    (GitGetWorktreeStatuses, GitWorktreeStatusesResponse),
+    (GitPruneWorktrees, Ack),
+    (GitMoveChangesToWorktree, Ack),
+    (GitBlameRevision, BlameBufferResponse),
     (AskPassRequest, AskPassResponse),
     (GitCreateRemote, Ack),
     (GitRemoveRemote, Ack),
@@ -808,6 +857,26 @@ entity_messages!(
     Fetch,
     GetRemotes,
     Pull,
+    GitRebase,
+    GitRebaseStep,
+    GitCherryPick,
+    GitRevert,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTags,
+    GitBisectMark,
+    GitBisectReset,
+    GitLoadConflictStages,
+    GitGetSubmodules,
+    GitSubmoduleCommand,
+    GitGetCommitSignature,
+    GitFormatPatch,
+    GitCheckPatch,
+    GitApplyPatch,
+    GitGetWorktreeStatuses,
+    GitPruneWorktrees,
+    GitMoveChangesToWorktree,
+    GitBlameRevision,
     AskPassRequest,
     GitChangeBranch,
     GitRenameBranch,
@@ -2061,6 +2061,50 @@ async fn test_remote_reload(cx: &mut TestAppContext, server_cx: &mut TestAppCont
     });
 }
 
+#[gpui::test]
+async fn test_remote_large_file(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "small.txt": "small",
+                "large.txt": "x".repeat(1024 * 1024),
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    server_cx.update_global::<SettingsStore, _>(|settings_store, cx| {
+        settings_store
+            .set_server_settings(&json!({ "large_file_threshold_mb": 1 }).to_string(), cx)
+            .unwrap();
+    });
+    server_cx.run_until_parked();
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+
+    // The server opens the file, and decides whether it's in large file mode.
+    for (path, is_large_file) in [("small.txt", false), ("large.txt", true)] {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree_id, rel_path(path)), cx)
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.is_large_file(), is_large_file, "{path}")
+        });
+    }
+}
+
 #[gpui::test]
 async fn test_remote_resolve_path_in_buffer(
     cx: &mut TestAppContext,
@@ -59,6 +59,9 @@ use plain::TerminalOutput;
 
 pub(crate) mod user_error;
 use user_error::ErrorView;
+
+mod summary;
+pub use summary::ExecutionSummary;
 use workspace::Workspace;
 
 use crate::repl_settings::ReplSettings;
@@ -0,0 +1,222 @@
+//! # Plain Text Summaries of Kernel Outputs
+//!
+//! Collects the outputs of an execution as Markdown, for consumers that can't
+//! render them, such as agent tools. Tables are rendered like they are copied
+//! to the clipboard, tracebacks have their ANSI escapes stripped, and images
+//! are described rather than included.
+
+use std::io::Cursor;
+
+use base64::Engine as _;
+use runtimelib::{JupyterMessageContent, MimeBundle, MimeType, ReplyStatus, Stdio};
+
+use crate::outputs::html::html_to_markdown;
+use crate::outputs::image::STANDARD_INDIFFERENT;
+use crate::outputs::rank_mime_type;
+use crate::outputs::table::TableView;
+
+/// The maximum number of table rows included in a summary.
+const MAX_TABLE_ROWS: usize = 50;
+
+#[derive(Default)]
+pub struct ExecutionSummary {
+    outputs: Vec<SummaryOutput>,
+    errored: bool,
+}
+
+enum SummaryOutput {
+    Stream { stderr: bool, text: String },
+    Rendered(String),
+}
+
+impl ExecutionSummary {
+    /// Accept a Jupyter message belonging to the summarized execution.
+    pub fn push_message(&mut self, message: &JupyterMessageContent) {
+        match message {
+            JupyterMessageContent::ExecuteResult(result) => self.push_bundle(&result.data),
+            JupyterMessageContent::DisplayData(result) => self.push_bundle(&result.data),
+            JupyterMessageContent::StreamContent(result) => {
+                let is_stderr = matches!(result.name, Stdio::Stderr);
+                if let Some(SummaryOutput::Stream { stderr, text }) = self.outputs.last_mut()
+                    && *stderr == is_stderr
+                {
+                    text.push_str(&result.text);
+                } else {
+                    self.outputs.push(SummaryOutput::Stream {
+                        stderr: is_stderr,
+                        text: result.text.clone(),
+                    });
+                }
+            }
+            JupyterMessageContent::ErrorOutput(result) => {
+                self.errored = true;
+                let traceback = terminal::strip_ansi_text(result.traceback.join("\n").as_bytes());
+                self.outputs.push(SummaryOutput::Rendered(format!(
+                    "{}: {}\n```\n{}\n```",
+                    result.ename,
+                    result.evalue,
+                    traceback.trim_end()
+                )));
+            }
+            JupyterMessageContent::ExecuteReply(reply) => {
+                if matches!(reply.status, ReplyStatus::Error) {
+                    self.errored = true;
+                }
+                for payload in reply.payload.iter() {
+                    if let runtimelib::Payload::Page { data, .. } = payload {
+                        self.push_bundle(data);
+                    }
+                }
+            }
+            JupyterMessageContent::ClearOutput(_) => self.outputs.clear(),
+            _ => {}
+        }
+    }
+
+    fn push_bundle(&mut self, data: &MimeBundle) {
+        if let Some(summary) = summarize_bundle(data) {
+            self.outputs.push(SummaryOutput::Rendered(summary));
+        }
+    }
+
+    /// Whether the kernel reported an error for the execution.
+    pub fn errored(&self) -> bool {
+        self.errored
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for output in &self.outputs {
+            if !markdown.is_empty() {
+                markdown.push_str("\n\n");
+            }
+            match output {
+                SummaryOutput::Stream { stderr, text } => {
+                    let text = terminal::strip_ansi_text(text.as_bytes());
+                    if *stderr {
+                        markdown.push_str("stderr:\n");
+                    }
+                    markdown.push_str("```\n");
+                    markdown.push_str(text.trim_end());
+                    markdown.push_str("\n```");
+                }
+                SummaryOutput::Rendered(text) => markdown.push_str(text),
+            }
+        }
+        markdown
+    }
+}
+
+fn summarize_bundle(data: &MimeBundle) -> Option<String> {
+    let plain_text = data.content.iter().find_map(|media| match media {
+        MimeType::Plain(text) => Some(terminal::strip_ansi_text(text.as_bytes())),
+        _ => None,
+    });
+
+    let summary = match data.richest(rank_mime_type)? {
+        MimeType::DataTable(table) => {
+            let markdown = TableView::create_clipboard_content(table);
+            truncate_table_rows(&markdown, MAX_TABLE_ROWS)
+        }
+        MimeType::Png(image) => describe_image("PNG", image, plain_text.as_deref()),
+        MimeType::Jpeg(image) => describe_image("JPEG", image, plain_text.as_deref()),
+        MimeType::Html(html) => match html_to_markdown(html) {
+            Ok(markdown) => markdown,
+            Err(_) => plain_text.unwrap_or_else(|| html.clone()),
+        },
+        MimeType::Json(value) => format!(
+            "```json\n{}\n```",
+            serde_json::to_string_pretty(value).unwrap_or_default()
+        ),
+        MimeType::Markdown(markdown) => markdown.clone(),
+        MimeType::Plain(_) => format!("```\n{}\n```", plain_text?.trim_end()),
+        _ => "(unsupported output type)".to_string(),
+    };
+    Some(summary)
+}
+
+fn describe_image(format: &str, base64_encoded_data: &str, alt_text: Option<&str>) -> String {
+    let filtered = base64_encoded_data.replace(&[' ', '\n', '\t', '\r', '\x0b', '\x0c'][..], "");
+    let dimensions = STANDARD_INDIFFERENT
+        .decode(filtered)
+        .ok()
+        .and_then(|bytes| {
+            image::ImageReader::new(Cursor::new(bytes))
+                .with_guessed_format()
+                .ok()?
+                .into_dimensions()
+                .ok()
+        });
+
+    let mut description = match dimensions {
+        Some((width, height)) => format!("({format} image, {width}x{height})"),
+        None => format!("({format} image)"),
+    };
+    if let Some(alt_text) = alt_text.map(str::trim).filter(|text| !text.is_empty()) {
+        description.push(' ');
+        description.push_str(alt_text);
+    }
+    description
+}
+
+fn truncate_table_rows(markdown: &str, max_rows: usize) -> String {
+    // The first two lines are the header and the separator.
+    let lines = markdown.lines().collect::<Vec<_>>();
+    let row_count = lines.len().saturating_sub(2);
+    if row_count <= max_rows {
+        return markdown.trim_end().to_string();
+    }
+    let mut truncated = lines[..max_rows + 2].join("\n");
+    truncated.push_str(&format!("\n({} more rows)", row_count - max_rows));
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runtimelib::{ErrorOutput, StreamContent};
+
+    #[test]
+    fn test_summary_merges_streams_and_strips_tracebacks() {
+        let mut summary = ExecutionSummary::default();
+        for text in ["first ", "second\n"] {
+            summary.push_message(&JupyterMessageContent::StreamContent(StreamContent {
+                name: Stdio::Stdout,
+                text: text.to_string(),
+            }));
+        }
+        summary.push_message(&JupyterMessageContent::ErrorOutput(ErrorOutput {
+            ename: "NameError".to_string(),
+            evalue: "name 'x' is not defined".to_string(),
+            traceback: vec![
+                "\u{1b}[0;31mTraceback (most recent call last)\u{1b}[0m".to_string(),
+                "NameError: name 'x' is not defined".to_string(),
+            ],
+        }));
+
+        assert!(summary.errored());
+        assert_eq!(
+            summary.to_markdown(),
+            "```\nfirst second\n```\n\n\
+             NameError: name 'x' is not defined\n\
+             ```\nTraceback (most recent call last)\nNameError: name 'x' is not defined\n```"
+        );
+    }
+
+    #[test]
+    fn test_truncate_table_rows() {
+        let markdown = "| a |\n|---|\n| 1 |\n| 2 |\n| 3 |\n";
+        assert_eq!(
+            truncate_table_rows(markdown, 5),
+            "| a |\n|---|\n| 1 |\n| 2 |\n| 3 |"
+        );
+        assert_eq!(
+            truncate_table_rows(markdown, 1),
+            "| a |\n|---|\n| 1 |\n(2 more rows)"
+        );
+    }
+}
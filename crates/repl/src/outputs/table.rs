@@ -138,7 +138,7 @@ impl TableView {
         }
     }
 
-    fn create_clipboard_content(table: &TabularDataResource) -> String {
+    pub(crate) fn create_clipboard_content(table: &TabularDataResource) -> String {
         let data = match table.data.as_ref() {
             Some(data) => data,
             None => &Vec::new(),
@@ -18,6 +18,7 @@ pub use runtimelib::ExecutionState;
 
 pub use crate::jupyter_settings::JupyterSettings;
 pub use crate::kernels::{Kernel, KernelSpecification, KernelStatus, PythonEnvKernelSpecification};
+pub use crate::outputs::ExecutionSummary;
 pub use crate::repl_editor::*;
 pub use crate::repl_sessions_ui::{
     ClearCurrentOutput, ClearOutputs, Interrupt, ReplSessionsPage, Restart, Run, Sessions, Shutdown,
@@ -3,14 +3,16 @@
 use std::ops::Range;
 use std::sync::Arc;
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use editor::{Editor, MultiBufferOffset};
-use gpui::{App, Entity, WeakEntity, Window, prelude::*};
+use gpui::{App, Entity, Task, WeakEntity, Window, prelude::*};
 use language::{BufferSnapshot, Language, LanguageName, Point};
 use project::{ProjectItem as _, WorktreeId};
+use util::ResultExt as _;
 use workspace::{Workspace, notifications::NotificationId};
 
 use crate::kernels::PythonEnvKernelSpecification;
+use crate::outputs::ExecutionSummary;
 use crate::repl_store::ReplStore;
 use crate::session::SessionEvent;
 use crate::{
@@ -243,37 +245,11 @@ pub fn run(
             .active_kernelspec(project_path.worktree_id, Some(language.clone()), cx)
             .with_context(|| format!("No kernel found for language: {}", language.name()))?;
 
-        let fs = store.read(cx).fs().clone();
-
         let session = if let Some(session) = store.read(cx).get_session(editor.entity_id()).cloned()
         {
             session
         } else {
-            let weak_editor = editor.downgrade();
-            let session =
-                cx.new(|cx| Session::new(weak_editor, fs, kernel_specification, window, cx));
-
-            editor.update(cx, |_editor, cx| {
-                cx.notify();
-
-                cx.subscribe(&session, {
-                    let store = store.clone();
-                    move |_this, _session, event, cx| match event {
-                        SessionEvent::Shutdown(shutdown_event) => {
-                            store.update(cx, |store, _cx| {
-                                store.remove_session(shutdown_event.entity_id());
-                            });
-                        }
-                    }
-                })
-                .detach();
-            });
-
-            store.update(cx, |store, _cx| {
-                store.insert_session(editor.entity_id(), session.clone());
-            });
-
-            session
+            start_session(&editor, kernel_specification, window, cx)
         };
 
         let selected_text;
@@ -304,6 +280,121 @@ pub fn run(
     anyhow::Ok(())
 }
 
+/// Runs `code` in the kernel session of `editor` without displaying its outputs, starting a
+/// session for the language of the editor's buffer if there is none, and resolves to a summary
+/// of the outputs once the kernel is idle again.
+pub fn execute_captured(
+    editor: WeakEntity<Editor>,
+    code: String,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<ExecutionSummary>> {
+    let store = ReplStore::global(cx);
+    if !store.read(cx).is_enabled() {
+        return Task::ready(Err(anyhow!("The REPL is disabled in the Jupyter settings")));
+    }
+
+    let existing_session = store.read(cx).get_session(editor.entity_id()).cloned();
+    let mut refresh_kernelspecs = Vec::new();
+    if existing_session.is_none() {
+        if store
+            .read(cx)
+            .pure_jupyter_kernel_specifications()
+            .next()
+            .is_none()
+        {
+            refresh_kernelspecs.push(store.update(cx, |store, cx| store.refresh_kernelspecs(cx)));
+        }
+        let project = editor
+            .upgrade()
+            .and_then(|editor| editor.read(cx).project().cloned());
+        if let Some(project) = project
+            && let Some(worktree_id) = worktree_id_for_editor(editor.clone(), cx)
+            && !store.read(cx).has_python_kernelspecs(worktree_id)
+        {
+            refresh_kernelspecs.push(store.update(cx, |store, cx| {
+                store.refresh_python_kernelspecs(worktree_id, &project, cx)
+            }));
+        }
+    }
+
+    let window_handle = window.window_handle();
+    cx.spawn(async move |cx| {
+        let session = match existing_session {
+            Some(session) => session,
+            None => {
+                for refresh in refresh_kernelspecs {
+                    refresh.await.log_err();
+                }
+                window_handle.update(cx, |_, window, cx| {
+                    let editor = editor.upgrade().context("editor was dropped")?;
+                    let buffer = editor
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .as_singleton()
+                        .context("editor is not a singleton buffer")?;
+                    let worktree_id = buffer
+                        .read(cx)
+                        .project_path(cx)
+                        .context("buffer is not in a worktree")?
+                        .worktree_id;
+                    let language = buffer
+                        .read(cx)
+                        .language()
+                        .cloned()
+                        .context("buffer has no language")?;
+                    let kernel_specification = ReplStore::global(cx)
+                        .read(cx)
+                        .active_kernelspec(worktree_id, Some(language.clone()), cx)
+                        .with_context(|| {
+                            format!("No kernel found for language: {}", language.name())
+                        })?;
+                    anyhow::Ok(start_session(&editor, kernel_specification, window, cx))
+                })??
+            }
+        };
+
+        session
+            .update(cx, |session, cx| session.execute_captured(code, cx))
+            .await
+    })
+}
+
+fn start_session(
+    editor: &Entity<Editor>,
+    kernel_specification: KernelSpecification,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<Session> {
+    let store = ReplStore::global(cx);
+    let fs = store.read(cx).fs().clone();
+    let weak_editor = editor.downgrade();
+    let session = cx.new(|cx| Session::new(weak_editor, fs, kernel_specification, window, cx));
+
+    editor.update(cx, |_editor, cx| {
+        cx.notify();
+
+        cx.subscribe(&session, {
+            let store = store.clone();
+            move |_this, _session, event, cx| match event {
+                SessionEvent::Shutdown(shutdown_event) => {
+                    store.update(cx, |store, _cx| {
+                        store.remove_session(shutdown_event.entity_id());
+                    });
+                }
+            }
+        })
+        .detach();
+    });
+
+    store.update(cx, |store, _cx| {
+        store.insert_session(editor.entity_id(), session.clone());
+    });
+
+    session
+}
+
 pub enum SessionSupport {
     ActiveSession(Entity<Session>),
     Inactive(KernelSpecification),
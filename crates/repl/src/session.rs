@@ -7,12 +7,12 @@ use crate::{
         SshRunningKernel, WslRunningKernel,
     },
     outputs::{
-        ExecutionStatus, ExecutionView, ExecutionViewFinishedEmpty, ExecutionViewFinishedSmall,
-        InputReplyEvent,
+        ExecutionStatus, ExecutionSummary, ExecutionView, ExecutionViewFinishedEmpty,
+        ExecutionViewFinishedSmall, InputReplyEvent,
     },
     repl_settings::ReplSettings,
 };
-use anyhow::Context as _;
+use anyhow::{Context as _, anyhow};
 use collections::{HashMap, HashSet};
 use editor::SelectionEffects;
 use editor::{
@@ -29,6 +29,7 @@ use project::InlayId;
 enum ReplExecutedRange {}
 
 use futures::FutureExt as _;
+use futures::channel::oneshot;
 use gpui::{
     Context, Entity, EventEmitter, Render, Subscription, Task, WeakEntity, Window, div, prelude::*,
 };
@@ -53,6 +54,7 @@ pub struct Session {
     blocks: HashMap<String, EditorBlock>,
     result_inlays: HashMap<String, (InlayId, Range<Anchor>, usize)>,
     next_inlay_id: usize,
+    captured_executions: HashMap<String, CapturedExecution>,
 
     _subscriptions: Vec<Subscription>,
 }
@@ -64,6 +66,12 @@ struct EditorBlock {
     execution_view: Entity<ExecutionView>,
 }
 
+/// An execution whose outputs are collected instead of being shown in the editor.
+struct CapturedExecution {
+    summary: ExecutionSummary,
+    done_tx: oneshot::Sender<anyhow::Result<ExecutionSummary>>,
+}
+
 type CloseBlockFn =
     Arc<dyn for<'a> Fn(CustomBlockId, &'a mut Window, &mut App) + Send + Sync + 'static>;
 
@@ -257,6 +265,7 @@ impl Session {
             blocks: HashMap::default(),
             result_inlays: HashMap::default(),
             next_inlay_id: 0,
+            captured_executions: HashMap::default(),
             kernel_specification,
             _subscriptions: vec![subscription],
         };
@@ -394,6 +403,13 @@ impl Session {
     pub fn kernel_errored(&mut self, error_message: String, cx: &mut Context<Self>) {
         self.kernel(Kernel::ErroredLaunch(error_message.clone()), cx);
 
+        for (_, execution) in self.captured_executions.drain() {
+            execution
+                .done_tx
+                .send(Err(anyhow!("Kernel errored: {error_message}")))
+                .ok();
+        }
+
         self.blocks.values().for_each(|block| {
             block.execution_view.update(cx, |execution_view, cx| {
                 match execution_view.status {
@@ -806,6 +822,92 @@ impl Session {
         }
     }
 
+    /// Executes `code` without showing its outputs in the editor, resolving to a summary of
+    /// them once the kernel is idle again.
+    pub fn execute_captured(
+        &mut self,
+        code: String,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<ExecutionSummary>> {
+        if code.is_empty() {
+            return Task::ready(Ok(ExecutionSummary::default()));
+        }
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            allow_stdin: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        match &self.kernel {
+            Kernel::RunningKernel(_) | Kernel::StartingKernel(_) => {}
+            Kernel::ErroredLaunch(error) => {
+                return Task::ready(Err(anyhow!("Kernel failed to start: {error}")));
+            }
+            Kernel::Restarting | Kernel::ShuttingDown | Kernel::Shutdown => {
+                return Task::ready(Err(anyhow!("Kernel is not running")));
+            }
+        }
+
+        let msg_id = message.header.msg_id.clone();
+        let (done_tx, done_rx) = oneshot::channel();
+        self.captured_executions.insert(
+            msg_id.clone(),
+            CapturedExecution {
+                summary: ExecutionSummary::default(),
+                done_tx,
+            },
+        );
+
+        // Stop capturing once the caller stops waiting, such as when it times out, so that
+        // outputs of a long-running execution aren't collected forever.
+        let (waiting_tx, waiting_rx) = oneshot::channel::<()>();
+        cx.spawn(async move |this, cx| {
+            waiting_rx.await.ok();
+            this.update(cx, |session, _| session.captured_executions.remove(&msg_id))
+                .ok();
+        })
+        .detach();
+
+        match &self.kernel {
+            Kernel::StartingKernel(task) => {
+                let task = task.clone();
+                cx.spawn(async move |this, cx| {
+                    task.await;
+                    this.update(cx, |session, _| session.send_captured(message))
+                        .ok();
+                })
+                .detach();
+            }
+            _ => self.send_captured(message),
+        }
+
+        cx.background_spawn(async move {
+            let _waiting_tx = waiting_tx;
+            done_rx
+                .await
+                .context("kernel stopped before the execution finished")?
+        })
+    }
+
+    /// Sends the request of a captured execution, failing it if the kernel can't take it.
+    fn send_captured(&mut self, message: JupyterMessage) {
+        let msg_id = message.header.msg_id.clone();
+        let result = match &mut self.kernel {
+            Kernel::RunningKernel(kernel) => kernel
+                .request_tx()
+                .try_send(message)
+                .map_err(|_| anyhow!("Kernel is not accepting requests")),
+            _ => Err(anyhow!("Kernel is not running")),
+        };
+        if let Err(error) = result
+            && let Some(execution) = self.captured_executions.remove(&msg_id)
+        {
+            execution.done_tx.send(Err(error)).ok();
+        }
+    }
+
     pub fn interrupt(&mut self, cx: &mut Context<Self>) {
         match &mut self.kernel {
             Kernel::RunningKernel(_kernel) => {
@@ -838,6 +940,7 @@ impl Session {
 
     pub fn shutdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let kernel = std::mem::replace(&mut self.kernel, Kernel::ShuttingDown);
+        self.captured_executions.clear();
 
         match kernel {
             Kernel::RunningKernel(mut kernel) => {
@@ -872,6 +975,7 @@ impl Session {
 
     pub fn restart(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
+        self.captured_executions.clear();
 
         match kernel {
             Kernel::Restarting => {
@@ -1016,6 +1120,17 @@ impl KernelSession for Session {
             _ => {}
         }
 
+        if let Some(execution) = self.captured_executions.get_mut(parent_message_id) {
+            execution.summary.push_message(&message.content);
+            if let JupyterMessageContent::Status(status) = &message.content
+                && matches!(status.execution_state, ExecutionState::Idle)
+                && let Some(execution) = self.captured_executions.remove(parent_message_id)
+            {
+                execution.done_tx.send(Ok(execution.summary)).ok();
+            }
+            return;
+        }
+
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
         }
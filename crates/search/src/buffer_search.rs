@@ -92,10 +92,16 @@ pub struct BufferSearchBar {
     selection_search_enabled: Option<FilteredSearchRange>,
     scroll_handle: ScrollHandle,
     regex_language: Option<Arc<Language>>,
+    query_translator: Option<QueryTranslator>,
     splittable_editor: Option<WeakEntity<SplittableEditor>>,
     _splittable_editor_subscription: Option<Subscription>,
 }
 
+/// Converts the query typed in the search bar into the regex syntax understood by
+/// [`SearchQuery::regex`], for callers whose queries use a different pattern dialect.
+/// Replacements of translated queries also convert case with `\u`, `\l`, `\U`, `\L` and `\E`.
+pub type QueryTranslator = fn(&str) -> String;
+
 impl EventEmitter<Event> for BufferSearchBar {}
 impl EventEmitter<workspace::ToolbarItemEvent> for BufferSearchBar {}
 impl Render for BufferSearchBar {
@@ -810,6 +816,7 @@ impl BufferSearchBar {
             selection_search_enabled: None,
             scroll_handle: ScrollHandle::new(),
             regex_language: None,
+            query_translator: None,
             splittable_editor: None,
             _splittable_editor_subscription: None,
         }
@@ -1249,6 +1256,14 @@ impl BufferSearchBar {
         cx.notify();
     }
 
+    /// Sets the translator applied to regex queries before they are compiled.
+    pub fn set_query_translator(&mut self, translator: Option<QueryTranslator>) {
+        if self.query_translator.is_some() != translator.is_some() {
+            self.active_search.take();
+        }
+        self.query_translator = translator;
+    }
+
     pub fn clear_search_within_ranges(
         &mut self,
         search_options: SearchOptions,
@@ -1527,8 +1542,12 @@ impl BufferSearchBar {
                     // Value doesn't matter, we only construct empty matchers with it
 
                     if self.search_options.contains(SearchOptions::REGEX) {
+                        let pattern = match self.query_translator {
+                            Some(translate) => translate(&query),
+                            None => query,
+                        };
                         match SearchQuery::regex(
-                            query,
+                            pattern,
                             self.search_options.contains(SearchOptions::WHOLE_WORD),
                             self.search_options.contains(SearchOptions::CASE_SENSITIVE),
                             false,
@@ -1539,6 +1558,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
+                            Ok(query) if self.query_translator.is_some() => query
+                                .with_replacement(self.replacement(cx))
+                                .with_case_conversions(),
                             Ok(query) => query.with_replacement(self.replacement(cx)),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
@@ -1571,7 +1593,8 @@ impl BufferSearchBar {
                 };
 
                 self.active_search = Some(query.clone());
-                let query_text = query.as_str().to_string();
+                // The history keeps the query as typed, before any translation.
+                let query_text = self.query(cx);
 
                 let matches_with_token =
                     active_searchable_item.find_matches_with_token(query, window, cx);
@@ -3176,6 +3199,71 @@ mod tests {
         .await;
     }
 
+    #[perf]
+    #[gpui::test]
+    async fn test_replace_case_conversion(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        // Without a query translator, case conversions are kept literally.
+        run_replacement_test(ReplacementTestParams {
+            editor: &editor,
+            search_bar: &search_bar,
+            cx,
+            search_text: r"(regular) (expression)",
+            search_options: Some(SearchOptions::REGEX),
+            replacement_text: r"C:\Users\lib \u$1 \L$2\E",
+            replace_all: true,
+            expected_text: r#"
+            A C:\Users\lib \uregular \Lexpression\E (shortened as regex or regexp;[1] also referred to as
+            rational expression[2][3]) is a sequence of characters that specifies a search
+            pattern in text. Usually such patterns are used by string-searching algorithms
+            for "find" or "find and replace" operations on strings, or for input validation.
+            "#
+            .unindent(),
+        })
+        .await;
+
+        search_bar.update(cx, |search_bar, _| {
+            search_bar.set_query_translator(Some(|query| query.to_string()));
+        });
+
+        run_replacement_test(ReplacementTestParams {
+            editor: &editor,
+            search_bar: &search_bar,
+            cx,
+            search_text: r"(rational) (expression)",
+            search_options: Some(SearchOptions::REGEX),
+            replacement_text: r"\u$1 \U$2\E!",
+            replace_all: true,
+            expected_text: r#"
+            A C:\Users\lib \uregular \Lexpression\E (shortened as regex or regexp;[1] also referred to as
+            Rational EXPRESSION![2][3]) is a sequence of characters that specifies a search
+            pattern in text. Usually such patterns are used by string-searching algorithms
+            for "find" or "find and replace" operations on strings, or for input validation.
+            "#
+            .unindent(),
+        })
+        .await;
+
+        run_replacement_test(ReplacementTestParams {
+            editor: &editor,
+            search_bar: &search_bar,
+            cx,
+            search_text: r"Usually (\w+)",
+            search_options: Some(SearchOptions::REGEX),
+            replacement_text: r"\L$0",
+            replace_all: true,
+            expected_text: r#"
+            A C:\Users\lib \uregular \Lexpression\E (shortened as regex or regexp;[1] also referred to as
+            Rational EXPRESSION![2][3]) is a sequence of characters that specifies a search
+            pattern in text. usually such patterns are used by string-searching algorithms
+            for "find" or "find and replace" operations on strings, or for input validation.
+            "#
+            .unindent(),
+        })
+        .await;
+    }
+
     #[gpui::test]
     async fn test_deploy_replace_focuses_replacement_editor(cx: &mut TestAppContext) {
         init_globals(cx);
@@ -215,6 +215,7 @@ impl VsCodeSettings {
             repl: None,
             server_url: None,
             session: None,
+            spell_check: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -526,6 +527,7 @@ impl VsCodeSettings {
             context_server_timeout: None,
             load_direnv: None,
             git_hosting_providers: None,
+            git_commit_signing: None,
             disable_ai: None,
         }
     }
@@ -1126,6 +1128,7 @@ impl VsCodeSettings {
                         .collect::<Vec<_>>()
                 })
                 .filter(|r| !r.is_empty()),
+            large_file_threshold_mb: None,
         }
     }
 }
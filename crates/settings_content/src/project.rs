@@ -81,6 +81,9 @@ pub struct ProjectSettingsContent {
     /// The list of custom Git hosting providers.
     pub git_hosting_providers: Option<ExtendingVec<GitHostingProviderConfig>>,
 
+    /// How commits made from Zed are signed.
+    pub git_commit_signing: Option<CommitSigningSettingsContent>,
+
     /// Whether to disable all AI features in Zed.
     ///
     /// Default: false
@@ -162,6 +165,13 @@ pub struct WorktreeSettingsContent {
     /// external dependencies that should not be modified directly.
     /// Default: []
     pub read_only_files: Option<Vec<String>>,
+
+    /// Files at least this many megabytes large are opened in large file mode, which reads them
+    /// from disk in chunks and turns off syntax highlighting, language servers and git diffs for
+    /// them. The whole file is still loaded into memory.
+    /// Set to 0 to never open files in large file mode.
+    /// Default: 64
+    pub large_file_threshold_mb: Option<u64>,
 }
 
 #[with_fallible_options]
@@ -683,13 +693,48 @@ pub struct InlineBlameSettings {
 }
 
 #[with_fallible_options]
-#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 #[serde(rename_all = "snake_case")]
 pub struct BlameSettings {
     /// Whether to show the avatar of the author of the commit.
     ///
     /// Default: true
     pub show_avatar: Option<bool>,
+    /// Additional files listing revisions that blame should skip, relative to
+    /// the repository root. `.git-blame-ignore-revs` is always used when it exists.
+    ///
+    /// Default: []
+    pub ignore_revs_files: Option<Vec<String>>,
+    /// Whether blame should look past lines that were moved or copied.
+    ///
+    /// Default: off
+    pub detect_moves: Option<BlameMoveDetection>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BlameMoveDetection {
+    /// Attribute every line to the commit that last touched it.
+    #[default]
+    Off,
+    /// Follow lines moved or copied within the same file (`git blame -M`).
+    WithinFile,
+    /// Also follow lines moved or copied from other files changed in the same
+    /// commit (`git blame -C`).
+    AcrossFiles,
 }
 
 #[with_fallible_options]
@@ -702,6 +747,80 @@ pub struct BranchPickerSettingsContent {
     pub show_author_name: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub struct CommitSigningSettingsContent {
+    /// Whether to sign commits.
+    ///
+    /// Default: git_config
+    pub sign_commits: Option<SignCommitsSetting>,
+    /// The format of the signature, overriding git's `gpg.format`.
+    ///
+    /// Default: git_config
+    pub format: Option<CommitSigningFormat>,
+    /// The key to sign commits with, overriding git's `user.signingKey`. For
+    /// SSH signing this is the path to a public key or the key itself.
+    ///
+    /// Default: null
+    pub key: Option<String>,
+    /// Whether to always add a `Signed-off-by` trailer to commits, even when
+    /// the git panel's sign-off toggle is off.
+    ///
+    /// Default: false
+    pub signoff: Option<bool>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SignCommitsSetting {
+    /// Sign commits when git's `commit.gpgSign` is set.
+    #[default]
+    GitConfig,
+    /// Always sign commits.
+    Always,
+    /// Never sign commits.
+    Never,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitSigningFormat {
+    /// Use the format set by git's `gpg.format`.
+    #[default]
+    GitConfig,
+    /// Sign with GPG.
+    Openpgp,
+    /// Sign with an SSH key.
+    Ssh,
+    /// Sign with an X.509 certificate through gpgsm.
+    X509,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 #[serde(rename_all = "snake_case")]
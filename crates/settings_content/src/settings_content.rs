@@ -280,6 +280,9 @@ pub struct SettingsContent {
     /// Settings for the which-key popup.
     pub which_key: Option<WhichKeySettingsContent>,
 
+    /// Settings for the built-in spell checker.
+    pub spell_check: Option<SpellCheckSettingsContent>,
+
     /// Settings related to Vim mode in Zed.
     pub vim: Option<VimSettingsContent>,
 
@@ -1371,6 +1374,26 @@ pub struct WhichKeySettingsContent {
     pub delay_ms: Option<u64>,
 }
 
+/// Settings for the built-in spell checker.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to underline misspelled words in comments, strings and prose files.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The names of the Hunspell dictionaries to check words against. Each one is
+    /// looked up in the `dictionaries` folder of the config directory first, and
+    /// then among the dictionaries bundled with Zed.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Languages whose whole text is checked, rather than only their comments
+    /// and strings.
+    ///
+    /// Default: ["Markdown", "Markdown-Inline", "Plain Text", "Git Commit"]
+    pub prose_languages: Option<Vec<String>>,
+}
+
 // An ExtendingVec in the settings can only accumulate new values.
 //
 // This is useful for things like private files where you only want
@@ -1769,6 +1769,31 @@ fn editor_page() -> SettingsPage {
         ]
     }
 
+    fn spell_check_section() -> [SettingsPageItem; 2] {
+        [
+            SettingsPageItem::SectionHeader("Spell Check"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Check Spelling",
+                description: "Underline misspelled words in comments, strings and prose files.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("spell_check.enabled"),
+                    pick: |settings_content| {
+                        settings_content
+                            .spell_check
+                            .as_ref()
+                            .and_then(|settings| settings.enabled.as_ref())
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content.spell_check.get_or_insert_default().enabled = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn multibuffer_section() -> [SettingsPageItem; 7] {
         [
             SettingsPageItem::SectionHeader("Multibuffer"),
@@ -3148,6 +3173,7 @@ fn editor_page() -> SettingsPage {
     let items = concat_sections!(
         auto_save_section(),
         which_key_section(),
+        spell_check_section(),
         multibuffer_section(),
         scrolling_section(),
         signature_help_section(),
@@ -7815,7 +7841,7 @@ fn version_control_page() -> SettingsPage {
         ]
     }
 
-    fn git_blame_view_section() -> [SettingsPageItem; 2] {
+    fn git_blame_view_section() -> [SettingsPageItem; 3] {
         [
             SettingsPageItem::SectionHeader("Git Blame View"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -7845,6 +7871,33 @@ fn version_control_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Detect Moves",
+                description: "Whether blame should look past lines that were moved or copied.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("git.blame.detect_moves"),
+                    pick: |settings_content| {
+                        settings_content
+                            .git
+                            .as_ref()?
+                            .blame
+                            .as_ref()?
+                            .detect_moves
+                            .as_ref()
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .git
+                            .get_or_insert_default()
+                            .blame
+                            .get_or_insert_default()
+                            .detect_moves = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
         ]
     }
 
@@ -7985,6 +8038,112 @@ fn version_control_page() -> SettingsPage {
         ]
     }
 
+    fn commit_signing_section() -> [SettingsPageItem; 5] {
+        [
+            SettingsPageItem::SectionHeader("Commit Signing"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Sign Commits",
+                description: "Whether to sign commits, or follow git's commit.gpgSign.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("git_commit_signing.sign_commits"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .as_ref()?
+                            .sign_commits
+                            .as_ref()
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .get_or_insert_default()
+                            .sign_commits = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Signing Format",
+                description: "The format of commit signatures, or git's gpg.format.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("git_commit_signing.format"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .as_ref()?
+                            .format
+                            .as_ref()
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .get_or_insert_default()
+                            .format = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Signing Key",
+                description: "The key to sign commits with, overriding git's user.signingKey.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("git_commit_signing.key"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .as_ref()?
+                            .key
+                            .as_ref()
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .get_or_insert_default()
+                            .key = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Always Sign Off",
+                description: "Whether to always add a Signed-off-by trailer to commits.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("git_commit_signing.signoff"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .as_ref()?
+                            .signoff
+                            .as_ref()
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .project
+                            .git_commit_signing
+                            .get_or_insert_default()
+                            .signoff = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+        ]
+    }
+
     SettingsPage {
         title: "Version Control",
         items: concat_sections![
@@ -7995,6 +8154,7 @@ fn version_control_page() -> SettingsPage {
             branch_picker_section(),
             file_diff_section(),
             git_hunks_section(),
+            commit_signing_section(),
         ],
     }
 }
@@ -36,8 +36,9 @@ pub(crate) use skills_setup::render_skills_setup_page;
 pub(crate) use tool_permissions_setup::render_tool_permissions_setup_page;
 
 pub use tool_permissions_setup::{
-    render_copy_path_tool_config, render_create_directory_tool_config,
-    render_delete_path_tool_config, render_edit_file_tool_config, render_fetch_tool_config,
-    render_move_path_tool_config, render_skill_tool_config, render_terminal_tool_config,
-    render_web_search_tool_config, render_write_file_tool_config,
+    render_copy_path_tool_config, render_create_directory_tool_config, render_debugger_tool_config,
+    render_delete_path_tool_config, render_edit_file_tool_config, render_execute_code_tool_config,
+    render_fetch_tool_config, render_move_path_tool_config, render_run_task_tool_config,
+    render_skill_tool_config, render_terminal_tool_config, render_web_search_tool_config,
+    render_write_file_tool_config,
 };
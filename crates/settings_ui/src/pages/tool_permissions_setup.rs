@@ -80,6 +80,24 @@ const TOOLS: &[ToolInfo] = &[
         description: "Loading agent skill instructions",
         regex_explanation: "Patterns are matched against the absolute path to the skill's SKILL.md file.",
     },
+    ToolInfo {
+        id: "debugger",
+        name: "Debugger",
+        description: "Starting debug sessions and evaluating expressions in them",
+        regex_explanation: "Patterns are matched against the debug scenario label when starting a session, and against the expression when evaluating.",
+    },
+    ToolInfo {
+        id: "run_task",
+        name: "Run Task",
+        description: "Project tasks and runnables, such as tests",
+        regex_explanation: "Patterns are matched against the resolved command line of the task being run.",
+    },
+    ToolInfo {
+        id: "execute_code",
+        name: "Execute Code",
+        description: "Running code in REPL kernels",
+        regex_explanation: "Patterns are matched against the code being run.",
+    },
 ];
 
 pub(crate) struct ToolInfo {
@@ -312,6 +330,9 @@ fn get_tool_render_fn(
         "fetch" => render_fetch_tool_config,
         "search_web" => render_web_search_tool_config,
         "skill" => render_skill_tool_config,
+        "debugger" => render_debugger_tool_config,
+        "run_task" => render_run_task_tool_config,
+        "execute_code" => render_execute_code_tool_config,
         _ => render_terminal_tool_config, // fallback
     }
 }
@@ -1390,6 +1411,9 @@ tool_config_page_fn!(render_create_directory_tool_config, "create_directory");
 tool_config_page_fn!(render_fetch_tool_config, "fetch");
 tool_config_page_fn!(render_web_search_tool_config, "search_web");
 tool_config_page_fn!(render_skill_tool_config, "skill");
+tool_config_page_fn!(render_debugger_tool_config, "debugger");
+tool_config_page_fn!(render_run_task_tool_config, "run_task");
+tool_config_page_fn!(render_execute_code_tool_config, "execute_code");
 
 #[cfg(test)]
 mod tests {
@@ -1408,6 +1432,7 @@ mod tests {
             "find_path",
             "find_references",
             "get_code_actions",
+            "git_history",
             "go_to_definition",
             "grep",
             "list_agents_and_models",
@@ -1415,6 +1440,7 @@ mod tests {
             "open",
             "read_file",
             "rename_symbol",
+            "symbol_info",
             "thinking",
             // streaming_edit_file uses "edit_file" for permission lookups,
             // so its rules are configured under the edit_file entry.
@@ -561,7 +561,10 @@ fn init_renderers(cx: &mut App) {
         .add_basic_renderer::<settings::GitHunkStyleSetting>(render_dropdown)
         .add_basic_renderer::<settings::GitDiffBaseSetting>(render_dropdown)
         .add_basic_renderer::<settings::GitPathStyle>(render_dropdown)
+        .add_basic_renderer::<settings::SignCommitsSetting>(render_dropdown)
+        .add_basic_renderer::<settings::CommitSigningFormat>(render_dropdown)
         .add_basic_renderer::<settings::InlineBlameLocation>(render_dropdown)
+        .add_basic_renderer::<settings::BlameMoveDetection>(render_dropdown)
         .add_basic_renderer::<settings::DiagnosticSeverityContent>(render_dropdown)
         .add_basic_renderer::<settings::SeedQuerySetting>(render_dropdown)
         .add_basic_renderer::<settings::DoubleClickInMultibuffer>(render_dropdown)
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use editor::{
+    Editor, EditorEvent,
+    actions::{SortLines, ToggleSortLinesPrompt},
+};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription,
+    WeakEntity, div, prelude::*,
+};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::ModalView;
+
+const HELP_TEXT: &str = "i: ignore case, n: numeric, v: natural, !: descending, u: unique, \
+    k2: by field 2, t,: fields split by `,`, /re/: text after a match, r: the match itself";
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(SortLinesPrompt::register).detach();
+}
+
+/// Prompts for sort options, written as flags like those of Vim's `:sort`, and sorts the
+/// selected lines of an editor with them.
+pub struct SortLinesPrompt {
+    flags_editor: Entity<Editor>,
+    active_editor: WeakEntity<Editor>,
+    _subscription: Subscription,
+}
+
+impl ModalView for SortLinesPrompt {}
+
+impl Focusable for SortLinesPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.flags_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SortLinesPrompt {}
+
+impl SortLinesPrompt {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let handle = cx.entity().downgrade();
+        editor
+            .register_action(move |_: &ToggleSortLinesPrompt, window, cx| {
+                let Some(editor) = handle.upgrade() else {
+                    return;
+                };
+                if editor.read(cx).read_only(cx) {
+                    return;
+                }
+                let Some(workspace) = editor.read(cx).workspace() else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        SortLinesPrompt::new(editor.downgrade(), window, cx)
+                    });
+                })
+            })
+            .detach();
+    }
+
+    fn new(active_editor: WeakEntity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let flags_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                "Sort options, such as \"n\", \"k2 t,\" or \"i /pattern/\"",
+                window,
+                cx,
+            );
+            editor
+        });
+        let subscription = cx.subscribe_in(&flags_editor, window, Self::on_flags_editor_event);
+        Self {
+            flags_editor,
+            active_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn on_flags_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            EditorEvent::Blurred if window.is_window_active() => cx.emit(DismissEvent),
+            EditorEvent::BufferEdited => cx.notify(),
+            _ => {}
+        }
+    }
+
+    fn sort_lines(&self, cx: &App) -> Result<SortLines> {
+        let flags = self.flags_editor.read(cx).text(cx);
+        SortLines::parse_flags(&flags, ToString::to_string)
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        // Invalid options stay in the prompt, which shows why they are invalid.
+        let Ok(action) = self.sort_lines(cx) else {
+            return;
+        };
+        if let Some(editor) = self.active_editor.upgrade() {
+            editor.update(cx, |editor, cx| {
+                editor.sort_lines(&action, window, cx);
+                editor.focus_handle(cx).focus(window, cx);
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SortLinesPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help = match self.sort_lines(cx) {
+            Ok(_) => Label::new(HELP_TEXT).color(Color::Muted),
+            Err(error) => Label::new(format!("{error:#}")).color(Color::Error),
+        };
+
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("SortLinesPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.flags_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().child(help))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::sync::Arc;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace, Workspace};
+
+    #[gpui::test]
+    async fn test_sort_lines_prompt(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"a.txt": "c,10\na,9\nb,100\nd,9"}))
+            .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        editor.update_in(cx, |editor, window, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx)
+        });
+
+        let prompt = open_sort_lines_prompt(&workspace, cx);
+        cx.simulate_input("x");
+        prompt.read_with(cx, |prompt, cx| assert!(prompt.sort_lines(cx).is_err()));
+
+        // Confirming invalid options keeps the prompt open.
+        cx.dispatch_action(menu::Confirm);
+        assert!(workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<SortLinesPrompt>(cx).is_some()
+        }));
+
+        prompt.update_in(cx, |prompt, window, cx| {
+            prompt.flags_editor.update(cx, |flags_editor, cx| {
+                flags_editor.set_text("t, k2n k1!", window, cx)
+            })
+        });
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<SortLinesPrompt>(cx).is_none()
+        }));
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "d,9\na,9\nc,10\nb,100"
+        );
+    }
+
+    fn open_sort_lines_prompt(
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<SortLinesPrompt> {
+        cx.dispatch_action(ToggleSortLinesPrompt);
+        workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<SortLinesPrompt>(cx).unwrap()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            crate::init(cx);
+            editor::init(cx);
+            state
+        })
+    }
+}
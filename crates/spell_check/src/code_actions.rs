@@ -0,0 +1,180 @@
+//! Code actions that correct a misspelled word, or teach it to the spell
+//! checker.
+
+use crate::{Misspellings, SpellChecker, project_root};
+use anyhow::{Context as _, Result, anyhow};
+use editor::CodeActionProvider;
+use gpui::{App, Entity, Task, Window};
+use language::{Buffer, OffsetRangeExt as _};
+use lsp::LanguageServerId;
+use project::{CodeAction, LspAction, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, sync::Arc};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckAction {
+    Replace { replacement: String },
+    AddToUserDictionary { word: String },
+    AddToProjectDictionary { word: String },
+}
+
+pub(crate) struct SpellCheckCodeActionProvider {
+    spell_checker: Entity<SpellChecker>,
+    misspellings: Misspellings,
+}
+
+impl SpellCheckCodeActionProvider {
+    pub(crate) fn new(spell_checker: Entity<SpellChecker>, misspellings: Misspellings) -> Self {
+        Self {
+            spell_checker,
+            misspellings,
+        }
+    }
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let range = range.to_offset(&snapshot);
+        let misspelling = self
+            .misspellings
+            .borrow()
+            .get(&snapshot.remote_id())
+            .and_then(|misspellings| {
+                misspellings
+                    .ranges
+                    .iter()
+                    .find(|misspelling| {
+                        let misspelling = misspelling.to_offset(&snapshot);
+                        misspelling.start <= range.end && misspelling.end >= range.start
+                    })
+                    .cloned()
+            });
+        let Some(misspelling) = misspelling else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let word = snapshot
+            .text_for_range(misspelling.clone())
+            .collect::<String>();
+        let project_root = project_root(buffer, cx);
+        let has_project = project_root.is_some();
+        let speller = self.spell_checker.update(cx, |spell_checker, cx| {
+            spell_checker.speller(project_root, cx)
+        });
+        cx.background_spawn(async move {
+            let mut actions = speller
+                .suggest(&word)
+                .into_iter()
+                .map(|replacement| {
+                    (
+                        format!("Change to \"{replacement}\""),
+                        SpellCheckAction::Replace { replacement },
+                    )
+                })
+                .collect::<Vec<_>>();
+            actions.push((
+                format!("Add \"{word}\" to user dictionary"),
+                SpellCheckAction::AddToUserDictionary { word: word.clone() },
+            ));
+            if has_project {
+                actions.push((
+                    format!("Add \"{word}\" to project dictionary"),
+                    SpellCheckAction::AddToProjectDictionary { word },
+                ));
+            }
+
+            actions
+                .into_iter()
+                .map(|(title, action)| {
+                    Ok(CodeAction {
+                        server_id: LanguageServerId(usize::MAX),
+                        range: misspelling.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title,
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: Some(serde_json::to_value(action)?),
+                            ..lsp::CodeAction::default()
+                        })),
+                        resolved: true,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer_handle: Entity<Buffer>,
+        action: CodeAction,
+        push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = match &action.lsp_action {
+            LspAction::Action(lsp_action) => lsp_action
+                .data
+                .clone()
+                .context("spell check code action has no data")
+                .and_then(|data| Ok(serde_json::from_value::<SpellCheckAction>(data)?)),
+            _ => Err(anyhow!("unexpected spell check code action")),
+        };
+        let spell_check_action = match spell_check_action {
+            Ok(spell_check_action) => spell_check_action,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace { replacement } => {
+                let transaction = buffer_handle.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    let transaction_id = buffer.end_transaction(cx)?;
+                    let transaction = buffer.finalize_last_transaction().cloned();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction_id);
+                    }
+                    transaction
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer_handle, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckAction::AddToUserDictionary { word } => {
+                let add_word = self.spell_checker.update(cx, |spell_checker, cx| {
+                    spell_checker.add_word(word, None, cx)
+                });
+                cx.background_spawn(async move {
+                    add_word.await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+            SpellCheckAction::AddToProjectDictionary { word } => {
+                let Some(project_root) = project_root(&buffer_handle, cx) else {
+                    return Task::ready(Err(anyhow!("buffer is not part of a local project")));
+                };
+                let add_word = self.spell_checker.update(cx, |spell_checker, cx| {
+                    spell_checker.add_word(word, Some(project_root), cx)
+                });
+                cx.background_spawn(async move {
+                    add_word.await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,735 @@
+//! A reader for Hunspell dictionaries.
+//!
+//! Only the parts of the `.aff` format that matter for checking single words are
+//! supported: flag formats and aliases, prefixes and suffixes (including cross
+//! products), `TRY` and `REP` for suggestions, and the `FORBIDDENWORD`,
+//! `NEEDAFFIX`, `NOSUGGEST` and `ONLYINCOMPOUND` flags. Compounding rules are
+//! ignored, so compound-only entries are never accepted on their own.
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::{HashMap, HashSet};
+
+/// The maximum number of suggestions returned for a misspelled word.
+pub const MAX_SUGGESTIONS: usize = 5;
+
+type Flag = u32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FlagFormat {
+    /// Every character is a flag.
+    #[default]
+    Single,
+    /// Every two characters are a flag.
+    Long,
+    /// Flags are comma-separated decimal numbers.
+    Numeric,
+}
+
+impl FlagFormat {
+    fn parse(self, flags: &str) -> Result<Vec<Flag>> {
+        let mut parsed = match self {
+            FlagFormat::Single => flags.chars().map(Flag::from).collect::<Vec<_>>(),
+            FlagFormat::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+                if chars.len() % 2 != 0 {
+                    bail!("long flags {flags:?} have an odd length");
+                }
+                chars
+                    .chunks(2)
+                    .map(|pair| (Flag::from(pair[0]) << 16) | Flag::from(pair[1]))
+                    .collect()
+            }
+            FlagFormat::Numeric => flags
+                .split(',')
+                .map(|flag| {
+                    flag.trim()
+                        .parse::<Flag>()
+                        .with_context(|| format!("invalid numeric flag {flag:?}"))
+                })
+                .collect::<Result<_>>()?,
+        };
+        parsed.sort_unstable();
+        parsed.dedup();
+        Ok(parsed)
+    }
+
+    fn parse_single(self, flag: &str) -> Result<Flag> {
+        match self.parse(flag)?.as_slice() {
+            [flag] => Ok(*flag),
+            _ => Err(anyhow!("expected a single flag, got {flag:?}")),
+        }
+    }
+}
+
+/// One character of an affix condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ConditionItem {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl ConditionItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionItem::Any => true,
+            ConditionItem::Char(expected) => *expected == c,
+            ConditionItem::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// The simplified regular expression that a stem must match for an affix to
+/// apply to it, like `[^aeiou]y`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Condition(Vec<ConditionItem>);
+
+impl Condition {
+    fn parse(condition: &str) -> Result<Self> {
+        if condition == "." {
+            return Ok(Self::default());
+        }
+
+        let mut items = Vec::new();
+        let mut chars = condition.chars();
+        while let Some(c) = chars.next() {
+            let item = match c {
+                '.' => ConditionItem::Any,
+                '[' => {
+                    let mut set = Vec::new();
+                    let mut negated = false;
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        match c {
+                            ']' => {
+                                closed = true;
+                                break;
+                            }
+                            '^' if set.is_empty() && !negated => negated = true,
+                            c => set.push(c),
+                        }
+                    }
+                    if !closed {
+                        bail!("unterminated set in condition {condition:?}");
+                    }
+                    ConditionItem::Set {
+                        chars: set,
+                        negated,
+                    }
+                }
+                c => ConditionItem::Char(c),
+            };
+            items.push(item);
+        }
+        Ok(Self(items))
+    }
+
+    fn matches_start(&self, stem: &str) -> bool {
+        let mut chars = stem.chars();
+        self.0
+            .iter()
+            .all(|item| chars.next().is_some_and(|c| item.matches(c)))
+    }
+
+    fn matches_end(&self, stem: &str) -> bool {
+        let mut chars = stem.chars().rev();
+        self.0
+            .iter()
+            .rev()
+            .all(|item| chars.next().is_some_and(|c| item.matches(c)))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Condition,
+    continuation: Vec<Flag>,
+}
+
+/// Affixes indexed by the character they add at the edge of a word, so that
+/// only the plausible ones are tried for each word.
+#[derive(Debug, Default)]
+struct AffixIndex {
+    affixes: Vec<Affix>,
+    by_edge: HashMap<char, Vec<usize>>,
+    empty: Vec<usize>,
+}
+
+impl AffixIndex {
+    fn build(affixes: Vec<Affix>, edge: impl Fn(&str) -> Option<char>) -> Self {
+        let mut by_edge = HashMap::<char, Vec<usize>>::default();
+        let mut empty = Vec::new();
+        for (ix, affix) in affixes.iter().enumerate() {
+            match edge(&affix.add) {
+                Some(c) => by_edge.entry(c).or_default().push(ix),
+                None => empty.push(ix),
+            }
+        }
+        Self {
+            affixes,
+            by_edge,
+            empty,
+        }
+    }
+
+    fn candidates(&self, edge: Option<char>) -> impl Iterator<Item = &Affix> {
+        edge.and_then(|c| self.by_edge.get(&c))
+            .into_iter()
+            .flatten()
+            .chain(&self.empty)
+            .map(|ix| &self.affixes[*ix])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Title,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Casing::Lower;
+        };
+        let (mut upper, mut lower) = (0, 0);
+        for c in chars {
+            if c.is_uppercase() {
+                upper += 1;
+            } else if c.is_lowercase() {
+                lower += 1;
+            }
+        }
+        match (first.is_uppercase(), upper, lower) {
+            (false, 0, _) => Casing::Lower,
+            (true, 0, _) => Casing::Title,
+            (true, _, 0) => Casing::Upper,
+            _ => Casing::Mixed,
+        }
+    }
+
+    fn apply(self, word: &str) -> String {
+        match self {
+            Casing::Title => title_case(word),
+            Casing::Upper => word.to_uppercase(),
+            Casing::Lower | Casing::Mixed => word.to_string(),
+        }
+    }
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn has_flag(flags: &[Flag], flag: Option<Flag>) -> bool {
+    flag.is_some_and(|flag| flags.binary_search(&flag).is_ok())
+}
+
+/// A parsed Hunspell dictionary, made of an affix file and a word list.
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: AffixIndex,
+    suffixes: AffixIndex,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    word_chars: HashSet<char>,
+    forbidden_flag: Option<Flag>,
+    need_affix_flag: Option<Flag>,
+    no_suggest_flag: Option<Flag>,
+    only_in_compound_flag: Option<Flag>,
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn new(aff: &str, dic: &str) -> Result<Self> {
+        let mut dictionary = Self::default();
+        let mut flag_format = FlagFormat::default();
+        let mut aliases = Vec::<Vec<Flag>>::new();
+        let mut affix_headers = HashSet::<(bool, Flag)>::default();
+        let mut cross_products = HashMap::<(bool, Flag), bool>::default();
+        let mut prefixes = Vec::new();
+        let mut suffixes = Vec::new();
+        let mut single_flags = Vec::new();
+
+        for (row, line) in aff.lines().enumerate() {
+            let line = line.trim_start_matches('\u{feff}');
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let Some((&directive, args)) = fields.split_first() else {
+                continue;
+            };
+            let result = (|| -> Result<()> {
+                match directive {
+                    "FLAG" => {
+                        flag_format = match args.first().copied() {
+                            Some("long") => FlagFormat::Long,
+                            Some("num") => FlagFormat::Numeric,
+                            Some("UTF-8") => FlagFormat::Single,
+                            other => bail!("unknown flag format {other:?}"),
+                        }
+                    }
+                    "TRY" => dictionary.try_chars = args.concat().chars().collect(),
+                    "WORDCHARS" => dictionary.word_chars = args.concat().chars().collect(),
+                    "FORBIDDENWORD" | "NEEDAFFIX" | "PSEUDOROOT" | "NOSUGGEST"
+                    | "ONLYINCOMPOUND" => {
+                        let flag = args.first().context("missing flag")?;
+                        single_flags.push((directive, flag.to_string()));
+                    }
+                    "AF" => {
+                        // The first line only holds the alias count. Aliases are
+                        // numbered from one, so it doubles as the unused zeroth alias.
+                        if aliases.is_empty() {
+                            aliases.push(Vec::new());
+                        } else {
+                            let flags = args.first().context("missing alias flags")?;
+                            aliases.push(flag_format.parse(flags)?);
+                        }
+                    }
+                    "REP" => {
+                        if let [from, to, ..] = args {
+                            dictionary
+                                .replacements
+                                .push((from.replace('_', " "), to.replace('_', " ")));
+                        }
+                    }
+                    "PFX" | "SFX" => {
+                        let is_prefix = directive == "PFX";
+                        let [flag, rest @ ..] = args else {
+                            bail!("missing affix flag");
+                        };
+                        let flag = flag_format.parse_single(flag)?;
+                        if affix_headers.insert((is_prefix, flag)) {
+                            cross_products.insert((is_prefix, flag), rest.first() == Some(&"Y"));
+                            return Ok(());
+                        }
+
+                        let [strip, add, condition @ ..] = rest else {
+                            bail!("incomplete affix entry");
+                        };
+                        let (add, continuation) = match add.split_once('/') {
+                            Some((add, flags)) => {
+                                (add, resolve_flags(flags, flag_format, &aliases)?)
+                            }
+                            None => (*add, Vec::new()),
+                        };
+                        let affix = Affix {
+                            flag,
+                            cross_product: cross_products[&(is_prefix, flag)],
+                            strip: if *strip == "0" {
+                                String::new()
+                            } else {
+                                strip.to_string()
+                            },
+                            add: if add == "0" {
+                                String::new()
+                            } else {
+                                add.to_string()
+                            },
+                            condition: Condition::parse(condition.first().unwrap_or(&"."))?,
+                            continuation,
+                        };
+                        if is_prefix {
+                            prefixes.push(affix);
+                        } else {
+                            suffixes.push(affix);
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(())
+            })();
+            result.with_context(|| format!("invalid affix file line {}: {line:?}", row + 1))?;
+        }
+
+        for (directive, flag) in single_flags {
+            let flag = Some(flag_format.parse_single(&flag)?);
+            match directive {
+                "FORBIDDENWORD" => dictionary.forbidden_flag = flag,
+                "NEEDAFFIX" | "PSEUDOROOT" => dictionary.need_affix_flag = flag,
+                "NOSUGGEST" => dictionary.no_suggest_flag = flag,
+                "ONLYINCOMPOUND" => dictionary.only_in_compound_flag = flag,
+                _ => {}
+            }
+        }
+        dictionary.prefixes = AffixIndex::build(prefixes, |add| add.chars().next());
+        dictionary.suffixes = AffixIndex::build(suffixes, |add| add.chars().next_back());
+
+        let mut lines = dic.lines().map(|line| line.trim_start_matches('\u{feff}'));
+        let mut first_line = lines.next();
+        if first_line.is_some_and(|line| line.trim().parse::<usize>().is_ok()) {
+            first_line = None;
+        }
+        for (row, line) in first_line.into_iter().chain(lines).enumerate() {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = split_dic_entry(entry);
+            let flags = match flags {
+                Some(flags) => resolve_flags(flags, flag_format, &aliases)
+                    .with_context(|| format!("invalid dictionary line {}: {line:?}", row + 1))?,
+                None => Vec::new(),
+            };
+            dictionary.words.entry(word).or_default().push(flags);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Characters other than letters that can be part of a word, such as the
+    /// apostrophe in English.
+    pub fn word_chars(&self) -> &HashSet<char> {
+        &self.word_chars
+    }
+
+    /// Returns whether `word` is spelled correctly.
+    pub fn check(&self, word: &str) -> bool {
+        self.find_cased(word).is_some()
+    }
+
+    /// Returns up to [`MAX_SUGGESTIONS`] correctly spelled words that are
+    /// close to `word`, most likely first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let casing = Casing::of(word);
+        let base = match casing {
+            Casing::Title | Casing::Upper => word.to_lowercase(),
+            Casing::Lower | Casing::Mixed => word.to_string(),
+        };
+
+        let mut suggestions = Vec::<String>::new();
+        let mut consider = |candidate: String| {
+            if suggestions.len() >= MAX_SUGGESTIONS
+                || candidate == word
+                || suggestions.contains(&candidate)
+            {
+                return;
+            }
+            let accepted = if let Some((first, second)) = candidate.split_once(' ') {
+                self.is_suggestable(first) && self.is_suggestable(second)
+            } else {
+                self.is_suggestable(&candidate)
+            };
+            if accepted {
+                suggestions.push(candidate);
+            }
+        };
+
+        if casing == Casing::Mixed {
+            consider(word.to_lowercase());
+            consider(title_case(word));
+        }
+        for candidate in self.edits(&base) {
+            consider(casing.apply(&candidate));
+        }
+        suggestions
+    }
+
+    fn is_suggestable(&self, word: &str) -> bool {
+        self.find_cased(word)
+            .is_some_and(|flags| !has_flag(flags, self.no_suggest_flag))
+    }
+
+    /// Candidate corrections one edit away from `word`, in the order
+    /// they should be offered.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let from_chars = |chars: &[char]| chars.iter().collect::<String>();
+        let mut edits = Vec::new();
+
+        for (from, to) in &self.replacements {
+            for (ix, _) in word.match_indices(from.as_str()) {
+                edits.push(format!("{}{to}{}", &word[..ix], &word[ix + from.len()..]));
+            }
+        }
+        for ix in 1..chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(ix - 1, ix);
+            edits.push(from_chars(&swapped));
+        }
+        for ix in 0..chars.len() {
+            for &c in &self.try_chars {
+                if c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = c;
+                    edits.push(from_chars(&replaced));
+                }
+            }
+        }
+        for ix in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(ix);
+            edits.push(from_chars(&removed));
+        }
+        for ix in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, c);
+                edits.push(from_chars(&inserted));
+            }
+        }
+        for ix in 1..chars.len() {
+            edits.push(format!(
+                "{} {}",
+                from_chars(&chars[..ix]),
+                from_chars(&chars[ix..])
+            ));
+        }
+        edits
+    }
+
+    /// Looks `word` up the way it was typed and, when it is capitalized, in
+    /// lowercase too. Returns the flags of the stem it was derived from.
+    fn find_cased(&self, word: &str) -> Option<&[Flag]> {
+        if word.is_empty() {
+            return None;
+        }
+        match Casing::of(word) {
+            Casing::Lower | Casing::Mixed => self.find(word),
+            Casing::Title => self.find(word).or_else(|| self.find(&word.to_lowercase())),
+            Casing::Upper => self
+                .find(word)
+                .or_else(|| self.find(&title_case(word)))
+                .or_else(|| self.find(&word.to_lowercase())),
+        }
+    }
+
+    fn find(&self, word: &str) -> Option<&[Flag]> {
+        if self.is_forbidden(word) {
+            return None;
+        }
+        self.stem(word, |flags| {
+            !has_flag(flags, self.need_affix_flag) && !has_flag(flags, self.only_in_compound_flag)
+        })
+        .or_else(|| self.find_with_suffix(word, None))
+        .or_else(|| self.find_with_prefix(word))
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.forbidden_flag.is_some()
+            && self.words.get(word).is_some_and(|homonyms| {
+                homonyms
+                    .iter()
+                    .any(|flags| has_flag(flags, self.forbidden_flag))
+            })
+    }
+
+    fn stem(&self, stem: &str, accept: impl Fn(&[Flag]) -> bool) -> Option<&[Flag]> {
+        self.words
+            .get(stem)?
+            .iter()
+            .map(Vec::as_slice)
+            .find(|flags| !has_flag(flags, self.forbidden_flag) && accept(flags))
+    }
+
+    fn find_with_suffix(&self, word: &str, prefix: Option<&Affix>) -> Option<&[Flag]> {
+        for suffix in self.suffixes.candidates(word.chars().next_back()) {
+            if prefix.is_some() && !suffix.cross_product {
+                continue;
+            }
+            let Some(rest) = word.strip_suffix(suffix.add.as_str()) else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+            let stem = format!("{rest}{}", suffix.strip);
+            if !suffix.condition.matches_end(&stem) {
+                continue;
+            }
+            let found = self.stem(&stem, |flags| {
+                has_flag(flags, Some(suffix.flag))
+                    && prefix.is_none_or(|prefix| {
+                        has_flag(flags, Some(prefix.flag))
+                            || has_flag(&suffix.continuation, Some(prefix.flag))
+                    })
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+
+    fn find_with_prefix(&self, word: &str) -> Option<&[Flag]> {
+        for prefix in self.prefixes.candidates(word.chars().next()) {
+            let Some(rest) = word.strip_prefix(prefix.add.as_str()) else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+            let stem = format!("{}{rest}", prefix.strip);
+            if !prefix.condition.matches_start(&stem) {
+                continue;
+            }
+            let found = self
+                .stem(&stem, |flags| has_flag(flags, Some(prefix.flag)))
+                .or_else(|| {
+                    prefix
+                        .cross_product
+                        .then(|| self.find_with_suffix(&stem, Some(prefix)))
+                        .flatten()
+                });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+/// Splits a `.dic` entry like `work/DGS` at the first slash that isn't
+/// escaped with a backslash.
+fn split_dic_entry(entry: &str) -> (String, Option<&str>) {
+    let mut word = String::new();
+    let mut chars = entry.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            '/' if !word.is_empty() => return (word, Some(&entry[ix + 1..])),
+            c => word.push(c),
+        }
+    }
+    (word, None)
+}
+
+fn resolve_flags(flags: &str, format: FlagFormat, aliases: &[Vec<Flag>]) -> Result<Vec<Flag>> {
+    if aliases.is_empty() {
+        return format.parse(flags);
+    }
+    let ix = flags
+        .parse::<usize>()
+        .with_context(|| format!("expected a flag alias, got {flags:?}"))?;
+    aliases
+        .get(ix)
+        .filter(|_| ix > 0)
+        .cloned()
+        .with_context(|| format!("unknown flag alias {ix}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwz'
+WORDCHARS '
+NOSUGGEST !
+FORBIDDENWORD *
+
+REP 2
+REP f ph
+REP alot a_lot
+
+PFX A Y 1
+PFX A   0     re         .
+
+PFX U N 1
+PFX U   0     un         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX G Y 2
+SFX G   e     ing        e
+SFX G   0     ing        [^e]
+
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+SFX M Y 1
+SFX M   0     's         .
+";
+
+    const DIC: &str = "
+10
+a
+lot
+work/ADGS
+try/ADS
+bake/DG
+happy/U
+box/SM
+Paris/M
+phone/S
+damn/!
+colour/*
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::new(AFF, DIC).unwrap()
+    }
+
+    #[test]
+    fn test_check_stems_and_affixes() {
+        let dictionary = dictionary();
+        for word in [
+            "work", "works", "worked", "working", "rework", "reworked", "tried", "tries",
+            "retried", "baked", "baking", "boxes", "box's", "unhappy", "Paris", "Paris's",
+        ] {
+            assert!(dictionary.check(word), "{word:?} should be accepted");
+        }
+        for word in [
+            "wrok", "bakeing", "trys", "boxs", "rebake", "unhappys", "unworked", "colour", "paris",
+        ] {
+            assert!(!dictionary.check(word), "{word:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_check_casing() {
+        let dictionary = dictionary();
+        assert!(dictionary.check("Working"));
+        assert!(dictionary.check("WORKING"));
+        assert!(dictionary.check("PARIS"));
+        assert!(!dictionary.check("wORKING"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("wrok"), ["work"]);
+        assert_eq!(dictionary.suggest("Wroks"), ["Works"]);
+        assert_eq!(dictionary.suggest("fone"), ["phone"]);
+        assert_eq!(dictionary.suggest("alot"), ["a lot", "lot"]);
+        assert_eq!(dictionary.suggest("bakeing")[0], "baking");
+        assert!(dictionary.suggest("dmn").is_empty());
+    }
+
+    #[test]
+    fn test_flag_formats_and_aliases() {
+        let aff = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dictionary = Dictionary::new(aff, "1\ncat/Aa\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let aff = "FLAG num\nAF 1\nAF 7,12\nSFX 12 Y 1\nSFX 12 0 s .\n";
+        let dictionary = Dictionary::new(aff, "1\ncat/1\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        assert!(Dictionary::new("SFX A Y 1\nSFX A 0 s [ab\n", "").is_err());
+    }
+}
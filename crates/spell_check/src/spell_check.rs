@@ -0,0 +1,808 @@
+//! Offline spell checking for comments, strings and prose.
+//!
+//! Words are checked against Hunspell dictionaries, either bundled with Zed or
+//! installed in the `dictionaries` folder of the config directory, and against
+//! the words listed in the user's and each project's `dictionary.txt`.
+//! Misspelled words are underlined, and code actions offer corrections or add
+//! the word to one of those lists.
+
+mod code_actions;
+mod dictionary;
+mod spell_check_settings;
+mod words;
+
+use anyhow::{Context as _, Result};
+use code_actions::SpellCheckCodeActionProvider;
+use collections::{HashMap, HashSet};
+use editor::{CodeActionProvider as _, Editor, EditorEvent, HighlightKey};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{
+    App, AppContext as _, AssetSource, Context, Entity, Global, HighlightStyle, Subscription, Task,
+    UnderlineStyle, Window, px,
+};
+use language::{Anchor, Buffer, BufferId, BufferSnapshot, OffsetRangeExt as _, Point};
+use settings::{Settings as _, SettingsStore};
+use std::{
+    cell::RefCell,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+use theme::ActiveTheme as _;
+
+pub use dictionary::{Dictionary, MAX_SUGGESTIONS};
+pub use spell_check_settings::SpellCheckSettings;
+
+/// How long to wait after the last edit before checking the buffers again.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+pub fn init(cx: &mut App) {
+    let fs = <dyn Fs>::global(cx);
+    let spell_checker = cx.new(|cx| SpellChecker::new(fs, cx));
+    cx.set_global(GlobalSpellChecker(spell_checker));
+
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        if let Some(window) = window {
+            register_editor(editor, window, cx);
+        }
+    })
+    .detach();
+}
+
+struct GlobalSpellChecker(Entity<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+/// The dictionaries and word lists that words are checked against.
+///
+/// Observers are notified whenever a dictionary or word list is (re)loaded, or
+/// when the spell checking settings change.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    settings: SpellCheckSettings,
+    dictionaries: Arc<[Arc<Dictionary>]>,
+    user_words: WordList,
+    project_words: HashMap<Arc<Path>, WordList>,
+    _load_dictionaries: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+struct WordList {
+    words: Arc<HashSet<String>>,
+    _watcher: Task<()>,
+}
+
+impl SpellChecker {
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSpellChecker>()
+            .map(|global| global.0.clone())
+    }
+
+    fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
+        let user_words =
+            Self::watch_word_list(paths::user_dictionary_file().clone(), None, fs.clone(), cx);
+        let mut this = Self {
+            fs,
+            settings: SpellCheckSettings::get_global(cx).clone(),
+            dictionaries: Arc::default(),
+            user_words,
+            project_words: HashMap::default(),
+            _load_dictionaries: Task::ready(()),
+            _settings_subscription: cx.observe_global::<SettingsStore>(Self::settings_changed),
+        };
+        this.load_dictionaries(cx);
+        this
+    }
+
+    fn settings_changed(&mut self, cx: &mut Context<Self>) {
+        let settings = SpellCheckSettings::get_global(cx);
+        if *settings == self.settings {
+            return;
+        }
+        let dictionaries_changed = settings.dictionaries != self.settings.dictionaries;
+        self.settings = settings.clone();
+        if dictionaries_changed {
+            self.load_dictionaries(cx);
+        }
+        cx.notify();
+    }
+
+    fn load_dictionaries(&mut self, cx: &mut Context<Self>) {
+        let names = self.settings.dictionaries.clone();
+        let fs = self.fs.clone();
+        let assets = cx.asset_source().clone();
+        self._load_dictionaries = cx.spawn(async move |this, cx| {
+            let dictionaries = cx
+                .background_spawn(async move {
+                    let mut dictionaries = Vec::new();
+                    for name in names {
+                        match load_dictionary(&name, fs.as_ref(), assets.as_ref()).await {
+                            Ok(dictionary) => dictionaries.push(Arc::new(dictionary)),
+                            Err(error) => {
+                                log::error!("failed to load dictionary {name:?}: {error:#}")
+                            }
+                        }
+                    }
+                    dictionaries
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.dictionaries = dictionaries.into();
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn watch_word_list(
+        path: PathBuf,
+        project_root: Option<Arc<Path>>,
+        fs: Arc<dyn Fs>,
+        cx: &mut Context<Self>,
+    ) -> WordList {
+        let (mut contents, watcher) =
+            settings::watch_config_file(cx.background_executor(), fs, path);
+        let task = cx.spawn(async move |this, cx| {
+            let _watcher = watcher;
+            while let Some(contents) = contents.next().await {
+                let words = Arc::new(parse_word_list(&contents));
+                let updated = this.update(cx, |this, cx| {
+                    let word_list = match &project_root {
+                        Some(root) => this.project_words.get_mut(root),
+                        None => Some(&mut this.user_words),
+                    };
+                    if let Some(word_list) = word_list {
+                        word_list.words = words;
+                        cx.notify();
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        });
+        WordList {
+            words: Arc::default(),
+            _watcher: task,
+        }
+    }
+
+    fn project_word_list(&mut self, root: Arc<Path>, cx: &mut Context<Self>) -> &mut WordList {
+        let fs = self.fs.clone();
+        self.project_words.entry(root.clone()).or_insert_with(|| {
+            let path = root.join(paths::local_dictionary_file_relative_path().as_std_path());
+            Self::watch_word_list(path, Some(root), fs, cx)
+        })
+    }
+
+    /// Returns the dictionaries and word lists that apply to files in the
+    /// project rooted at `project_root`.
+    pub(crate) fn speller(
+        &mut self,
+        project_root: Option<Arc<Path>>,
+        cx: &mut Context<Self>,
+    ) -> Speller {
+        let mut word_lists = vec![self.user_words.words.clone()];
+        if let Some(root) = project_root {
+            word_lists.push(self.project_word_list(root, cx).words.clone());
+        }
+        Speller {
+            dictionaries: self.dictionaries.clone(),
+            word_lists,
+        }
+    }
+
+    /// Adds `word` to the user's word list, or to the one of the project rooted
+    /// at `project_root`.
+    pub fn add_word(
+        &mut self,
+        word: String,
+        project_root: Option<Arc<Path>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let (path, word_list) = match project_root {
+            Some(root) => (
+                root.join(paths::local_dictionary_file_relative_path().as_std_path()),
+                self.project_word_list(root, cx),
+            ),
+            None => (paths::user_dictionary_file().clone(), &mut self.user_words),
+        };
+        Arc::make_mut(&mut word_list.words).insert(word.clone());
+        cx.notify();
+
+        let fs = self.fs.clone();
+        cx.background_spawn(async move {
+            let mut contents = if fs.is_file(&path).await {
+                fs.load(&path).await?
+            } else {
+                String::new()
+            };
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&word);
+            contents.push('\n');
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.atomic_write(path, contents).await
+        })
+    }
+}
+
+async fn load_dictionary(name: &str, fs: &dyn Fs, assets: &dyn AssetSource) -> Result<Dictionary> {
+    let aff_path = paths::dictionaries_dir().join(format!("{name}.aff"));
+    let dic_path = paths::dictionaries_dir().join(format!("{name}.dic"));
+    let (aff, dic) = if fs.is_file(&aff_path).await && fs.is_file(&dic_path).await {
+        (fs.load(&aff_path).await?, fs.load(&dic_path).await?)
+    } else {
+        (
+            load_bundled_file(&format!("dictionaries/{name}.aff"), assets)?,
+            load_bundled_file(&format!("dictionaries/{name}.dic"), assets)?,
+        )
+    };
+    Dictionary::new(&aff, &dic)
+}
+
+fn load_bundled_file(path: &str, assets: &dyn AssetSource) -> Result<String> {
+    let contents = assets
+        .load(path)?
+        .with_context(|| format!("no bundled dictionary file at {path:?}"))?;
+    Ok(String::from_utf8(contents.into_owned())?)
+}
+
+fn parse_word_list(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// The dictionaries and word lists that apply to a buffer, in a form that can
+/// be moved to a background thread.
+#[derive(Clone)]
+pub(crate) struct Speller {
+    dictionaries: Arc<[Arc<Dictionary>]>,
+    word_lists: Vec<Arc<HashSet<String>>>,
+}
+
+impl Speller {
+    pub(crate) fn check(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        let lowercase = word.to_lowercase();
+        self.word_lists
+            .iter()
+            .any(|words| words.contains(&word) || words.contains(&lowercase))
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.check(&word))
+    }
+
+    pub(crate) fn suggest(&self, word: &str) -> Vec<String> {
+        let curly_apostrophes = word.contains('’');
+        let word = word.replace('’', "'");
+        let mut suggestions = Vec::new();
+        for suggestion in self
+            .dictionaries
+            .iter()
+            .flat_map(|dictionary| dictionary.suggest(&word))
+        {
+            let suggestion = if curly_apostrophes {
+                suggestion.replace('\'', "’")
+            } else {
+                suggestion
+            };
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+        }
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Checks the given ranges of `snapshot`. Ranges of a buffer that was
+    /// checked before, as `previous`, keep their misspellings.
+    fn check_buffer(
+        &self,
+        snapshot: &BufferSnapshot,
+        ranges: Vec<Range<usize>>,
+        previous: Option<&BufferMisspellings>,
+        prose_languages: &[String],
+    ) -> BufferMisspellings {
+        let mut misspellings = self.misspellings(snapshot, &ranges, prose_languages);
+        if let Some(previous) = previous {
+            misspellings.extend(previous.ranges.iter().filter_map(|misspelling| {
+                let offsets = misspelling.to_offset(snapshot);
+                let rechecked = ranges
+                    .iter()
+                    .any(|range| range.start <= offsets.end && offsets.start <= range.end);
+                (!rechecked).then(|| misspelling.clone())
+            }));
+            misspellings.sort_by(|a, b| a.start.cmp(&b.start, snapshot));
+        }
+        BufferMisspellings {
+            version: snapshot.version().clone(),
+            syntax_update_count: snapshot.syntax_update_count(),
+            ranges: misspellings.into(),
+        }
+    }
+
+    fn misspellings(
+        &self,
+        snapshot: &BufferSnapshot,
+        ranges: &[Range<usize>],
+        prose_languages: &[String],
+    ) -> Vec<Range<Anchor>> {
+        if self.dictionaries.is_empty() {
+            return Vec::new();
+        }
+
+        let word_chars = self
+            .dictionaries
+            .iter()
+            .flat_map(|dictionary| dictionary.word_chars().iter().copied())
+            .collect::<HashSet<_>>();
+        let mut checked_words = HashMap::<String, bool>::default();
+        let mut misspellings = Vec::new();
+        for range in ranges
+            .iter()
+            .flat_map(|range| words::checked_ranges(snapshot, range.clone(), prose_languages))
+        {
+            let text = snapshot.text_for_range(range.clone()).collect::<String>();
+            for word_range in words::words(&text, &word_chars) {
+                let word = &text[word_range.clone()];
+                let correct = match checked_words.get(word) {
+                    Some(correct) => *correct,
+                    None => *checked_words
+                        .entry(word.to_string())
+                        .or_insert_with(|| self.check(word)),
+                };
+                if !correct {
+                    misspellings.push(
+                        snapshot.anchor_after(range.start + word_range.start)
+                            ..snapshot.anchor_before(range.start + word_range.end),
+                    );
+                }
+            }
+        }
+        misspellings
+    }
+}
+
+/// The misspelled words of a buffer, as of the given version and parse.
+#[derive(Clone)]
+pub(crate) struct BufferMisspellings {
+    version: clock::Global,
+    syntax_update_count: usize,
+    pub(crate) ranges: Arc<[Range<Anchor>]>,
+}
+
+/// Misspelled words by buffer, shared by an editor's addon and its code action
+/// provider.
+pub(crate) type Misspellings = Rc<RefCell<HashMap<BufferId, BufferMisspellings>>>;
+
+struct SpellCheckAddon {
+    misspellings: Misspellings,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl editor::Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+fn register_editor(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(spell_checker) = SpellChecker::global(cx) else {
+        return;
+    };
+
+    let misspellings = Misspellings::default();
+    let provider = Rc::new(SpellCheckCodeActionProvider::new(
+        spell_checker.clone(),
+        misspellings.clone(),
+    ));
+    let mut provider_added = false;
+    let mut sync_provider =
+        move |editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>| {
+            let enabled = SpellCheckSettings::get_global(cx).enabled;
+            if enabled && !provider_added {
+                editor.add_code_action_provider(provider.clone(), window, cx);
+            } else if !enabled && provider_added {
+                editor.remove_code_action_provider(provider.id(), window, cx);
+            }
+            provider_added = enabled;
+        };
+    sync_provider(editor, window, cx);
+
+    let editor_entity = cx.entity();
+    let subscriptions = vec![
+        cx.observe_in(&spell_checker, window, move |editor, _, window, cx| {
+            if let Some(addon) = editor.addon::<SpellCheckAddon>() {
+                addon.misspellings.borrow_mut().clear();
+            }
+            sync_provider(editor, window, cx);
+            refresh_misspellings(editor, window, cx);
+        }),
+        cx.subscribe_in(
+            &editor_entity,
+            window,
+            |editor, _, event: &EditorEvent, window, cx| match event {
+                EditorEvent::BufferEdited
+                | EditorEvent::Reparsed(_)
+                | EditorEvent::BufferRangesUpdated { .. }
+                | EditorEvent::BuffersRemoved { .. } => refresh_misspellings(editor, window, cx),
+                _ => {}
+            },
+        ),
+    ];
+    editor.register_addon(SpellCheckAddon {
+        misspellings,
+        refresh_task: Task::ready(()),
+        _subscriptions: subscriptions,
+    });
+    refresh_misspellings(editor, window, cx);
+}
+
+fn refresh_misspellings(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    if !SpellCheckSettings::get_global(cx).enabled {
+        if let Some(addon) = editor.addon_mut::<SpellCheckAddon>() {
+            addon.misspellings.borrow_mut().clear();
+            addon.refresh_task = Task::ready(());
+        }
+        editor.clear_highlights(HighlightKey::SpellCheck, cx);
+        return;
+    }
+
+    let task = cx.spawn_in(window, async move |editor, cx| {
+        cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+        let Some((pending, mut misspellings, prose_languages)) = editor
+            .update(cx, |editor, cx| {
+                let spell_checker = SpellChecker::global(cx)?;
+                let display_snapshot = editor.display_snapshot(cx);
+                let visible_range = editor.multi_buffer_visible_range(&display_snapshot, cx);
+                let mut visible_ranges = HashMap::<BufferId, Vec<Range<usize>>>::default();
+                for (buffer, range, _) in display_snapshot
+                    .buffer_snapshot()
+                    .range_to_buffer_ranges(visible_range)
+                {
+                    visible_ranges
+                        .entry(buffer.remote_id())
+                        .or_default()
+                        .push(range.start.0..range.end.0);
+                }
+
+                let addon = editor.addon::<SpellCheckAddon>()?;
+                let cached = addon.misspellings.borrow();
+
+                let mut pending = Vec::new();
+                let mut unchanged = HashMap::default();
+                for buffer in editor.buffer().read(cx).all_buffers() {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let buffer_id = snapshot.remote_id();
+                    match cached.get(&buffer_id) {
+                        Some(misspellings)
+                            if misspellings.version == *snapshot.version()
+                                && misspellings.syntax_update_count
+                                    == snapshot.syntax_update_count() =>
+                        {
+                            unchanged.insert(buffer_id, misspellings.clone());
+                        }
+                        previous => {
+                            let project_root = project_root(&buffer, cx);
+                            let speller = spell_checker.update(cx, |spell_checker, cx| {
+                                spell_checker.speller(project_root, cx)
+                            });
+                            let visible = visible_ranges.remove(&buffer_id).unwrap_or_default();
+                            pending.push((snapshot, speller, previous.cloned(), visible));
+                        }
+                    }
+                }
+                Some((
+                    pending,
+                    unchanged,
+                    SpellCheckSettings::get_global(cx).prose_languages.clone(),
+                ))
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+
+        let checked = cx
+            .background_spawn(async move {
+                pending
+                    .into_iter()
+                    .map(|(snapshot, speller, previous, visible)| {
+                        let ranges = match &previous {
+                            Some(previous) => ranges_to_recheck(&snapshot, previous, visible),
+                            None => vec![0..snapshot.len()],
+                        };
+                        let misspellings = speller.check_buffer(
+                            &snapshot,
+                            ranges,
+                            previous.as_ref(),
+                            &prose_languages,
+                        );
+                        (snapshot.remote_id(), misspellings)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+        misspellings.extend(checked);
+
+        editor
+            .update_in(cx, |editor, window, cx| {
+                let Some(addon) = editor.addon::<SpellCheckAddon>() else {
+                    return;
+                };
+                *addon.misspellings.borrow_mut() = misspellings;
+                highlight_misspellings(editor, window, cx);
+            })
+            .ok();
+    });
+    if let Some(addon) = editor.addon_mut::<SpellCheckAddon>() {
+        addon.refresh_task = task;
+    }
+}
+
+/// Returns the ranges of a buffer to check again after it changed: the lines
+/// edited since it was last checked, and the visible ones, which a reparse may
+/// have highlighted differently. Misspellings elsewhere are kept until their
+/// lines are edited.
+fn ranges_to_recheck(
+    snapshot: &BufferSnapshot,
+    previous: &BufferMisspellings,
+    visible: Vec<Range<usize>>,
+) -> Vec<Range<usize>> {
+    let mut lines = snapshot
+        .edits_since::<usize>(&previous.version)
+        .map(|edit| edit.new)
+        .chain(visible)
+        .map(|range| {
+            let start = snapshot.offset_to_point(range.start).row;
+            let end = snapshot.offset_to_point(range.end).row;
+            snapshot.point_to_offset(Point::new(start, 0))
+                ..snapshot.point_to_offset(Point::new(end, snapshot.line_len(end)))
+        })
+        .collect::<Vec<_>>();
+    lines.sort_by_key(|range| range.start);
+
+    let mut ranges = Vec::<Range<usize>>::new();
+    for range in lines {
+        match ranges.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => ranges.push(range),
+        }
+    }
+    ranges
+}
+
+fn highlight_misspellings(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let Some(addon) = editor.addon::<SpellCheckAddon>() else {
+        return;
+    };
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut ranges = addon
+        .misspellings
+        .borrow()
+        .values()
+        .flat_map(|misspellings| misspellings.ranges.iter().cloned())
+        .filter_map(|range| snapshot.anchor_range_in_buffer(range))
+        .collect::<Vec<_>>();
+    ranges.sort_by(|a, b| a.start.cmp(&b.start, &snapshot));
+
+    // Code actions for the cursor were fetched before its word was checked.
+    let head = editor.selections.newest_anchor().head();
+    let cursor_in_misspelling = ranges.iter().any(|range| {
+        range.start.cmp(&head, &snapshot).is_le() && range.end.cmp(&head, &snapshot).is_ge()
+    });
+
+    editor.highlight_text(
+        HighlightKey::SpellCheck,
+        ranges,
+        HighlightStyle {
+            underline: Some(UnderlineStyle {
+                color: Some(cx.theme().status().info),
+                thickness: px(1.),
+                wavy: true,
+            }),
+            ..HighlightStyle::default()
+        },
+        cx,
+    );
+    if cursor_in_misspelling {
+        editor.refresh_code_actions_for_selection(window, cx);
+    }
+}
+
+/// Returns the root of the local worktree containing `buffer`, whose
+/// `.zed/dictionary.txt` applies to it.
+pub(crate) fn project_root(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+    let file = project::File::from_dyn(buffer.read(cx).file())?;
+    let worktree = file.worktree.read(cx);
+    worktree.is_local().then(|| worktree.abs_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{
+        actions::{ConfirmCodeAction, ToggleCodeActions},
+        test::editor_test_context::EditorTestContext,
+    };
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+WORDCHARS '
+
+SFX S Y 1
+SFX S   0     s          .
+";
+
+    const DIC: &str = "
+6
+the
+quick
+brown
+fox/S
+jump/S
+over
+";
+
+    async fn init_test(cx: &mut TestAppContext) -> Arc<FakeFs> {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            paths::dictionaries_dir(),
+            serde_json::json!({ "en_US.aff": AFF, "en_US.dic": DIC }),
+        )
+        .await;
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            <dyn Fs>::set_global(fs.clone(), cx);
+            crate::init(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.spell_check.get_or_insert_default().enabled = Some(true);
+                });
+            });
+        });
+        cx.run_until_parked();
+        fs
+    }
+
+    #[gpui::test]
+    async fn test_misspellings_are_underlined(cx: &mut TestAppContext) {
+        init_test(cx).await;
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("ˇThe qiuck brown foxes jmups over teh foxes\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::SpellCheck,
+            "The «qiuck» brown foxes «jmups» over «teh» foxes\n",
+        );
+
+        cx.set_state("ˇThe quick brown foxes jump over the foxes\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::SpellCheck,
+            "The quick brown foxes jump over the foxes\n",
+        );
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.spell_check.get_or_insert_default().enabled = Some(false);
+                });
+            });
+        });
+        cx.set_state("ˇThe qiuck brown fox\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(HighlightKey::SpellCheck, "The qiuck brown fox\n");
+    }
+
+    #[gpui::test]
+    async fn test_edits_keep_other_misspellings(cx: &mut TestAppContext) {
+        init_test(cx).await;
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("The qiuck fox\nˇjmups over\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::SpellCheck,
+            "The «qiuck» fox\n«jmups» over\n",
+        );
+
+        cx.simulate_input("teh ");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::SpellCheck,
+            "The «qiuck» fox\n«teh» «jmups» over\n",
+        );
+    }
+
+    #[gpui::test]
+    async fn test_code_actions(cx: &mut TestAppContext) {
+        let fs = init_test(cx).await;
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("The qiˇuck zorp fox\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.executor()
+            .advance_clock(editor::CODE_ACTIONS_DEBOUNCE_TIMEOUT * 2);
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_code_actions(
+                &ToggleCodeActions {
+                    deployed_from: None,
+                    quick_launch: false,
+                },
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        let confirm = cx.update_editor(|editor, window, cx| {
+            editor.confirm_code_action(&ConfirmCodeAction { item_ix: Some(0) }, window, cx)
+        });
+        confirm.unwrap().await.unwrap();
+        assert_eq!(cx.buffer_text(), "The quick zorp fox\n");
+
+        cx.set_state("The quick zoˇrp fox\n");
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.executor()
+            .advance_clock(editor::CODE_ACTIONS_DEBOUNCE_TIMEOUT * 2);
+        cx.run_until_parked();
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_code_actions(
+                &ToggleCodeActions {
+                    deployed_from: None,
+                    quick_launch: false,
+                },
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        let confirm = cx.update_editor(|editor, window, cx| {
+            editor.confirm_code_action(&ConfirmCodeAction { item_ix: Some(0) }, window, cx)
+        });
+        confirm.unwrap().await.unwrap();
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+
+        cx.assert_editor_text_highlights(HighlightKey::SpellCheck, "The quick zorp fox\n");
+        assert_eq!(
+            fs.load(paths::user_dictionary_file()).await.unwrap(),
+            "zorp\n"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use settings::{RegisterSetting, Settings, SettingsContent, SpellCheckSettingsContent};
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub dictionaries: Vec<String>,
+    pub prose_languages: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        let spell_check: &SpellCheckSettingsContent = content.spell_check.as_ref().unwrap();
+
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.clone().unwrap(),
+            prose_languages: spell_check.prose_languages.clone().unwrap(),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Finding the words to check in a buffer.
+
+use collections::HashSet;
+use language::BufferSnapshot;
+use std::{cmp::Reverse, ops::Range};
+
+/// Characters that mark a whitespace-separated token as code, a path or a URL
+/// rather than prose.
+const CODE_CHARS: &[char] = &[
+    '/', '\\', '_', '@', '#', '$', '%', '^', '&', '*', '=', '<', '>', '{', '}', '|', '~', '`',
+];
+
+/// Returns the byte ranges of the words in `text` that should be spell checked.
+///
+/// Tokens that look like code, paths or URLs are skipped, and so are words
+/// that contain digits, acronyms and identifiers in camel case.
+pub(crate) fn words(text: &str, word_chars: &HashSet<char>) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for (token_start, token) in tokens(text) {
+        if looks_like_code(token) {
+            continue;
+        }
+
+        let mut chars = token.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if !is_word_char(c, word_chars) {
+                continue;
+            }
+            let mut end = start + c.len_utf8();
+            while let Some((ix, c)) = chars.next_if(|(_, c)| is_word_char(*c, word_chars)) {
+                end = ix + c.len_utf8();
+            }
+
+            let run = &token[start..end];
+            let start = start + run.len() - run.trim_start_matches(is_apostrophe).len();
+            let word = run.trim_matches(is_apostrophe);
+            if is_checkable(word) {
+                words.push(token_start + start..token_start + start + word.len());
+            }
+        }
+    }
+    words
+}
+
+fn tokens(text: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    for (ix, c) in text.char_indices() {
+        match (c.is_whitespace(), token_start) {
+            (true, Some(start)) => {
+                tokens.push((start, &text[start..ix]));
+                token_start = None;
+            }
+            (false, None) => token_start = Some(ix),
+            _ => {}
+        }
+    }
+    if let Some(start) = token_start {
+        tokens.push((start, &text[start..]));
+    }
+    tokens
+}
+
+fn looks_like_code(token: &str) -> bool {
+    if token.contains(CODE_CHARS) || token.contains("::") || token.contains("://") {
+        return true;
+    }
+    // Member access and file names, like `foo.bar` or `main.rs`.
+    token
+        .split('.')
+        .skip(1)
+        .any(|part| part.starts_with(char::is_alphanumeric))
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+fn is_word_char(c: char, word_chars: &HashSet<char>) -> bool {
+    c.is_alphabetic()
+        || c.is_ascii_digit()
+        || is_apostrophe(c) && word_chars.contains(&'\'')
+        || word_chars.contains(&c)
+}
+
+fn is_checkable(word: &str) -> bool {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if chars.clone().next().is_none() || word.contains(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+    // Acronyms like `HTTP` and identifiers like `isEmpty`.
+    first.is_alphabetic() && !chars.any(char::is_uppercase)
+}
+
+/// Returns the parts of `range` in `snapshot` whose words should be checked:
+/// comments and strings in code, and everything but code and links in prose
+/// languages.
+///
+/// Text is classified by the innermost capture of the grammar's highlights
+/// query, so that it doesn't depend on which captures the theme styles.
+pub(crate) fn checked_ranges(
+    snapshot: &BufferSnapshot,
+    range: Range<usize>,
+    prose_languages: &[String],
+) -> Vec<Range<usize>> {
+    let is_prose = |offset: usize| {
+        snapshot.language_at(offset).is_none_or(|language| {
+            prose_languages
+                .iter()
+                .any(|name| language.name().as_ref() == name)
+        })
+    };
+    let prose_buffer = is_prose(0);
+
+    let captures = snapshot.captures(range.clone(), |grammar| {
+        grammar
+            .highlights_config
+            .as_ref()
+            .map(|config| &config.query)
+    });
+    let capture_kinds = captures
+        .grammars()
+        .iter()
+        .map(|grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map_or(Vec::new(), |config| {
+                    config
+                        .query
+                        .capture_names()
+                        .iter()
+                        .map(|name| CaptureKind::of(name))
+                        .collect()
+                })
+        })
+        .collect::<Vec<_>>();
+    let mut spans = captures
+        .map(|capture| {
+            let kind = capture_kinds[capture.grammar_index]
+                .get(capture.index as usize)
+                .copied()
+                .unwrap_or(CaptureKind::Other);
+            (capture.node.byte_range(), kind)
+        })
+        .collect::<Vec<_>>();
+    // Enclosing captures come before the ones they contain.
+    spans.sort_by_key(|(span, _)| (span.start, Reverse(span.end)));
+
+    let mut ranges = Vec::<Range<usize>>::new();
+    let mut enclosing = Vec::<(usize, CaptureKind)>::new();
+    let mut offset = range.start;
+    let end_of_range = (range.end..range.end, CaptureKind::Other);
+    for (span, kind) in spans.into_iter().chain([end_of_range]) {
+        let span_start = span.start.min(range.end);
+        while offset < span_start {
+            while enclosing.last().is_some_and(|(end, _)| *end <= offset) {
+                enclosing.pop();
+            }
+            let (end, kind) = enclosing
+                .last()
+                .map_or((span_start, CaptureKind::Other), |(end, kind)| {
+                    ((*end).min(span_start), *kind)
+                });
+            let checked = match kind {
+                CaptureKind::Text => true,
+                CaptureKind::Code => false,
+                CaptureKind::Other => prose_buffer && is_prose(offset),
+            };
+            if checked {
+                match ranges.last_mut() {
+                    Some(last) if last.end == offset => last.end = end,
+                    _ => ranges.push(offset..end),
+                }
+            }
+            offset = end;
+        }
+        enclosing.push((span.end, kind));
+    }
+    ranges
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaptureKind {
+    /// Comments and strings, which are checked in every language.
+    Text,
+    /// Escapes and symbols in code, and code spans, links and markup in
+    /// prose, which are never checked.
+    Code,
+    Other,
+}
+
+impl CaptureKind {
+    fn of(capture_name: &str) -> Self {
+        let is = |prefix: &str| {
+            capture_name == prefix
+                || capture_name
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        };
+        if is("string.escape")
+            || is("string.regex")
+            || is("string.special")
+            || is("text.literal")
+            || is("link_uri")
+            || is("punctuation")
+        {
+            CaptureKind::Code
+        } else if is("comment") || is("string") {
+            CaptureKind::Text
+        } else {
+            CaptureKind::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+
+    fn checked_words(text: &str) -> Vec<&str> {
+        let word_chars = HashSet::from_iter(['\'']);
+        words(text, &word_chars)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            checked_words("Teh quick, brown fox's  jumps—over 'the' lazy dog."),
+            [
+                "Teh", "quick", "brown", "fox's", "jumps", "over", "the", "lazy", "dog"
+            ]
+        );
+        assert_eq!(
+            checked_words("don’t well-known"),
+            ["don’t", "well", "known"]
+        );
+        assert_eq!(
+            checked_words("See https://zed.dev or foo.bar() and main.rs, e.g. here."),
+            ["See", "or", "and", "here"]
+        );
+        assert_eq!(
+            checked_words("HTTP isEmpty x86 a snake_case `code` std::mem OK Zed"),
+            ["Zed"]
+        );
+    }
+
+    #[gpui::test]
+    fn test_checked_ranges(cx: &mut TestAppContext) {
+        let text = "// Teh comment\nfn mian() { let x = \"Helo\\nwrold\"; }\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language::rust_lang(), cx));
+        cx.run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let word_chars = HashSet::default();
+        let checked_words = |range: Range<usize>| {
+            checked_ranges(&snapshot, range, &[])
+                .into_iter()
+                .flat_map(|range| {
+                    words(&text[range.clone()], &word_chars)
+                        .into_iter()
+                        .map(move |word| &text[range.start + word.start..range.start + word.end])
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            checked_words(0..text.len()),
+            ["Teh", "comment", "Helo", "wrold"]
+        );
+        assert_eq!(
+            checked_words(text.find("let").unwrap()..text.len()),
+            ["Helo", "wrold"]
+        );
+    }
+
+    #[test]
+    fn test_capture_kinds() {
+        assert_eq!(CaptureKind::of("comment"), CaptureKind::Text);
+        assert_eq!(CaptureKind::of("comment.doc"), CaptureKind::Text);
+        assert_eq!(CaptureKind::of("string"), CaptureKind::Text);
+        assert_eq!(CaptureKind::of("string.escape"), CaptureKind::Code);
+        assert_eq!(CaptureKind::of("text.literal"), CaptureKind::Code);
+        assert_eq!(CaptureKind::of("link_uri"), CaptureKind::Code);
+        assert_eq!(CaptureKind::of("keyword"), CaptureKind::Other);
+        assert_eq!(CaptureKind::of("commentary"), CaptureKind::Other);
+    }
+}
@@ -3,9 +3,10 @@ use collections::{HashMap, HashSet};
 use command_palette_hooks::{CommandInterceptItem, CommandInterceptResult};
 use editor::{
     Bias, Editor, EditorSettings, SelectionEffects, ToPoint,
-    actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
+    actions::{SortKey, SortLines},
     display_map::ToDisplayPoint,
 };
+use fancy_regex::Regex;
 use futures::AsyncWriteExt as _;
 use gpui::{
     Action, App, AppContext as _, Context, Global, Keystroke, Task, TaskExt, WeakEntity, Window,
@@ -15,7 +16,6 @@ use itertools::Itertools;
 use language::Point;
 use multi_buffer::MultiBufferRow;
 use project::ProjectPath;
-use regex::Regex;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions};
 use serde::Deserialize;
@@ -42,14 +42,17 @@ use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
     ToggleMarksView, ToggleRegistersView, Vim, VimSettings,
+    helix::{HelixShell, ShellBehavior},
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
         search::{FindCommand, ReplaceCommand, Replacement},
     },
     object::Object,
+    pattern::translate_pattern,
     rewrap::Rewrap,
-    state::{Mark, Mode},
+    state::{Mark, Mode, VimGlobals},
+    vimrc::{self, VimUserConfig},
     visual::VisualDeleteLine,
 };
 
@@ -84,7 +87,7 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
-#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
     Number(bool),
@@ -147,7 +150,7 @@ impl VimOption {
         .map(|(_, option)| option)
     }
 
-    fn from(option: &str) -> Option<Self> {
+    pub(crate) fn from(option: &str) -> Option<Self> {
         match option {
             "wrap" => Some(Self::Wrap(true)),
             "nowrap" => Some(Self::Wrap(false)),
@@ -190,6 +193,47 @@ impl VimOption {
             VimOption::GDefault(false) => "nogdefault",
         }
     }
+
+    /// Applies options that affect all editors, like `ignorecase`.
+    pub(crate) fn apply_global(&self, cx: &mut App) {
+        match self {
+            VimOption::IgnoreCase(enabled) => {
+                let mut settings = EditorSettings::get_global(cx).clone();
+                settings.search.case_sensitive = !*enabled;
+                SettingsStore::update(cx, |store, _| {
+                    store.override_global(settings);
+                });
+            }
+            VimOption::GDefault(enabled) => {
+                let mut settings = VimSettings::get_global(cx).clone();
+                settings.gdefault = *enabled;
+
+                SettingsStore::update(cx, |store, _| {
+                    store.override_global(settings);
+                })
+            }
+            VimOption::Wrap(_) | VimOption::Number(_) | VimOption::RelativeNumber(_) => {}
+        }
+    }
+
+    /// Applies options that are local to an editor, like `wrap`.
+    pub(crate) fn apply_to_editor(&self, editor: &mut Editor, cx: &mut Context<Editor>) {
+        match self {
+            VimOption::Wrap(true) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
+            }
+            VimOption::Wrap(false) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            }
+            VimOption::Number(enabled) => {
+                editor.set_show_line_numbers(*enabled, cx);
+            }
+            VimOption::RelativeNumber(enabled) => {
+                editor.set_relative_line_number(Some(*enabled), cx);
+            }
+            VimOption::IgnoreCase(_) | VimOption::GDefault(_) => {}
+        }
+    }
 }
 
 /// Sets vim options and configuration values.
@@ -237,6 +281,13 @@ actions!(
     ]
 );
 
+/// Defines a mapping, leader or user command, as in a vimrc.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct VimMap {
+    pub command: String,
+}
+
 /// Opens the specified file for editing.
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = vim, no_json, no_register)]
@@ -287,36 +338,8 @@ impl Deref for WrappedAction {
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, action: &VimSet, _, cx| {
         for option in action.options.iter() {
-            vim.update_editor(cx, |_, editor, cx| match option {
-                VimOption::Wrap(true) => {
-                    editor
-                        .set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-                }
-                VimOption::Wrap(false) => {
-                    editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
-                }
-                VimOption::Number(enabled) => {
-                    editor.set_show_line_numbers(*enabled, cx);
-                }
-                VimOption::RelativeNumber(enabled) => {
-                    editor.set_relative_line_number(Some(*enabled), cx);
-                }
-                VimOption::IgnoreCase(enabled) => {
-                    let mut settings = EditorSettings::get_global(cx).clone();
-                    settings.search.case_sensitive = !*enabled;
-                    SettingsStore::update(cx, |store, _| {
-                        store.override_global(settings);
-                    });
-                }
-                VimOption::GDefault(enabled) => {
-                    let mut settings = VimSettings::get_global(cx).clone();
-                    settings.gdefault = *enabled;
-
-                    SettingsStore::update(cx, |store, _| {
-                        store.override_global(settings);
-                    })
-                }
-            });
+            option.apply_global(cx);
+            vim.update_editor(cx, |_, editor, cx| option.apply_to_editor(editor, cx));
         }
     });
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
@@ -347,6 +370,17 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         );
     });
 
+    Vim::action(editor, cx, |vim, action: &VimMap, window, cx| {
+        let result = VimUserConfig::run_command(&action.command, cx);
+        if result.is_err()
+            && let Some(workspace) = vim.workspace(window, cx)
+        {
+            workspace.update(cx, |workspace, cx| {
+                result.notify_err(workspace, cx);
+            });
+        }
+    });
+
     Vim::action(editor, cx, |vim, action: &VimSave, window, cx| {
         if let Some(range) = &action.range {
             vim.update_editor(cx, |vim, editor, cx| {
@@ -1760,10 +1794,23 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("delm", "arks"), ArgumentRequired)
             .bang(DeleteMarks::AllLocal)
             .args(|_, args| Some(DeleteMarks::Marks(args).boxed_clone())),
-        VimCommand::new(("sor", "t"), SortLinesCaseSensitive)
-            .range(select_range)
-            .default_range(CommandRange::buffer()),
-        VimCommand::new(("sort i", ""), SortLinesCaseInsensitive)
+        VimCommand::new(("sor", "t"), SortLines::default())
+            .bang(SortLines {
+                keys: vec![SortKey {
+                    descending: true,
+                    ..Default::default()
+                }],
+                unique: false,
+            })
+            .args(|action, args| {
+                let descending = action
+                    .as_any()
+                    .downcast_ref::<SortLines>()
+                    .is_some_and(|action| action.keys.iter().any(|key| key.descending));
+                let flags = if descending { format!("!{args}") } else { args };
+                let action = SortLines::parse_flags(&flags, translate_pattern).ok()?;
+                Some(action.boxed_clone())
+            })
             .range(select_range)
             .default_range(CommandRange::buffer()),
         VimCommand::str(("E", "xplore"), "project_panel::ToggleFocus"),
@@ -1784,9 +1831,59 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("ex", ""), editor::actions::ReloadFile).bang(editor::actions::ReloadFile),
         VimCommand::new(("cpp", "link"), editor::actions::CopyPermalinkToLine).range(act_on_range),
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
-        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
+        VimCommand::new(("let", ""), ArgumentRequired).args(|_, args| {
+            Some(
+                VimMap {
+                    command: format!("let {args}"),
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("com", "mand"), ArgumentRequired).args(|_, args| {
+            Some(
+                VimMap {
+                    command: format!("command {args}"),
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(("h", "elp"), OpenDocs),
     ]
+    .into_iter()
+    .chain(vimrc::map_command_names().map(map_command))
+    .chain(ShellBehavior::ALL.into_iter().map(helix_shell_command))
+    .collect()
+}
+
+fn helix_shell_command(behavior: ShellBehavior) -> VimCommand {
+    VimCommand::new((behavior.command_name(), ""), ArgumentRequired)
+        .args(move |_, command| Some(HelixShell { behavior, command }.boxed_clone()))
+}
+
+fn map_command(pattern: (&'static str, &'static str)) -> VimCommand {
+    let name = format!("{}{}", pattern.0, pattern.1);
+    let command = if name == "map" {
+        // Without arguments, `:map` opens the default keymap for reference.
+        VimCommand::str(pattern, "vim::OpenDefaultKeymap")
+    } else {
+        VimCommand::new(pattern, ArgumentRequired)
+    };
+    command
+        .bang(VimMap {
+            command: format!("{name}!"),
+        })
+        .args(move |action, args| {
+            let command = match action.as_any().downcast_ref::<VimMap>() {
+                Some(bang) => &bang.command,
+                None => &name,
+            };
+            Some(
+                VimMap {
+                    command: format!("{command} {args}"),
+                }
+                .boxed_clone(),
+            )
+        })
 }
 
 struct VimCommands(Vec<VimCommand>);
@@ -1846,6 +1943,7 @@ pub fn command_interceptor(
     let (range, query) = VimCommand::parse_range(input);
     let range_prefix = input[0..(input.len() - query.len())].to_string();
     let has_trailing_space = query.ends_with(" ");
+    let query = VimUserConfig::expand_command(query.trim_start(), cx).unwrap_or(query);
     let mut query = query.as_str().trim_start();
 
     let on_matching_lines = (query.starts_with('g') || query.starts_with('v'))
@@ -1895,7 +1993,10 @@ pub fn command_interceptor(
             substitute.next();
             query.next();
         }
-        if let Some(replacement) = Replacement::parse(query) {
+        let previous_replacement = cx
+            .try_global::<VimGlobals>()
+            .and_then(|globals| globals.last_replacement.clone());
+        if let Some(replacement) = Replacement::parse(query, previous_replacement.as_deref()) {
             let range = range.clone().unwrap_or(CommandRange {
                 start: Position::CurrentLine { offset: 0 },
                 end: None,
@@ -2071,10 +2172,8 @@ pub(crate) struct OnMatchingLines {
 }
 
 impl OnMatchingLines {
-    // convert a vim query into something more usable by zed.
-    // we don't attempt to fully convert between the two regex syntaxes,
-    // but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-    // and convert \0..\9 to $0..$9 in the replacement so that common idioms work.
+    // split a :g command into its parts. the pattern is kept in vim's syntax
+    // and translated by `pattern::translate_pattern` when the command runs.
     pub(crate) fn parse(
         query: &str,
         range: &Option<CommandRange>,
@@ -2112,8 +2211,7 @@ impl OnMatchingLines {
         for c in query_chars.by_ref() {
             if escaped {
                 escaped = false;
-                // unescape escaped parens
-                if c != '(' && c != ')' && c != delimiter {
+                if c != delimiter {
                     search.push('\\')
                 }
                 search.push(c)
@@ -2122,10 +2220,6 @@ impl OnMatchingLines {
             } else if c == delimiter {
                 break;
             } else {
-                // escape unescaped parens
-                if c == '(' || c == ')' {
-                    search.push('\\')
-                }
                 search.push(c)
             }
         }
@@ -2155,7 +2249,7 @@ impl OnMatchingLines {
         let mut action = self.action.boxed_clone();
         let mut last_pattern = self.search.clone();
 
-        let mut regexes = match Regex::new(&self.search) {
+        let mut regexes = match Regex::new(&translate_pattern(&self.search)) {
             Ok(regex) => vec![(regex, !self.invert)],
             e @ Err(_) => {
                 let Some(workspace) = vim.workspace(window, cx) else {
@@ -2172,7 +2266,7 @@ impl OnMatchingLines {
             .as_any()
             .downcast_ref::<OnMatchingLines>()
         {
-            let Some(regex) = Regex::new(&inner.search).ok() else {
+            let Some(regex) = Regex::new(&translate_pattern(&inner.search)).ok() else {
                 break;
             };
             last_pattern = inner.search.clone();
@@ -2186,6 +2280,7 @@ impl OnMatchingLines {
                 {
                     search_bar.update(cx, |search_bar, cx| {
                         if search_bar.show(window, cx) {
+                            search_bar.set_query_translator(Some(translate_pattern));
                             let _ = search_bar.search(
                                 &last_pattern,
                                 Some(SearchOptions::REGEX | SearchOptions::CASE_SENSITIVE),
@@ -2221,7 +2316,7 @@ impl OnMatchingLines {
                             for (newline_ix, text) in chunk.split('\n').enumerate() {
                                 if newline_ix > 0 {
                                     if regexes.iter().all(|(regex, should_match)| {
-                                        regex.is_match(&line) == *should_match
+                                        regex.is_match(&line).unwrap_or(false) == *should_match
                                     }) {
                                         new_selections
                                             .push(Point::new(row, 0).to_display_point(&snapshot))
@@ -2303,7 +2398,7 @@ impl Vim {
         }
     }
 
-    fn prepare_shell_command(
+    pub(crate) fn prepare_shell_command(
         &mut self,
         command: &str,
         _: &mut Window,
@@ -3520,6 +3615,97 @@ mod test {
             Mode::Normal,
         );
 
+        // Numbers are compared by value, and lines without one come first.
+        cx.set_state(
+            indoc! {"
+                «x10
+                y9
+                none
+                z-1ˇ»
+            "},
+            Mode::Visual,
+        );
+
+        cx.simulate_keystrokes(": sort space n");
+        cx.simulate_keystrokes("enter");
+
+        cx.assert_state(
+            indoc! {"
+                ˇnone
+                z-1
+                y9
+                x10
+            "},
+            Mode::Normal,
+        );
+
+        // With a bang the order is reversed, and `u` drops duplicates.
+        cx.set_state(
+            indoc! {"
+                «b
+                a
+                b
+                cˇ»
+            "},
+            Mode::Visual,
+        );
+
+        cx.simulate_keystrokes(": sort ! space u");
+        cx.simulate_keystrokes("enter");
+
+        cx.assert_state(
+            indoc! {"
+                ˇc
+                b
+                a
+            "},
+            Mode::Normal,
+        );
+
+        // Vim patterns select the text to compare: what follows the match, or
+        // the match itself with `r`.
+        cx.set_state(
+            indoc! {"
+                «a3 z
+                b12 y
+                c2 xˇ»
+            "},
+            Mode::Visual,
+        );
+
+        cx.simulate_keystrokes(": sort space / \\ d \\ + space /");
+        cx.simulate_keystrokes("enter");
+
+        cx.assert_state(
+            indoc! {"
+                ˇc2 x
+                b12 y
+                a3 z
+            "},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+                «a3 z
+                b12 y
+                c2 xˇ»
+            "},
+            Mode::Visual,
+        );
+
+        cx.simulate_keystrokes(": sort space r n space / \\ d \\ + /");
+        cx.simulate_keystrokes("enter");
+
+        cx.assert_state(
+            indoc! {"
+                ˇc2 x
+                a3 z
+                b12 y
+            "},
+            Mode::Normal,
+        );
+
         // When no range is provided, sorts the whole buffer.
         cx.set_state(
             indoc! {"
@@ -3,8 +3,11 @@ mod duplicate;
 mod object;
 mod paste;
 mod select;
+mod shell;
 mod surround;
 
+pub(crate) use shell::{HelixShell, ShellBehavior};
+
 use editor::display_map::{DisplayRow, DisplaySnapshot};
 use editor::{
     DisplayPoint, Editor, EditorSettings, MultiBufferOffset, NavigationOverlayLabel,
@@ -93,6 +96,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_select_next);
     Vim::action(editor, cx, Vim::helix_select_previous);
     Vim::action(editor, cx, Vim::helix_trim_selections);
+    shell::register(editor, cx);
     Vim::action(editor, cx, |vim, _: &PushHelixSurroundAdd, window, cx| {
         vim.clear_operator(window, cx);
         vim.push_operator(Operator::HelixSurroundAdd, window, cx);
@@ -681,6 +685,7 @@ impl Vim {
                     cx.focus_self(window);
 
                     search_bar.set_replacement(None, cx);
+                    search_bar.set_query_translator(None);
                     let mut options = SearchOptions::NONE;
                     options |= SearchOptions::REGEX;
                     if EditorSettings::get_global(cx).search.case_sensitive {
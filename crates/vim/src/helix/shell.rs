@@ -0,0 +1,380 @@
+use std::{ops::Range, process::Stdio};
+
+use anyhow::{Result, anyhow};
+use editor::{Editor, MultiBufferOffset, ToOffset};
+use futures::{AsyncWriteExt as _, future::join_all};
+use gpui::{Action, Context, Window, actions};
+use multi_buffer::Anchor;
+use util::ResultExt as _;
+use workspace::notifications::NotifyResultExt as _;
+
+use crate::Vim;
+
+actions!(
+    vim,
+    [
+        /// Prompts for a shell command to pipe each selection through, replacing the
+        /// selections with its output.
+        HelixPipe,
+        /// Prompts for a shell command to pipe each selection into, ignoring its output.
+        HelixPipeTo,
+        /// Prompts for a shell command and inserts its output before each selection.
+        HelixInsertOutput,
+        /// Prompts for a shell command and appends its output after each selection.
+        HelixAppendOutput,
+        /// Prompts for a shell command and keeps only the selections for which it succeeds.
+        HelixKeepPipe,
+    ]
+);
+
+/// What to do with the selections and the output of a shell command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShellBehavior {
+    Replace,
+    Ignore,
+    Insert,
+    Append,
+    Keep,
+}
+
+impl ShellBehavior {
+    pub(crate) const ALL: [Self; 5] = [
+        Self::Replace,
+        Self::Ignore,
+        Self::Insert,
+        Self::Append,
+        Self::Keep,
+    ];
+
+    /// The name of the command that runs a shell command with this behavior,
+    /// matching Helix's typable commands.
+    pub(crate) fn command_name(self) -> &'static str {
+        match self {
+            Self::Replace => "pipe",
+            Self::Ignore => "pipe-to",
+            Self::Insert => "insert-output",
+            Self::Append => "append-output",
+            Self::Keep => "keep-pipe",
+        }
+    }
+
+    /// Whether each selection is piped to its own run of the command. Otherwise the
+    /// command runs once and its output is used for every selection.
+    fn pipes_selections(self) -> bool {
+        matches!(self, Self::Replace | Self::Ignore | Self::Keep)
+    }
+}
+
+/// Runs a shell command for the selections, like Helix's `|`, `A-|`, `!`, `A-!` and `$`.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct HelixShell {
+    pub(crate) behavior: ShellBehavior,
+    pub(crate) command: String,
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, _: &HelixPipe, window, cx| {
+        vim.prompt_helix_shell(ShellBehavior::Replace, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixPipeTo, window, cx| {
+        vim.prompt_helix_shell(ShellBehavior::Ignore, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixInsertOutput, window, cx| {
+        vim.prompt_helix_shell(ShellBehavior::Insert, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixAppendOutput, window, cx| {
+        vim.prompt_helix_shell(ShellBehavior::Append, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixKeepPipe, window, cx| {
+        vim.prompt_helix_shell(ShellBehavior::Keep, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &HelixShell, window, cx| {
+        vim.helix_shell(action.behavior, &action.command, window, cx)
+    });
+}
+
+struct ShellOutput {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl Vim {
+    fn prompt_helix_shell(
+        &mut self,
+        behavior: ShellBehavior,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window, cx) else {
+            return;
+        };
+        let query = format!("{} ", behavior.command_name());
+        workspace.update(cx, |workspace, cx| {
+            command_palette::CommandPalette::toggle(workspace, &query, window, cx);
+        })
+    }
+
+    /// Runs `command` through the project's shell, once per selection when the selections
+    /// are piped to it, and applies the outputs in a single transaction.
+    fn helix_shell(
+        &mut self,
+        behavior: ShellBehavior,
+        command: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window, cx) else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let command = self.prepare_shell_command(command, window, cx);
+
+        let Some(selections) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            editor
+                .selections
+                .all_anchors(&editor.display_snapshot(cx))
+                .iter()
+                .map(|selection| {
+                    let range = selection.range();
+                    let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                    (range, text)
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        if selections.is_empty() {
+            return;
+        }
+
+        let inputs = if behavior.pipes_selections() {
+            selections
+                .iter()
+                .map(|(_, text)| Some(text.clone()))
+                .collect()
+        } else {
+            vec![None]
+        };
+        let processes = inputs
+            .iter()
+            .map(|_| project.update(cx, |project, cx| project.exec_in_shell(command.clone(), cx)))
+            .collect::<Vec<_>>();
+
+        let task = cx.spawn_in(window, async move |vim, cx| {
+            let mut runs = Vec::new();
+            for (process, input) in processes.into_iter().zip(inputs) {
+                let Some(mut process) = process.await.log_err() else {
+                    vim.update_in(cx, |vim, window, cx| vim.cancel_running_command(window, cx))
+                        .log_err();
+                    return;
+                };
+                runs.push(cx.background_spawn(async move {
+                    process.stdin(if input.is_some() {
+                        Stdio::piped()
+                    } else {
+                        Stdio::null()
+                    });
+                    process.stdout(Stdio::piped());
+                    process.stderr(Stdio::piped());
+                    let mut running = process.spawn()?;
+                    let stdin = running.stdin.take();
+                    let write_input = async move {
+                        if let Some((mut stdin, input)) = stdin.zip(input) {
+                            // The command is free to exit without reading its input.
+                            stdin.write_all(input.as_bytes()).await.ok();
+                        }
+                    };
+                    let (_, output) = futures::join!(write_input, running.output());
+                    let output = output?;
+                    anyhow::Ok(ShellOutput {
+                        success: output.status.success(),
+                        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                    })
+                }));
+            }
+            let outputs = join_all(runs).await.into_iter().collect::<Result<Vec<_>>>();
+
+            vim.update_in(cx, |vim, window, cx| {
+                let result = outputs.and_then(|outputs| {
+                    vim.apply_helix_shell_outputs(behavior, selections, outputs, window, cx)
+                });
+                vim.cancel_running_command(window, cx);
+                if result.is_err()
+                    && let Some(workspace) = vim.workspace(window, cx)
+                {
+                    workspace.update(cx, |workspace, cx| result.notify_err(workspace, cx));
+                }
+            })
+            .log_err();
+        });
+        self.running_command.replace(task);
+    }
+
+    fn apply_helix_shell_outputs(
+        &mut self,
+        behavior: ShellBehavior,
+        selections: Vec<(Range<Anchor>, String)>,
+        outputs: Vec<ShellOutput>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if behavior == ShellBehavior::Keep {
+            let kept = selections
+                .into_iter()
+                .zip(&outputs)
+                .filter(|(_, output)| output.success)
+                .map(|((range, _), _)| range)
+                .collect::<Vec<_>>();
+            if kept.is_empty() {
+                return Err(anyhow!("No selections remaining"));
+            }
+            self.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_anchor_ranges(kept)
+                });
+            });
+            return Ok(());
+        }
+
+        if let Some(failed) = outputs.iter().find(|output| !output.success) {
+            return Err(anyhow!(
+                "Shell command failed: {}",
+                failed.stderr.trim_end()
+            ));
+        }
+        if behavior == ShellBehavior::Ignore {
+            return Ok(());
+        }
+
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let selections = selections
+                    .iter()
+                    .map(|(range, text)| {
+                        (
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot),
+                            text.as_str(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let outputs = outputs
+                    .iter()
+                    .map(|output| output.stdout.as_str())
+                    .collect::<Vec<_>>();
+                let (edits, new_selections) = shell_edits(behavior, &selections, &outputs);
+                editor.edit(edits, cx);
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_ranges(new_selections)
+                });
+            });
+        });
+        Ok(())
+    }
+}
+
+/// Computes the edits inserting the command output for each selection, along with the
+/// selections covering the inserted output afterwards. `selections` must be sorted and
+/// disjoint. `outputs` holds either one output per selection, or one output for all of them.
+fn shell_edits(
+    behavior: ShellBehavior,
+    selections: &[(Range<MultiBufferOffset>, &str)],
+    outputs: &[&str],
+) -> (
+    Vec<(Range<MultiBufferOffset>, String)>,
+    Vec<Range<MultiBufferOffset>>,
+) {
+    let mut edits = Vec::new();
+    let mut new_selections = Vec::new();
+    let mut delta = 0isize;
+    for (ix, (range, text)) in selections.iter().enumerate() {
+        let output = outputs.get(ix).or(outputs.first()).copied().unwrap_or("");
+        let output = trim_output(output, text);
+        let edit_range = match behavior {
+            ShellBehavior::Insert => range.start..range.start,
+            ShellBehavior::Append => range.end..range.end,
+            _ => range.clone(),
+        };
+        let start = edit_range.start.0.saturating_add_signed(delta);
+        new_selections.push(MultiBufferOffset(start)..MultiBufferOffset(start + output.len()));
+        delta += output.len() as isize - (edit_range.end.0 - edit_range.start.0) as isize;
+        edits.push((edit_range, output.to_string()));
+    }
+    (edits, new_selections)
+}
+
+/// Commands usually end their output with a newline, which is only kept when the
+/// selection also ends with one, so that piping part of a line keeps it on one line.
+fn trim_output<'a>(output: &'a str, selection: &str) -> &'a str {
+    if selection.ends_with('\n') {
+        return output;
+    }
+    output
+        .strip_suffix("\r\n")
+        .or_else(|| output.strip_suffix('\n'))
+        .unwrap_or(output)
+}
+
+#[cfg(test)]
+mod test {
+    use editor::MultiBufferOffset;
+
+    use super::{ShellBehavior, shell_edits, trim_output};
+
+    fn offsets(range: std::ops::Range<usize>) -> std::ops::Range<MultiBufferOffset> {
+        MultiBufferOffset(range.start)..MultiBufferOffset(range.end)
+    }
+
+    #[test]
+    fn test_trim_output() {
+        assert_eq!(trim_output("FOO\n", "foo"), "FOO");
+        assert_eq!(trim_output("FOO\r\n", "foo"), "FOO");
+        assert_eq!(trim_output("FOO\n\n", "foo"), "FOO\n");
+        assert_eq!(trim_output("FOO\n", "foo\n"), "FOO\n");
+        assert_eq!(trim_output("FOO", "foo"), "FOO");
+    }
+
+    #[test]
+    fn test_shell_edits() {
+        // "ab cd ef" with "ab" and "ef" selected.
+        let selections = [(offsets(0..2), "ab"), (offsets(6..8), "ef")];
+
+        let (edits, new_selections) =
+            shell_edits(ShellBehavior::Replace, &selections, &["one\n", "three\n"]);
+        assert_eq!(
+            edits,
+            [
+                (offsets(0..2), "one".to_string()),
+                (offsets(6..8), "three".to_string())
+            ]
+        );
+        // "one cd three"
+        assert_eq!(new_selections, [offsets(0..3), offsets(7..12)]);
+
+        let (edits, new_selections) = shell_edits(ShellBehavior::Insert, &selections, &["x\n"]);
+        assert_eq!(
+            edits,
+            [
+                (offsets(0..0), "x".to_string()),
+                (offsets(6..6), "x".to_string())
+            ]
+        );
+        // "xab cd xef"
+        assert_eq!(new_selections, [offsets(0..1), offsets(7..8)]);
+
+        let (edits, new_selections) = shell_edits(ShellBehavior::Append, &selections, &["xy"]);
+        assert_eq!(
+            edits,
+            [
+                (offsets(2..2), "xy".to_string()),
+                (offsets(8..8), "xy".to_string())
+            ]
+        );
+        // "abxy cd efxy"
+        assert_eq!(new_selections, [offsets(2..4), offsets(10..12)]);
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     motion::Motion,
     normal::InsertBefore,
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
+    vimrc,
 };
 use editor::Editor;
 use gpui::{Action, App, Context, Window, actions};
@@ -52,7 +53,7 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 None
             }
         }
-        ReplayableAction::Insertion { .. } => None,
+        ReplayableAction::Insertion { .. } | ReplayableAction::Keystroke(_) => None,
     }
 }
 
@@ -67,9 +68,8 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, _: &ToggleRecord, window, cx| {
-        let globals = Vim::globals(cx);
-        if let Some(char) = globals.recording_register.take() {
-            globals.last_recorded_register = Some(char)
+        if Vim::globals(cx).recording_register.is_some() {
+            Vim::update_globals(cx, |globals, cx| globals.stop_recording(cx));
         } else {
             vim.push_operator(Operator::RecordRegister, window, cx);
         }
@@ -161,6 +161,16 @@ impl Replayer {
                     cx.defer(move |cx| Vim::globals(cx).observe_action(action.boxed_clone()));
                 }
             }
+            ReplayableAction::Keystroke(keystroke) => {
+                let focused = window.focused(cx);
+                window.dispatch_keystroke(keystroke, cx);
+                if window.focused(cx) != focused {
+                    // Keystrokes are dispatched to the element tree from the
+                    // last frame, so redraw once the focus has changed, e.g.
+                    // after `:` opens the command palette.
+                    window.draw(cx).clear(cx);
+                }
+            }
             ReplayableAction::Insertion {
                 text,
                 utf16_range_to_replace,
@@ -194,6 +204,7 @@ impl Vim {
         let globals = Vim::globals(cx);
         globals.recording_register = Some(register);
         globals.recordings.remove(&register);
+        globals.recording_keystrokes.clear();
         globals.ignore_current_insertion = true;
         self.clear_operator(window, cx)
     }
@@ -215,7 +226,22 @@ impl Vim {
             };
             register = last;
         }
-        let Some(actions) = globals.recordings.get(&register) else {
+        let actions = if let Some(actions) = globals.recordings.get(&register) {
+            actions.clone()
+        } else if let Some(content) = globals.registers.get(&register.to_ascii_lowercase()) {
+            // The macro was edited as text or restored from a previous
+            // session, so replay its keys.
+            match vimrc::parse_keystrokes(&content.text) {
+                Ok(keystrokes) => keystrokes
+                    .into_iter()
+                    .map(ReplayableAction::Keystroke)
+                    .collect(),
+                Err(error) => {
+                    log::error!("failed to replay register {register}: {error:#}");
+                    return;
+                }
+            }
+        } else {
             return;
         };
 
@@ -416,11 +442,12 @@ mod test {
     use editor::test::editor_lsp_test_context::EditorLspTestContext;
     use futures::StreamExt;
     use indoc::indoc;
+    use std::time::Duration;
 
     use gpui::EntityInputHandler;
 
     use crate::{
-        VimGlobals,
+        Vim, VimGlobals,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
@@ -1118,6 +1145,93 @@ mod test {
         cx.shared_state().await.assert_eq("aaaaaaabbbˇd");
     }
 
+    #[gpui::test]
+    async fn test_edit_recording_as_text(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello\nworld", Mode::Normal);
+        cx.simulate_keystrokes("q q shift-a ! escape q");
+        cx.assert_state("hellˇo!\nworld", Mode::Normal);
+        let register = cx.read(|cx| cx.global::<VimGlobals>().registers[&'q'].text.clone());
+        assert_eq!(register.as_ref(), "A!<Esc>");
+
+        cx.simulate_keystrokes("o escape \" q p");
+        cx.assert_state("hello!\nA!<Escˇ>\nworld", Mode::Normal);
+        cx.simulate_keystrokes("0 f ! r ? 0 \" q y $ d d");
+        cx.assert_state("hello!\nˇworld", Mode::Normal);
+        cx.simulate_keystrokes("@ q");
+        cx.assert_state("hello!\nworldˇ?", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_record_append_to_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("q a r a l q");
+        cx.assert_state("aˇello world", Mode::Normal);
+        cx.simulate_keystrokes("q shift-a r b l q");
+        cx.assert_state("abˇllo world", Mode::Normal);
+        let register = cx.read(|cx| cx.global::<VimGlobals>().registers[&'a'].text.clone());
+        assert_eq!(register.as_ref(), "ralrbl");
+
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state("ababˇo world", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_text_with_multiple_cursors(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.update(|_, cx| {
+            Vim::update_globals(cx, |globals, _| {
+                globals
+                    .registers
+                    .insert('q', "A;<Esc>0cwfoo<Esc>".to_string().into());
+            })
+        });
+        cx.set_state("ˇhello\nˇworld\nagain", Mode::Normal);
+        cx.simulate_keystrokes("@ q");
+        cx.assert_state("foˇo;\nfoˇo;\nagain", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_registers_persist_across_sessions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("q a c w h i escape q w \" b y e");
+        cx.assert_state("hi ˇworld", Mode::Normal);
+        // Registers too large for viminfo aren't saved either.
+        cx.update(|_, cx| {
+            Vim::update_globals(cx, |globals, cx| {
+                globals.registers.insert('c', "x".repeat(20 * 1024).into());
+                globals.persist_registers(cx);
+            })
+        });
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+
+        // Disabling vim resets the registers, as a restart would, and
+        // enabling it loads them from the database.
+        cx.disable_vim();
+        cx.enable_vim();
+        cx.run_until_parked();
+        cx.read(|cx| {
+            let globals = cx.global::<VimGlobals>();
+            assert!(globals.recordings.is_empty());
+            assert_eq!(globals.registers[&'a'].text.as_ref(), "cwhi<Esc>");
+            assert_eq!(globals.registers[&'b'].text.as_ref(), "world");
+            // Only named registers are saved.
+            assert!(!globals.registers.contains_key(&'c'));
+            assert!(!globals.registers.contains_key(&'"'));
+            assert!(!globals.registers.contains_key(&'0'));
+        });
+
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state("hi hˇi", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_repeat_clear(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
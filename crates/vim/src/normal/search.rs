@@ -13,6 +13,7 @@ use crate::{
     Vim, VimSettings,
     command::CommandRange,
     motion::Motion,
+    pattern::{translate_pattern, translate_replacement},
     state::{Mode, SearchState},
 };
 
@@ -235,6 +236,12 @@ impl Vim {
             return;
         };
 
+        // Helix searches use the regex syntax of the search bar.
+        let query_translator = if self.mode.is_helix() {
+            None
+        } else {
+            Some(translate_pattern as buffer_search::QueryTranslator)
+        };
         let shown = search_bar.update(cx, |search_bar, cx| {
             if !search_bar.show(window, cx) {
                 return false;
@@ -248,6 +255,7 @@ impl Vim {
             if action.regex && VimSettings::get_global(cx).use_regex_search {
                 options |= SearchOptions::REGEX;
             }
+            search_bar.set_query_translator(query_translator);
             if action.backwards {
                 options |= SearchOptions::BACKWARDS;
             }
@@ -294,7 +302,21 @@ impl Vim {
     }
 
     // hook into the existing to clear out any vim search state on cmd+f or edit -> find.
-    fn search_deploy(&mut self, _: &buffer_search::Deploy, _: &mut Window, cx: &mut Context<Self>) {
+    fn search_deploy(
+        &mut self,
+        _: &buffer_search::Deploy,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Searches started outside of vim use the regex syntax of the search bar.
+        if let Some(search_bar) = self.pane(window, cx).and_then(|pane| {
+            pane.read(cx)
+                .toolbar()
+                .read(cx)
+                .item_of_type::<BufferSearchBar>()
+        }) {
+            search_bar.update(cx, |search_bar, _| search_bar.set_query_translator(None));
+        }
         // Preserve the current mode when resetting search state
         let current_mode = self.mode;
         self.search = Default::default();
@@ -483,6 +505,7 @@ impl Vim {
                 };
 
                 let query = regex::escape(&query);
+                search_bar.set_query_translator(None);
                 Some(search_bar.search(&query, Some(options), true, window, cx))
             });
 
@@ -545,6 +568,7 @@ impl Vim {
                         );
                     }
 
+                    search_bar.set_query_translator(Some(translate_pattern));
                     Some(search_bar.search(&query, Some(options), true, window, cx))
                 });
                 let Some(search) = search else { return };
@@ -573,6 +597,7 @@ impl Vim {
         cx: &mut Context<Self>,
     ) {
         let replacement = action.replacement.clone();
+        Vim::globals(cx).last_replacement = Some(replacement.replacement.clone());
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
             .zip(self.workspace(window, cx))
@@ -634,6 +659,7 @@ impl Vim {
             }
 
             search_bar.set_replacement(Some(&replacement.replacement), cx);
+            search_bar.set_query_translator(Some(translate_pattern));
             if replacement.flag_c {
                 search_bar.focus_replace(window, cx);
             }
@@ -688,13 +714,13 @@ impl Vim {
 }
 
 impl Replacement {
-    // convert a vim query into something more usable by zed.
-    // we don't attempt to fully convert between the two regex syntaxes,
-    // but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-    // convert \0..\9 to $0..$9 in the replacement so that common idioms work,
-    // and escape literal `$` to `$$` in the replacement so vim's literal `$`
-    // is not interpreted as a Rust regex capture-group reference.
-    pub(crate) fn parse(mut chars: Peekable<Chars>) -> Option<Replacement> {
+    // split a :s command into its parts. the pattern is kept in vim's syntax
+    // and translated by the search bar, while the replacement is translated
+    // right away since `~` refers to the previous replacement.
+    pub(crate) fn parse(
+        mut chars: Peekable<Chars>,
+        previous_replacement: Option<&str>,
+    ) -> Option<Replacement> {
         let delimiter = chars
             .next()
             .filter(|c| !c.is_alphanumeric() && *c != '"' && *c != '|' && *c != '\'')?;
@@ -714,14 +740,7 @@ impl Replacement {
         for c in chars {
             if escaped {
                 escaped = false;
-                if phase == 1 && c.is_ascii_digit() {
-                    buffer.push('$')
-                } else if phase == 1 && c == '$' {
-                    // Second '$' escapes by fallthrough
-                    buffer.push('$')
-                // unescape escaped parens
-                } else if phase == 0 && (c == '(' || c == ')') {
-                } else if c != delimiter {
+                if c != delimiter {
                     buffer.push('\\')
                 }
                 buffer.push(c)
@@ -738,21 +757,13 @@ impl Replacement {
                     break;
                 }
             } else {
-                // escape unescaped parens
-                if phase == 0 && (c == '(' || c == ')') {
-                    buffer.push('\\')
-                } else if phase == 1 && c == '$' {
-                    // '$' is not special in the replacement clause,
-                    // so we also escape here.
-                    buffer.push('$')
-                }
                 buffer.push(c)
             }
         }
 
         let mut replacement = Replacement {
             search,
-            replacement,
+            replacement: translate_replacement(&replacement, previous_replacement),
             case_sensitive: None,
             flag_g: false,
             flag_n: false,
@@ -790,7 +801,7 @@ mod test {
 
     #[test]
     fn test_replacement_parse_escaped_dollar() {
-        let parsed = super::Replacement::parse(r"/\$test/\$rest/g".chars().peekable())
+        let parsed = super::Replacement::parse(r"/\$test/\$rest/g".chars().peekable(), None)
             .expect("parse should succeed");
 
         assert_eq!(parsed.search, r"\$test");
@@ -0,0 +1,474 @@
+//! Translation of Vim search patterns (`:help pattern`) and substitute strings
+//! (`:help sub-replace-special`) into the regex syntax used by project search.
+//!
+//! Vim items without an equivalent are matched literally. Notably `~` in a
+//! pattern matches a `~` rather than the last substitute string, and the text
+//! before a `\zs` must have a fixed length.
+
+use std::{iter::Peekable, str::Chars};
+
+/// How many characters have a special meaning without a backslash (`:help /magic`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Magic {
+    /// `\V`
+    VeryNomagic,
+    /// `\M`
+    Nomagic,
+    /// `\m`, the default.
+    Magic,
+    /// `\v`
+    VeryMagic,
+}
+
+impl Magic {
+    fn special_chars(self) -> &'static str {
+        match self {
+            Magic::VeryNomagic => "",
+            Magic::Nomagic => "^$",
+            Magic::Magic => "^$.*[~",
+            Magic::VeryMagic => "^$.*[~+?=@{()|<>%",
+        }
+    }
+}
+
+/// Characters that are special either by themselves or after a backslash, depending on the magic level.
+const SPECIAL_CHARS: &str = "^$.*[~+?=@{()|<>%";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    /// A character with its special meaning, like `*` in `a*` or `+` in `a\+`.
+    Special(char),
+    /// A character that matches itself.
+    Literal(char),
+    /// A backslash followed by a character from outside [`SPECIAL_CHARS`], like `\s`.
+    Escaped(char),
+}
+
+#[derive(Clone)]
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    magic: Magic,
+}
+
+impl Lexer<'_> {
+    fn next_token(&mut self) -> Option<Token> {
+        let c = self.chars.next()?;
+        let special = self.magic.special_chars();
+        if c != '\\' {
+            return Some(if special.contains(c) {
+                Token::Special(c)
+            } else {
+                Token::Literal(c)
+            });
+        }
+        let Some(c) = self.chars.next() else {
+            return Some(Token::Literal('\\'));
+        };
+        Some(if special.contains(c) || c == '\\' {
+            Token::Literal(c)
+        } else if SPECIAL_CHARS.contains(c) {
+            Token::Special(c)
+        } else {
+            Token::Escaped(c)
+        })
+    }
+
+    fn peek_token(&self) -> Option<Token> {
+        self.clone().next_token()
+    }
+
+    fn next_token_if(&mut self, token: Token) -> bool {
+        if self.peek_token() == Some(token) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_char_if(&mut self, c: char) -> bool {
+        self.chars.next_if_eq(&c).is_some()
+    }
+}
+
+/// Translates a Vim search pattern into an equivalent regex.
+pub(crate) fn translate_pattern(pattern: &str) -> String {
+    let mut translator = Translator {
+        lexer: Lexer {
+            chars: pattern.chars().peekable(),
+            magic: Magic::Magic,
+        },
+        case_sensitive: None,
+    };
+    let regex = translator.top_level();
+    match translator.case_sensitive {
+        Some(true) => format!("(?-i){regex}"),
+        Some(false) => format!("(?i){regex}"),
+        None => regex,
+    }
+}
+
+struct Translator<'a> {
+    lexer: Lexer<'a>,
+    /// Set by `\c` and `\C`, which apply to the whole pattern wherever they appear.
+    case_sensitive: Option<bool>,
+}
+
+impl Translator<'_> {
+    /// Translates the whole pattern. `\zs` and `\ze` are only supported outside of groups, where
+    /// the text before `\zs` becomes a lookbehind and the text after `\ze` a lookahead.
+    fn top_level(&mut self) -> String {
+        let mut branches = Vec::new();
+        loop {
+            let (atoms, match_start, match_end) = self.branch(0);
+            let match_start = match_start.unwrap_or(0);
+            let match_end = match_end.unwrap_or(atoms.len()).max(match_start);
+            let mut branch = String::new();
+            if match_start > 0 {
+                branch.push_str(&format!("(?<={})", atoms[..match_start].concat()));
+            }
+            branch.push_str(&atoms[match_start..match_end].concat());
+            if match_end < atoms.len() {
+                branch.push_str(&format!("(?={})", atoms[match_end..].concat()));
+            }
+            branches.push(branch);
+            if !self.lexer.next_token_if(Token::Special('|')) {
+                break;
+            }
+        }
+        branches.join("|")
+    }
+
+    fn alternation(&mut self, depth: usize) -> String {
+        let mut branches = Vec::new();
+        loop {
+            branches.push(self.branch(depth).0.concat());
+            if !self.lexer.next_token_if(Token::Special('|')) {
+                break;
+            }
+        }
+        branches.join("|")
+    }
+
+    /// Translates the atoms up to the next `|` (or the `)` closing the current group), returning
+    /// them along with the positions of `\zs` and `\ze`.
+    fn branch(&mut self, depth: usize) -> (Vec<String>, Option<usize>, Option<usize>) {
+        let mut atoms: Vec<String> = Vec::new();
+        let mut match_start = None;
+        let mut match_end = None;
+        while let Some(token) = self.lexer.peek_token() {
+            match token {
+                Token::Special('|') => break,
+                Token::Special(')') if depth > 0 => break,
+                _ => {}
+            }
+            self.lexer.next_token();
+            match token {
+                Token::Special(c @ ('*' | '+' | '=' | '?' | '{' | '@')) if !atoms.is_empty() => {
+                    let atom = atoms.pop().unwrap();
+                    atoms.push(self.multi(c, atom));
+                }
+                Token::Escaped('z') if self.lexer.next_char_if('s') => {
+                    match_start = Some(atoms.len());
+                }
+                Token::Escaped('z') if self.lexer.next_char_if('e') => {
+                    match_end = Some(atoms.len());
+                }
+                Token::Escaped('v') => self.lexer.magic = Magic::VeryMagic,
+                Token::Escaped('m') => self.lexer.magic = Magic::Magic,
+                Token::Escaped('M') => self.lexer.magic = Magic::Nomagic,
+                Token::Escaped('V') => self.lexer.magic = Magic::VeryNomagic,
+                Token::Escaped('c') => self.case_sensitive = Some(false),
+                Token::Escaped('C') => self.case_sensitive = Some(true),
+                token => {
+                    let at_start = atoms.is_empty();
+                    let atom = self.atom(token, at_start, depth);
+                    atoms.push(atom);
+                }
+            }
+        }
+        (atoms, match_start, match_end)
+    }
+
+    fn atom(&mut self, token: Token, at_start: bool, depth: usize) -> String {
+        match token {
+            Token::Literal(c) => escape(c),
+            Token::Special('^') if at_start => "^".to_string(),
+            Token::Special('$') if self.at_branch_end(depth) => "$".to_string(),
+            Token::Special('.') => ".".to_string(),
+            Token::Special('[') => self.collection(false).unwrap_or_else(|| escape('[')),
+            Token::Special('(') => format!("({})", self.group(depth)),
+            Token::Special('%') => self.percent_item(depth),
+            Token::Special('<' | '>') => r"\b".to_string(),
+            Token::Special(c) => escape(c),
+            Token::Escaped(c) => self.escaped_atom(c),
+        }
+    }
+
+    fn at_branch_end(&self, depth: usize) -> bool {
+        match self.lexer.peek_token() {
+            None | Some(Token::Special('|') | Token::Escaped('n')) => true,
+            Some(Token::Special(')')) => depth > 0,
+            _ => false,
+        }
+    }
+
+    fn group(&mut self, depth: usize) -> String {
+        let inner = self.alternation(depth + 1);
+        self.lexer.next_token_if(Token::Special(')'));
+        inner
+    }
+
+    /// Applies a multi (`:help /multi`) to the atom it follows.
+    fn multi(&mut self, multi: char, atom: String) -> String {
+        match multi {
+            '*' => format!("{atom}*"),
+            '+' => format!("{atom}+"),
+            '=' | '?' => format!("{atom}?"),
+            '{' => self.brace_multi(atom),
+            '@' => self.lookaround(atom),
+            _ => atom,
+        }
+    }
+
+    /// Translates `\{n,m}` and its variants; `\{-n,m}` matches as few as possible.
+    fn brace_multi(&mut self, atom: String) -> String {
+        let lazy = self.lexer.next_char_if('-');
+        let mut contents = String::new();
+        while let Some(c) = self.lexer.chars.next() {
+            match c {
+                '}' => break,
+                '\\' if self.lexer.chars.peek() == Some(&'}') => {}
+                c => contents.push(c),
+            }
+        }
+        let (min, max) = match contents.split_once(',') {
+            Some((min, max)) => (min.trim(), Some(max.trim())),
+            None => (contents.trim(), None),
+        };
+        let quantifier = match (min, max) {
+            ("", None | Some("")) => "*".to_string(),
+            (min, None) => format!("{{{min}}}"),
+            ("", Some(max)) => format!("{{0,{max}}}"),
+            (min, Some("")) => format!("{{{min},}}"),
+            (min, Some(max)) => format!("{{{min},{max}}}"),
+        };
+        let lazy = if lazy { "?" } else { "" };
+        format!("{atom}{quantifier}{lazy}")
+    }
+
+    /// Translates `\@=`, `\@!`, `\@<=`, `\@<!` and `\@>`.
+    fn lookaround(&mut self, atom: String) -> String {
+        // The byte limit in `\@123<=` has no equivalent.
+        while self.lexer.chars.next_if(char::is_ascii_digit).is_some() {}
+        match self.lexer.chars.next() {
+            Some('=') => format!("(?={atom})"),
+            Some('!') => format!("(?!{atom})"),
+            Some('>') => format!("(?>{atom})"),
+            Some('<') if self.lexer.next_char_if('=') => format!("(?<={atom})"),
+            Some('<') if self.lexer.next_char_if('!') => format!("(?<!{atom})"),
+            _ => atom,
+        }
+    }
+
+    /// Translates the items starting with `\%`.
+    fn percent_item(&mut self, depth: usize) -> String {
+        let Some(c) = self.lexer.chars.next() else {
+            return escape('%');
+        };
+        match c {
+            '(' => format!("(?:{})", self.group(depth)),
+            '^' => r"\A".to_string(),
+            '$' => r"\z".to_string(),
+            'd' => self.char_code(10, usize::MAX),
+            'x' => self.char_code(16, 2),
+            'u' => self.char_code(16, 4),
+            'U' => self.char_code(16, 8),
+            'o' => self.char_code(8, 4),
+            '[' => self.optional_sequence(),
+            c => format!("{}{}", escape('%'), escape(c)),
+        }
+    }
+
+    /// Translates `\%[abc]`, which matches as much of `abc` as possible.
+    fn optional_sequence(&mut self) -> String {
+        let mut sequence = Vec::new();
+        for c in self.lexer.chars.by_ref() {
+            if c == ']' {
+                break;
+            }
+            sequence.push(c);
+        }
+        sequence.iter().rev().fold(String::new(), |inner, c| {
+            format!("(?:{}{inner})?", escape(*c))
+        })
+    }
+
+    fn char_code(&mut self, radix: u32, max_digits: usize) -> String {
+        let mut digits = String::new();
+        while digits.len() < max_digits
+            && let Some(digit) = self.lexer.chars.next_if(|c| c.is_digit(radix))
+        {
+            digits.push(digit);
+        }
+        u32::from_str_radix(&digits, radix)
+            .ok()
+            .and_then(char::from_u32)
+            .map(escape)
+            .unwrap_or_default()
+    }
+
+    fn escaped_atom(&mut self, c: char) -> String {
+        match c {
+            '_' => self.newline_atom(),
+            '1'..='9' => format!("\\{c}"),
+            'n' => r"\n".to_string(),
+            't' => r"\t".to_string(),
+            'e' => r"\x1b".to_string(),
+            'r' => r"\r".to_string(),
+            'b' => r"\x08".to_string(),
+            c => match character_class(c) {
+                Some((negated, class)) => render_class(negated, class, false),
+                None => escape(c),
+            },
+        }
+    }
+
+    /// Translates the `\_` items, which also match an end-of-line.
+    fn newline_atom(&mut self) -> String {
+        let Some(c) = self.lexer.chars.next() else {
+            return escape('_');
+        };
+        match c {
+            '^' => "^".to_string(),
+            '$' => "$".to_string(),
+            '.' => r"(?:.|\n)".to_string(),
+            '[' => self.collection(true).unwrap_or_else(|| escape('[')),
+            c => match character_class(c) {
+                Some((negated, class)) => render_class(negated, class, true),
+                None => format!("{}{}", escape('_'), escape(c)),
+            },
+        }
+    }
+
+    /// Translates a `[...]` collection, returning `None` if it is not terminated, in which case
+    /// the `[` is matched literally.
+    fn collection(&mut self, with_newline: bool) -> Option<String> {
+        let mut chars = self.lexer.chars.clone();
+        let negated = chars.next_if_eq(&'^').is_some();
+        let mut class = String::new();
+        if chars.next_if_eq(&']').is_some() {
+            class.push_str(r"\]");
+        }
+        loop {
+            match chars.next()? {
+                ']' => break,
+                '[' if chars.peek() == Some(&':') => {
+                    class.push('[');
+                    loop {
+                        let c = chars.next()?;
+                        class.push(c);
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '\\' => match chars.next()? {
+                    'e' => class.push_str(r"\x1b"),
+                    't' => class.push_str(r"\t"),
+                    'r' => class.push_str(r"\r"),
+                    'n' => class.push_str(r"\n"),
+                    'b' => class.push_str(r"\x08"),
+                    c @ ('\\' | ']' | '^' | '-') => {
+                        class.push('\\');
+                        class.push(c);
+                    }
+                    c => {
+                        class.push_str(r"\\");
+                        class.push_str(&escape(c));
+                    }
+                },
+                c @ ('[' | '&' | '~') => {
+                    class.push('\\');
+                    class.push(c);
+                }
+                c => class.push(c),
+            }
+        }
+        self.lexer.chars = chars;
+        Some(render_class(negated, &class, with_newline))
+    }
+}
+
+/// Returns whether the class is negated and its contents for the character classes
+/// in `:help /character-classes`.
+fn character_class(c: char) -> Option<(bool, &'static str)> {
+    let class = match c.to_ascii_lowercase() {
+        's' => r" \t",
+        'd' => "0-9",
+        'w' => "0-9A-Za-z_",
+        'a' => "A-Za-z",
+        'l' => "a-z",
+        'u' => "A-Z",
+        'x' => "0-9A-Fa-f",
+        'o' => "0-7",
+        'h' => "A-Za-z_",
+        // `\i`, `\k`, `\f` and `\p` depend on options like 'iskeyword'; these match their
+        // defaults. The uppercase variants exclude digits.
+        'i' | 'k' if c.is_lowercase() => r"\w",
+        'i' | 'k' => "A-Za-z_",
+        'f' if c.is_lowercase() => r"0-9A-Za-z_/.\-+,#$%~=",
+        'f' => r"A-Za-z_/.\-+,#$%~=",
+        'p' if c.is_lowercase() => "[:print:]",
+        _ => return None,
+    };
+    let negated = c.is_uppercase() && !matches!(c, 'I' | 'K' | 'F');
+    Some((negated, class))
+}
+
+/// Renders the contents of a character class. When `with_newline` is set, the class also matches
+/// a newline, which a negated class in the target syntax does anyway, but which is spelled out so
+/// that the search runs across lines.
+fn render_class(negated: bool, class: &str, with_newline: bool) -> String {
+    match (negated, with_newline) {
+        (false, false) => format!("[{class}]"),
+        (false, true) => format!(r"[{class}\n]"),
+        (true, false) => format!("[^{class}]"),
+        (true, true) => format!(r"(?:[^{class}]|\n)"),
+    }
+}
+
+fn escape(c: char) -> String {
+    regex::escape(c.encode_utf8(&mut [0; 4]))
+}
+
+/// Translates the replacement of a Vim `:s` command into the replacement syntax of project
+/// search, where `$1` refers to a capture group and `\u`, `\U`, `\l`, `\L` and `\E` convert case
+/// in queries built with [`project::search::SearchQuery::with_case_conversions`].
+/// `previous` is the previous translated replacement, which `~` stands for.
+pub(crate) fn translate_replacement(replacement: &str, previous: Option<&str>) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => result.push_str(&format!("${{{digit}}}")),
+                Some('n' | 'r') => result.push_str(r"\n"),
+                Some('t') => result.push_str(r"\t"),
+                Some('\\') => result.push_str(r"\\"),
+                Some(c @ ('u' | 'U' | 'l' | 'L' | 'e' | 'E')) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                Some('$') => result.push_str("$$"),
+                Some(c) => result.push(c),
+                None => result.push_str(r"\\"),
+            },
+            '&' => result.push_str("${0}"),
+            '~' => result.push_str(previous.unwrap_or_default()),
+            '$' => result.push_str("$$"),
+            c => result.push(c),
+        }
+    }
+    result
+}
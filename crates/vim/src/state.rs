@@ -2,6 +2,7 @@ use crate::command::command_interceptor;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
 use crate::surrounds::SurroundsType;
+use crate::vimrc;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
@@ -15,8 +16,8 @@ use editor::display_map::{is_invisible, replacement};
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
-    EntityId, Global, HighlightStyle, StyledText, Subscription, Task, TaskExt, TextStyle,
-    WeakEntity,
+    EntityId, Global, HighlightStyle, Keystroke, StyledText, Subscription, Task, TaskExt,
+    TextStyle, WeakEntity,
 };
 use language::{Buffer, BufferEvent, BufferId, Chunk, LanguageAwareStyling, Point};
 
@@ -28,6 +29,7 @@ use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
 use std::collections::HashSet;
 use std::path::Path;
+use std::time::Duration;
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme_settings::ThemeSettings;
@@ -249,9 +251,14 @@ impl From<String> for Register {
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
+    /// The translated replacement of the last `:s` command, which `~` refers to.
+    pub last_replacement: Option<String>,
 
     pub dot_recording: bool,
     pub dot_replaying: bool,
+    /// Whether the keys of a `:noremap` mapping are being replayed, during
+    /// which user-defined mappings are disabled.
+    pub replaying_noremap: bool,
 
     /// pre_count is the number before an operator is specified (3 in 3d2d)
     pub pre_count: Option<usize>,
@@ -278,9 +285,15 @@ pub struct VimGlobals {
     pub last_recorded_register: Option<char>,
     pub last_replayed_register: Option<char>,
     pub replayer: Option<Replayer>,
+    /// The keystrokes typed during the active macro recording, which become
+    /// the text of its register once the recording stops.
+    pub recording_keystrokes: Vec<Keystroke>,
 
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
+    /// Whether the named registers changed since they were last saved.
+    registers_dirty: bool,
+    _persist_registers_task: Option<Task<()>>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
 
     pub focused_vim: Option<WeakEntity<Vim>>,
@@ -764,6 +777,11 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.intercept_keystrokes(|event, _, cx| {
+            Vim::globals(cx).observe_keystroke(&event.keystroke)
+        })
+        .detach();
+
         cx.observe_new(|workspace: &mut Workspace, window, _| {
             RegistersView::register(workspace, window);
         })
@@ -774,6 +792,16 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.on_app_quit(|cx| {
+            let write = VimGlobals::flush_registers(cx);
+            async move {
+                if let Some(write) = write {
+                    write.await.log_err();
+                }
+            }
+        })
+        .detach();
+
         let mut was_enabled = None;
 
         cx.observe_global::<SettingsStore>(move |cx| {
@@ -784,6 +812,7 @@ impl VimGlobals {
             was_enabled = Some(is_enabled);
             if is_enabled {
                 KeyBinding::set_vim_mode(cx, true);
+                VimGlobals::load_registers(cx);
                 CommandPaletteFilter::update_global(cx, |filter, _| {
                     filter.show_namespace(Vim::NAMESPACE);
                 });
@@ -805,6 +834,9 @@ impl VimGlobals {
                 }
             } else {
                 KeyBinding::set_vim_mode(cx, false);
+                if let Some(write) = VimGlobals::flush_registers(cx) {
+                    write.detach_and_log_err(cx);
+                }
                 *Vim::globals(cx) = VimGlobals::default();
                 GlobalCommandPaletteInterceptor::clear(cx);
                 CommandPaletteFilter::update_global(cx, |filter, _| {
@@ -841,33 +873,34 @@ impl VimGlobals {
         if let Some(register) = register {
             let lower = register.to_lowercase().next().unwrap_or(register);
             if lower != register {
+                self.recordings.remove(&lower);
                 let current = self.registers.entry(lower).or_default();
                 current.text = (current.text.to_string() + &content.text).into();
                 // not clear how to support appending to registers with multiple cursors
                 current.clipboard_selections.take();
                 let yanked = current.clone();
-                self.registers.insert('"', yanked);
+                self.set_register('"', yanked);
             } else {
                 match lower {
                     '_' | ':' | '.' | '%' | '#' | '=' | '/' => {}
                     '+' => {
-                        self.registers.insert('"', content.clone());
+                        self.set_register('"', content.clone());
                         cx.write_to_clipboard(content.into());
                     }
                     '*' => {
-                        self.registers.insert('"', content.clone());
+                        self.set_register('"', content.clone());
                         #[cfg(any(target_os = "linux", target_os = "freebsd"))]
                         cx.write_to_primary(content.into());
                         #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
                         cx.write_to_clipboard(content.into());
                     }
                     '"' => {
-                        self.registers.insert('"', content.clone());
-                        self.registers.insert('0', content);
+                        self.set_register('"', content.clone());
+                        self.set_register('0', content);
                     }
                     _ => {
-                        self.registers.insert('"', content.clone());
-                        self.registers.insert(lower, content);
+                        self.set_register('"', content.clone());
+                        self.set_register(lower, content);
                     }
                 }
             }
@@ -883,18 +916,18 @@ impl VimGlobals {
                     self.last_yank.replace(text.into());
                 }
             }
-            self.registers.insert('"', content.clone());
+            self.set_register('"', content.clone());
             if is_yank {
-                self.registers.insert('0', content);
+                self.set_register('0', content);
             } else {
                 let contains_newline = content.text.contains('\n');
                 if !contains_newline {
-                    self.registers.insert('-', content.clone());
+                    self.set_register('-', content.clone());
                 }
                 if kind.linewise() || contains_newline {
                     let mut content = content;
                     for i in '1'..='9' {
-                        if let Some(moved) = self.registers.insert(i, content) {
+                        if let Some(moved) = self.set_register(i, content) {
                             content = moved;
                         } else {
                             break;
@@ -903,6 +936,74 @@ impl VimGlobals {
                 }
             }
         }
+        if register.is_some_and(|register| register.is_ascii_alphabetic()) {
+            self.persist_registers(cx);
+        }
+    }
+
+    /// Sets the contents of a register. A recorded macro in the register is
+    /// replaced too, so that it is replayed from the new text.
+    fn set_register(&mut self, register: char, content: Register) -> Option<Register> {
+        self.recordings.remove(&register);
+        self.registers.insert(register, content)
+    }
+
+    /// Saves the named registers shortly, so that they're restored after a
+    /// restart. Writes are debounced, since registers change on every yank.
+    pub(crate) fn persist_registers(&mut self, cx: &App) {
+        self.registers_dirty = true;
+        self._persist_registers_task = Some(cx.spawn(async move |cx| {
+            cx.background_executor()
+                .timer(PERSIST_REGISTERS_DEBOUNCE)
+                .await;
+            let write = cx.update(|cx| {
+                Vim::update_globals(cx, |globals, cx| globals.write_persisted_registers(cx))
+            });
+            write.await.log_err();
+        }));
+    }
+
+    fn write_persisted_registers(&mut self, cx: &App) -> Task<Result<()>> {
+        self.registers_dirty = false;
+        // Like viminfo, registers too large to save are dropped rather than
+        // restored with their previous contents.
+        let registers = self
+            .registers
+            .iter()
+            .filter(|(name, _)| is_persisted_register(**name))
+            .map(|(name, register)| {
+                let register =
+                    (register.text.len() <= MAX_PERSISTED_REGISTER_LEN).then(|| register.clone());
+                (*name, register)
+            })
+            .collect::<Vec<_>>();
+        let db = VimDb::global(cx);
+        cx.background_spawn(async move { db.set_registers(registers).await })
+    }
+
+    /// Saves the registers still waiting for their debounced write.
+    fn flush_registers(cx: &mut App) -> Option<Task<Result<()>>> {
+        cx.try_global::<VimGlobals>()
+            .is_some_and(|globals| globals.registers_dirty)
+            .then(|| Vim::update_globals(cx, |globals, cx| globals.write_persisted_registers(cx)))
+    }
+
+    fn load_registers(cx: &mut App) {
+        let db = VimDb::global(cx);
+        cx.spawn(async move |cx| {
+            let registers = cx
+                .background_spawn(async move { db.get_registers() })
+                .await?;
+            cx.update(|cx| {
+                let globals = Vim::globals(cx);
+                for (name, register) in registers {
+                    // Registers written before the load finished are newer.
+                    globals.registers.entry(name).or_insert(register);
+                }
+            });
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
     }
 
     pub(crate) fn read_register(
@@ -988,6 +1089,43 @@ impl VimGlobals {
         }
     }
 
+    pub fn observe_keystroke(&mut self, keystroke: &Keystroke) {
+        if self.replayer.is_none() && self.recording_register.is_some() {
+            self.recording_keystrokes.push(keystroke.clone());
+        }
+    }
+
+    /// Stops the active macro recording, and stores its keys as the text of
+    /// the register so that it can be edited by pasting and yanking it.
+    /// Recording into an uppercase register appends to the lowercase one.
+    pub(crate) fn stop_recording(&mut self, cx: &App) {
+        let Some(register) = self.recording_register.take() else {
+            return;
+        };
+        self.last_recorded_register = Some(register);
+        // The last keystroke is the one that stopped the recording.
+        self.recording_keystrokes.pop();
+        let keys = std::mem::take(&mut self.recording_keystrokes)
+            .iter()
+            .map(vimrc::keystroke_notation)
+            .collect::<String>();
+        let lower = register.to_ascii_lowercase();
+        if lower == register {
+            self.registers.insert(lower, keys.into());
+        } else {
+            let actions = self.recordings.remove(&register).unwrap_or_default();
+            // A register without a recording is replayed from its text, which
+            // the new keys are appended to.
+            if let Some(recording) = self.recordings.get_mut(&lower) {
+                recording.extend(actions);
+            }
+            let current = self.registers.entry(lower).or_default();
+            current.text = (current.text.to_string() + &keys).into();
+            current.clipboard_selections.take();
+        }
+        self.persist_registers(cx);
+    }
+
     pub fn observe_insertion(&mut self, text: &Arc<str>, range_to_replace: Option<Range<isize>>) {
         if self.ignore_current_insertion {
             self.ignore_current_insertion = false;
@@ -1041,6 +1179,9 @@ pub enum ReplayableAction {
         text: Arc<str>,
         utf16_range_to_replace: Option<Range<isize>>,
     },
+    /// A keystroke from a macro that was edited as text, or loaded from a
+    /// previous session.
+    Keystroke(Keystroke),
 }
 
 impl Clone for ReplayableAction {
@@ -1054,6 +1195,7 @@ impl Clone for ReplayableAction {
                 text: text.clone(),
                 utf16_range_to_replace: utf16_range_to_replace.clone(),
             },
+            Self::Keystroke(keystroke) => Self::Keystroke(keystroke.clone()),
         }
     }
 }
@@ -1826,6 +1968,13 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_registers(
+                register_name TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                clipboard_selections TEXT
+            );
+        ),
     ];
 }
 
@@ -1944,4 +2093,82 @@ impl VimDb {
         })
         .await
     }
+
+    /// Saves the given registers, and deletes the ones that are `None`, along
+    /// with any register that's no longer persisted.
+    pub(crate) async fn set_registers(
+        &self,
+        registers: Vec<(char, Option<Register>)>,
+    ) -> Result<()> {
+        log::debug!("Setting {} registers", registers.len());
+        self.write(move |conn| {
+            conn.exec(sql!(
+                DELETE FROM vim_registers
+                WHERE length(register_name) != 1
+                    OR register_name NOT BETWEEN 'a' AND 'z'
+            ))?()?;
+            let mut query = conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_registers
+                    (register_name, value, clipboard_selections)
+                VALUES
+                    (?, ?, ?)
+            ))?;
+            let mut delete = conn.exec_bound(sql!(
+                DELETE FROM vim_registers WHERE register_name = ?
+            ))?;
+            for (name, register) in registers {
+                let Some(register) = register else {
+                    delete(name.to_string())?;
+                    continue;
+                };
+                let clipboard_selections = register
+                    .clipboard_selections
+                    .map(|selections| serde_json::to_string(&selections))
+                    .transpose()?;
+                query((
+                    name.to_string(),
+                    register.text.to_string(),
+                    clipboard_selections,
+                ))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn get_registers(&self) -> Result<Vec<(char, Register)>> {
+        let result: Vec<(String, String, Option<String>)> = self.select(sql!(
+            SELECT register_name, value, clipboard_selections FROM vim_registers
+        ))?()?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(name, value, clipboard_selections)| {
+                let mut chars = name.chars();
+                let name = chars
+                    .next()
+                    .filter(|name| chars.next().is_none() && is_persisted_register(*name))?;
+                Some((
+                    name,
+                    Register {
+                        text: value.into(),
+                        clipboard_selections: clipboard_selections
+                            .and_then(|json| serde_json::from_str(&json).log_err()),
+                    },
+                ))
+            })
+            .collect())
+    }
 }
+
+/// Whether a register is saved across restarts. Only the named registers are,
+/// so that deleted text doesn't end up on disk.
+fn is_persisted_register(register: char) -> bool {
+    register.is_ascii_lowercase()
+}
+
+/// The largest register saved across restarts, in bytes, matching viminfo's
+/// default limit of 10 KiB.
+const MAX_PERSISTED_REGISTER_LEN: usize = 10 * 1024;
+
+const PERSIST_REGISTERS_DEBOUNCE: Duration = Duration::from_secs(1);
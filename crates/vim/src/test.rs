@@ -1,5 +1,6 @@
 mod neovim_backed_test_context;
 mod neovim_connection;
+mod pattern_conformance;
 mod vim_test_context;
 
 use std::{sync::Arc, time::Duration};
@@ -0,0 +1,207 @@
+//! Checks that Vim patterns match the same text once translated, following the
+//! examples in `:help pattern`.
+
+use fancy_regex::Regex;
+use indoc::indoc;
+
+use crate::{
+    pattern::{translate_pattern, translate_replacement},
+    test::VimTestContext,
+};
+
+#[track_caller]
+fn assert_matches(pattern: &str, haystack: &str, expected: &[&str]) {
+    let translated = translate_pattern(pattern);
+    let regex = Regex::new(&translated).unwrap_or_else(|error| {
+        panic!("{pattern:?} translated to invalid {translated:?}: {error}")
+    });
+    let matches = regex
+        .find_iter(haystack)
+        .map(|found| found.unwrap().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        matches, expected,
+        "{pattern:?} (translated to {translated:?}) on {haystack:?}"
+    );
+}
+
+#[test]
+fn test_literals() {
+    assert_matches("foo", "foo bar foo", &["foo", "foo"]);
+    assert_matches(r"a\.b", "a.b axb", &["a.b"]);
+    assert_matches("a+b", "a+b aab", &["a+b"]);
+    assert_matches("(x)", "(x) x", &["(x)"]);
+    assert_matches("{1}", "a{1}", &["{1}"]);
+    assert_matches("a|b", "a|b ab", &["a|b"]);
+    assert_matches(r"a\\b", r"a\b", &[r"a\b"]);
+    assert_matches("~", "a~b", &["~"]);
+}
+
+#[test]
+fn test_magic_levels() {
+    // magic: `.` and `*` are special, `+` needs a backslash.
+    assert_matches("a.*c", "abbc", &["abbc"]);
+    assert_matches(r"ab\+", "abbb a", &["abbb"]);
+    // very magic: everything but letters, digits and `_` is special.
+    assert_matches(r"\v(ab)+|c{2}", "ababx cc c", &["abab", "cc"]);
+    assert_matches(r"\v<foo>", "foo foobar", &["foo"]);
+    assert_matches(r"\va\.b", "a.b axb", &["a.b"]);
+    // nomagic: only `^` and `$` are special.
+    assert_matches(r"\Ma.*", "a.* abc", &["a.*"]);
+    assert_matches(r"\Ma\.", "ab", &["ab"]);
+    // very nomagic: only a backslash is special.
+    assert_matches(r"\Va.b$", "a.b$ axb", &["a.b$"]);
+    assert_matches(r"\Va\.b", "axb", &["axb"]);
+    // magic can be switched mid pattern.
+    assert_matches(r"\Va.\mb.", "a.bc axbc", &["a.bc"]);
+}
+
+#[test]
+fn test_anchors_and_word_boundaries() {
+    assert_matches("^foo", "foo foo", &["foo"]);
+    assert_matches("foo$", "foo foo", &["foo"]);
+    assert_matches("a^b$c", "a^b$c", &["a^b$c"]);
+    assert_matches(r"\<is\>", "this is island", &["is"]);
+    assert_matches(r"\(^a\|b$\)", "aab", &["a", "b"]);
+    assert_matches(r"\%^a", "aa", &["a"]);
+}
+
+#[test]
+fn test_multis() {
+    assert_matches(r"ab\=c", "ac abc abbc", &["ac", "abc"]);
+    assert_matches(r"ab\?c", "ac abc", &["ac", "abc"]);
+    assert_matches(r"a\{2}", "aaaaa", &["aa", "aa"]);
+    assert_matches(r"a\{2,3}", "aaaaa", &["aaa", "aa"]);
+    assert_matches(r"a\{,2}b", "aaab", &["aab"]);
+    assert_matches(r"a\{2,}", "a aaaa", &["aaaa"]);
+    assert_matches(r"ba\{}", "b baaa", &["b", "baaa"]);
+    assert_matches(r"a\{-1,}", "aaa", &["a", "a", "a"]);
+    assert_matches(r"<.\{-}>", "<a><b>", &["<a>", "<b>"]);
+    assert_matches(r"a\{2\}", "aaa", &["aa"]);
+    assert_matches(r"\v(ab){2}", "ababab", &["abab"]);
+}
+
+#[test]
+fn test_groups_and_backreferences() {
+    assert_matches(r"\(a\|b\)\1", "aa ab bb", &["aa", "bb"]);
+    assert_matches(r"\%(a\|b\)c", "ac bc cc", &["ac", "bc"]);
+    assert_matches(r"\v(\w)\1", "hello", &["ll"]);
+    assert_matches(
+        r"fu\%[nction]",
+        "fu fun function",
+        &["fu", "fun", "function"],
+    );
+}
+
+#[test]
+fn test_lookarounds() {
+    assert_matches(r"foo\(bar\)\@=", "foobar foobaz", &["foo"]);
+    assert_matches(r"foo\(bar\)\@!", "foobar foobaz", &["foo"]);
+    assert_matches(r"\(foo\)\@<=bar", "foobar bazbar", &["bar"]);
+    assert_matches(r"\(foo\)\@<!bar", "foobar bazbar", &["bar"]);
+    assert_matches(r"\v(a*)@>a", "aaa", &[]);
+}
+
+#[test]
+fn test_match_start_and_end() {
+    assert_matches(r"foo\zsbar", "foobar bar", &["bar"]);
+    assert_matches(r"foo\zebar", "foobar foo", &["foo"]);
+    assert_matches(r"a\zsb\zec", "abc abd", &["b"]);
+    assert_matches(r"x\zsy\|z", "xy z y", &["y", "z"]);
+}
+
+#[test]
+fn test_character_classes() {
+    assert_matches(r"\s\+", "a \t b", &[" \t "]);
+    assert_matches(r"\S\+", "ab cd", &["ab", "cd"]);
+    assert_matches(r"\d\+", "a12b3", &["12", "3"]);
+    assert_matches(r"\D\+", "a12b3", &["a", "b"]);
+    assert_matches(r"\w\+", "foo-bar_1", &["foo", "bar_1"]);
+    assert_matches(r"\a\+", "ab1c", &["ab", "c"]);
+    assert_matches(r"\u\l\+", "Foo bar Baz", &["Foo", "Baz"]);
+    assert_matches(r"\x\+", "0xfF zz", &["0", "fF"]);
+    assert_matches(r"\o\+", "0789", &["07"]);
+    assert_matches(r"\h\w*", "_foo 1bar", &["_foo", "bar"]);
+    assert_matches(r"[a-c]\+", "abcd", &["abc"]);
+    assert_matches(r"[^a-c]\+", "abcd", &["d"]);
+    assert_matches(r"[]x]", "]x", &["]", "x"]);
+    assert_matches(r"[[:digit:]]\+", "a12", &["12"]);
+    assert_matches(r"[\t]", "a\tb", &["\t"]);
+    assert_matches(r"[&~]", "a&b~", &["&", "~"]);
+    assert_matches("[abc", "[abc", &["[abc"]);
+    assert_matches(r"\%d65\%x42\%u0043", "ABC", &["ABC"]);
+}
+
+#[test]
+fn test_multiline_items() {
+    assert_matches(r"a\nb", "a\nb", &["a\nb"]);
+    assert_matches(r"a\_s\+b", "a \n b", &["a \n b"]);
+    assert_matches(r"a\_.b", "a\nb axb", &["a\nb", "axb"]);
+    assert_matches(r"a\_[xy]\+b", "ax\nyb", &["ax\nyb"]);
+    assert_matches(r"a\_S", "a\n", &["a\n"]);
+}
+
+#[test]
+fn test_case_sensitivity_items() {
+    assert_matches(r"foo\c", "FOO foo", &["FOO", "foo"]);
+    assert_matches(r"\cfoo", "Foo", &["Foo"]);
+    assert_matches(r"\CFoo", "foo Foo", &["Foo"]);
+    assert_eq!(translate_pattern(r"a\cb"), "(?i)ab");
+    assert_eq!(translate_pattern(r"\Cab"), "(?-i)ab");
+}
+
+#[test]
+fn test_replacement_translation() {
+    assert_eq!(translate_replacement(r"\1-\2", None), "${1}-${2}");
+    assert_eq!(translate_replacement(r"[&]", None), "[${0}]");
+    assert_eq!(translate_replacement(r"\&\~", None), "&~");
+    assert_eq!(translate_replacement("$5", None), "$$5");
+    assert_eq!(translate_replacement(r"\$", None), "$$");
+    assert_eq!(translate_replacement(r"a\rb\nc\td", None), r"a\nb\nc\td");
+    assert_eq!(translate_replacement(r"\\", None), r"\\");
+    assert_eq!(
+        translate_replacement(r"\u\0\U\1\E", None),
+        r"\u${0}\U${1}\E"
+    );
+    assert_eq!(translate_replacement(r"x~y", Some("${0}!")), "x${0}!y");
+    assert_eq!(translate_replacement("~", None), "");
+}
+
+#[gpui::test]
+async fn test_substitute_with_vim_patterns(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state(
+        indoc! {"
+            ˇfoo_bar baz_qux
+            one two
+            "},
+        crate::state::Mode::Normal,
+    );
+    cx.simulate_keystrokes(": s / \\ v ( \\ w + ) _ ( \\ w + ) / \\ u \\ 2 _ \\ U \\ 1 / g enter");
+    cx.assert_state(
+        indoc! {"
+            ˇBar_FOO Qux_BAZ
+            one two
+            "},
+        crate::state::Mode::Normal,
+    );
+
+    cx.simulate_keystrokes("j : s / \\ < t \\ w * / [ & ] / enter");
+    cx.assert_state(
+        indoc! {"
+            Bar_FOO Qux_BAZ
+            ˇone [two]
+            "},
+        crate::state::Mode::Normal,
+    );
+
+    cx.simulate_keystrokes(": s / o n \\ z s e / ~ ~ / enter");
+    cx.assert_state(
+        indoc! {"
+            Bar_FOO Qux_BAZ
+            ˇon[e][e] [two]
+            "},
+        crate::state::Mode::Normal,
+    );
+}
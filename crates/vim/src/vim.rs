@@ -13,10 +13,12 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod pattern;
 mod replace;
 mod rewrap;
 mod state;
 mod surrounds;
+mod vimrc;
 mod visual;
 
 use crate::normal::paste::Paste as VimPaste;
@@ -55,6 +57,7 @@ use theme_settings::ThemeSettings;
 use ui::{IntoElement, SharedString, px};
 use vim_mode_setting::HelixModeSetting;
 use vim_mode_setting::VimModeSetting;
+pub use vimrc::{VimUserConfig, handle_vimrc_file_changes, user_key_bindings};
 use workspace::{self, Pane, Workspace};
 
 use crate::{
@@ -287,6 +290,7 @@ pub fn init(cx: &mut App) {
     VimGlobals::register(cx);
 
     cx.observe_new(Vim::register).detach();
+    cx.observe_new(vimrc::register).detach();
 
     cx.observe_new(|workspace: &mut Workspace, _, _| {
         workspace.register_action(|workspace, _: &ToggleVimMode, _, cx| {
@@ -660,6 +664,19 @@ impl Vim {
             }
         })
         .detach();
+
+        let mut vimrc_version = VimUserConfig::vimrc_version(cx);
+        cx.observe_global::<VimUserConfig>(move |editor, cx| {
+            let version = VimUserConfig::vimrc_version(cx);
+            if version != vimrc_version {
+                vimrc_version = version;
+                if editor.addon::<VimAddon>().is_some() {
+                    VimUserConfig::apply_editor_options(editor, cx);
+                }
+            }
+        })
+        .detach();
+
         if was_enabled {
             Self::activate(editor, window, cx)
         }
@@ -676,6 +693,7 @@ impl Vim {
         });
 
         Vim::sync_vim_settings_to_editor(&state, editor, window, cx);
+        VimUserConfig::apply_editor_options(editor, cx);
 
         editor.register_addon(VimAddon {
             entity: vim.clone(),
@@ -1519,6 +1537,10 @@ impl Vim {
             }
         }
 
+        if cx.global::<VimGlobals>().replaying_noremap {
+            context.add("VimNoremap");
+        }
+
         if mode == "normal"
             || mode == "visual"
             || mode == "operator"
@@ -0,0 +1,1039 @@
+//! Support for user-defined mappings, set via `:map` and friends or loaded
+//! from a small subset of vimrc syntax in `~/.config/zed/vimrc`.
+//!
+//! Mappings are compiled into regular keymap bindings that replay their
+//! right-hand side through [`workspace::SendKeystrokes`], or through
+//! [`SendKeystrokesNoremap`] for `:noremap` and friends, so that the keys
+//! they send don't trigger other mappings.
+
+use std::rc::Rc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::HashMap;
+use editor::Editor;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    Action, App, Context, Global, KeyBinding, KeyBindingContextPredicate, Keystroke, NoAction,
+    Window,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use util::ResultExt as _;
+use workspace::{SendKeystrokes, Workspace};
+
+use crate::{Vim, command::VimOption};
+
+/// Sends keystrokes like `workspace::SendKeystrokes`, but without triggering
+/// the mappings from the vimrc or from `:map` commands while they're replayed.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = vim)]
+pub(crate) struct SendKeystrokesNoremap(pub String);
+
+/// The leader used when `mapleader` has not been set.
+const DEFAULT_LEADER: &str = "\\";
+
+/// Special arguments accepted (and ignored) before the left-hand side of a mapping.
+const SPECIAL_MAP_ARGUMENTS: &[&str] = &[
+    "<buffer>",
+    "<nowait>",
+    "<silent>",
+    "<special>",
+    "<script>",
+    "<unique>",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct MapModes {
+    normal: bool,
+    visual: bool,
+    operator: bool,
+    insert: bool,
+}
+
+impl MapModes {
+    const NORMAL: Self = Self {
+        normal: true,
+        visual: false,
+        operator: false,
+        insert: false,
+    };
+    const VISUAL: Self = Self {
+        normal: false,
+        visual: true,
+        operator: false,
+        insert: false,
+    };
+    const OPERATOR: Self = Self {
+        normal: false,
+        visual: false,
+        operator: true,
+        insert: false,
+    };
+    const INSERT: Self = Self {
+        normal: false,
+        visual: false,
+        operator: false,
+        insert: true,
+    };
+    const NORMAL_VISUAL_OPERATOR: Self = Self {
+        normal: true,
+        visual: true,
+        operator: true,
+        insert: false,
+    };
+
+    fn is_empty(&self) -> bool {
+        !(self.normal || self.visual || self.operator || self.insert)
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        (self.normal && other.normal)
+            || (self.visual && other.visual)
+            || (self.operator && other.operator)
+            || (self.insert && other.insert)
+    }
+
+    fn remove(&mut self, other: &Self) {
+        self.normal &= !other.normal;
+        self.visual &= !other.visual;
+        self.operator &= !other.operator;
+        self.insert &= !other.insert;
+    }
+
+    /// The keymap context in which a mapping for these modes is active.
+    fn context(&self) -> String {
+        [
+            (self.normal, "vim_mode == normal"),
+            (self.visual, "vim_mode == visual"),
+            (self.operator, "vim_mode == operator"),
+            (self.insert, "vim_mode == insert"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, context)| enabled.then_some(context))
+        .collect::<Vec<_>>()
+        .join(" || ")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapKind {
+    Map,
+    Noremap,
+    Unmap,
+}
+
+/// The `:map` family of commands, as `(prefix, suffix)` abbreviations
+/// following the same convention as the `:` command table.
+const MAP_COMMANDS: &[((&str, &str), MapKind, MapModes)] = &[
+    (("map", ""), MapKind::Map, MapModes::NORMAL_VISUAL_OPERATOR),
+    (("nm", "ap"), MapKind::Map, MapModes::NORMAL),
+    (("vm", "ap"), MapKind::Map, MapModes::VISUAL),
+    (("xm", "ap"), MapKind::Map, MapModes::VISUAL),
+    (("om", "ap"), MapKind::Map, MapModes::OPERATOR),
+    (("im", "ap"), MapKind::Map, MapModes::INSERT),
+    (
+        ("no", "remap"),
+        MapKind::Noremap,
+        MapModes::NORMAL_VISUAL_OPERATOR,
+    ),
+    (("nn", "oremap"), MapKind::Noremap, MapModes::NORMAL),
+    (("vn", "oremap"), MapKind::Noremap, MapModes::VISUAL),
+    (("xn", "oremap"), MapKind::Noremap, MapModes::VISUAL),
+    (("ono", "remap"), MapKind::Noremap, MapModes::OPERATOR),
+    (("ino", "remap"), MapKind::Noremap, MapModes::INSERT),
+    (
+        ("unm", "ap"),
+        MapKind::Unmap,
+        MapModes::NORMAL_VISUAL_OPERATOR,
+    ),
+    (("nun", "map"), MapKind::Unmap, MapModes::NORMAL),
+    (("vu", "nmap"), MapKind::Unmap, MapModes::VISUAL),
+    (("xu", "nmap"), MapKind::Unmap, MapModes::VISUAL),
+    (("ou", "nmap"), MapKind::Unmap, MapModes::OPERATOR),
+    (("iu", "nmap"), MapKind::Unmap, MapModes::INSERT),
+];
+
+/// The names of the `:map` family of commands, used to register them with
+/// the `:` command parser.
+pub(crate) fn map_command_names() -> impl Iterator<Item = (&'static str, &'static str)> {
+    MAP_COMMANDS.iter().map(|(name, _, _)| *name)
+}
+
+fn matches_abbreviation(word: &str, (prefix, suffix): (&str, &str)) -> bool {
+    word.strip_prefix(prefix)
+        .is_some_and(|rest| suffix.starts_with(rest))
+}
+
+/// A single line of a vimrc, or a mapping command entered at runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum VimrcCommand {
+    Map {
+        modes: MapModes,
+        lhs: String,
+        rhs: String,
+        /// Whether the keys of `rhs` are sent without triggering other mappings.
+        noremap: bool,
+    },
+    Unmap {
+        modes: MapModes,
+        lhs: String,
+    },
+    Set(Vec<VimOption>),
+    SetLeader {
+        local: bool,
+        leader: String,
+    },
+    Command {
+        name: String,
+        replacement: String,
+    },
+}
+
+impl VimrcCommand {
+    /// Parses a line of vimrc. Returns `Ok(None)` for blank lines and comments.
+    pub(crate) fn parse(line: &str) -> Result<Option<Self>> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        if line.is_empty() || line.starts_with('"') {
+            return Ok(None);
+        }
+
+        let (word, rest) = line
+            .split_once(char::is_whitespace)
+            .map(|(word, rest)| (word, rest.trim_start()))
+            .unwrap_or((line, ""));
+        let (word, bang) = match word.strip_suffix('!') {
+            Some(word) => (word, true),
+            None => (word, false),
+        };
+
+        if let Some((_, kind, modes)) = MAP_COMMANDS
+            .iter()
+            .find(|(name, _, _)| matches_abbreviation(word, *name))
+        {
+            // `:map!` and friends apply to insert and command-line mode.
+            let modes = match (bang, *modes) {
+                (false, modes) => modes,
+                (true, MapModes::NORMAL_VISUAL_OPERATOR) => MapModes::INSERT,
+                (true, _) => bail!("trailing characters: !"),
+            };
+            return Self::parse_map(*kind, modes, rest).map(Some);
+        }
+
+        if matches_abbreviation(word, ("se", "t")) {
+            let options = rest
+                .split_whitespace()
+                .filter_map(|option| {
+                    let parsed = VimOption::from(option);
+                    if parsed.is_none() {
+                        log::warn!("vimrc: ignoring unsupported option {option:?}");
+                    }
+                    parsed
+                })
+                .collect();
+            return Ok(Some(Self::Set(options)));
+        }
+
+        if word == "let" {
+            return Self::parse_let(rest).map(Some);
+        }
+
+        if matches_abbreviation(word, ("com", "mand")) {
+            return Self::parse_command(rest).map(Some);
+        }
+
+        Err(anyhow!("unsupported command: {line}"))
+    }
+
+    fn parse_map(kind: MapKind, modes: MapModes, mut rest: &str) -> Result<Self> {
+        loop {
+            let lowercase = rest.to_ascii_lowercase();
+            if lowercase.starts_with("<expr>") {
+                bail!("<expr> mappings are not supported");
+            }
+            let Some(argument) = SPECIAL_MAP_ARGUMENTS
+                .iter()
+                .find(|argument| lowercase.starts_with(*argument))
+            else {
+                break;
+            };
+            rest = rest[argument.len()..].trim_start();
+        }
+
+        let (lhs, rhs) = rest
+            .split_once(char::is_whitespace)
+            .map(|(lhs, rhs)| (lhs, rhs.trim()))
+            .unwrap_or((rest, ""));
+        if lhs.is_empty() {
+            bail!("listing mappings is not supported");
+        }
+
+        match kind {
+            MapKind::Unmap if !rhs.is_empty() => bail!("trailing characters: {rhs}"),
+            MapKind::Unmap => Ok(Self::Unmap {
+                modes,
+                lhs: lhs.to_string(),
+            }),
+            MapKind::Map | MapKind::Noremap if rhs.is_empty() => {
+                bail!("listing mappings is not supported")
+            }
+            MapKind::Map | MapKind::Noremap => Ok(Self::Map {
+                modes,
+                lhs: lhs.to_string(),
+                rhs: rhs.to_string(),
+                noremap: kind == MapKind::Noremap,
+            }),
+        }
+    }
+
+    fn parse_let(rest: &str) -> Result<Self> {
+        let (name, value) = rest
+            .split_once('=')
+            .context("expected `let {name} = {value}`")?;
+        let name = name.trim();
+        let name = name.strip_prefix("g:").unwrap_or(name);
+        let local = match name {
+            "mapleader" => false,
+            "maplocalleader" => true,
+            _ => bail!("unsupported variable: {name}"),
+        };
+        Ok(Self::SetLeader {
+            local,
+            leader: parse_string_literal(value.trim())?,
+        })
+    }
+
+    fn parse_command(mut rest: &str) -> Result<Self> {
+        while rest.starts_with('-') {
+            rest = rest
+                .split_once(char::is_whitespace)
+                .map(|(_, rest)| rest.trim_start())
+                .unwrap_or("");
+        }
+
+        let (name, replacement) = rest
+            .split_once(char::is_whitespace)
+            .map(|(name, replacement)| (name, replacement.trim()))
+            .unwrap_or((rest, ""));
+        if !name.starts_with(|c: char| c.is_ascii_uppercase())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            bail!("user-defined commands must start with an uppercase letter: {name}");
+        }
+        if replacement.is_empty() {
+            bail!("listing commands is not supported");
+        }
+        Ok(Self::Command {
+            name: name.to_string(),
+            replacement: replacement.to_string(),
+        })
+    }
+}
+
+/// Parses a vim string literal, expanding `\<Key>` notation in double-quoted
+/// strings.
+fn parse_string_literal(value: &str) -> Result<String> {
+    if let Some(value) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Ok(value.replace("''", "'"));
+    }
+
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .with_context(|| format!("expected a string: {value}"))?;
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('<') => {
+                let name = chars.by_ref().take_while(|c| *c != '>').collect::<String>();
+                match name.to_ascii_lowercase().as_str() {
+                    "space" => result.push(' '),
+                    "bslash" => result.push('\\'),
+                    "bar" => result.push('|'),
+                    "lt" => result.push('<'),
+                    _ => {
+                        result.push('<');
+                        result.push_str(&name);
+                        result.push('>');
+                    }
+                }
+            }
+            Some(c) => result.push(c),
+            None => result.push('\\'),
+        }
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Debug, Default)]
+struct Leaders {
+    leader: Option<String>,
+    local_leader: Option<String>,
+}
+
+/// Translates vim key notation, such as `<leader>f<C-w>`, into a list of
+/// keystrokes in the format used by Zed keymaps.
+fn translate_keys(keys: &str, leaders: &Leaders) -> Result<Vec<String>> {
+    let mut result = Vec::new();
+    let mut rest = keys;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && end > 1
+        {
+            let name = &rest[1..end];
+            match name.to_ascii_lowercase().as_str() {
+                "leader" => {
+                    let leader = leaders.leader.as_deref().unwrap_or(DEFAULT_LEADER);
+                    result.extend(leader.chars().map(translate_char));
+                }
+                "localleader" => {
+                    let leader = leaders
+                        .local_leader
+                        .as_deref()
+                        .or(leaders.leader.as_deref())
+                        .unwrap_or(DEFAULT_LEADER);
+                    result.extend(leader.chars().map(translate_char));
+                }
+                _ => {
+                    if let Some(key) = translate_special_key(name) {
+                        result.push(key);
+                    } else {
+                        // Not key notation, so the `<` is a literal key.
+                        result.push(translate_char(c));
+                        rest = &rest[1..];
+                        continue;
+                    }
+                }
+            }
+            rest = &rest[end + 1..];
+        } else {
+            result.push(translate_char(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    for key in &result {
+        Keystroke::parse(key).map_err(|_| anyhow!("invalid key {key:?} in {keys:?}"))?;
+    }
+    Ok(result)
+}
+
+fn translate_char(c: char) -> String {
+    match c {
+        ' ' => "space".to_string(),
+        '\t' => "tab".to_string(),
+        '\n' => "enter".to_string(),
+        c => c.to_string(),
+    }
+}
+
+/// Translates the contents of a `<...>` key notation, e.g. `C-w` or `CR`.
+fn translate_special_key(name: &str) -> Option<String> {
+    let mut modifiers = Vec::new();
+    let mut rest = name;
+    while let Some((modifier, tail)) = rest.split_once('-')
+        && !tail.is_empty()
+    {
+        modifiers.push(match modifier.to_ascii_lowercase().as_str() {
+            "c" => "ctrl",
+            "s" => "shift",
+            "m" | "a" => "alt",
+            "d" => "cmd",
+            _ => return None,
+        });
+        rest = tail;
+    }
+
+    let key = if rest.chars().count() == 1 {
+        if modifiers.is_empty() {
+            return None;
+        }
+        match rest {
+            "-" => "-".to_string(),
+            rest => rest.to_ascii_lowercase(),
+        }
+    } else {
+        let lowercase = rest.to_ascii_lowercase();
+        match lowercase.as_str() {
+            "cr" | "enter" | "return" => "enter",
+            "esc" => "escape",
+            "space" => "space",
+            "tab" => "tab",
+            "bs" => "backspace",
+            "del" => "delete",
+            "up" => "up",
+            "down" => "down",
+            "left" => "left",
+            "right" => "right",
+            "home" => "home",
+            "end" => "end",
+            "pageup" => "pageup",
+            "pagedown" => "pagedown",
+            "insert" => "insert",
+            "lt" => "<",
+            "bar" => "|",
+            "bslash" => "\\",
+            _ => match lowercase
+                .strip_prefix('f')
+                .and_then(|n| n.parse::<u8>().ok())
+            {
+                Some(1..=12) => return Some(with_modifiers(&modifiers, &lowercase)),
+                _ => return None,
+            },
+        }
+        .to_string()
+    };
+
+    Some(with_modifiers(&modifiers, &key))
+}
+
+fn with_modifiers(modifiers: &[&str], key: &str) -> String {
+    modifiers
+        .iter()
+        .map(|modifier| format!("{modifier}-"))
+        .chain([key.to_string()])
+        .collect()
+}
+
+/// Parses the contents of a macro register, which uses the same key notation
+/// as mappings, into keystrokes that can be replayed.
+pub(crate) fn parse_keystrokes(keys: &str) -> Result<Vec<Keystroke>> {
+    translate_keys(keys, &Leaders::default())?
+        .iter()
+        .map(|key| Keystroke::parse(key).map_err(|_| anyhow!("invalid key {key:?} in {keys:?}")))
+        .collect()
+}
+
+/// Formats a keystroke in vim key notation, so that a recorded macro can be
+/// edited as text. This is the inverse of [`parse_keystrokes`].
+pub(crate) fn keystroke_notation(keystroke: &Keystroke) -> String {
+    let modifiers = &keystroke.modifiers;
+    let key = keystroke.key.as_str();
+    let special_key = special_key_name(key);
+    if !modifiers.control && !modifiers.alt && !modifiers.platform {
+        match key {
+            "space" => return " ".to_string(),
+            "tab" => return "\t".to_string(),
+            _ => {}
+        }
+        if special_key.is_none() {
+            if let Some(key_char) = &keystroke.key_char {
+                return key_char.replace('<', "<lt>");
+            }
+            if !modifiers.shift {
+                return key.replace('<', "<lt>");
+            }
+        }
+    }
+
+    let mut notation = "<".to_string();
+    for (enabled, prefix) in [
+        (modifiers.control, "C-"),
+        (modifiers.alt, "M-"),
+        (modifiers.platform, "D-"),
+        (modifiers.shift, "S-"),
+    ] {
+        if enabled {
+            notation.push_str(prefix);
+        }
+    }
+    match (special_key, key) {
+        (Some(name), _) => notation.push_str(&name),
+        (None, "<") => notation.push_str("lt"),
+        (None, key) => notation.push_str(key),
+    }
+    notation.push('>');
+    notation
+}
+
+/// The vim name of a key that can't be typed as a single character.
+fn special_key_name(key: &str) -> Option<String> {
+    let name = match key {
+        "enter" => "CR",
+        "escape" => "Esc",
+        "space" => "Space",
+        "tab" => "Tab",
+        "backspace" => "BS",
+        "delete" => "Del",
+        "up" => "Up",
+        "down" => "Down",
+        "left" => "Left",
+        "right" => "Right",
+        "home" => "Home",
+        "end" => "End",
+        "pageup" => "PageUp",
+        "pagedown" => "PageDown",
+        "insert" => "Insert",
+        key => {
+            return key
+                .strip_prefix('f')
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=12).contains(n))
+                .map(|n| format!("F{n}"));
+        }
+    };
+    Some(name.to_string())
+}
+
+#[derive(Clone, Debug)]
+struct Mapping {
+    modes: MapModes,
+    lhs: Vec<String>,
+    /// `None` for mappings to `<Nop>`.
+    rhs: Option<Vec<String>>,
+    noremap: bool,
+}
+
+/// The result of applying the vimrc and runtime mapping commands in order.
+#[derive(Clone, Debug, Default)]
+struct VimrcState {
+    leaders: Leaders,
+    mappings: Vec<Mapping>,
+    options: Vec<VimOption>,
+    commands: HashMap<String, String>,
+}
+
+impl VimrcState {
+    fn apply(&mut self, command: &VimrcCommand) -> Result<()> {
+        match command {
+            VimrcCommand::Map {
+                modes,
+                lhs,
+                rhs,
+                noremap,
+            } => {
+                let lhs = translate_keys(lhs, &self.leaders)?;
+                let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+                    None
+                } else {
+                    Some(translate_keys(rhs, &self.leaders)?)
+                };
+                self.remove_mappings(&lhs, modes);
+                self.mappings.push(Mapping {
+                    modes: *modes,
+                    lhs,
+                    rhs,
+                    noremap: *noremap,
+                });
+            }
+            VimrcCommand::Unmap { modes, lhs } => {
+                let lhs = translate_keys(lhs, &self.leaders)?;
+                if !self.remove_mappings(&lhs, modes) {
+                    bail!("no such mapping: {}", lhs.join(" "));
+                }
+            }
+            VimrcCommand::Set(options) => self.options.extend(options.iter().cloned()),
+            VimrcCommand::SetLeader {
+                local: false,
+                leader,
+            } => self.leaders.leader = Some(leader.clone()),
+            VimrcCommand::SetLeader {
+                local: true,
+                leader,
+            } => self.leaders.local_leader = Some(leader.clone()),
+            VimrcCommand::Command { name, replacement } => {
+                self.commands.insert(name.clone(), replacement.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes `modes` from the mappings for `lhs`, returning whether any matched.
+    fn remove_mappings(&mut self, lhs: &[String], modes: &MapModes) -> bool {
+        let mut found = false;
+        self.mappings.retain_mut(|mapping| {
+            if mapping.lhs != lhs || !mapping.modes.intersects(modes) {
+                return true;
+            }
+            found = true;
+            mapping.modes.remove(modes);
+            !mapping.modes.is_empty()
+        });
+        found
+    }
+}
+
+/// User-defined mappings, options and commands from the vimrc file and from
+/// mapping commands run in the current session.
+#[derive(Default)]
+pub struct VimUserConfig {
+    vimrc: Vec<VimrcCommand>,
+    runtime: Vec<VimrcCommand>,
+    state: VimrcState,
+    /// Incremented each time the vimrc is loaded.
+    vimrc_version: usize,
+}
+
+impl Global for VimUserConfig {}
+
+impl VimUserConfig {
+    /// Replaces the vimrc commands, replaying any commands run in this session
+    /// on top of them. Returns an error for each line that could not be applied.
+    fn load_vimrc(&mut self, content: &str) -> Vec<anyhow::Error> {
+        let mut errors = Vec::new();
+        let mut state = VimrcState::default();
+        self.vimrc.clear();
+        for (ix, line) in content.lines().enumerate() {
+            match VimrcCommand::parse(line).and_then(|command| {
+                if let Some(command) = &command {
+                    state.apply(command)?;
+                }
+                Ok(command)
+            }) {
+                Ok(Some(command)) => self.vimrc.push(command),
+                Ok(None) => {}
+                Err(error) => errors.push(error.context(format!("vimrc line {}", ix + 1))),
+            }
+        }
+        self.runtime
+            .retain(|command| state.apply(command).log_err().is_some());
+        self.state = state;
+        self.vimrc_version += 1;
+        errors
+    }
+
+    /// Runs a mapping command entered in the `:` command palette.
+    pub(crate) fn run_command(line: &str, cx: &mut App) -> Result<()> {
+        let Some(command) = VimrcCommand::parse(line)? else {
+            return Ok(());
+        };
+        let mut state = cx
+            .try_global::<Self>()
+            .map(|config| config.state.clone())
+            .unwrap_or_default();
+        state.apply(&command)?;
+        let config = cx.default_global::<Self>();
+        config.state = state;
+        config.runtime.push(command);
+        Ok(())
+    }
+
+    /// Expands a user-defined command (from `command!`) at the start of `query`.
+    pub(crate) fn expand_command(query: &str, cx: &App) -> Option<String> {
+        let config = cx.try_global::<Self>()?;
+        let (name, args) = query
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .map(|ix| (&query[..ix], query[ix..].trim()))
+            .unwrap_or((query, ""));
+        let replacement = config.state.commands.get(name)?;
+        Some(if replacement.contains("<args>") {
+            replacement.replace("<args>", args)
+        } else if args.is_empty() {
+            replacement.clone()
+        } else {
+            format!("{replacement} {args}")
+        })
+    }
+
+    pub(crate) fn vimrc_version(cx: &App) -> usize {
+        cx.try_global::<Self>()
+            .map_or(0, |config| config.vimrc_version)
+    }
+
+    /// Applies the per-editor options (like `wrap` or `number`) set in the vimrc.
+    pub(crate) fn apply_editor_options(editor: &mut Editor, cx: &mut Context<Editor>) {
+        let Some(config) = cx.try_global::<Self>() else {
+            return;
+        };
+        for option in config.state.options.clone() {
+            option.apply_to_editor(editor, cx);
+        }
+    }
+}
+
+/// Returns the keymap bindings for the user-defined mappings.
+pub fn user_key_bindings(cx: &App) -> Vec<KeyBinding> {
+    let Some(config) = cx.try_global::<VimUserConfig>() else {
+        return Vec::new();
+    };
+    config
+        .state
+        .mappings
+        .iter()
+        .filter_map(|mapping| {
+            let action: Box<dyn Action> = match &mapping.rhs {
+                Some(rhs) if mapping.noremap => Box::new(SendKeystrokesNoremap(rhs.join(" "))),
+                Some(rhs) => Box::new(SendKeystrokes(rhs.join(" "))),
+                None => Box::new(NoAction),
+            };
+            // `VimNoremap` is set while the keys of a `noremap` mapping are replayed.
+            let context = format!("({}) && !VimNoremap", mapping.modes.context());
+            let predicate = KeyBindingContextPredicate::parse(&context).ok()?;
+            KeyBinding::load(
+                &mapping.lhs.join(" "),
+                action,
+                Some(Rc::new(predicate)),
+                false,
+                None,
+                cx.keyboard_mapper().as_ref(),
+            )
+            .log_err()
+        })
+        .collect()
+}
+
+pub(crate) fn register(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    _: &mut Context<Workspace>,
+) {
+    workspace.register_action(|workspace, action: &SendKeystrokesNoremap, window, cx| {
+        let keystrokes = action
+            .0
+            .split(' ')
+            .flat_map(|key| Keystroke::parse(key).log_err())
+            .map(|keystroke| {
+                cx.keyboard_mapper()
+                    .map_key_equivalent(keystroke, false)
+                    .inner()
+                    .clone()
+            })
+            .collect();
+
+        // The mappings stay disabled until every queued keystroke has been
+        // dispatched, including those of the mapping that sent this one.
+        Vim::globals(cx).replaying_noremap = true;
+        window.refresh();
+        let replay = workspace.send_keystrokes_impl(keystrokes, window, cx);
+        cx.spawn_in(window, async move |_, cx| {
+            replay.await;
+            cx.update(|window, cx| {
+                Vim::globals(cx).replaying_noremap = false;
+                window.refresh();
+            })
+            .ok();
+        })
+        .detach();
+    });
+}
+
+/// Loads the vimrc file each time its contents change.
+pub fn handle_vimrc_file_changes(
+    mut vimrc_file_rx: mpsc::UnboundedReceiver<String>,
+    vimrc_watcher: gpui::Task<()>,
+    cx: &mut App,
+) {
+    cx.spawn(async move |cx| {
+        let _vimrc_watcher = vimrc_watcher;
+        while let Some(content) = vimrc_file_rx.next().await {
+            cx.update(|cx| {
+                let errors = cx.default_global::<VimUserConfig>().load_vimrc(&content);
+                for error in errors {
+                    log::error!("{error:#}");
+                }
+                let options = cx.global::<VimUserConfig>().state.options.clone();
+                for option in options {
+                    option.apply_global(cx);
+                }
+            });
+        }
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn parse(line: &str) -> VimrcCommand {
+        VimrcCommand::parse(line).unwrap().unwrap()
+    }
+
+    fn keys(keys: &str) -> Vec<String> {
+        translate_keys(keys, &Leaders::default()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_map_commands() {
+        assert_eq!(VimrcCommand::parse("").unwrap(), None);
+        assert_eq!(VimrcCommand::parse("  \" a comment").unwrap(), None);
+        assert_eq!(
+            parse("nnoremap <silent> <leader>w :w<CR>"),
+            VimrcCommand::Map {
+                modes: MapModes::NORMAL,
+                lhs: "<leader>w".into(),
+                rhs: ":w<CR>".into(),
+                noremap: true,
+            }
+        );
+        assert_eq!(
+            parse(":vmap J  :m '>+1<CR>gv"),
+            VimrcCommand::Map {
+                modes: MapModes::VISUAL,
+                lhs: "J".into(),
+                rhs: ":m '>+1<CR>gv".into(),
+                noremap: false,
+            }
+        );
+        assert_eq!(
+            parse("map! jk <Esc>"),
+            VimrcCommand::Map {
+                modes: MapModes::INSERT,
+                lhs: "jk".into(),
+                rhs: "<Esc>".into(),
+                noremap: false,
+            }
+        );
+        assert_eq!(
+            parse("nun Y"),
+            VimrcCommand::Unmap {
+                modes: MapModes::NORMAL,
+                lhs: "Y".into(),
+            }
+        );
+        assert!(VimrcCommand::parse("nnoremap <expr> j v:count ? 'j' : 'gj'").is_err());
+        assert!(VimrcCommand::parse("nmap").is_err());
+        assert!(VimrcCommand::parse("autocmd BufWritePre * :%s/\\s\\+$//e").is_err());
+    }
+
+    #[test]
+    fn test_parse_other_commands() {
+        assert_eq!(
+            parse("set nowrap ic nocompatible"),
+            VimrcCommand::Set(vec![VimOption::Wrap(false), VimOption::IgnoreCase(true)])
+        );
+        assert_eq!(
+            parse(r#"let mapleader = "\<Space>""#),
+            VimrcCommand::SetLeader {
+                local: false,
+                leader: " ".into(),
+            }
+        );
+        assert_eq!(
+            parse("let g:maplocalleader = ','"),
+            VimrcCommand::SetLeader {
+                local: true,
+                leader: ",".into(),
+            }
+        );
+        assert_eq!(
+            parse("command! -nargs=* W w <args>"),
+            VimrcCommand::Command {
+                name: "W".into(),
+                replacement: "w <args>".into(),
+            }
+        );
+        assert!(VimrcCommand::parse("command! w write").is_err());
+    }
+
+    #[test]
+    fn test_translate_keys() {
+        assert_eq!(keys("dd"), ["d", "d"]);
+        assert_eq!(keys("<leader>f"), ["\\", "f"]);
+        assert_eq!(keys(":w<CR>"), [":", "w", "enter"]);
+        assert_eq!(keys("<C-w>v"), ["ctrl-w", "v"]);
+        assert_eq!(keys("<C-S-Tab><M-j>"), ["ctrl-shift-tab", "alt-j"]);
+        assert_eq!(keys("<lt>a<Bar>"), ["<", "a", "|"]);
+        assert_eq!(keys("a<b"), ["a", "<", "b"]);
+        assert_eq!(keys("<F5>G"), ["f5", "G"]);
+        assert!(translate_keys("<C-Foo>", &Leaders::default()).is_err());
+
+        let leaders = Leaders {
+            leader: Some(" ".into()),
+            local_leader: None,
+        };
+        assert_eq!(
+            translate_keys("<Leader>ff", &leaders).unwrap(),
+            ["space", "f", "f"]
+        );
+        assert_eq!(
+            translate_keys("<LocalLeader>x", &leaders).unwrap(),
+            ["space", "x"]
+        );
+    }
+
+    #[test]
+    fn test_keystroke_notation() {
+        let notation = |keys: &str| {
+            keys.split(' ')
+                .map(|key| keystroke_notation(&Keystroke::parse(key).unwrap().with_simulated_ime()))
+                .collect::<String>()
+        };
+        assert_eq!(notation("i h i escape"), "ihi<Esc>");
+        assert_eq!(notation(": w enter"), ":w<CR>");
+        assert_eq!(notation("ctrl-w v"), "<C-w>v");
+        assert_eq!(notation("shift-a space < tab"), "A <lt>\t");
+        assert_eq!(notation("ctrl-shift-tab f5"), "<C-S-Tab><F5>");
+
+        for keys in [
+            "0f(ci(foo<Esc>j",
+            ":s/a/b<CR>",
+            "<C-v>jjI<lt>!--<Esc>",
+            "A;\t<F5>",
+        ] {
+            let keystrokes = parse_keystrokes(keys).unwrap();
+            let round_trip = keystrokes
+                .into_iter()
+                .map(|keystroke| keystroke_notation(&keystroke.with_simulated_ime()))
+                .collect::<String>();
+            assert_eq!(round_trip, keys);
+        }
+        assert_eq!(
+            parse_keystrokes("x\n").unwrap(),
+            [
+                Keystroke::parse("x").unwrap(),
+                Keystroke::parse("enter").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_mappings() {
+        let mut state = VimrcState::default();
+        for line in [
+            "let mapleader = ' '",
+            "map <leader>w :w<CR>",
+            "nnoremap Y y$",
+            "vunmap <leader>w",
+            "command W w",
+        ] {
+            state.apply(&parse(line)).unwrap();
+        }
+
+        assert_eq!(state.mappings.len(), 2);
+        assert_eq!(state.mappings[0].lhs, ["space", "w"]);
+        assert_eq!(
+            state.mappings[0].modes.context(),
+            "vim_mode == normal || vim_mode == operator"
+        );
+        assert_eq!(
+            state.mappings[1].rhs,
+            Some(vec!["y".to_string(), "$".to_string()])
+        );
+        assert_eq!(state.commands.get("W").map(String::as_str), Some("w"));
+
+        assert!(state.apply(&parse("iunmap Y")).is_err());
+        state.apply(&parse("nunmap Y")).unwrap();
+        assert_eq!(state.mappings.len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_noremap(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            for line in ["nnoremap j k", "nnoremap k j", "nmap Q j"] {
+                VimUserConfig::run_command(line, cx).unwrap();
+            }
+            cx.bind_keys(user_key_bindings(cx));
+        });
+
+        cx.set_state("a\nb\nˇc\nd", Mode::Normal);
+        cx.simulate_keystrokes("j");
+        cx.assert_state("a\nˇb\nc\nd", Mode::Normal);
+        cx.simulate_keystrokes("k k");
+        cx.assert_state("a\nb\nc\nˇd", Mode::Normal);
+
+        // The keys of `nmap` trigger other mappings.
+        cx.simulate_keystrokes("Q");
+        cx.assert_state("a\nb\nˇc\nd", Mode::Normal);
+    }
+}
@@ -96,6 +96,62 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         vim.visual_yank(true, window, cx)
     });
 
+    // The editor's rectangle actions treat each line of a visual block as a
+    // line of the rectangle.
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &editor::actions::CopyRectangle, window, cx| {
+            vim.rectangle_action(window, cx, |editor, window, cx| {
+                editor.copy_rectangle(action, window, cx)
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &editor::actions::KillRectangle, window, cx| {
+            vim.rectangle_action(window, cx, |editor, window, cx| {
+                editor.kill_rectangle(action, window, cx)
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &editor::actions::NumberRectangleLines, window, cx| {
+            vim.rectangle_action(window, cx, |editor, window, cx| {
+                editor.number_rectangle_lines(action, window, cx)
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &editor::actions::AlignOnDelimiter, window, cx| {
+            vim.rectangle_action(window, cx, |editor, window, cx| {
+                editor.align_on_delimiter(action, window, cx)
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &editor::actions::StringRectangle, window, cx| {
+            if action.text.is_some() {
+                vim.rectangle_action(window, cx, |editor, window, cx| {
+                    editor.string_rectangle(action, window, cx)
+                });
+                return;
+            }
+            // Like `c` on a visual block, typing replaces every line of it.
+            vim.update_editor(cx, |_, editor, cx| {
+                editor.string_rectangle(action, window, cx)
+            });
+            vim.switch_mode(Mode::Insert, true, window, cx);
+        },
+    );
+
     Vim::action(editor, cx, Vim::select_next);
     Vim::action(editor, cx, Vim::select_previous);
     Vim::action(editor, cx, |vim, _: &SelectNextMatch, window, cx| {
@@ -710,6 +766,30 @@ impl Vim {
         transaction_id
     }
 
+    /// Runs one of the editor's rectangle actions. From visual mode, it then
+    /// returns to normal mode at the top left of the rectangle, as visual
+    /// block operators do.
+    fn rectangle_action(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        action: impl FnOnce(&mut Editor, &mut Window, &mut Context<Editor>),
+    ) {
+        let was_visual = self.mode.is_visual();
+        self.update_editor(cx, |_, editor, cx| {
+            action(editor, window, cx);
+            if was_visual {
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    let cursor = s.first_anchor().start;
+                    s.select_anchor_ranges([cursor..cursor]);
+                });
+            }
+        });
+        if was_visual {
+            self.switch_mode(Mode::Normal, true, window, cx);
+        }
+    }
+
     pub fn visual_yank(&mut self, line_mode: bool, window: &mut Window, cx: &mut Context<Self>) {
         self.store_visual_marks(window, cx);
         self.update_editor(cx, |vim, editor, cx| {
@@ -2051,4 +2131,32 @@ mod test {
         cx.simulate_keystrokes("r 1");
         cx.assert_state("ˇ1", Mode::Normal);
     }
+
+    #[gpui::test]
+    async fn test_visual_block_rectangle_actions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("aˇbcd\nefgh\nijkl\n", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v j l");
+        cx.dispatch_action(editor::actions::KillRectangle);
+        cx.assert_state("aˇd\neh\nijkl\n", Mode::Normal);
+
+        cx.set_state("ˇabcd\nefgh\nijkl\n", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v j j");
+        cx.dispatch_action(editor::actions::NumberRectangleLines::default());
+        cx.assert_state("ˇ1 abcd\n2 efgh\n3 ijkl\n", Mode::Normal);
+
+        cx.set_state("ˇa = 1\nbbb = 2\n", Mode::Normal);
+        cx.simulate_keystrokes("shift-v j");
+        cx.dispatch_action(editor::actions::AlignOnDelimiter::default());
+        cx.assert_state("ˇa   = 1\nbbb = 2\n", Mode::Normal);
+
+        // Without text, the rectangle is replaced by typing on every line.
+        cx.set_state("aˇbcd\nefgh\nijkl\n", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v j l");
+        cx.dispatch_action(editor::actions::StringRectangle::default());
+        assert_eq!(cx.mode(), Mode::Insert);
+        cx.simulate_keystrokes("X");
+        cx.assert_state("aXˇd\neXˇh\nijkl\n", Mode::Insert);
+    }
 }
@@ -73,7 +73,9 @@ pub use item::{
     ProjectItem, SerializableItem, SerializableItemHandle, WeakItemHandle,
 };
 use itertools::Itertools;
-use language::{Buffer, LanguageRegistry, Rope, language_settings::all_language_settings};
+use language::{
+    BinaryFileError, Buffer, LanguageRegistry, Rope, language_settings::all_language_settings,
+};
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
 use notifications::{
@@ -840,6 +842,8 @@ type BuildProjectItemForPathFn =
 struct ProjectItemRegistry {
     build_project_item_fns_by_type: TypeIdHashMap<BuildProjectItemFn>,
     build_project_item_for_path_fns: Vec<BuildProjectItemForPathFn>,
+    /// Openers for files that failed to open because they're binary.
+    build_project_item_for_binary_path_fns: Vec<BuildProjectItemForPathFn>,
 }
 
 impl ProjectItemRegistry {
@@ -891,6 +895,19 @@ impl ProjectItemRegistry {
                             Ok((project_entry_id, build_workspace_item))
                         }
                         Err(e) => {
+                            if e.downcast_ref::<BinaryFileError>().is_some()
+                                && let Some(open_binary_file) = cx.update(|window, cx| {
+                                    ProjectItemRegistry::open_binary_path(
+                                        &project,
+                                        &project_path,
+                                        window,
+                                        cx,
+                                    )
+                                })?
+                            {
+                                return open_binary_file.await;
+                            }
+
                             log::warn!("Failed to open a project item: {e:#}");
                             if e.error_code() == ErrorCode::Internal {
                                 if let Some(abs_path) =
@@ -920,6 +937,52 @@ impl ProjectItemRegistry {
             });
     }
 
+    fn register_binary<T: ProjectItem>(&mut self) {
+        self.build_project_item_fns_by_type.insert(
+            TypeId::of::<T::Item>(),
+            |item, project, pane, window, cx| {
+                let item = item.downcast().unwrap();
+                Box::new(cx.new(|cx| T::for_project_item(project, pane, item, window, cx)))
+                    as Box<dyn ItemHandle>
+            },
+        );
+        self.build_project_item_for_binary_path_fns
+            .push(|project, project_path, window, cx| {
+                let project_item =
+                    <T::Item as project::ProjectItem>::try_open(project, project_path, cx)?;
+                let project = project.clone();
+                Some(window.spawn(cx, async move |cx| {
+                    let project_item = project_item.await?;
+                    let project_entry_id: Option<ProjectEntryId> =
+                        project_item.read_with(cx, project::ProjectItem::entry_id);
+                    let build_workspace_item = Box::new(
+                        |pane: &mut Pane, window: &mut Window, cx: &mut Context<Pane>| {
+                            Box::new(cx.new(|cx| {
+                                T::for_project_item(project, Some(pane), project_item, window, cx)
+                            })) as Box<dyn ItemHandle>
+                        },
+                    ) as Box<_>;
+                    Ok((project_entry_id, build_workspace_item))
+                }))
+            });
+    }
+
+    fn open_binary_path(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>>> {
+        let open_binary_path_fns = cx
+            .try_global::<Self>()?
+            .build_project_item_for_binary_path_fns
+            .clone();
+        open_binary_path_fns
+            .iter()
+            .rev()
+            .find_map(|open_project_item| open_project_item(project, path, window, cx))
+    }
+
     fn open_path(
         &self,
         project: &Entity<Project>,
@@ -965,6 +1028,14 @@ pub fn register_project_item<I: ProjectItem>(cx: &mut App) {
     cx.default_global::<ProjectItemRegistry>().register::<I>();
 }
 
+/// Registers a [ProjectItem] for opening files that can't be opened as text, because they're
+/// binary. It gets a chance to open a file only after the items registered with
+/// [register_project_item] have failed to open it for that reason.
+pub fn register_binary_project_item<I: ProjectItem>(cx: &mut App) {
+    cx.default_global::<ProjectItemRegistry>()
+        .register_binary::<I>();
+}
+
 #[derive(Default)]
 pub struct FollowableViewRegistry(TypeIdHashMap<FollowableViewDescriptor>);
 
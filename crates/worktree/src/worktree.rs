@@ -32,7 +32,9 @@ use gpui::{
     Task,
 };
 use ignore::IgnoreStack;
-use language::{ByteContent, DiskState, FILE_ANALYSIS_BYTES, analyze_byte_content};
+use language::{
+    BinaryFileError, ByteContent, DiskState, FILE_ANALYSIS_BYTES, analyze_byte_content,
+};
 
 use async_channel::{self, Sender};
 use parking_lot::Mutex;
@@ -115,6 +117,21 @@ pub struct LoadedFile {
     pub is_writable: bool,
 }
 
+/// A file opened in large file mode, decoded in chunks straight into a [`Rope`] with its line
+/// endings normalized, so that it's never held in memory as a single string.
+///
+/// The file isn't memory-mapped or paged in lazily: the rope holds all of it, and search and go
+/// to line work on the rope as they do for any other buffer.
+#[derive(Debug)]
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub text: Rope,
+    pub line_ending: LineEnding,
+    pub encoding: &'static Encoding,
+    pub has_bom: bool,
+    pub is_writable: bool,
+}
+
 pub struct LoadedBinaryFile {
     pub file: Arc<File>,
     pub content: Vec<u8>,
@@ -889,6 +906,32 @@ impl Worktree {
         }
     }
 
+    pub fn load_large_file(
+        &self,
+        path: &RelPath,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
+    /// Whether the file at the given path is large enough to be opened in large file mode.
+    ///
+    /// Always false for remote worktrees, whose buffers are opened by the host. The host decides
+    /// whether they're in large file mode, and replicates that with the rest of the buffer state.
+    pub fn is_large_file(&self, path: &RelPath) -> bool {
+        match self {
+            Worktree::Local(this) => this
+                .entry_for_path(path)
+                .is_some_and(|entry| entry.is_file() && this.settings.is_large_file(entry.size)),
+            Worktree::Remote(_) => false,
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &RelPath,
@@ -1720,6 +1763,62 @@ impl LocalWorktree {
         })
     }
 
+    fn load_large_file(
+        &self,
+        path: &RelPath,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let entry = self.refresh_entry(path.clone(), None, cx);
+        let is_private = self.is_path_private(path.as_ref());
+
+        let this = cx.weak_entity();
+        cx.background_spawn(async move {
+            let metadata = fs.metadata(&abs_path).await?;
+            let (text, line_ending, encoding, has_bom) =
+                decode_large_file_text(fs.as_ref(), &abs_path).await?;
+            let is_writable = metadata.is_some_and(|metadata| metadata.is_writable);
+
+            let worktree = this.upgrade().context("worktree was dropped")?;
+            let file = match entry.await? {
+                Some(entry) => File::for_entry(entry, worktree),
+                None => {
+                    let metadata = fs
+                        .metadata(&abs_path)
+                        .await
+                        .with_context(|| {
+                            format!("Loading metadata for excluded file {abs_path:?}")
+                        })?
+                        .with_context(|| {
+                            format!("Excluded file {abs_path:?} got removed during loading")
+                        })?;
+                    Arc::new(File {
+                        entry_id: None,
+                        worktree,
+                        path,
+                        disk_state: DiskState::Present {
+                            mtime: metadata.mtime,
+                            size: metadata.len,
+                        },
+                        is_local: true,
+                        is_private,
+                    })
+                }
+            };
+
+            Ok(LoadedLargeFile {
+                file,
+                text,
+                line_ending,
+                encoding,
+                has_bom,
+                is_writable,
+            })
+        })
+    }
+
     /// Find the lowest path in the worktree's datastructures that is an ancestor
     fn lowest_ancestor(&self, path: &RelPath) -> Arc<RelPath> {
         let mut lowest_ancestor = None;
@@ -7117,10 +7216,7 @@ async fn decode_file_text(
         file_first_bytes.extend_from_slice(&buf[..n]);
     }
     let (bom_encoding, byte_content) = decode_byte_header(&file_first_bytes);
-    anyhow::ensure!(
-        byte_content != ByteContent::Binary,
-        "Binary files are not supported"
-    );
+    anyhow::ensure!(byte_content != ByteContent::Binary, BinaryFileError);
 
     // If the file is eligible for opening, read the rest of the file.
     let mut content = file_first_bytes;
@@ -7139,6 +7235,94 @@ async fn decode_file_text(
     decode_byte_full(content, bom_encoding, byte_content)
 }
 
+/// Decodes a file too large to hold as a single string, a chunk at a time. The encoding is
+/// chosen from the file's first chunk, and each decoded chunk is pushed onto the rope with its
+/// line endings normalized.
+async fn decode_large_file_text(
+    fs: &dyn Fs,
+    abs_path: &Path,
+) -> Result<(Rope, LineEnding, &'static Encoding, bool)> {
+    const CHUNK_BYTES: usize = 1024 * 1024;
+
+    let mut file = fs
+        .open_sync(&abs_path)
+        .await
+        .with_context(|| format!("opening file {abs_path:?}"))?;
+    let mut read_chunk = |bytes: &mut Vec<u8>| -> Result<bool> {
+        bytes.clear();
+        bytes.resize(CHUNK_BYTES, 0);
+        let mut len = 0;
+        while len < CHUNK_BYTES {
+            let n = file
+                .read(&mut bytes[len..])
+                .with_context(|| format!("reading bytes of the file {abs_path:?}"))?;
+            if n == 0 {
+                break;
+            }
+            len += n;
+        }
+        bytes.truncate(len);
+        Ok(len < CHUNK_BYTES)
+    };
+
+    let mut bytes = Vec::with_capacity(CHUNK_BYTES);
+    let mut reached_eof = read_chunk(&mut bytes)?;
+    let (bom_encoding, byte_content) =
+        decode_byte_header(&bytes[..bytes.len().min(FILE_ANALYSIS_BYTES)]);
+    let encoding = match (bom_encoding, byte_content) {
+        (Some(encoding), _) => encoding,
+        (None, ByteContent::Utf16Le) => encoding_rs::UTF_16LE,
+        (None, ByteContent::Utf16Be) => encoding_rs::UTF_16BE,
+        (None, ByteContent::Binary) => anyhow::bail!(BinaryFileError),
+        (None, ByteContent::Unknown) => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(&bytes, reached_eof);
+            detector.guess(None, true)
+        }
+    };
+    let has_bom = bom_encoding.is_some();
+    let mut decoder = if has_bom {
+        encoding.new_decoder_with_bom_removal()
+    } else {
+        encoding.new_decoder_without_bom_handling()
+    };
+
+    let mut rope = Rope::new();
+    let mut line_ending = None;
+    let mut text = String::new();
+    loop {
+        // A `\r` ending a chunk may be the start of a `\r\n` split across chunks, so it's held
+        // back until the next chunk is decoded.
+        let held_back_cr = text.ends_with('\r');
+        text.clear();
+        if held_back_cr {
+            text.push('\r');
+        }
+        text.reserve(
+            decoder
+                .max_utf8_buffer_length(bytes.len())
+                .context("file chunk is too large to decode")?,
+        );
+        let _ = decoder.decode_to_string(&bytes, &mut text, reached_eof);
+
+        let line_ending = *line_ending.get_or_insert_with(|| LineEnding::detect(&text));
+        let holds_back_cr = !reached_eof && text.ends_with('\r');
+        if holds_back_cr {
+            text.pop();
+        }
+        LineEnding::normalize(&mut text);
+        rope.push(&text);
+        if holds_back_cr {
+            text.push('\r');
+        }
+
+        if reached_eof {
+            return Ok((rope, line_ending, encoding, has_bom));
+        }
+        reached_eof = read_chunk(&mut bytes)?;
+    }
+}
+
 fn decode_byte_header(prefix: &[u8]) -> (Option<&'static Encoding>, ByteContent) {
     if let Some((encoding, _bom_len)) = Encoding::for_bom(prefix) {
         return (Some(encoding), ByteContent::Unknown);
@@ -7168,7 +7352,7 @@ fn decode_byte_full(
             return Ok((cow.into_owned(), encoding, false));
         }
         ByteContent::Binary => {
-            anyhow::bail!("Binary files are not supported");
+            anyhow::bail!(BinaryFileError);
         }
         ByteContent::Unknown => {}
     }
@@ -21,6 +21,8 @@ pub struct WorktreeSettings {
     pub private_files: PathMatcher,
     pub hidden_files: PathMatcher,
     pub read_only_files: PathMatcher,
+    /// Files at least this many bytes large are opened in large file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -54,6 +56,10 @@ impl WorktreeSettings {
     pub fn is_std_path_read_only(&self, path: &Path) -> bool {
         self.read_only_files.is_match_std_path(path)
     }
+
+    pub fn is_large_file(&self, size: u64) -> bool {
+        size >= self.large_file_threshold
+    }
 }
 
 impl Settings for WorktreeSettings {
@@ -65,6 +71,10 @@ impl Settings for WorktreeSettings {
         let hidden_files = worktree.hidden_files.unwrap();
         let read_only_files = worktree.read_only_files.unwrap_or_default();
         let scan_symlinks = worktree.scan_symlinks.unwrap();
+        let large_file_threshold = match worktree.large_file_threshold_mb.unwrap() {
+            0 => u64::MAX,
+            megabytes => megabytes.saturating_mul(1024 * 1024),
+        };
         let parsed_file_scan_inclusions: Vec<String> = file_scan_inclusions
             .iter()
             .flat_map(|glob| {
@@ -98,6 +108,7 @@ impl Settings for WorktreeSettings {
                 .log_err()
                 .unwrap_or_default(),
             scan_symlinks,
+            large_file_threshold,
         }
     }
 }
@@ -438,6 +438,11 @@ fn main() {
         fs.clone(),
         paths::keymap_file().clone(),
     );
+    let (user_vimrc_file_rx, user_vimrc_watcher) = watch_config_file(
+        &app.background_executor(),
+        fs.clone(),
+        paths::vimrc_file().clone(),
+    );
 
     let (shell_env_loaded_tx, shell_env_loaded_rx) = oneshot::channel();
     if !stdout_is_a_pty() {
@@ -726,6 +731,7 @@ fn main() {
 
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -734,6 +740,7 @@ fn main() {
         ui_prompt::init(cx);
 
         go_to_line::init(cx);
+        sort_lines::init(cx);
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
@@ -755,6 +762,7 @@ fn main() {
             wrap_div_with_search_actions: search::buffer_search::register_pane_search_actions,
         });
         vim::init(cx);
+        vim::handle_vimrc_file_changes(user_vimrc_file_rx, user_vimrc_watcher, cx);
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         encoding_selector::init(cx);
@@ -781,6 +789,8 @@ fn main() {
         json_schema_store::init(cx);
         miniprofiler_ui::init(*STARTUP_TIME.get().unwrap(), cx);
         which_key::init(cx);
+        spell_check::init(cx);
+        emacs::init(cx);
         #[cfg(target_os = "windows")]
         etw_tracing::init(cx);
 
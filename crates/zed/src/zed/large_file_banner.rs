@@ -0,0 +1,47 @@
+use editor::Editor;
+use gpui::EventEmitter;
+use ui::{Banner, prelude::*};
+use workspace::{ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+/// Tells that the active editor's file was opened in large file mode, so that the missing
+/// highlighting and language features don't look like a bug.
+pub struct LargeFileBanner;
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        Banner::new().child(
+            v_flex()
+                .gap_0p5()
+                .child(Label::new("This file was opened in large file mode").mt_0p5())
+                .child(
+                    Label::new(
+                        "Syntax highlighting, language servers and git diffs are turned off for it. \
+                        The size past which files open this way is set by `large_file_threshold_mb`.",
+                    )
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                ),
+        )
+    }
+}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        if let Some(item) = active_pane_item
+            && let Some(editor) = item.act_as::<Editor>(cx)
+            && let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton()
+            && buffer.read(cx).is_large_file()
+        {
+            return ToolbarItemLocation::Secondary;
+        }
+
+        ToolbarItemLocation::Hidden
+    }
+}